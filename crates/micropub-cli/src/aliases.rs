@@ -0,0 +1,145 @@
+// ABOUTME: User-defined command aliases from the [aliases] config section
+// ABOUTME: Expands an alias into its command line before clap parses the arguments
+
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+
+use crate::editor::split_command;
+
+/// Replace an alias in `args` (program name first) with the command line it
+/// stands for, like git aliases. Built-in commands win over aliases of the
+/// same name, and an alias may expand to another alias.
+pub fn expand(
+    mut args: Vec<OsString>,
+    aliases: &BTreeMap<String, String>,
+    is_builtin: impl Fn(&str) -> bool,
+) -> Result<Vec<OsString>> {
+    let mut expanded: Vec<String> = Vec::new();
+    let mut from = 1;
+
+    while let Some(index) = subcommand_index(&args, from) {
+        let Some(name) = args[index].to_str().map(String::from) else {
+            break;
+        };
+        let Some(command) = aliases.get(&name).filter(|_| !is_builtin(&name)) else {
+            break;
+        };
+        if expanded.contains(&name) {
+            bail!(
+                "Alias `{}` refers to itself: {} -> {}",
+                name,
+                expanded.join(" -> "),
+                name
+            );
+        }
+
+        let words = split_command(command);
+        if words.is_empty() {
+            bail!("Alias `{}` is empty", name);
+        }
+        args.splice(index..=index, words.into_iter().map(OsString::from));
+        expanded.push(name);
+        from = index;
+    }
+
+    Ok(args)
+}
+
+/// Position of the first argument from `from` on that isn't a global flag
+fn subcommand_index(args: &[OsString], from: usize) -> Option<usize> {
+    let mut index = from;
+    while let Some(arg) = args.get(index) {
+        match arg.to_str() {
+            Some("--") => return None,
+            // The one global flag that takes a separate value
+            Some("--profile") => index += 2,
+            Some(flag) if flag.starts_with('-') => index += 1,
+            _ => return Some(index),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(args: &[&str], aliases: &[(&str, &str)]) -> Result<Vec<String>> {
+        let aliases = aliases
+            .iter()
+            .map(|(name, command)| (name.to_string(), command.to_string()))
+            .collect();
+        let args = args.iter().map(OsString::from).collect();
+        let expanded = expand(args, &aliases, |name| name == "publish")?;
+        Ok(expanded
+            .into_iter()
+            .map(|arg| arg.into_string().unwrap())
+            .collect())
+    }
+
+    #[test]
+    fn test_expand_alias_after_global_flags() {
+        let aliases = [
+            ("note", "draft new --category note"),
+            ("ship", "--profile 'b.test' publish"),
+        ];
+
+        assert_eq!(
+            run(
+                &[
+                    "micropub",
+                    "-v",
+                    "--profile",
+                    "a.test",
+                    "note",
+                    "--title",
+                    "Hi"
+                ],
+                &aliases
+            )
+            .unwrap(),
+            vec![
+                "micropub",
+                "-v",
+                "--profile",
+                "a.test",
+                "draft",
+                "new",
+                "--category",
+                "note",
+                "--title",
+                "Hi"
+            ]
+        );
+        assert_eq!(
+            run(&["micropub", "ship", "abc"], &aliases).unwrap(),
+            vec!["micropub", "--profile", "b.test", "publish", "abc"]
+        );
+        // Arguments that merely match an alias name are left alone
+        assert_eq!(
+            run(&["micropub", "draft", "note"], &aliases).unwrap(),
+            vec!["micropub", "draft", "note"]
+        );
+    }
+
+    #[test]
+    fn test_builtins_win_and_cycles_fail() {
+        assert_eq!(
+            run(
+                &["micropub", "publish", "abc"],
+                &[("publish", "draft list")]
+            )
+            .unwrap(),
+            vec!["micropub", "publish", "abc"]
+        );
+        assert_eq!(
+            run(&["micropub", "n", "x"], &[("n", "nn"), ("nn", "draft new")]).unwrap(),
+            vec!["micropub", "draft", "new", "x"]
+        );
+
+        let err = run(&["micropub", "a"], &[("a", "b"), ("b", "a")]).unwrap_err();
+        assert!(err.to_string().contains("a -> b -> a"), "{}", err);
+        assert!(run(&["micropub", "blank"], &[("blank", "  ")]).is_err());
+    }
+}
@@ -0,0 +1,184 @@
+// ABOUTME: `micropub archive` browses published drafts kept in the archive/ data directory
+// ABOUTME: Lists, shows, and searches them, and restores one to drafts for reuse
+
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::get_archive_dir;
+use crate::draft::{load_dir, match_snippet, matched_fields, Draft, DraftId};
+use crate::output::{json, print_json};
+
+/// Load an archived draft
+pub fn load(draft_id: &str) -> Result<Draft> {
+    let id = DraftId::parse(draft_id)?;
+    let path = id.archive_path()?;
+    if !path.exists() {
+        bail!("No archived draft: {}", id);
+    }
+    let contents = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read archived draft: {}", id))?;
+    Draft::from_string(id.to_string(), contents)
+}
+
+/// All archived drafts, most recently published first
+pub fn list() -> Result<Vec<Draft>> {
+    let mut drafts: Vec<Draft> = load_dir(&get_archive_dir()?)?
+        .into_iter()
+        .map(|(_, draft)| draft)
+        .collect();
+    drafts.sort_by(|a, b| {
+        b.metadata
+            .published_at
+            .cmp(&a.metadata.published_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    Ok(drafts)
+}
+
+/// A fresh draft from an archived one: publishing it again makes a new post
+fn restored(mut draft: Draft) -> Draft {
+    draft.metadata.status = None;
+    draft.metadata.url = None;
+    draft.metadata.published_at = None;
+    draft.metadata.pushed_hashes.clear();
+    draft
+}
+
+/// Copy an archived draft back to drafts, leaving the archived copy in place
+pub fn restore(draft_id: &str) -> Result<PathBuf> {
+    let id = DraftId::parse(draft_id)?;
+    let draft = restored(load(draft_id)?);
+
+    if id.draft_path()?.exists() {
+        bail!("A draft named {} already exists", id);
+    }
+    draft.save()
+}
+
+/// One line per archived draft: id, title, publish date, and URL
+fn summary(draft: &Draft) -> String {
+    let title = draft.metadata.name.as_deref().unwrap_or("[untitled]");
+    let date = draft
+        .metadata
+        .published_at
+        .map(|at| at.format(" (%Y-%m-%d)").to_string())
+        .unwrap_or_default();
+    let url = draft
+        .metadata
+        .url
+        .as_deref()
+        .map(|url| format!(" {}", url))
+        .unwrap_or_default();
+    format!("{} - {}{}{}", draft.id, title, date, url)
+}
+
+pub fn cmd_archive_list(category_filter: Option<&str>, limit: usize, offset: usize) -> Result<()> {
+    let page: Vec<Draft> = list()?
+        .into_iter()
+        .filter(|draft| {
+            category_filter.is_none_or(|c| draft.metadata.category.iter().any(|d| d == c))
+        })
+        .skip(offset)
+        .take(limit)
+        .collect();
+
+    if json() {
+        let page: Vec<_> = page
+            .iter()
+            .map(|draft| serde_json::json!({ "id": draft.id, "metadata": draft.metadata }))
+            .collect();
+        return print_json(&page);
+    }
+
+    if page.is_empty() {
+        println!("No archived drafts found.");
+        return Ok(());
+    }
+    for draft in &page {
+        println!("  {}", summary(draft));
+    }
+    Ok(())
+}
+
+pub fn cmd_archive_show(draft_id: &str) -> Result<()> {
+    let draft = load(draft_id)?;
+    println!("{}", draft.to_string()?);
+    Ok(())
+}
+
+/// Search archived drafts by title, content, or category
+pub fn cmd_archive_search(query: &str) -> Result<()> {
+    let mut results = Vec::new();
+    for draft in list()? {
+        let matches = matched_fields(
+            draft.metadata.name.as_deref(),
+            &draft.content,
+            &draft.metadata.category,
+            query,
+        );
+        if !matches.is_empty() {
+            results.push((draft, matches));
+        }
+    }
+
+    if json() {
+        let results: Vec<_> = results
+            .iter()
+            .map(|(draft, matches)| {
+                serde_json::json!({
+                    "id": draft.id,
+                    "metadata": draft.metadata,
+                    "matched_in": matches,
+                })
+            })
+            .collect();
+        return print_json(&results);
+    }
+
+    if results.is_empty() {
+        println!("No archived drafts found matching '{}'.", query);
+        return Ok(());
+    }
+    for (draft, matches) in &results {
+        println!("{}", summary(draft));
+        println!("  Matched in: {}", matches.join(", "));
+        if matches.contains(&"content") {
+            if let Some(snippet) = match_snippet(&draft.content, query) {
+                println!("  {}", snippet);
+            }
+        }
+        println!();
+    }
+    println!("Found {} archived draft(s).", results.len());
+    Ok(())
+}
+
+pub fn cmd_archive_restore(draft_id: &str) -> Result<()> {
+    let path = restore(draft_id)?;
+    println!("Restored {} to {}", draft_id, path.display());
+    println!("Publishing it again creates a new post.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restored_clears_publish_state() {
+        let mut draft = Draft::new("20240101-abc".to_string());
+        draft.metadata.status = Some("published".to_string());
+        draft.metadata.url = Some("https://example.com/1".to_string());
+        draft.metadata.published_at = Some(chrono::Utc::now());
+        draft.metadata.category = vec!["rust".to_string()];
+        draft.content = "Hello".to_string();
+
+        let draft = restored(draft);
+        assert_eq!(draft.metadata.status, None);
+        assert_eq!(draft.metadata.url, None);
+        assert_eq!(draft.metadata.published_at, None);
+        assert_eq!(draft.metadata.category, ["rust"]);
+        assert_eq!(draft.content, "Hello");
+    }
+}
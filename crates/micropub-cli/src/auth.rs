@@ -0,0 +1,719 @@
+// ABOUTME: Authentication and OAuth flow handling
+// ABOUTME: Performs IndieAuth discovery and token management (browser sign-in is in oauth.rs)
+
+use anyhow::{Context, Result};
+use reqwest::Client as HttpClient;
+use scraper::{Html, Selector};
+use url::Url;
+
+use crate::config::{
+    load_token, load_token_metadata, save_token, Config, EndpointOverrides, Profile, TokenMetadata,
+};
+
+/// Endpoints advertised by a site, any of which may be missing
+pub(crate) struct DiscoveredEndpoints {
+    pub(crate) micropub: Option<String>,
+    pub(crate) authorization: Option<String>,
+    pub(crate) token: Option<String>,
+}
+
+/// Fetch a site and collect its micropub and IndieAuth endpoint links
+async fn discover_links(domain: &str) -> Result<DiscoveredEndpoints> {
+    // Check if this is a localhost/development domain
+    let is_localhost = domain.starts_with("localhost")
+        || domain.starts_with("127.0.0.1")
+        || domain.starts_with("::1")
+        || domain.starts_with("[::1]")
+        || domain.starts_with("http://localhost")
+        || domain.starts_with("http://127.0.0.1")
+        || domain.starts_with("http://::1")
+        || domain.starts_with("http://[::1]")
+        || domain.starts_with("https://localhost")
+        || domain.starts_with("https://127.0.0.1")
+        || domain.starts_with("https://::1")
+        || domain.starts_with("https://[::1]");
+
+    // Enforce HTTPS for security (except localhost for development)
+    let url = if domain.starts_with("https://") {
+        domain.to_string()
+    } else if domain.starts_with("http://") {
+        if is_localhost {
+            domain.to_string() // Allow HTTP for localhost
+        } else {
+            anyhow::bail!(
+                "Insecure HTTP not allowed. Please use HTTPS: {}",
+                domain.replace("http://", "https://")
+            );
+        }
+    } else {
+        // No scheme provided
+        if is_localhost {
+            format!("http://{}", domain) // Default to HTTP for localhost
+        } else {
+            format!("https://{}", domain) // Default to HTTPS for remote
+        }
+    };
+
+    let client = HttpClient::new();
+    let response = client.get(&url).send().await?;
+
+    // Use final URL after redirects for resolving relative links
+    let final_url = response.url().to_string();
+
+    // Validate that redirects didn't downgrade us from HTTPS to HTTP (except localhost)
+    let final_url_parsed = Url::parse(&final_url)?;
+    if final_url_parsed.scheme() != "https" && !is_localhost {
+        anyhow::bail!(
+            "Security error: Server redirected to insecure HTTP ({}). This is not allowed.",
+            final_url
+        );
+    }
+
+    let mut micropub_endpoint = None;
+    let mut authorization_endpoint = None;
+    let mut token_endpoint = None;
+
+    // First, check HTTP Link headers (preferred by spec)
+    for link_header in response.headers().get_all("link") {
+        if let Ok(link_str) = link_header.to_str() {
+            // Parse Link header format: <url>; rel="relation"
+            for link in link_str.split(',') {
+                let parts: Vec<&str> = link.split(';').collect();
+                if parts.len() < 2 {
+                    continue;
+                }
+
+                // Extract URL (remove < and >)
+                let url_part = parts[0].trim();
+                let endpoint_url = url_part.trim_start_matches('<').trim_end_matches('>');
+
+                // Extract rel value
+                for param in &parts[1..] {
+                    if let Some(rel_value) = param.trim().strip_prefix("rel=") {
+                        let rel = rel_value.trim_matches('"').trim_matches('\'');
+
+                        let resolved = resolve_url(&final_url, endpoint_url)?;
+                        match rel {
+                            "micropub" => micropub_endpoint = Some(resolved),
+                            "authorization_endpoint" => authorization_endpoint = Some(resolved),
+                            "token_endpoint" => token_endpoint = Some(resolved),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let html = response.text().await?;
+
+    // Then check HTML <link> tags (fallback)
+    let document = Html::parse_document(&html);
+    let link_selector = Selector::parse("link[rel]").unwrap();
+
+    for element in document.select(&link_selector) {
+        let rel = element.value().attr("rel");
+        let href = element.value().attr("href");
+
+        match (rel, href) {
+            (Some("micropub"), Some(href)) if micropub_endpoint.is_none() => {
+                micropub_endpoint = Some(resolve_url(&final_url, href)?);
+            }
+            (Some("authorization_endpoint"), Some(href)) if authorization_endpoint.is_none() => {
+                authorization_endpoint = Some(resolve_url(&final_url, href)?);
+            }
+            (Some("token_endpoint"), Some(href)) if token_endpoint.is_none() => {
+                token_endpoint = Some(resolve_url(&final_url, href)?);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(DiscoveredEndpoints {
+        micropub: micropub_endpoint,
+        authorization: authorization_endpoint,
+        token: token_endpoint,
+    })
+}
+
+/// Discover endpoints, letting overrides win and skipping the fetch if they cover everything
+pub(crate) async fn discover_with_overrides(
+    domain: &str,
+    overrides: &EndpointOverrides,
+) -> Result<DiscoveredEndpoints> {
+    let found = if overrides.micropub.is_some()
+        && overrides.authorization.is_some()
+        && overrides.token.is_some()
+    {
+        DiscoveredEndpoints {
+            micropub: None,
+            authorization: None,
+            token: None,
+        }
+    } else {
+        discover_links(domain).await?
+    };
+
+    Ok(DiscoveredEndpoints {
+        micropub: overrides.micropub.clone().or(found.micropub),
+        authorization: overrides.authorization.clone().or(found.authorization),
+        token: overrides.token.clone().or(found.token),
+    })
+}
+
+/// Resolve a potentially relative URL
+fn resolve_url(base: &str, href: &str) -> Result<String> {
+    let base_url = Url::parse(base)?;
+    let resolved = base_url.join(href)?;
+    Ok(resolved.to_string())
+}
+
+/// Discover media endpoint from micropub endpoint
+async fn discover_media_endpoint(micropub_endpoint: &str, token: &str) -> Result<Option<String>> {
+    let client = HttpClient::new();
+    let response = client
+        .get(format!("{}?q=config", micropub_endpoint))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .context("Failed to query micropub config endpoint for media discovery")?;
+
+    if response.status().is_success() {
+        let config: serde_json::Value = response
+            .json()
+            .await
+            .context("Failed to parse micropub config response")?;
+        if let Some(media) = config.get("media-endpoint") {
+            if let Some(media_str) = media.as_str() {
+                return Ok(Some(media_str.to_string()));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// An access token plus any refresh details the token endpoint issued
+pub struct TokenGrant {
+    pub access_token: String,
+    pub metadata: TokenMetadata,
+}
+
+/// Pull the access token, refresh token, and expiry out of a token response
+pub fn parse_token_response(response: &serde_json::Value) -> Result<TokenGrant> {
+    let access_token = response
+        .get("access_token")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .context("No access_token in response")?;
+
+    let refresh_token = response
+        .get("refresh_token")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    // Some servers send expires_in as a string
+    let expires_in = response.get("expires_in").and_then(|v| {
+        v.as_i64()
+            .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+    });
+
+    Ok(TokenGrant {
+        access_token,
+        metadata: TokenMetadata::from_response(refresh_token, expires_in),
+    })
+}
+
+/// POST a grant to the token endpoint and parse the result
+pub(crate) async fn request_token(
+    token_endpoint: &str,
+    params: &[(&str, &str)],
+) -> Result<TokenGrant> {
+    let _turn = crate::polite::turn(token_endpoint).await;
+    let response = HttpClient::new()
+        .post(token_endpoint)
+        .header("Accept", "application/json")
+        .form(params)
+        .send()
+        .await
+        .context("Failed to reach token endpoint")?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| String::from("<unable to read response>"));
+        anyhow::bail!("Token request failed with status {}: {}", status, body);
+    }
+
+    let token_response: serde_json::Value = response
+        .json()
+        .await
+        .context("Failed to parse token response")?;
+
+    parse_token_response(&token_response)
+}
+
+/// Check a token against the micropub endpoint before saving it
+pub(crate) async fn validate_token(micropub_endpoint: &str, token: &str) -> Result<()> {
+    println!("\nValidating token...");
+    let client = HttpClient::new();
+    let validation_response = tokio::time::timeout(
+        tokio::time::Duration::from_secs(10),
+        client
+            .get(format!("{}?q=config", micropub_endpoint))
+            .header("Authorization", format!("Bearer {}", token))
+            .send(),
+    )
+    .await
+    .context("Timeout validating token (10 seconds) - micropub endpoint did not respond")??;
+
+    match validation_response.status() {
+        // Success - token is valid
+        status if status.is_success() => {
+            println!("✓ Token validated");
+        }
+        // Token is actually invalid
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+            anyhow::bail!(
+                "Token validation failed - the token was rejected (status {}). The authorization server may have issued an invalid token.",
+                validation_response.status()
+            );
+        }
+        // Rate limited - token is probably valid, just can't verify right now
+        reqwest::StatusCode::TOO_MANY_REQUESTS => {
+            println!("⚠ Warning: Rate limited during token validation (status 429). Saving token anyway.");
+            println!("  The token is likely valid but couldn't be verified due to rate limiting.");
+        }
+        // Server error - don't reject token due to temporary issues
+        status if status.is_server_error() => {
+            println!("⚠ Warning: Micropub endpoint returned server error (status {}). Saving token anyway.", status);
+            println!("  The token is likely valid but couldn't be verified due to server issues.");
+        }
+        // Other client errors
+        status => {
+            let body = validation_response
+                .text()
+                .await
+                .unwrap_or_else(|_| String::from("<unable to read response>"));
+            anyhow::bail!(
+                "Token validation failed with unexpected status {}: {}",
+                status,
+                body
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Profile name derived from a domain (host, plus port when present)
+fn profile_name_for_domain(domain: &str) -> Result<String> {
+    if domain.starts_with("http://") || domain.starts_with("https://") {
+        let parsed = Url::parse(domain)?;
+        let host = parsed.host_str().context("Invalid domain: missing host")?;
+
+        // Include port in profile name if present
+        Ok(match parsed.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        })
+    } else {
+        Ok(domain.to_string())
+    }
+}
+
+/// Discover the media endpoint, reporting (but not failing on) problems
+async fn discover_media(micropub_endpoint: &str, token: &str) -> Option<String> {
+    println!("\nDiscovering media endpoint...");
+    match discover_media_endpoint(micropub_endpoint, token).await {
+        Ok(endpoint) => {
+            if let Some(ref media) = endpoint {
+                println!("✓ Found media endpoint: {}", media);
+            } else {
+                println!("⚠ No media endpoint found");
+            }
+            endpoint
+        }
+        Err(e) => {
+            println!("⚠ Could not discover media endpoint: {}", e);
+            None
+        }
+    }
+}
+
+/// The profile to save after signing in: the domain and endpoints just found,
+/// with everything else (license, syndication targets, transforms, ...) kept
+/// from an existing profile
+fn signed_in_profile(existing: Option<&Profile>, found: Profile) -> Profile {
+    match existing {
+        Some(existing) => Profile {
+            domain: found.domain,
+            micropub_endpoint: found.micropub_endpoint,
+            media_endpoint: found.media_endpoint,
+            token_endpoint: found.token_endpoint,
+            authorization_endpoint: found.authorization_endpoint,
+            endpoint_overrides: found.endpoint_overrides,
+            ..existing.clone()
+        },
+        None => found,
+    }
+}
+
+/// Store a validated token and its profile, making it the default
+pub(crate) async fn save_credentials(
+    config: &mut Config,
+    grant: &TokenGrant,
+    mut profile: Profile,
+) -> Result<()> {
+    let profile_name = profile_name_for_domain(&profile.domain)?;
+    let token = grant.access_token.as_str();
+
+    // Save token immediately after obtaining it
+    save_token(&profile_name, token, &grant.metadata)?;
+
+    println!("✓ Token saved");
+
+    // Cached server config may belong to a previous token or endpoint
+    crate::server_config::invalidate(&profile_name)?;
+
+    // Now discover media endpoint unless one was given (non-fatal if it fails)
+    if profile.media_endpoint.is_none() {
+        if let Some(ref micropub_endpoint) = profile.micropub_endpoint {
+            profile.media_endpoint = discover_media(micropub_endpoint, token).await;
+        }
+    }
+
+    // Save profile configuration
+    let profile = signed_in_profile(config.get_profile(&profile_name), profile);
+    config.upsert_profile(profile_name.clone(), profile);
+
+    // Always set this profile as default when authenticating
+    config.default_profile = profile_name.clone();
+
+    config.save()?;
+
+    println!(
+        "\n✓ Authentication configured for profile: {}",
+        profile_name
+    );
+
+    Ok(())
+}
+
+/// Check endpoint overrides are absolute HTTPS URLs (HTTP allowed for localhost)
+fn validate_overrides(overrides: &EndpointOverrides) -> Result<()> {
+    for endpoint in [
+        &overrides.micropub,
+        &overrides.media,
+        &overrides.authorization,
+        &overrides.token,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        validate_endpoint_url(endpoint)?;
+    }
+    Ok(())
+}
+
+/// Flags given on this run, plus any overrides saved on the profile earlier
+pub(crate) fn merge_overrides(
+    config: &Config,
+    domain: &str,
+    overrides: EndpointOverrides,
+) -> Result<EndpointOverrides> {
+    validate_overrides(&overrides)?;
+    let profile_name = profile_name_for_domain(domain)?;
+    Ok(match config.get_profile(&profile_name) {
+        Some(profile) => overrides.or(&profile.endpoint_overrides),
+        None => overrides,
+    })
+}
+
+#[cfg(feature = "auth-server")]
+pub use crate::oauth::cmd_auth;
+
+/// Browser sign-in needs the callback server, which library builds can leave out
+#[cfg(not(feature = "auth-server"))]
+pub async fn cmd_auth(
+    _domain: &str,
+    _scope: Option<&str>,
+    _overrides: EndpointOverrides,
+) -> Result<()> {
+    anyhow::bail!(
+        "This build has no browser sign-in (the `auth-server` feature is off). \
+         Use `micropub auth <domain> --token <token>` instead"
+    )
+}
+
+/// Prompt on the terminal for an endpoint URL; empty input returns `None`
+fn prompt_for_endpoint(label: &str) -> Result<Option<String>> {
+    use std::io::{self, Write};
+
+    if !crate::prompt::can_prompt() {
+        return Ok(None);
+    }
+
+    print!("{}: ", label);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim();
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    validate_endpoint_url(input).map(Some)
+}
+
+/// Parse an endpoint URL, requiring HTTPS unless it points at localhost
+fn validate_endpoint_url(input: &str) -> Result<String> {
+    let url = Url::parse(input).with_context(|| format!("Invalid URL: {}", input))?;
+    let is_localhost = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    if url.scheme() != "https" && !is_localhost {
+        anyhow::bail!("Endpoint must use HTTPS: {}", input);
+    }
+
+    Ok(url.to_string())
+}
+
+/// Set up a profile from a manually issued token, skipping the OAuth flow
+pub async fn cmd_auth_with_token(
+    domain: &str,
+    token: &str,
+    overrides: EndpointOverrides,
+) -> Result<()> {
+    let token = token.trim();
+    if token.is_empty() {
+        anyhow::bail!("Token cannot be empty");
+    }
+
+    let mut config = Config::load()?;
+    let overrides = merge_overrides(&config, domain, overrides)?;
+
+    println!("Discovering endpoints for {}...", domain);
+    let found = match discover_with_overrides(domain, &overrides).await {
+        Ok(found) => found,
+        Err(e) => {
+            println!("⚠ Could not discover endpoints: {}", e);
+            DiscoveredEndpoints {
+                micropub: overrides.micropub.clone(),
+                authorization: overrides.authorization.clone(),
+                token: overrides.token.clone(),
+            }
+        }
+    };
+
+    let micropub_endpoint = match found.micropub {
+        Some(endpoint) => {
+            println!("✓ Found micropub endpoint: {}", endpoint);
+            endpoint
+        }
+        None => prompt_for_endpoint("Micropub endpoint URL")?
+            .context("No micropub endpoint found; run interactively to enter one")?,
+    };
+
+    validate_token(&micropub_endpoint, token).await?;
+
+    // Fall back to asking for the media endpoint if the server doesn't advertise one
+    let media_endpoint = match overrides.media.clone() {
+        Some(media) => Some(media),
+        None => match discover_media(&micropub_endpoint, token).await {
+            Some(media) => Some(media),
+            None => prompt_for_endpoint("Media endpoint URL (Enter to skip)")?,
+        },
+    };
+
+    let grant = TokenGrant {
+        access_token: token.to_string(),
+        metadata: TokenMetadata::default(),
+    };
+
+    let profile = Profile {
+        domain: domain.to_string(),
+        micropub_endpoint: Some(micropub_endpoint),
+        media_endpoint,
+        token_endpoint: found.token,
+        authorization_endpoint: found.authorization,
+        transforms: Vec::new(),
+        endpoint_overrides: overrides,
+        request_format: Default::default(),
+        syndicate_to: Vec::new(),
+        polite: false,
+        license: None,
+    };
+    save_credentials(&mut config, &grant, profile).await
+}
+
+/// Trade a profile's refresh token for a new access token and store it
+pub async fn refresh_access_token(profile_name: &str) -> Result<String> {
+    let config = Config::load()?;
+    let profile = config
+        .get_profile(profile_name)
+        .with_context(|| format!("Profile not found: {}", profile_name))?;
+    let token_endpoint = profile
+        .token_endpoint
+        .as_deref()
+        .context("Profile has no token endpoint; re-authenticate with: micropub auth <domain>")?;
+
+    let metadata = load_token_metadata(profile_name);
+    let refresh_token = metadata.refresh_token.as_deref().context(
+        "No refresh token stored for this profile; re-authenticate with: micropub auth <domain>",
+    )?;
+
+    let client_id = config
+        .client_id
+        .as_deref()
+        .unwrap_or("https://github.com/harperreed/micropub");
+    let params = [
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("client_id", client_id),
+    ];
+
+    let mut grant = request_token(token_endpoint, &params)
+        .await
+        .context("Failed to refresh access token")?;
+
+    // Servers that don't rotate refresh tokens omit it from the response
+    if grant.metadata.refresh_token.is_none() {
+        grant.metadata.refresh_token = metadata.refresh_token.clone();
+    }
+
+    save_token(profile_name, &grant.access_token, &grant.metadata)?;
+    crate::server_config::invalidate(profile_name)?;
+
+    Ok(grant.access_token)
+}
+
+/// Load a profile's token, refreshing it first if it has expired
+pub async fn load_fresh_token(profile_name: &str) -> Result<String> {
+    let token = load_token(profile_name)?;
+    let metadata = load_token_metadata(profile_name);
+
+    if !metadata.is_expired(chrono::Utc::now()) {
+        return Ok(token);
+    }
+
+    if metadata.refresh_token.is_none() {
+        anyhow::bail!(
+            "Token for profile '{}' has expired. Re-authenticate with: micropub auth <domain>",
+            profile_name
+        );
+    }
+
+    refresh_access_token(profile_name).await.with_context(|| {
+        format!(
+            "Token for profile '{}' has expired and could not be refreshed",
+            profile_name
+        )
+    })
+}
+
+/// Refresh a profile's access token on demand
+pub async fn cmd_auth_refresh(profile_name: Option<&str>) -> Result<()> {
+    let config = Config::load()?;
+    let profile_name = match profile_name {
+        Some(name) => name.to_string(),
+        None => config.active_profile()?.0.to_string(),
+    };
+
+    println!("Refreshing token for {}...", profile_name);
+    refresh_access_token(&profile_name).await?;
+
+    match load_token_metadata(&profile_name).expires_at {
+        Some(expires_at) => println!(
+            "✓ Token refreshed (expires {})",
+            expires_at.format("%Y-%m-%d %H:%M UTC")
+        ),
+        None => println!("✓ Token refreshed"),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_name_for_domain() {
+        assert_eq!(
+            profile_name_for_domain("example.com").unwrap(),
+            "example.com"
+        );
+        assert_eq!(
+            profile_name_for_domain("https://example.com/blog").unwrap(),
+            "example.com"
+        );
+        assert_eq!(
+            profile_name_for_domain("http://localhost:8080").unwrap(),
+            "localhost:8080"
+        );
+    }
+
+    #[test]
+    fn test_signed_in_profile_keeps_existing_settings() {
+        let existing = Profile {
+            domain: "example.com".to_string(),
+            micropub_endpoint: Some("https://example.com/old".to_string()),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            polite: true,
+            ..Default::default()
+        };
+        let found = Profile {
+            domain: "example.com".to_string(),
+            micropub_endpoint: Some("https://example.com/micropub".to_string()),
+            ..Default::default()
+        };
+
+        let profile = signed_in_profile(Some(&existing), found.clone());
+        assert_eq!(
+            profile.micropub_endpoint.as_deref(),
+            Some("https://example.com/micropub")
+        );
+        assert_eq!(profile.license, existing.license);
+        assert!(profile.polite);
+
+        assert_eq!(signed_in_profile(None, found).license, None);
+    }
+
+    #[test]
+    fn test_validate_endpoint_url() {
+        assert_eq!(
+            validate_endpoint_url("https://example.com/micropub").unwrap(),
+            "https://example.com/micropub"
+        );
+        assert!(validate_endpoint_url("http://localhost:3000/micropub").is_ok());
+        assert!(validate_endpoint_url("http://example.com/micropub").is_err());
+        assert!(validate_endpoint_url("/micropub").is_err());
+    }
+
+    #[test]
+    fn test_parse_token_response() {
+        let grant = parse_token_response(&serde_json::json!({
+            "access_token": "abc",
+            "refresh_token": "def",
+            "expires_in": 3600,
+        }))
+        .unwrap();
+        assert_eq!(grant.access_token, "abc");
+        assert_eq!(grant.metadata.refresh_token.as_deref(), Some("def"));
+        assert!(grant.metadata.expires_at.is_some());
+
+        let grant = parse_token_response(&serde_json::json!({
+            "access_token": "abc",
+            "expires_in": "60",
+        }))
+        .unwrap();
+        assert!(grant.metadata.refresh_token.is_none());
+        assert!(grant.metadata.expires_at.is_some());
+
+        let grant = parse_token_response(&serde_json::json!({"access_token": "abc"})).unwrap();
+        assert_eq!(grant.metadata, TokenMetadata::default());
+
+        assert!(parse_token_response(&serde_json::json!({"error": "invalid_grant"})).is_err());
+    }
+}
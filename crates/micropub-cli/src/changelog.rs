@@ -0,0 +1,91 @@
+// ABOUTME: Publishes a version's section of a Keep a Changelog file as an article
+// ABOUTME: Meant for CI release jobs using MICROPUB_ENDPOINT/MICROPUB_TOKEN
+
+use anyhow::{Context, Result};
+use std::fs;
+
+use crate::draft::{generate_draft_id, Draft};
+use crate::publish::{cmd_publish, cmd_publish_dry_run, DraftEdits};
+
+/// Version from a changelog heading like `## [1.2.0] - 2024-01-15` or `## v1.2.0`
+fn heading_version(line: &str) -> Option<&str> {
+    let rest = line.strip_prefix("## ")?.trim_start();
+    let version = match rest.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next()?,
+        None => rest.split_whitespace().next()?,
+    };
+    Some(version.trim_start_matches('v'))
+}
+
+/// The body of the `## ` section for `version` (with or without a leading `v`)
+pub fn extract_section(changelog: &str, version: &str) -> Option<String> {
+    let version = version.trim_start_matches('v');
+    let mut lines = changelog.lines();
+
+    lines.find(|line| heading_version(line) == Some(version))?;
+    let section: Vec<&str> = lines.take_while(|line| !line.starts_with("## ")).collect();
+
+    let section = section.join("\n").trim().to_string();
+    (!section.is_empty()).then_some(section)
+}
+
+pub async fn cmd_post_changelog(
+    path: &str,
+    version: &str,
+    title: Option<&str>,
+    categories: &[String],
+    dry_run: bool,
+) -> Result<()> {
+    let changelog =
+        fs::read_to_string(path).with_context(|| format!("Failed to read changelog: {}", path))?;
+    let section = extract_section(&changelog, version)
+        .with_context(|| format!("No entries for version {} in {}", version, path))?;
+
+    let mut draft = Draft::new(generate_draft_id());
+    draft.metadata.post_type = "article".to_string();
+    draft.metadata.name = Some(
+        title
+            .map(String::from)
+            .unwrap_or_else(|| format!("Release {}", version)),
+    );
+    draft.metadata.category = categories.to_vec();
+    draft.content = section;
+
+    let draft_path = draft.save()?;
+    let draft_path = draft_path.to_string_lossy();
+
+    if dry_run {
+        let result = cmd_publish_dry_run(&draft_path, None, &DraftEdits::default()).await;
+        fs::remove_file(draft_path.as_ref()).context("Failed to remove dry-run draft")?;
+        return result;
+    }
+
+    let result = cmd_publish(&draft_path, None, false).await?;
+    if crate::output::json() {
+        crate::output::print_json(&result)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog\n\n\
+        ## [Unreleased]\n\n### Added\n- Work in progress\n\n\
+        ## [1.2.0] - 2024-01-15\n\n### Added\n- Scheduling\n\n### Fixed\n- A crash\n\n\
+        ## v1.1.0\n\n- First release\n";
+
+    #[test]
+    fn test_extract_section() {
+        assert_eq!(
+            extract_section(CHANGELOG, "v1.2.0").as_deref(),
+            Some("### Added\n- Scheduling\n\n### Fixed\n- A crash")
+        );
+        assert_eq!(
+            extract_section(CHANGELOG, "1.1.0").as_deref(),
+            Some("- First release")
+        );
+        assert_eq!(extract_section(CHANGELOG, "2.0.0"), None);
+    }
+}
@@ -0,0 +1,107 @@
+// ABOUTME: Machine mode for CI runners (`--ci`): no prompts, browsers, or editors
+// ABOUTME: Reports errors/warnings as GitHub Actions annotations and writes step outputs
+
+use anyhow::{bail, Context, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static CI: AtomicBool = AtomicBool::new(false);
+
+/// Run in CI mode for the rest of the process (`--ci`)
+pub fn set_ci(ci: bool) {
+    CI.store(ci, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    CI.load(Ordering::Relaxed)
+}
+
+/// Fail instead of doing something that needs a person at the keyboard
+pub fn ensure_interactive(what: &str) -> Result<()> {
+    if enabled() {
+        bail!("{} isn't available in --ci mode", what);
+    }
+    Ok(())
+}
+
+/// A workflow command like `::error::message`, with the message escaped
+pub fn annotation(level: &str, message: &str) -> String {
+    let message = message
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    format!("::{}::{}", level, message)
+}
+
+/// Report a fatal error (annotations go to stderr so `--json` stdout stays clean)
+pub fn error(message: &str) {
+    eprintln!("{}", annotation("error", message));
+}
+
+/// Report a non-fatal problem: an annotation in CI mode, `⚠` otherwise
+pub fn warning(message: &str) {
+    if enabled() {
+        eprintln!("{}", annotation("warning", message));
+    } else {
+        crate::progress!("⚠ {}", message);
+    }
+}
+
+/// One `name=value` entry in $GITHUB_OUTPUT format, using a delimiter for multi-line values
+pub fn output_entry(name: &str, value: &str) -> String {
+    if value.contains('\n') {
+        let delimiter = format!("ghadelimiter_{}", uuid::Uuid::new_v4());
+        format!("{name}<<{delimiter}\n{value}\n{delimiter}\n")
+    } else {
+        format!("{name}={value}\n")
+    }
+}
+
+/// Set a step output in CI mode when $GITHUB_OUTPUT is present; otherwise do nothing
+pub fn set_output(name: &str, value: &str) -> Result<()> {
+    if !enabled() {
+        return Ok(());
+    }
+    let Some(path) = std::env::var_os("GITHUB_OUTPUT") else {
+        return Ok(());
+    };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context("Failed to open $GITHUB_OUTPUT")?;
+    file.write_all(output_entry(name, value).as_bytes())
+        .context("Failed to write $GITHUB_OUTPUT")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotation_escapes_message() {
+        assert_eq!(
+            annotation("error", "100% broken\nsee log"),
+            "::error::100%25 broken%0Asee log"
+        );
+    }
+
+    #[test]
+    fn test_output_entry() {
+        assert_eq!(
+            output_entry("url", "https://example.com/1"),
+            "url=https://example.com/1\n"
+        );
+
+        let entry = output_entry("notes", "a\nb");
+        let delimiter = entry
+            .lines()
+            .next()
+            .unwrap()
+            .strip_prefix("notes<<")
+            .unwrap();
+        assert_eq!(entry, format!("notes<<{delimiter}\na\nb\n{delimiter}\n"));
+    }
+}
@@ -0,0 +1,71 @@
+// ABOUTME: Copies text to the system clipboard via the platform's own tools
+// ABOUTME: pbcopy on macOS, clip on Windows, wl-copy, xclip, or xsel on Linux and BSD
+
+use anyhow::{bail, Context, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Clipboard tools worth trying, best first, given which display servers are running
+fn tools(wayland: bool, x11: bool) -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        return vec![("pbcopy", &[])];
+    }
+    if cfg!(windows) {
+        return vec![("clip", &[])];
+    }
+
+    let mut tools: Vec<(&str, &[&str])> = Vec::new();
+    if wayland {
+        tools.push(("wl-copy", &[]));
+    }
+    if x11 {
+        tools.push(("xclip", &["-selection", "clipboard"]));
+        tools.push(("xsel", &["--clipboard", "--input"]));
+    }
+    tools
+}
+
+fn run(program: &str, args: &[&str], text: &str) -> Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run `{}`", program))?;
+    child
+        .stdin
+        .take()
+        .context("Failed to open clipboard tool input")?
+        .write_all(text.as_bytes())?;
+    if !child.wait()?.success() {
+        bail!("`{}` failed", program);
+    }
+    Ok(())
+}
+
+/// Put `text` on the clipboard; fails when no clipboard tool is available
+pub fn copy(text: &str) -> Result<()> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let x11 = std::env::var_os("DISPLAY").is_some();
+    for (program, args) in tools(wayland, x11) {
+        if run(program, args, text).is_ok() {
+            return Ok(());
+        }
+    }
+    bail!("No clipboard available")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn test_tools_follow_display_servers() {
+        assert!(tools(false, false).is_empty());
+        assert_eq!(tools(true, false)[0].0, "wl-copy");
+        let names: Vec<_> = tools(true, true).iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["wl-copy", "xclip", "xsel"]);
+    }
+}
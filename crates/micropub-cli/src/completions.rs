@@ -0,0 +1,313 @@
+// ABOUTME: `micropub completions <shell>`: clap_complete scripts for the command tree
+// ABOUTME: plus a hook that asks the hidden `__complete` for draft IDs and profile names at Tab time
+
+use anyhow::{bail, Result};
+use clap::{Command, ValueHint};
+use clap_complete::Shell;
+use std::fmt::Write;
+
+/// Values that change between runs, so the scripts ask `micropub __complete` for them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dynamic {
+    Drafts,
+    Profiles,
+}
+
+impl Dynamic {
+    pub fn parse(kind: &str) -> Result<Self> {
+        match kind {
+            "drafts" => Ok(Dynamic::Drafts),
+            "profiles" => Ok(Dynamic::Profiles),
+            _ => bail!("Unknown completion list '{}'", kind),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Dynamic::Drafts => "drafts",
+            Dynamic::Profiles => "profiles",
+        }
+    }
+
+    /// Current values, sorted
+    pub fn candidates(self) -> Result<Vec<String>> {
+        let mut values = match self {
+            Dynamic::Drafts => crate::draft::Draft::list_all()?,
+            Dynamic::Profiles => crate::config::Config::load()?
+                .profiles
+                .into_keys()
+                .collect(),
+        };
+        values.sort();
+        values.dedup();
+        Ok(values)
+    }
+}
+
+/// A subcommand whose first positional is a draft ID or profile name
+struct Hooked {
+    /// e.g. `["draft", "edit"]`
+    path: Vec<String>,
+    /// Its own subcommands, which win over the positional
+    children: Vec<String>,
+    kind: Dynamic,
+}
+
+fn hooked_commands(cmd: &Command) -> Vec<Hooked> {
+    let mut found = Vec::new();
+    collect_hooked(cmd, &[], &mut found);
+    found
+}
+
+fn collect_hooked(cmd: &Command, path: &[String], found: &mut Vec<Hooked>) {
+    for sub in cmd.get_subcommands().filter(|c| !c.is_hide_set()) {
+        let mut sub_path = path.to_vec();
+        sub_path.push(sub.get_name().to_string());
+        let kind = sub.get_positionals().next().and_then(|arg| {
+            if arg.get_value_hint() == ValueHint::FilePath {
+                return None;
+            }
+            match arg.get_id().as_str() {
+                "draft" | "draft_id" => Some(Dynamic::Drafts),
+                "profile" | "profile_name" => Some(Dynamic::Profiles),
+                _ => None,
+            }
+        });
+        if let Some(kind) = kind {
+            found.push(Hooked {
+                path: sub_path.clone(),
+                children: sub
+                    .get_subcommands()
+                    .map(|c| c.get_name().to_string())
+                    .collect(),
+                kind,
+            });
+        }
+        collect_hooked(sub, &sub_path, found);
+    }
+}
+
+/// Escape for a single-quoted bash or fish string
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\\', r"\\").replace('\'', r"'\''"))
+}
+
+/// Wrap clap_complete's `_<bin>` so `--profile` and draft or profile
+/// arguments complete from `__complete`
+fn bash_hook(bin: &str, hooked: &[Hooked]) -> String {
+    let func = format!("_{}_dynamic", bin.replace('-', "__"));
+    let mut prefixes: Vec<String> = hooked
+        .iter()
+        .flat_map(|h| {
+            let path = h.path.join(" ");
+            (1..h.path.len())
+                .map(|n| h.path[..n].join(" "))
+                .chain(h.children.iter().map(move |c| format!("{} {}", path, c)))
+                .chain([h.path.join(" ")])
+        })
+        .collect();
+    prefixes.sort();
+    prefixes.dedup();
+    let labels = |paths: Vec<String>| {
+        paths
+            .iter()
+            .map(|p| quote(&format!("{} ", p)))
+            .collect::<Vec<_>>()
+            .join("|")
+    };
+    let reply = |kind: Dynamic| {
+        format!(
+            "COMPREPLY=($(compgen -W \"$({} __complete {} 2>/dev/null)\" -- \"$cur\"))",
+            bin,
+            kind.name()
+        )
+    };
+
+    let mut out = String::new();
+    let _ = writeln!(out, "\n{}() {{", func);
+    let _ = writeln!(out, "    _{} \"$@\"", bin.replace('-', "__"));
+    out.push_str("    local cur=\"$2\" prev=\"$3\" path=\"\" word\n");
+    out.push_str("    if [[ \"$prev\" == --profile ]]; then\n");
+    let _ = writeln!(out, "        {}", reply(Dynamic::Profiles));
+    out.push_str("        return 0\n    fi\n");
+    if hooked.is_empty() {
+        out.push_str("}\n");
+    } else {
+        out.push_str("    [[ \"$cur\" == -* ]] && return 0\n");
+        out.push_str("    for word in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n");
+        out.push_str("        case \"$path$word \" in\n");
+        let _ = writeln!(
+            out,
+            "            {}) path=\"$path$word \" ;;",
+            labels(prefixes)
+        );
+        out.push_str("        esac\n    done\n");
+        out.push_str("    case \"$path\" in\n");
+        for kind in [Dynamic::Drafts, Dynamic::Profiles] {
+            let paths: Vec<String> = hooked
+                .iter()
+                .filter(|h| h.kind == kind)
+                .map(|h| h.path.join(" "))
+                .collect();
+            if !paths.is_empty() {
+                let _ = writeln!(out, "        {}) {} ;;", labels(paths), reply(kind));
+            }
+        }
+        out.push_str("    esac\n}\n");
+    }
+    let _ = writeln!(
+        out,
+        "complete -F {} -o bashdefault -o default {}",
+        func, bin
+    );
+    out
+}
+
+/// Extra `complete` lines; fish merges them with clap_complete's own
+fn fish_hook(bin: &str, hooked: &[Hooked]) -> String {
+    let using = format!("__fish_{}_using_subcommand", bin.replace('-', "_"));
+    let values =
+        |kind: Dynamic| quote(&format!("({} __complete {} 2>/dev/null)", bin, kind.name()));
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "complete -c {} -l profile -r -f -a {}",
+        bin,
+        values(Dynamic::Profiles)
+    );
+    for hooked in hooked {
+        let mut condition = format!("{} {}", using, hooked.path[0]);
+        for word in &hooked.path[1..] {
+            let _ = write!(condition, "; and __fish_seen_subcommand_from {}", word);
+        }
+        if !hooked.children.is_empty() {
+            let _ = write!(
+                condition,
+                "; and not __fish_seen_subcommand_from {}",
+                hooked.children.join(" ")
+            );
+        }
+        let _ = writeln!(
+            out,
+            "complete -c {} -n {} -f -a {}",
+            bin,
+            quote(&condition),
+            values(hooked.kind)
+        );
+    }
+    out
+}
+
+/// The completion script for `shell`. Bash, zsh, and fish also complete draft
+/// IDs and profile names; zsh loads the bash script through bashcompinit.
+pub fn generate(shell: Shell, mut cmd: Command) -> Result<String> {
+    let bin = cmd.get_name().to_string();
+    let hooked = hooked_commands(&cmd);
+    let script = |shell: Shell, cmd: &mut Command| {
+        let mut buf = Vec::new();
+        clap_complete::generate(shell, cmd, &bin, &mut buf);
+        String::from_utf8(buf)
+    };
+
+    Ok(match shell {
+        Shell::Bash => script(Shell::Bash, &mut cmd)? + &bash_hook(&bin, &hooked),
+        Shell::Zsh => format!(
+            "# zsh completion for {bin}; load with: source <({bin} completions zsh)\n\
+             autoload -U +X compinit && compinit\n\
+             autoload -U +X bashcompinit && bashcompinit\n\n{}{}",
+            script(Shell::Bash, &mut cmd)?,
+            bash_hook(&bin, &hooked)
+        ),
+        Shell::Fish => script(Shell::Fish, &mut cmd)? + &fish_hook(&bin, &hooked),
+        other => script(other, &mut cmd)?,
+    })
+}
+
+/// Print the completion script for `shell`
+pub fn cmd_completions(shell: Shell, cmd: Command) -> Result<()> {
+    print!("{}", generate(shell, cmd)?);
+    Ok(())
+}
+
+/// Print the current draft IDs or profiles, one per line.
+/// Failures print nothing, so a broken config never spills errors into the prompt.
+pub fn cmd_complete(kind: &str) -> Result<()> {
+    for value in Dynamic::parse(kind)?.candidates().unwrap_or_default() {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+
+    fn cli() -> Command {
+        Command::new("micropub")
+            .arg(Arg::new("profile").long("profile").global(true))
+            .subcommand(
+                Command::new("draft")
+                    .about("Draft management commands")
+                    .subcommand(Command::new("edit").arg(Arg::new("draft_id").required(true)))
+                    .subcommand(Command::new("new")),
+            )
+            .subcommand(
+                Command::new("publish").arg(Arg::new("draft").value_hint(ValueHint::FilePath)),
+            )
+            .subcommand(
+                Command::new("schedule")
+                    .arg(Arg::new("draft"))
+                    .subcommand(Command::new("list")),
+            )
+            .subcommand(Command::new("debug").arg(Arg::new("profile")))
+    }
+
+    #[test]
+    fn test_hooked_commands() {
+        let hooked: Vec<(String, Dynamic)> = hooked_commands(&cli())
+            .into_iter()
+            .map(|h| (h.path.join(" "), h.kind))
+            .collect();
+        // File paths complete as files, not draft IDs
+        assert_eq!(
+            hooked,
+            [
+                ("draft edit".to_string(), Dynamic::Drafts),
+                ("schedule".to_string(), Dynamic::Drafts),
+                ("debug".to_string(), Dynamic::Profiles),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scripts_complete_dynamic_values() {
+        let bash = generate(Shell::Bash, cli()).unwrap();
+        assert!(bash.starts_with("_micropub() {"));
+        assert!(bash.contains(
+            "'debug '|'draft '|'draft edit '|'schedule '|'schedule list ') path=\"$path$word \" ;;"
+        ));
+        assert!(bash.contains(
+            "'draft edit '|'schedule ') COMPREPLY=($(compgen -W \"$(micropub __complete drafts 2>/dev/null)\""
+        ));
+        assert!(
+            bash.ends_with("complete -F _micropub_dynamic -o bashdefault -o default micropub\n")
+        );
+
+        let zsh = generate(Shell::Zsh, cli()).unwrap();
+        assert!(zsh.contains("bashcompinit"));
+        assert!(zsh.contains("micropub __complete profiles"));
+
+        let fish = generate(Shell::Fish, cli()).unwrap();
+        assert!(fish.contains("function __fish_micropub_using_subcommand"));
+        assert!(fish.contains(
+            "-n '__fish_micropub_using_subcommand draft; and __fish_seen_subcommand_from edit' -f -a '(micropub __complete drafts 2>/dev/null)'"
+        ));
+        assert!(fish.contains(
+            "-n '__fish_micropub_using_subcommand schedule; and not __fish_seen_subcommand_from list'"
+        ));
+
+        let ps = generate(Shell::PowerShell, cli()).unwrap();
+        assert!(ps.contains("Register-ArgumentCompleter"));
+    }
+}
@@ -0,0 +1,274 @@
+// ABOUTME: Token storage for micropub CLI profiles, in token files or the OS credential store
+// ABOUTME: Re-exports micropub-core's configuration so callers keep using crate::config
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+pub use micropub_core::config::*;
+
+fn last_used_path(profile_name: &str) -> Result<PathBuf> {
+    Ok(get_tokens_dir()?.join(format!("{}.last_used", profile_name)))
+}
+
+/// Record that a profile's token was just used
+pub fn record_token_use(profile_name: &str) -> Result<()> {
+    fs::write(last_used_path(profile_name)?, Utc::now().to_rfc3339())
+        .context("Failed to record token usage")
+}
+
+/// When a profile's token was last used, if ever recorded
+pub fn token_last_used(profile_name: &str) -> Option<DateTime<Utc>> {
+    let contents = fs::read_to_string(last_used_path(profile_name).ok()?).ok()?;
+    DateTime::parse_from_rfc3339(contents.trim())
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// Refresh details issued alongside an access token
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TokenMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// Absolute expiry computed from the token response's `expires_in`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Treat tokens this close to expiry as already expired
+const EXPIRY_SKEW_SECS: i64 = 60;
+
+impl TokenMetadata {
+    /// Build metadata from a token response's `refresh_token` and `expires_in`
+    pub fn from_response(refresh_token: Option<String>, expires_in: Option<i64>) -> Self {
+        Self {
+            refresh_token,
+            expires_at: expires_in.map(|secs| Utc::now() + chrono::Duration::seconds(secs)),
+        }
+    }
+
+    /// Whether the token has expired (or is about to) as of `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at
+            .map(|at| at - chrono::Duration::seconds(EXPIRY_SKEW_SECS) <= now)
+            .unwrap_or(false)
+    }
+}
+
+fn token_metadata_path(profile_name: &str) -> Result<PathBuf> {
+    Ok(get_tokens_dir()?.join(format!("{}.meta.json", profile_name)))
+}
+
+/// Write a file readable only by the current user
+fn write_private(path: &std::path::Path, contents: &str) -> Result<()> {
+    crate::draft::lock::write_private(path, contents.as_bytes())
+}
+
+/// Credential store entry holding a profile's refresh details
+fn keychain_meta_account(profile_name: &str) -> String {
+    format!("{}.meta", profile_name)
+}
+
+/// Whether tokens go to the OS credential store: configured and reachable.
+/// Decided once per run, so token loads don't re-read the config or probe the store.
+fn use_keychain() -> bool {
+    static USE_KEYCHAIN: OnceLock<bool> = OnceLock::new();
+
+    *USE_KEYCHAIN.get_or_init(|| {
+        let configured = Config::load()
+            .map(|config| config.token_storage == TokenStorage::Keychain)
+            .unwrap_or(false);
+        if !configured {
+            return false;
+        }
+        if crate::keychain::available() {
+            return true;
+        }
+        eprintln!("⚠ token_storage is \"keychain\" but no credential store is available; using token files");
+        false
+    })
+}
+
+fn save_token_to_keychain(profile_name: &str, token: &str, metadata: &TokenMetadata) -> Result<()> {
+    crate::keychain::set(profile_name, token)?;
+
+    let meta_account = keychain_meta_account(profile_name);
+    if metadata == &TokenMetadata::default() {
+        crate::keychain::delete(&meta_account)
+    } else {
+        let contents =
+            serde_json::to_string(metadata).context("Failed to serialize token metadata")?;
+        crate::keychain::set(&meta_account, &contents)
+    }
+}
+
+/// Remove a profile's token and metadata files, keeping its usage record
+fn remove_token_files(profile_name: &str) -> Result<()> {
+    let tokens_dir = get_tokens_dir()?;
+    for path in [
+        tokens_dir.join(format!("{}.token", profile_name)),
+        token_metadata_path(profile_name)?,
+    ] {
+        if path.exists() {
+            fs::remove_file(&path).context("Failed to remove token file")?;
+        }
+    }
+    Ok(())
+}
+
+/// Move a token file saved before switching to the keychain into the credential store
+fn migrate_token_file(profile_name: &str) -> Result<Option<String>> {
+    let token_path = get_tokens_dir()?.join(format!("{}.token", profile_name));
+    let Ok(token) = fs::read_to_string(&token_path) else {
+        return Ok(None);
+    };
+    let token = token.trim().to_string();
+
+    save_token_to_keychain(profile_name, &token, &load_token_metadata(profile_name))
+        .context("Failed to move token into the credential store")?;
+    remove_token_files(profile_name)?;
+    eprintln!("Moved the {} token into the credential store", profile_name);
+
+    Ok(Some(token))
+}
+
+/// Save a profile's access token and its refresh details
+pub fn save_token(profile_name: &str, token: &str, metadata: &TokenMetadata) -> Result<()> {
+    if use_keychain() {
+        match save_token_to_keychain(profile_name, token, metadata) {
+            Ok(()) => return remove_token_files(profile_name),
+            Err(e) => eprintln!(
+                "⚠ Could not save the token to the credential store ({:#}); using a token file",
+                e
+            ),
+        }
+    }
+
+    let tokens_dir = get_tokens_dir()?;
+    write_private(&tokens_dir.join(format!("{}.token", profile_name)), token)
+        .context("Failed to write token file")?;
+
+    let meta_path = token_metadata_path(profile_name)?;
+    if metadata == &TokenMetadata::default() {
+        // Manually issued tokens have nothing to refresh; drop stale details
+        if meta_path.exists() {
+            fs::remove_file(&meta_path).context("Failed to remove token metadata")?;
+        }
+    } else {
+        let contents =
+            serde_json::to_string_pretty(metadata).context("Failed to serialize token metadata")?;
+        write_private(&meta_path, &contents).context("Failed to write token metadata")?;
+    }
+
+    Ok(())
+}
+
+/// Refresh details for a profile's token, empty if none were issued
+pub fn load_token_metadata(profile_name: &str) -> TokenMetadata {
+    if use_keychain() {
+        let stored = crate::keychain::get(&keychain_meta_account(profile_name))
+            .ok()
+            .flatten()
+            .and_then(|contents| serde_json::from_str(&contents).ok());
+        if let Some(metadata) = stored {
+            return metadata;
+        }
+    }
+
+    token_metadata_path(profile_name)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Delete a profile's token, from the credential store too, and its usage record
+pub fn remove_token(profile_name: &str) -> Result<()> {
+    if use_keychain() {
+        crate::keychain::delete(profile_name)?;
+        crate::keychain::delete(&keychain_meta_account(profile_name))?;
+    }
+
+    remove_token_files(profile_name)?;
+    let last_used = last_used_path(profile_name)?;
+    if last_used.exists() {
+        fs::remove_file(&last_used).context("Failed to remove token file")?;
+    }
+    Ok(())
+}
+
+/// Load authentication token for a profile
+pub fn load_token(profile_name: &str) -> Result<String> {
+    if profile_name == ENV_PROFILE {
+        if let Ok(token) = std::env::var("MICROPUB_TOKEN") {
+            return Ok(token.trim().to_string());
+        }
+    }
+
+    if use_keychain() {
+        let stored = match crate::keychain::get(profile_name)? {
+            Some(token) => Some(token),
+            None => migrate_token_file(profile_name)?,
+        };
+        if let Some(token) = stored {
+            let _ = record_token_use(profile_name);
+            return Ok(token);
+        }
+    }
+
+    let tokens_dir = get_tokens_dir()?;
+    let token_path = tokens_dir.join(format!("{}.token", profile_name));
+
+    let token = fs::read_to_string(&token_path)
+        .context("Token not found. Run 'micropub auth <domain>' to authenticate")?
+        .trim()
+        .to_string();
+
+    if token.is_empty() {
+        anyhow::bail!("Token file is empty. Re-authenticate with: micropub auth <domain>");
+    }
+
+    // Usage tracking is best-effort and must never block a command
+    let _ = record_token_use(profile_name);
+
+    Ok(token)
+}
+
+/// Point `MICROPUB_DATA_DIR` at a temp directory shared by this test process,
+/// so tests never touch real drafts or caches
+#[cfg(test)]
+pub(crate) fn use_temp_data_dir() {
+    static DIR: OnceLock<tempfile::TempDir> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = tempfile::TempDir::new().expect("Failed to create temp data dir");
+        std::env::set_var(DATA_DIR_ENV, dir.path());
+        dir
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_metadata_expiry() {
+        let now = Utc::now();
+        assert!(!TokenMetadata::default().is_expired(now));
+
+        let fresh = TokenMetadata {
+            refresh_token: None,
+            expires_at: Some(now + chrono::Duration::hours(1)),
+        };
+        assert!(!fresh.is_expired(now));
+
+        // Within the skew window counts as expired
+        let expiring = TokenMetadata {
+            refresh_token: None,
+            expires_at: Some(now + chrono::Duration::seconds(30)),
+        };
+        assert!(expiring.is_expired(now));
+    }
+}
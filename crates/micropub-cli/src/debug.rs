@@ -0,0 +1,438 @@
+// ABOUTME: `micropub debug <profile>` connection diagnostics
+// ABOUTME: Re-runs discovery and checks the token, q=config, scopes, and media endpoint
+
+use anyhow::{bail, Context, Result};
+use reqwest::Client as HttpClient;
+use serde::Serialize;
+use std::time::Duration;
+
+use crate::auth::discover_with_overrides;
+use crate::config::{load_token, load_token_metadata, Config, Profile, ENV_PROFILE};
+use crate::server_config::ServerConfig;
+
+/// Per-request limit so an unresponsive server doesn't hang the report
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl CheckStatus {
+    fn symbol(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✓",
+            CheckStatus::Warn => "⚠",
+            CheckStatus::Fail => "✗",
+            CheckStatus::Skip => "-",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Scopes from a token verification response, which may be JSON or form-encoded
+pub fn scope_from_verification(body: &str) -> Option<String> {
+    let scope = match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(json) => json.get("scope")?.as_str()?.to_string(),
+        Err(_) => url::form_urlencoded::parse(body.as_bytes())
+            .find(|(key, _)| key == "scope")?
+            .1
+            .into_owned(),
+    };
+    Some(scope)
+}
+
+/// Compare a freshly discovered endpoint with the stored one
+fn compare_endpoint(stored: Option<&str>, found: Option<&str>) -> (CheckStatus, String) {
+    match (stored, found) {
+        (Some(stored), Some(found)) if stored == found => (CheckStatus::Pass, found.to_string()),
+        (Some(stored), Some(found)) => (
+            CheckStatus::Warn,
+            format!("site now advertises {} (stored: {})", found, stored),
+        ),
+        (None, Some(found)) => (CheckStatus::Warn, format!("{} (not stored)", found)),
+        (Some(stored), None) => (
+            CheckStatus::Warn,
+            format!("no longer advertised (stored: {})", stored),
+        ),
+        (None, None) => (CheckStatus::Fail, "not advertised".to_string()),
+    }
+}
+
+/// Discovery checks, plus the token endpoint the site currently advertises
+async fn check_discovery(profile_name: &str, profile: &Profile) -> (Vec<Check>, Option<String>) {
+    if profile_name == ENV_PROFILE {
+        let check = Check::new(
+            "discovery",
+            CheckStatus::Skip,
+            "endpoints come from MICROPUB_ENDPOINT",
+        );
+        return (vec![check], None);
+    }
+
+    let found = match discover_with_overrides(&profile.domain, &profile.endpoint_overrides).await {
+        Ok(found) => found,
+        Err(e) => {
+            let check = Check::new("discovery", CheckStatus::Fail, format!("{:#}", e));
+            return (vec![check], None);
+        }
+    };
+
+    let mut checks = Vec::new();
+    for (name, stored, found) in [
+        (
+            "micropub endpoint",
+            profile.micropub_endpoint.as_deref(),
+            found.micropub.as_deref(),
+        ),
+        (
+            "authorization endpoint",
+            profile.authorization_endpoint.as_deref(),
+            found.authorization.as_deref(),
+        ),
+        (
+            "token endpoint",
+            profile.token_endpoint.as_deref(),
+            found.token.as_deref(),
+        ),
+    ] {
+        let (mut status, detail) = compare_endpoint(stored, found);
+        // Sites used with manually issued tokens may not advertise IndieAuth endpoints
+        if status == CheckStatus::Fail && name != "micropub endpoint" {
+            status = CheckStatus::Warn;
+        }
+        checks.push(Check::new(name, status, detail));
+    }
+    (checks, found.token)
+}
+
+fn check_token_stored(profile_name: &str) -> (Check, Option<String>) {
+    let token = match load_token(profile_name) {
+        Ok(token) => token,
+        Err(e) => {
+            return (
+                Check::new("token", CheckStatus::Fail, format!("{:#}", e)),
+                None,
+            )
+        }
+    };
+
+    let metadata = load_token_metadata(profile_name);
+    let check = match metadata.expires_at {
+        _ if metadata.is_expired(chrono::Utc::now()) => {
+            let status = if metadata.refresh_token.is_some() {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Fail
+            };
+            let detail = match metadata.refresh_token {
+                Some(_) => "expired; run `micropub auth refresh` to renew it",
+                None => "expired and can't be refreshed; re-authenticate",
+            };
+            Check::new("token", status, detail)
+        }
+        Some(expires_at) => Check::new(
+            "token",
+            CheckStatus::Pass,
+            format!(
+                "stored, expires {}",
+                expires_at.format("%Y-%m-%d %H:%M UTC")
+            ),
+        ),
+        None => Check::new("token", CheckStatus::Pass, "stored"),
+    };
+    (check, Some(token))
+}
+
+async fn check_config_query(
+    client: &HttpClient,
+    micropub_endpoint: &str,
+    token: &str,
+) -> (Check, Option<ServerConfig>) {
+    let response = match client
+        .get(micropub_endpoint)
+        .query(&[("q", "config")])
+        .bearer_auth(token)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            return (
+                Check::new("q=config", CheckStatus::Fail, e.to_string()),
+                None,
+            )
+        }
+    };
+
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        let check = Check::new(
+            "q=config",
+            CheckStatus::Fail,
+            format!("token rejected (HTTP {})", status),
+        );
+        return (check, None);
+    }
+    if !status.is_success() {
+        return (
+            Check::new("q=config", CheckStatus::Fail, format!("HTTP {}", status)),
+            None,
+        );
+    }
+
+    match response.json::<ServerConfig>().await {
+        Ok(server_config) => {
+            let queries = if server_config.q.is_empty() {
+                "no queries listed".to_string()
+            } else {
+                format!("supports q={}", server_config.q.join(", q="))
+            };
+            (
+                Check::new(
+                    "q=config",
+                    CheckStatus::Pass,
+                    format!("token accepted, {}", queries),
+                ),
+                Some(server_config),
+            )
+        }
+        Err(e) => (
+            Check::new(
+                "q=config",
+                CheckStatus::Warn,
+                format!(
+                    "token accepted, but the response isn't valid config JSON: {}",
+                    e
+                ),
+            ),
+            None,
+        ),
+    }
+}
+
+async fn check_scopes(client: &HttpClient, token_endpoint: Option<&str>, token: &str) -> Check {
+    let Some(token_endpoint) = token_endpoint else {
+        return Check::new(
+            "scopes",
+            CheckStatus::Skip,
+            "no token endpoint to verify with",
+        );
+    };
+
+    // IndieAuth token verification: GET the token endpoint with the token
+    let response = client
+        .get(token_endpoint)
+        .header("Accept", "application/json")
+        .bearer_auth(token)
+        .send()
+        .await;
+    let body = match response {
+        Ok(response) if response.status().is_success() => response.text().await.ok(),
+        Ok(response) => {
+            return Check::new(
+                "scopes",
+                CheckStatus::Warn,
+                format!("token endpoint returned HTTP {}", response.status()),
+            )
+        }
+        Err(e) => return Check::new("scopes", CheckStatus::Warn, e.to_string()),
+    };
+
+    let Some(scope) = body.as_deref().and_then(scope_from_verification) else {
+        return Check::new(
+            "scopes",
+            CheckStatus::Warn,
+            "token endpoint didn't report scopes",
+        );
+    };
+
+    let missing: Vec<&str> = ["create", "update", "delete", "media"]
+        .into_iter()
+        .filter(|wanted| !scope.split_whitespace().any(|s| s == *wanted))
+        .collect();
+    if missing.is_empty() {
+        Check::new("scopes", CheckStatus::Pass, scope)
+    } else {
+        Check::new(
+            "scopes",
+            CheckStatus::Warn,
+            format!("{} (missing: {})", scope, missing.join(", ")),
+        )
+    }
+}
+
+async fn check_media_endpoint(
+    client: &HttpClient,
+    media_endpoint: Option<&str>,
+    token: &str,
+) -> Check {
+    let Some(media_endpoint) = media_endpoint else {
+        return Check::new(
+            "media endpoint",
+            CheckStatus::Warn,
+            "none configured or advertised",
+        );
+    };
+
+    // Media endpoints only have to accept POSTs, so any non-auth, non-server error means it's up
+    match client.get(media_endpoint).bearer_auth(token).send().await {
+        Ok(response) => {
+            let status = response.status();
+            if status == reqwest::StatusCode::UNAUTHORIZED
+                || status == reqwest::StatusCode::FORBIDDEN
+            {
+                Check::new(
+                    "media endpoint",
+                    CheckStatus::Fail,
+                    format!("{} rejected the token (HTTP {})", media_endpoint, status),
+                )
+            } else if status.is_server_error() {
+                Check::new(
+                    "media endpoint",
+                    CheckStatus::Fail,
+                    format!("{} returned HTTP {}", media_endpoint, status),
+                )
+            } else {
+                Check::new(
+                    "media endpoint",
+                    CheckStatus::Pass,
+                    format!("{} reachable", media_endpoint),
+                )
+            }
+        }
+        Err(e) => Check::new(
+            "media endpoint",
+            CheckStatus::Fail,
+            format!("{}: {}", media_endpoint, e),
+        ),
+    }
+}
+
+/// Run every check for a profile, in order
+pub async fn diagnose(profile_name: &str) -> Result<Vec<Check>> {
+    let config = Config::load()?;
+    let profile = config
+        .get_profile(profile_name)
+        .with_context(|| format!("Profile not found: {}", profile_name))?;
+    let client = HttpClient::builder()
+        .timeout(CHECK_TIMEOUT)
+        .build()
+        .context("Failed to build HTTP client")?;
+
+    let (mut checks, discovered_token_endpoint) = check_discovery(profile_name, profile).await;
+
+    let (token_check, token) = check_token_stored(profile_name);
+    checks.push(token_check);
+
+    let (Some(token), Some(micropub_endpoint)) = (token, profile.micropub_endpoint.as_deref())
+    else {
+        for name in ["q=config", "scopes", "media endpoint"] {
+            checks.push(Check::new(
+                name,
+                CheckStatus::Skip,
+                "needs a token and micropub endpoint",
+            ));
+        }
+        return Ok(checks);
+    };
+
+    let (config_check, server_config) =
+        check_config_query(&client, micropub_endpoint, &token).await;
+    checks.push(config_check);
+    let token_endpoint = profile.token_endpoint.clone().or(discovered_token_endpoint);
+    checks.push(check_scopes(&client, token_endpoint.as_deref(), &token).await);
+
+    let media_endpoint = server_config
+        .and_then(|c| c.media_endpoint)
+        .or_else(|| profile.media_endpoint.clone());
+    checks.push(check_media_endpoint(&client, media_endpoint.as_deref(), &token).await);
+
+    Ok(checks)
+}
+
+pub async fn cmd_debug(profile_name: &str) -> Result<()> {
+    crate::progress!("Checking {}...\n", profile_name);
+    let checks = diagnose(profile_name).await?;
+
+    if crate::output::json() {
+        crate::output::print_json(&checks)?;
+    } else {
+        for check in &checks {
+            println!("{} {}: {}", check.status.symbol(), check.name, check.detail);
+        }
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+    if failed > 0 {
+        bail!("{} of {} checks failed", failed, checks.len());
+    }
+    let warned = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Warn)
+        .count();
+    if warned > 0 {
+        crate::progress!("\nNo checks failed ({} warning(s))", warned);
+    } else {
+        crate::progress!("\nAll checks passed");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scope_from_verification() {
+        assert_eq!(
+            scope_from_verification(r#"{"me": "https://example.com/", "scope": "create media"}"#)
+                .as_deref(),
+            Some("create media")
+        );
+        assert_eq!(
+            scope_from_verification("me=https%3A%2F%2Fexample.com%2F&scope=create+update")
+                .as_deref(),
+            Some("create update")
+        );
+        assert_eq!(
+            scope_from_verification(r#"{"me": "https://example.com/"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_compare_endpoint() {
+        let url = "https://example.com/micropub";
+        assert_eq!(compare_endpoint(Some(url), Some(url)).0, CheckStatus::Pass);
+        assert_eq!(
+            compare_endpoint(Some(url), Some("https://example.com/mp")).0,
+            CheckStatus::Warn
+        );
+        assert_eq!(compare_endpoint(Some(url), None).0, CheckStatus::Warn);
+        assert_eq!(compare_endpoint(None, None).0, CheckStatus::Fail);
+    }
+}
@@ -0,0 +1,38 @@
+// ABOUTME: Commands for browsing and restoring draft snapshots
+// ABOUTME: Snapshot storage itself lives in micropub-core
+
+use anyhow::Result;
+use std::fs;
+
+use super::Draft;
+
+pub use micropub_core::draft::history::*;
+
+pub fn cmd_history(draft_id: &str) -> Result<()> {
+    let snapshots = list(draft_id)?;
+    if snapshots.is_empty() {
+        println!("No snapshots of {}", draft_id);
+        return Ok(());
+    }
+
+    for (i, path) in snapshots.iter().enumerate() {
+        let title = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| Draft::from_string(draft_id.to_string(), contents).ok())
+            .and_then(|draft| draft.metadata.name)
+            .unwrap_or_else(|| "[untitled]".to_string());
+        println!("{:>3}  {}  {}", i + 1, snapshot_label(path), title);
+    }
+    println!(
+        "\nRestore one with: micropub draft restore {} <number>",
+        draft_id
+    );
+    Ok(())
+}
+
+/// Put a snapshot back (1 = newest); the current version is snapshotted first
+pub fn cmd_restore(draft_id: &str, number: usize) -> Result<()> {
+    let path = restore(draft_id, number)?;
+    println!("✓ Restored {} from {}", draft_id, snapshot_label(&path));
+    Ok(())
+}
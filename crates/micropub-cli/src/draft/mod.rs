@@ -0,0 +1,370 @@
+// ABOUTME: Draft commands for the micropub CLI
+// ABOUTME: Creating, editing, listing, and searching drafts on top of micropub-core
+
+use anyhow::Result;
+use std::io::Write;
+
+use crate::editor::open_in_editor;
+use crate::output::{json, print_json};
+use crate::pager::Pager;
+
+pub mod history;
+
+pub use micropub_core::draft::*;
+
+/// Print lint warnings for a draft that was just written by the user
+fn warn_on_lint(id: &str) {
+    if let Ok((_, warnings)) = lint_file(id) {
+        for warning in warnings {
+            println!("⚠ {}", warning);
+        }
+    }
+}
+
+/// Create a new draft with the given categories and open in editor
+pub fn cmd_new(categories: &[String]) -> Result<()> {
+    let id = generate_draft_id();
+    let mut draft = Draft::new(id.clone());
+    for category in categories {
+        if !draft.metadata.category.contains(category) {
+            draft.metadata.category.push(category.clone());
+        }
+    }
+
+    // Save initial draft
+    let path = draft.save()?;
+
+    // Open in editor
+    open_in_editor(&path)?;
+
+    println!("Draft created: {}", id);
+    println!("Path: {}", path.display());
+    let cached = crate::config::Config::load()
+        .map(|config| crate::server_config::cached_categories(&config.active_profile_name()))
+        .unwrap_or_default();
+    for category in unfamiliar_categories(categories, cached) {
+        println!("⚠ Category '{}' has never been used before", category);
+    }
+    warn_on_lint(&id);
+
+    Ok(())
+}
+
+/// Create a reply, like, repost, or bookmark draft for a URL and open it in the editor
+pub fn cmd_new_response(kind: ResponseKind, url: &str) -> Result<()> {
+    if !is_http_url(url) {
+        anyhow::bail!("Not an http(s) URL: {}", url);
+    }
+
+    let id = generate_draft_id();
+    let mut draft = Draft::new(id.clone());
+    draft.metadata.post_type = kind.post_type().to_string();
+    draft.metadata.set_response_target(kind, url.to_string());
+
+    let path = draft.save()?;
+
+    open_in_editor(&path)?;
+
+    println!("Draft created: {}", id);
+    println!("Path: {}", path.display());
+    warn_on_lint(&id);
+
+    Ok(())
+}
+
+/// Edit an existing draft
+pub fn cmd_edit(draft_id: &str) -> Result<()> {
+    let path = DraftId::parse(draft_id)?.existing_draft_path()?;
+    history::snapshot(draft_id, &path)?;
+
+    open_in_editor(&path)?;
+
+    warn_on_lint(draft_id);
+
+    Ok(())
+}
+
+/// Check a draft for problems before publishing
+pub fn cmd_lint(draft_id: &str) -> Result<()> {
+    let (_, warnings) = lint_file(draft_id)?;
+
+    if warnings.is_empty() {
+        println!("✓ No problems found in {}", draft_id);
+        return Ok(());
+    }
+
+    for warning in &warnings {
+        crate::ci::warning(&warning.to_string());
+    }
+
+    // Missing alt text is worth flagging but shouldn't block publishing
+    let errors = warnings.iter().filter(|w| w.kind.is_error()).count();
+    if errors > 0 {
+        anyhow::bail!("{} problem(s) found in {}", errors, draft_id);
+    }
+    Ok(())
+}
+
+/// Fail with every blocking problem in `warnings`, one per line
+pub fn ensure_valid(draft_id: &str, warnings: &[LintWarning]) -> Result<()> {
+    let errors: Vec<String> = warnings
+        .iter()
+        .filter(|w| w.kind.is_error())
+        .map(|w| format!("  {}", w))
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    anyhow::bail!(
+        "Draft {} is not valid:\n{}\nFix these, then check with `micropub draft validate {}`",
+        draft_id,
+        errors.join("\n"),
+        draft_id
+    )
+}
+
+/// Check a draft's frontmatter, content, media, and syndication targets, with line numbers
+pub fn cmd_validate(draft_id: &str) -> Result<()> {
+    let (_, warnings) = lint_file(draft_id)?;
+
+    if json() {
+        let problems: Vec<_> = warnings
+            .iter()
+            .map(|w| {
+                serde_json::json!({
+                    "line": w.line,
+                    "kind": w.kind.badge(),
+                    "error": w.kind.is_error(),
+                    "message": w.message,
+                })
+            })
+            .collect();
+        print_json(&problems)?;
+    } else if warnings.is_empty() {
+        println!("✓ {} is valid", draft_id);
+    } else {
+        for warning in &warnings {
+            if warning.kind.is_error() {
+                println!("error: {}", warning);
+            } else {
+                println!("warning: {}", warning);
+            }
+        }
+    }
+
+    let errors = warnings.iter().filter(|w| w.kind.is_error()).count();
+    if errors > 0 {
+        anyhow::bail!("{} error(s) in {}", errors, draft_id);
+    }
+    Ok(())
+}
+
+/// List all drafts with optional category filter
+pub fn cmd_list(category_filter: Option<&str>, limit: usize, offset: usize) -> Result<()> {
+    let mut all_draft_ids = Draft::list_all()?;
+
+    if all_draft_ids.is_empty() && !json() {
+        println!("No drafts found.");
+        return Ok(());
+    }
+
+    // Sort for consistent ordering
+    all_draft_ids.sort();
+
+    // Apply category filter first to get filtered list
+    let filtered_drafts: Vec<_> = if let Some(filter) = category_filter {
+        all_draft_ids
+            .into_iter()
+            .filter_map(|id| {
+                Draft::load(&id).ok().and_then(|draft| {
+                    if draft.metadata.category.iter().any(|c| c == filter) {
+                        Some((id, draft))
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect()
+    } else {
+        all_draft_ids
+            .into_iter()
+            .filter_map(|id| Draft::load(&id).ok().map(|draft| (id, draft)))
+            .collect()
+    };
+
+    if json() {
+        let page: Vec<_> = filtered_drafts
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(id, draft)| serde_json::json!({ "id": id, "metadata": draft.metadata }))
+            .collect();
+        return print_json(&page);
+    }
+
+    if filtered_drafts.is_empty() {
+        if category_filter.is_some() {
+            println!("No drafts found with that category.");
+        } else {
+            println!("No drafts found.");
+        }
+        return Ok(());
+    }
+
+    let mut out = Pager::start();
+    let mut current_offset = offset;
+    let mut first_page = true;
+
+    loop {
+        let page_items: Vec<_> = filtered_drafts
+            .iter()
+            .skip(current_offset)
+            .take(limit)
+            .collect();
+
+        if page_items.is_empty() {
+            if first_page {
+                writeln!(out, "No drafts found at offset {}.", current_offset)?;
+            } else {
+                writeln!(out, "No more drafts.")?;
+            }
+            return Ok(());
+        }
+
+        if first_page {
+            if let Some(filter) = category_filter {
+                writeln!(out, "Drafts with category '{}':", filter)?;
+            } else {
+                writeln!(out, "Drafts:")?;
+            }
+        }
+
+        for (id, draft) in page_items {
+            let title = draft.metadata.name.as_deref().unwrap_or("[untitled]");
+            let post_type = &draft.metadata.post_type;
+            let categories = if draft.metadata.category.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", draft.metadata.category.join(", "))
+            };
+            writeln!(out, "  {} - {} ({}){}", id, title, post_type, categories)?;
+        }
+
+        // Keep writing pages into the pager until the user quits it
+        let remaining = filtered_drafts.len().saturating_sub(current_offset + limit);
+        if remaining == 0 || !out.is_paging() || out.is_closed() {
+            return Ok(());
+        }
+
+        current_offset += limit;
+        first_page = false;
+    }
+}
+
+/// Which of a title, content, and categories contain `query`, ignoring case
+pub fn matched_fields(
+    title: Option<&str>,
+    content: &str,
+    categories: &[String],
+    query: &str,
+) -> Vec<&'static str> {
+    let query = query.to_lowercase();
+    let mut matches = Vec::new();
+    if title.is_some_and(|title| title.to_lowercase().contains(&query)) {
+        matches.push("title");
+    }
+    if content.to_lowercase().contains(&query) {
+        matches.push("content");
+    }
+    if categories.iter().any(|c| c.to_lowercase().contains(&query)) {
+        matches.push("category");
+    }
+    matches
+}
+
+/// The first line of `content` containing `query`, cut to 80 characters
+pub fn match_snippet(content: &str, query: &str) -> Option<String> {
+    let query = query.to_lowercase();
+    let line = content
+        .lines()
+        .find(|line| line.to_lowercase().contains(&query))?;
+    Some(if line.chars().count() > 80 {
+        format!("{}...", line.chars().take(77).collect::<String>())
+    } else {
+        line.to_string()
+    })
+}
+
+/// Search drafts by content or metadata
+pub fn cmd_search(query: &str) -> Result<()> {
+    let draft_ids = Draft::list_all()?;
+
+    if draft_ids.is_empty() && !json() {
+        println!("No drafts found.");
+        return Ok(());
+    }
+
+    let mut found_count = 0;
+    let mut json_results = Vec::new();
+
+    if !json() {
+        println!("Searching for '{}'...\n", query);
+    }
+
+    for id in draft_ids {
+        match Draft::load(&id) {
+            Ok(draft) => {
+                let matches = matched_fields(
+                    draft.metadata.name.as_deref(),
+                    &draft.content,
+                    &draft.metadata.category,
+                    query,
+                );
+
+                if !matches.is_empty() && json() {
+                    json_results.push(serde_json::json!({
+                        "id": id,
+                        "metadata": draft.metadata,
+                        "matched_in": matches,
+                    }));
+                } else if !matches.is_empty() {
+                    found_count += 1;
+                    let title = draft
+                        .metadata
+                        .name
+                        .unwrap_or_else(|| "[untitled]".to_string());
+                    println!("{} - {}", id, title);
+                    println!("  Matched in: {}", matches.join(", "));
+
+                    // Show a snippet of content if it matched
+                    if matches.contains(&"content") {
+                        if let Some(snippet) = match_snippet(&draft.content, query) {
+                            println!("  {}", snippet);
+                        }
+                    }
+                    println!();
+                }
+            }
+            Err(_) => continue,
+        }
+    }
+
+    if json() {
+        return print_json(&json_results);
+    }
+
+    if found_count == 0 {
+        println!("No drafts found matching '{}'.", query);
+    } else {
+        println!("Found {} draft(s).", found_count);
+    }
+
+    Ok(())
+}
+
+/// Show a draft's content
+pub fn cmd_show(draft_id: &str) -> Result<()> {
+    let draft = Draft::load(draft_id)?;
+    println!("{}", draft.to_string()?);
+    Ok(())
+}
@@ -0,0 +1,529 @@
+// ABOUTME: Draft push functionality for server-side drafts
+// ABOUTME: Handles pushing local drafts to server with post-status: draft
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::auth::load_fresh_token;
+use crate::client::{MicropubAction, MicropubClient, MicropubRequest};
+use crate::config::{Config, Profile};
+pub use crate::draft::paths::validate_draft_id;
+use crate::draft::{Draft, DraftId};
+use crate::media::{
+    alt_text_for, find_media_references, replace_paths, resolve_path, upload_or_reuse,
+};
+use crate::publish::{placeholder_upload_url, print_dry_run};
+use crate::queue::{enqueue, queued_message, QueueKind};
+use crate::transform::apply_transforms;
+use crate::transport::is_connection_error;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PushResult {
+    /// Post URL; `None` while a 202 Accepted push is still being processed
+    pub url: Option<String>,
+    pub is_update: bool,
+    pub uploads: Vec<(String, String)>,
+    /// True when the server accepted the draft but hasn't made it available yet
+    pub pending: bool,
+    /// Properties sent or removed; empty when an update found nothing changed
+    pub changed: Vec<String>,
+    /// Offline queue ID when the server couldn't be reached and the request was queued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queued: Option<String>,
+    /// Profile the draft was pushed with
+    pub profile: String,
+}
+
+/// Push a draft to the server as a server-side draft
+/// ABOUTME: Loads draft, validates it, and sends to server with post-status: draft
+pub async fn cmd_push_draft(draft_id: &str, backdate: Option<DateTime<Utc>>) -> Result<PushResult> {
+    push_draft(draft_id, backdate, false)
+        .await?
+        .context("Draft was not pushed")
+}
+
+/// Print the request `draft push` would send, without uploading or sending anything
+pub async fn cmd_push_draft_dry_run(draft_id: &str, backdate: Option<DateTime<Utc>>) -> Result<()> {
+    push_draft(draft_id, backdate, true).await.map(|_| ())
+}
+
+/// Micropub properties for a draft, with media still pointing at local paths
+fn build_properties(
+    draft: &Draft,
+    content: &str,
+    default_license: Option<&String>,
+) -> Map<String, Value> {
+    let mut properties = Map::new();
+
+    // Likes and reposts usually have no text of their own
+    if !content.trim().is_empty() || draft.metadata.response_properties().is_empty() {
+        properties.insert(
+            "content".to_string(),
+            Value::Array(vec![Value::String(content.to_string())]),
+        );
+    }
+
+    for (property, url) in draft.metadata.response_properties() {
+        properties.insert(
+            property.to_string(),
+            Value::Array(vec![Value::String(url.to_string())]),
+        );
+    }
+
+    if let Some(name) = &draft.metadata.name {
+        properties.insert(
+            "name".to_string(),
+            Value::Array(vec![Value::String(name.clone())]),
+        );
+    }
+
+    if let Some(summary) = &draft.metadata.summary {
+        properties.insert(
+            "summary".to_string(),
+            Value::Array(vec![Value::String(summary.clone())]),
+        );
+    }
+
+    let strings =
+        |values: &[String]| Value::Array(values.iter().cloned().map(Value::String).collect());
+
+    if !draft.metadata.category.is_empty() {
+        properties.insert("category".to_string(), strings(&draft.metadata.category));
+    }
+
+    if !draft.metadata.photo.is_empty() {
+        properties.insert("photo".to_string(), strings(&draft.metadata.photo));
+    }
+
+    if let Some(license) = draft.metadata.license.as_ref().or(default_license) {
+        properties.insert(
+            "license".to_string(),
+            Value::Array(vec![Value::String(license.clone())]),
+        );
+    }
+
+    if !draft.metadata.syndicate_to.is_empty() {
+        properties.insert(
+            "mp-syndicate-to".to_string(),
+            strings(&draft.metadata.syndicate_to),
+        );
+    }
+
+    // Handle published date
+    if let Some(date) = draft.metadata.published {
+        properties.insert(
+            "published".to_string(),
+            Value::Array(vec![Value::String(date.to_rfc3339())]),
+        );
+    }
+
+    // CRITICAL: Set post-status to draft
+    properties.insert(
+        "post-status".to_string(),
+        Value::Array(vec![Value::String("draft".to_string())]),
+    );
+
+    properties
+}
+
+/// `build_properties` with the profile's content transforms applied, as
+/// `publish` does. A backdate becomes the draft's own date first, so the
+/// next push doesn't see `published` as removed and delete it on the server.
+fn push_properties(
+    draft: &mut Draft,
+    backdate: Option<DateTime<Utc>>,
+    profile: &Profile,
+) -> Result<Map<String, Value>> {
+    if let Some(date) = backdate {
+        draft.metadata.published = Some(date);
+    }
+    let content = apply_transforms(&draft.content, &profile.transforms)?;
+    Ok(build_properties(draft, &content, profile.license.as_ref()))
+}
+
+/// Short content hash of each property, stored after a push to detect changes
+pub fn property_hashes(properties: &Map<String, Value>) -> BTreeMap<String, String> {
+    properties
+        .iter()
+        .map(|(property, value)| {
+            let digest = Sha256::digest(value.to_string().as_bytes());
+            (property.clone(), format!("{:x}", digest)[..16].to_string())
+        })
+        .collect()
+}
+
+/// Properties whose hash differs from the last push, and ones that were removed since
+pub fn changed_properties(
+    pushed: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+) -> (Vec<String>, Vec<String>) {
+    let changed = current
+        .iter()
+        .filter(|(property, hash)| pushed.get(*property) != Some(*hash))
+        .map(|(property, _)| property.clone())
+        .collect();
+    let removed = pushed
+        .keys()
+        .filter(|property| !current.contains_key(*property))
+        .cloned()
+        .collect();
+    (changed, removed)
+}
+
+async fn push_draft(
+    draft_id: &str,
+    backdate: Option<DateTime<Utc>>,
+    dry_run: bool,
+) -> Result<Option<PushResult>> {
+    // Load draft (validates the ID), keeping other tasks off it until it's saved
+    let _lock = crate::draft::lock::lock(DraftId::parse(draft_id)?.as_str()).await;
+    let mut draft = Draft::load(draft_id)?;
+
+    // Load config
+    let config = Config::load()?;
+    config.check_draft_profiles(&draft.metadata.profile)?;
+
+    // Determine profile (owned, since the draft's metadata changes after pushing)
+    let profile_name = &config.profile_for_draft(draft.metadata.profile());
+
+    let profile = config
+        .get_profile(profile_name)
+        .context(format!("Profile not found: {}", profile_name))?;
+
+    // Load token (a dry run never talks to the server)
+    let token = if dry_run {
+        String::new()
+    } else {
+        load_fresh_token(profile_name).await?
+    };
+
+    // Fail before uploading anything if a referenced photo is missing
+    let missing_photos = draft.missing_photos();
+    if !missing_photos.is_empty() {
+        bail!("Photo file(s) not found: {}", missing_photos.join(", "));
+    }
+
+    // Determine if this is an update or create
+    let is_update = draft.metadata.url.is_some();
+
+    // Validate that we're not accidentally overwriting a published post
+    if is_update {
+        match draft.metadata.status.as_deref() {
+            Some("server-draft") | Some("draft") | None => {
+                // Safe to update: server-draft, draft, or no status
+            }
+            Some(status) => {
+                bail!(
+                    "Cannot push draft with status '{}' - only server-draft or draft status can be updated. \
+                     This appears to be a published post.",
+                    status
+                );
+            }
+        }
+    }
+
+    // Properties as written locally, before media is uploaded
+    let mut properties = push_properties(&mut draft, backdate, profile)?;
+    let hashes = property_hashes(&properties);
+
+    // The slug only names a new post, so it's left out of later diffs
+    if let (false, Some(slug)) = (is_update, &draft.metadata.slug) {
+        properties.insert(
+            "mp-slug".to_string(),
+            Value::Array(vec![Value::String(slug.clone())]),
+        );
+    }
+
+    // Updates only send what changed since the last push
+    let (changed, removed) = if is_update {
+        changed_properties(&draft.metadata.pushed_hashes, &hashes)
+    } else {
+        (hashes.keys().cloned().collect(), Vec::new())
+    };
+
+    if is_update && changed.is_empty() && removed.is_empty() {
+        return Ok((!dry_run).then_some(PushResult {
+            url: draft.metadata.url.clone(),
+            is_update,
+            uploads: Vec::new(),
+            pending: false,
+            changed: Vec::new(),
+            queued: None,
+            profile: profile_name.to_string(),
+        }));
+    }
+    let sent: Vec<String> = changed.iter().chain(&removed).cloned().collect();
+
+    // Collect media references from the properties being sent, deduplicated
+    let mut media_refs_set: HashSet<String> = HashSet::new();
+
+    if changed.iter().any(|p| p == "content") {
+        for ref_path in find_media_references(&draft.content) {
+            media_refs_set.insert(ref_path);
+        }
+    }
+
+    // Add local photo references (skip remote URLs)
+    if changed.iter().any(|p| p == "photo") {
+        for photo_path in &draft.metadata.photo {
+            if !photo_path.starts_with("http://") && !photo_path.starts_with("https://") {
+                media_refs_set.insert(photo_path.clone());
+            }
+        }
+    }
+
+    // Convert to Vec for iteration
+    let media_refs: Vec<String> = media_refs_set.into_iter().collect();
+
+    // Upload media
+    let mut replacements = Vec::new();
+    let mut upload_results = Vec::new();
+    let mut pending_uploads = Vec::new();
+
+    if dry_run {
+        for local_path in &media_refs {
+            let resolved = resolve_path(local_path, None)?;
+            replacements.push((local_path.clone(), placeholder_upload_url(&resolved)));
+            pending_uploads.push(resolved);
+        }
+    } else if !media_refs.is_empty() {
+        let media_endpoint = profile.media_endpoint.as_ref().context(format!(
+            "No media endpoint found for profile '{}'. Re-authenticate:\n  micropub auth {}",
+            profile_name, profile.domain
+        ))?;
+
+        crate::progress!("Uploading {} media file(s)...", media_refs.len());
+
+        for local_path in media_refs {
+            let resolved = resolve_path(&local_path, None)?;
+            crate::progress!("  Uploading: {}", resolved.display());
+
+            let alt = alt_text_for(&draft.content, &local_path);
+            let upload = upload_or_reuse(
+                media_endpoint,
+                &token,
+                &resolved,
+                profile_name,
+                alt.as_deref(),
+            )
+            .await?;
+            let url = upload.url;
+            if upload.reused {
+                crate::progress!("    -> {} (already uploaded)", url);
+            } else {
+                crate::progress!("    -> {}", url);
+            }
+
+            let filename = resolved
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            upload_results.push((filename, url.clone()));
+            replacements.push((local_path.clone(), url));
+        }
+    }
+
+    // Point content and photos at the uploaded URLs
+    if let Some(Value::Array(content)) = properties.get_mut("content") {
+        content[0] = Value::String(replace_paths(&draft.content, &replacements));
+    }
+    if let Some(Value::Array(photos)) = properties.get_mut("photo") {
+        for photo in photos.iter_mut() {
+            let Value::String(photo_path) = photo else {
+                continue;
+            };
+            if photo_path.starts_with("http://") || photo_path.starts_with("https://") {
+                // Keep remote URLs as-is
+                continue;
+            }
+            match replacements.iter().find(|(local, _)| local == photo_path) {
+                Some((_, url)) => *photo = Value::String(url.clone()),
+                // Unchanged photos aren't re-uploaded, so they aren't sent either
+                None if !changed.iter().any(|p| p == "photo") => {}
+                None => bail!("Photo file not found or not uploaded: {}", photo_path),
+            }
+        }
+    }
+
+    let request = if is_update {
+        // Update existing server draft with only the changed properties
+        let replace: Map<String, Value> = properties
+            .into_iter()
+            .filter(|(property, _)| changed.contains(property))
+            .collect();
+
+        MicropubRequest {
+            action: MicropubAction::Update {
+                replace,
+                add: Map::new(),
+                delete: removed,
+            },
+            properties: Map::new(),
+            url: draft.metadata.url.clone(),
+        }
+    } else {
+        // Create new server draft
+        MicropubRequest {
+            action: MicropubAction::Create,
+            properties,
+            url: None,
+        }
+    };
+
+    // Send request
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured for this profile")?;
+
+    if dry_run {
+        print_dry_run(
+            micropub_endpoint,
+            profile.request_format,
+            &pending_uploads,
+            &request,
+        )?;
+        return Ok(None);
+    }
+
+    let client =
+        MicropubClient::new(micropub_endpoint.clone(), token).with_format(profile.request_format);
+
+    crate::progress!("Pushing draft to {}...", profile.domain);
+    let response = match client.send(&request).await {
+        Ok(response) => response,
+        Err(e) if is_connection_error(&e) => {
+            if backdate.is_some() {
+                // Keep the date for when the queued push records its hashes
+                draft.save()?;
+            }
+            let entry = enqueue(QueueKind::Push, profile_name, &draft.id, &request, hashes)?;
+            crate::ci::warning(&queued_message(&entry));
+            return Ok(Some(PushResult {
+                url: draft.metadata.url.clone(),
+                is_update,
+                uploads: upload_results,
+                pending: false,
+                changed: sent,
+                queued: Some(entry.id),
+                profile: profile_name.to_string(),
+            }));
+        }
+        Err(e) => return Err(e),
+    };
+
+    // Updates don't always echo a Location, so fall back to the URL we already know
+    let server_url = response.url.clone().or_else(|| draft.metadata.url.clone());
+
+    let server_url = match server_url {
+        Some(url) => url,
+        None if response.pending => {
+            crate::progress!(
+                "✓ Server accepted the draft for processing; its URL is not available yet."
+            );
+            crate::progress!("  Check your site later and re-run the push once the draft appears.");
+            return Ok(Some(PushResult {
+                url: None,
+                is_update,
+                uploads: upload_results,
+                pending: true,
+                changed: sent,
+                queued: None,
+                profile: profile_name.to_string(),
+            }));
+        }
+        None => bail!("Server didn't return URL"),
+    };
+
+    // Update draft metadata
+    draft.metadata.status = Some("server-draft".to_string());
+    draft.metadata.url = Some(server_url.clone());
+    draft.metadata.pushed_hashes = hashes;
+
+    // Save updated draft (stays in drafts directory)
+    draft.save().context(
+        "Failed to save draft metadata after successful push. \
+         The draft was successfully pushed to the server, but local metadata could not be updated.",
+    )?;
+
+    if response.pending {
+        crate::progress!("✓ Draft accepted by server (processing pending)");
+    } else {
+        crate::progress!("✓ Draft pushed successfully!");
+    }
+    crate::progress!("  URL: {}", server_url);
+    crate::ci::set_output("url", &server_url)?;
+
+    Ok(Some(PushResult {
+        url: Some(server_url),
+        is_update,
+        uploads: upload_results,
+        pending: response.pending,
+        changed: sent,
+        queued: None,
+        profile: profile_name.to_string(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::Transform;
+
+    /// Build properties and record their hashes the way a successful push does,
+    /// then reload the draft as the next push would see it
+    fn push(draft: &mut Draft, backdate: Option<DateTime<Utc>>) -> (Vec<String>, Vec<String>) {
+        let hashes =
+            property_hashes(&push_properties(draft, backdate, &Profile::default()).unwrap());
+        let changes = changed_properties(&draft.metadata.pushed_hashes, &hashes);
+        draft.metadata.pushed_hashes = hashes;
+        *draft = Draft::from_string(draft.id.clone(), draft.to_string().unwrap()).unwrap();
+        changes
+    }
+
+    #[test]
+    fn test_push_twice_after_backdate_changes_nothing() {
+        let mut draft = Draft::new("backdated".to_string());
+        draft.content = "Hello".to_string();
+        let date = "2024-01-15T10:30:00Z".parse().unwrap();
+
+        let (changed, _) = push(&mut draft, Some(date));
+        assert!(changed.contains(&"published".to_string()));
+        assert_eq!(draft.metadata.published, Some(date));
+
+        assert_eq!(push(&mut draft, None), (Vec::new(), Vec::new()));
+    }
+
+    #[test]
+    fn test_push_applies_profile_transforms() {
+        let mut draft = Draft::new("transformed".to_string());
+        draft.content = "Hi <!-- note to self -->there, see [[Home]]".to_string();
+        let profile = Profile {
+            transforms: vec![
+                Transform::StripComments,
+                Transform::WikiLinks {
+                    base_url: "https://example.com/wiki/".to_string(),
+                },
+            ],
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            ..Default::default()
+        };
+
+        let properties = push_properties(&mut draft, None, &profile).unwrap();
+        let content = properties["content"][0].as_str().unwrap();
+        assert!(!content.contains("note to self"));
+        assert!(content.contains("https://example.com/wiki/"));
+        assert_eq!(
+            properties["license"][0],
+            "https://creativecommons.org/licenses/by/4.0/"
+        );
+        // The local draft keeps what was written
+        assert!(draft.content.contains("note to self"));
+    }
+}
@@ -0,0 +1,231 @@
+// ABOUTME: Catches a draft that repeats a post published in the last few days
+// ABOUTME: Compares a fingerprint of its text and response URLs against the archive
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::config::Config;
+use crate::draft::{Draft, DraftId};
+
+/// An archived post with the same fingerprint as the draft being published
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate {
+    pub id: String,
+    pub url: Option<String>,
+    pub published_at: DateTime<Utc>,
+}
+
+impl Duplicate {
+    /// e.g. "https://example.com/1 (published 3 hours ago)"
+    pub fn describe(&self, now: DateTime<Utc>) -> String {
+        let elapsed = now - self.published_at;
+        let ago = if elapsed.num_days() > 0 {
+            format!("{} days ago", elapsed.num_days())
+        } else if elapsed.num_hours() > 0 {
+            format!("{} hours ago", elapsed.num_hours())
+        } else {
+            format!("{} minutes ago", elapsed.num_minutes().max(0))
+        };
+        format!(
+            "{} (published {})",
+            self.url.as_deref().unwrap_or(&self.id),
+            ago
+        )
+    }
+}
+
+/// Hash of the title, content, photos, and response URLs, ignoring case,
+/// punctuation, and whitespace so near-identical retries still match.
+/// `None` when there's nothing to compare.
+pub fn fingerprint(draft: &Draft) -> Option<String> {
+    let mut text = String::new();
+    for part in [draft.metadata.name.as_deref().unwrap_or(""), &draft.content] {
+        for word in part
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+        {
+            text.push_str(&word.to_lowercase());
+            text.push(' ');
+        }
+        text.push('\n');
+    }
+    for (property, url) in draft.metadata.response_properties() {
+        text.push_str(&format!("{}={}\n", property, url));
+    }
+    for photo in &draft.metadata.photo {
+        text.push_str(&format!("photo={}\n", photo));
+    }
+
+    if text.trim().is_empty() {
+        return None;
+    }
+    let digest = Sha256::digest(text.as_bytes());
+    Some(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// The most recent post in `archived` published with `profile_name` in the
+/// `window` before `now` that has the same fingerprint as `draft`
+pub fn find_in(
+    draft: &Draft,
+    archived: &[Draft],
+    profile_name: &str,
+    window: Duration,
+    now: DateTime<Utc>,
+) -> Option<Duplicate> {
+    let target = fingerprint(draft)?;
+    archived
+        .iter()
+        .filter(|a| a.metadata.status.as_deref() != Some("deleted"))
+        // Archives published with another profile belong to another site
+        .filter(|a| a.metadata.profile().is_none_or(|p| p == profile_name))
+        .filter_map(|a| Some((a, a.metadata.published_at?)))
+        .filter(|(_, at)| now - *at <= window)
+        .filter(|(a, _)| fingerprint(a).as_deref() == Some(target.as_str()))
+        .max_by_key(|(_, at)| *at)
+        .map(|(a, at)| Duplicate {
+            id: a.id.clone(),
+            url: a.metadata.url.clone(),
+            published_at: at,
+        })
+}
+
+/// A recent post the draft would repeat on any of the sites it's published to,
+/// going back `duplicate_window_days` (0 turns the check off)
+pub fn find(draft: &Draft) -> Result<Option<Duplicate>> {
+    let config = Config::load()?;
+    let days = config.duplicate_window_days();
+    if days == 0 || fingerprint(draft).is_none() {
+        return Ok(None);
+    }
+
+    let archived = crate::archive::list()?;
+    let now = Utc::now();
+    Ok(config
+        .profiles_for_draft(&draft.metadata.profile)
+        .iter()
+        .find_map(|profile| find_in(draft, &archived, profile, Duration::days(days.into()), now)))
+}
+
+/// Publishing stopped because the draft repeats a recent post
+#[derive(Debug)]
+pub struct DuplicateError(pub Duplicate);
+
+impl fmt::Display for DuplicateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "This draft matches {}; pass --allow-duplicate to publish it anyway",
+            self.0.describe(Utc::now())
+        )
+    }
+}
+
+impl std::error::Error for DuplicateError {}
+
+/// Refuse to publish a draft that repeats a recent post unless `allow` is set.
+/// The shared publish path runs this, so every way of publishing is covered.
+pub fn ensure_not_duplicate(draft: &Draft, allow: bool) -> Result<()> {
+    if allow {
+        return Ok(());
+    }
+    match find(draft)? {
+        Some(duplicate) => Err(DuplicateError(duplicate).into()),
+        None => Ok(()),
+    }
+}
+
+/// Ask at the terminal before publishing a draft that repeats a recent post.
+/// Returns whether to publish it anyway; without a terminal the answer is
+/// `allow`, leaving the publish path to refuse.
+pub fn confirm_before_publish(draft_path: &str, allow: bool) -> Result<bool> {
+    if allow || !crate::prompt::can_prompt() {
+        return Ok(allow);
+    }
+    let draft = Draft::load(DraftId::from_path(draft_path)?.as_str())?;
+    let Some(duplicate) = find(&draft)? else {
+        return Ok(false);
+    };
+
+    println!("⚠ This draft matches {}", duplicate.describe(Utc::now()));
+    print!("Publish it again? [y/N] ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+        bail!("Not published");
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft(id: &str, name: Option<&str>, content: &str) -> Draft {
+        let mut draft = Draft::new(id.to_string());
+        draft.metadata.name = name.map(str::to_string);
+        draft.content = content.to_string();
+        draft
+    }
+
+    fn published(mut draft: Draft, at: DateTime<Utc>, profile: Option<&str>) -> Draft {
+        draft.metadata.status = Some("published".to_string());
+        draft.metadata.url = Some(format!("https://example.com/{}", draft.id));
+        draft.metadata.published_at = Some(at);
+        draft.metadata.profile = profile.map(|p| vec![p.to_string()]).unwrap_or_default();
+        draft
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_case_spacing_and_punctuation() {
+        let a = draft("a", Some("Hello"), "Shipped the  new release!\n");
+        let b = draft("b", Some("hello"), "shipped the new release");
+        let c = draft("c", Some("Hello"), "Shipped the old release");
+        assert_eq!(fingerprint(&a), fingerprint(&b));
+        assert_ne!(fingerprint(&a), fingerprint(&c));
+
+        let mut like = draft("d", None, "");
+        assert_eq!(fingerprint(&like), None);
+        like.metadata.like_of = Some("https://example.com/post".to_string());
+        assert!(fingerprint(&like).is_some());
+    }
+
+    #[test]
+    fn test_find_in_respects_window_and_profile() {
+        let now = Utc::now();
+        let new = draft("new", None, "Same words");
+        let archived = vec![
+            published(
+                draft("old", None, "same words"),
+                now - Duration::days(10),
+                None,
+            ),
+            published(draft("other-site", None, "Same words"), now, Some("work")),
+            published(
+                draft("recent", None, "Same words."),
+                now - Duration::hours(2),
+                None,
+            ),
+            published(draft("different", None, "Other words"), now, None),
+        ];
+
+        let found = find_in(&new, &archived, "blog", Duration::days(7), now).unwrap();
+        assert_eq!(found.id, "recent");
+        assert_eq!(
+            found.describe(now),
+            "https://example.com/recent (published 2 hours ago)"
+        );
+
+        assert_eq!(
+            find_in(&new, &archived, "blog", Duration::hours(1), now),
+            None
+        );
+        assert_eq!(
+            find_in(&new, &archived, "work", Duration::hours(1), now).map(|d| d.id),
+            Some("other-site".to_string())
+        );
+    }
+}
@@ -0,0 +1,163 @@
+// ABOUTME: Shared external editor launching for drafts, post updates, and the TUI
+// ABOUTME: Resolves config.editor, $VISUAL, $EDITOR with argument splitting and platform fallbacks
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+use crate::config::Config;
+
+#[cfg(windows)]
+const FALLBACK_EDITORS: &[&str] = &["notepad"];
+#[cfg(not(windows))]
+const FALLBACK_EDITORS: &[&str] = &["vim", "vi", "nano"];
+
+/// Split an editor command line into program and arguments, honoring quotes
+/// and backslash escapes (e.g. `code --wait` or `"/Applications/My Editor" -w`)
+pub fn split_command(command: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+                in_token = true;
+            }
+            (Some(_), c) => current.push(c),
+            (None, '"') | (None, '\'') => {
+                quote = Some(c);
+                in_token = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_token {
+                    parts.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+
+    if in_token {
+        parts.push(current);
+    }
+
+    parts
+}
+
+/// Pick the editor command: config first, then $VISUAL, then $EDITOR
+pub fn resolve_editor(
+    config_editor: Option<&str>,
+    visual: Option<&str>,
+    editor: Option<&str>,
+) -> Option<String> {
+    [config_editor, visual, editor]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|e| !e.is_empty())
+        .map(str::to_string)
+}
+
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| {
+            std::env::split_paths(&paths).any(|dir| {
+                let candidate = dir.join(program);
+                candidate.is_file() || (cfg!(windows) && candidate.with_extension("exe").is_file())
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// The editor command line to use, falling back to a platform default
+pub fn editor_command() -> Result<Vec<String>> {
+    let config = Config::load()?;
+    let visual = std::env::var("VISUAL").ok();
+    let editor = std::env::var("EDITOR").ok();
+
+    let command = resolve_editor(
+        config.editor.as_deref(),
+        visual.as_deref(),
+        editor.as_deref(),
+    )
+    .or_else(|| {
+        FALLBACK_EDITORS
+            .iter()
+            .find(|e| on_path(e))
+            .map(|e| e.to_string())
+    })
+    .unwrap_or_else(|| FALLBACK_EDITORS[0].to_string());
+
+    let parts = split_command(&command);
+    if parts.is_empty() {
+        bail!("Editor command is empty");
+    }
+    Ok(parts)
+}
+
+/// Open a file in the user's editor and wait for it to exit
+pub fn open_in_editor(path: &Path) -> Result<()> {
+    crate::ci::ensure_interactive("Opening an editor")?;
+    let command = editor_command()?;
+
+    let status = Command::new(&command[0])
+        .args(&command[1..])
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to open editor: {}", command[0]))?;
+
+    if !status.success() {
+        bail!("Editor exited with error");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(split_command("vim"), vec!["vim"]);
+        assert_eq!(split_command("code --wait"), vec!["code", "--wait"]);
+        assert_eq!(
+            split_command(r#""/Applications/My Editor" -w"#),
+            vec!["/Applications/My Editor", "-w"]
+        );
+        assert_eq!(
+            split_command(r"emacsclient -a '' -t"),
+            vec!["emacsclient", "-a", "", "-t"]
+        );
+        assert_eq!(split_command(r"my\ editor"), vec!["my editor"]);
+        assert!(split_command("   ").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_editor_precedence() {
+        assert_eq!(
+            resolve_editor(Some("hx"), Some("code -w"), Some("vim")),
+            Some("hx".to_string())
+        );
+        assert_eq!(
+            resolve_editor(None, Some("code -w"), Some("vim")),
+            Some("code -w".to_string())
+        );
+        assert_eq!(
+            resolve_editor(Some(" "), None, Some("nano")),
+            Some("nano".to_string())
+        );
+        assert_eq!(resolve_editor(None, None, None), None);
+    }
+}
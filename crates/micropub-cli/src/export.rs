@@ -0,0 +1,184 @@
+// ABOUTME: `micropub export` writes every published post to local files for backups and migrations
+// ABOUTME: One markdown file with frontmatter, or one JSON file, per post, named by date and slug
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::operations::PostData;
+use crate::reconcile::list_server_posts;
+
+/// Frontmatter of an exported markdown file
+#[derive(Debug, Serialize)]
+struct Frontmatter<'a> {
+    url: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    published: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    categories: Vec<&'a str>,
+}
+
+/// A post as markdown with YAML frontmatter, the way static site generators read it
+pub fn render_markdown(post: &PostData) -> Result<String> {
+    let frontmatter = Frontmatter {
+        url: &post.url,
+        title: post.name.as_deref().filter(|n| !n.trim().is_empty()),
+        published: post.published.as_deref().filter(|p| !p.is_empty()),
+        categories: post.categories.iter().map(String::as_str).collect(),
+    };
+    let yaml = serde_yaml::to_string(&frontmatter).context("Failed to serialize frontmatter")?;
+    Ok(format!("---\n{}---\n\n{}\n", yaml, post.content.trim_end()))
+}
+
+/// `2024-01-15-hello-world`: the publish date and the last segment of the URL path,
+/// reduced to lowercase letters, digits, and hyphens
+pub fn file_stem(post: &PostData) -> String {
+    let date = post
+        .published
+        .as_deref()
+        .and_then(|p| p.get(..10))
+        .filter(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").is_ok());
+
+    let last_segment = url::Url::parse(&post.url)
+        .ok()
+        .and_then(|url| {
+            url.path_segments()?
+                .rev()
+                .find(|s| !s.is_empty())
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+    let last_segment = last_segment
+        .rsplit_once('.')
+        .map_or(last_segment.as_str(), |(stem, _)| stem);
+    let mut slug = String::new();
+    for c in last_segment.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    match (date, slug.is_empty()) {
+        (Some(date), false) if !slug.starts_with(date) => format!("{}-{}", date, slug),
+        (_, false) => slug.to_string(),
+        (Some(date), true) => date.to_string(),
+        (None, true) => "post".to_string(),
+    }
+}
+
+/// Write every published post on the active profile's server into `dir`,
+/// as `md` (frontmatter and content) or `json` (the post's fields)
+pub async fn cmd_export(dir: &Path, format: &str) -> Result<()> {
+    if !matches!(format, "md" | "json") {
+        bail!("Unknown export format '{}'; use md or json", format);
+    }
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    crate::progress!("Fetching posts...");
+    let mut posts: Vec<PostData> = list_server_posts()
+        .await?
+        .into_values()
+        .filter(|post| !post.draft)
+        .collect();
+    // Oldest first, so names that collide get suffixes in a stable order
+    posts.sort_by(|a, b| a.published.cmp(&b.published).then(a.url.cmp(&b.url)));
+
+    let mut used = HashSet::new();
+    for post in &posts {
+        let stem = file_stem(post);
+        let mut name = format!("{}.{}", stem, format);
+        let mut n = 2;
+        while !used.insert(name.clone()) {
+            name = format!("{}-{}.{}", stem, n, format);
+            n += 1;
+        }
+
+        let contents = if format == "json" {
+            serde_json::to_string_pretty(post)? + "\n"
+        } else {
+            render_markdown(post)?
+        };
+        let path = dir.join(&name);
+        fs::write(&path, contents)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    println!("Exported {} post(s) to {}", posts.len(), dir.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(url: &str, published: &str) -> PostData {
+        PostData {
+            url: url.to_string(),
+            content: "Hello *world*\n".to_string(),
+            name: Some("Hello".to_string()),
+            published: Some(published.to_string()),
+            categories: vec!["rust".to_string(), "notes".to_string()],
+            draft: false,
+        }
+    }
+
+    #[test]
+    fn test_file_stem_uses_date_and_slug() {
+        let stem = |url, published| file_stem(&post(url, published));
+        assert_eq!(
+            stem(
+                "https://example.com/2024/01/hello-world/",
+                "2024-01-15T10:00:00Z"
+            ),
+            "2024-01-15-hello-world"
+        );
+        assert_eq!(
+            stem("https://example.com/posts/Hello_There.html", "not a date"),
+            "hello-there"
+        );
+        assert_eq!(
+            stem("https://example.com/", "2024-01-15T10:00:00Z"),
+            "2024-01-15"
+        );
+        // Slugs that already start with the date don't repeat it
+        assert_eq!(
+            stem(
+                "https://example.com/2024-01-15-notes",
+                "2024-01-15T10:00:00Z"
+            ),
+            "2024-01-15-notes"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_frontmatter() {
+        let rendered =
+            render_markdown(&post("https://example.com/1", "2024-01-15T10:00:00Z")).unwrap();
+        assert_eq!(
+            rendered,
+            "---\nurl: https://example.com/1\ntitle: Hello\npublished: 2024-01-15T10:00:00Z\ncategories:\n- rust\n- notes\n---\n\nHello *world*\n"
+        );
+    }
+
+    #[test]
+    fn test_undated_post_has_no_published() {
+        let undated = PostData {
+            published: None,
+            ..post("https://example.com/notes/undated", "")
+        };
+        assert_eq!(file_stem(&undated), "undated");
+        assert_eq!(
+            render_markdown(&undated).unwrap(),
+            "---\nurl: https://example.com/notes/undated\ntitle: Hello\ncategories:\n- rust\n- notes\n---\n\nHello *world*\n"
+        );
+        let json = serde_json::to_value(&undated).unwrap();
+        assert!(json["published"].is_null());
+    }
+}
@@ -0,0 +1,126 @@
+// ABOUTME: Reverse lookup from a published post URL to its local draft or archive file
+// ABOUTME: Matches URLs loosely (scheme, trailing slash, fragment) across drafts, scheduled, and archive
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::path::PathBuf;
+use url::Url;
+
+use crate::config::{get_archive_dir, get_drafts_dir, get_scheduled_dir};
+use crate::draft::{load_dir, Draft, DraftMetadata};
+use crate::output::{json, print_json};
+
+/// Compare URLs ignoring scheme, host case, `www.`, fragment, and a trailing slash
+pub fn normalize_url(url: &str) -> String {
+    let trimmed = url.trim();
+    let Ok(parsed) = Url::parse(trimmed) else {
+        return trimmed.trim_end_matches('/').to_lowercase();
+    };
+
+    let host = parsed.host_str().unwrap_or_default().to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let mut normalized = match parsed.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    };
+    normalized.push_str(parsed.path().trim_end_matches('/'));
+    if let Some(query) = parsed.query() {
+        normalized.push('?');
+        normalized.push_str(query);
+    }
+    normalized
+}
+
+#[derive(Debug, Serialize)]
+pub struct Found {
+    /// `draft`, `scheduled`, or `archive`
+    pub location: &'static str,
+    pub path: PathBuf,
+    pub id: String,
+    pub metadata: DraftMetadata,
+}
+
+/// Local files whose `url` matches a published URL
+pub fn find_by_url(url: &str) -> Result<Vec<Found>> {
+    let wanted = normalize_url(url);
+    let mut found = Vec::new();
+
+    for (location, dir) in [
+        ("draft", get_drafts_dir()?),
+        ("scheduled", get_scheduled_dir()?),
+        ("archive", get_archive_dir()?),
+    ] {
+        for (path, draft) in load_dir(&dir)? {
+            let Draft { id, metadata, .. } = draft;
+            if metadata.url.as_deref().map(normalize_url).as_ref() == Some(&wanted) {
+                found.push(Found {
+                    location,
+                    path,
+                    id,
+                    metadata,
+                });
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+pub fn cmd_find(url: &str) -> Result<()> {
+    let found = find_by_url(url)?;
+
+    if json() {
+        return print_json(&found);
+    }
+    if found.is_empty() {
+        bail!("No local draft or archive file has url: {}", url);
+    }
+
+    for (i, file) in found.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        let metadata = &file.metadata;
+        println!("{} ({})", file.path.display(), file.location);
+        println!("  ID: {}", file.id);
+        println!("  Type: {}", metadata.post_type);
+        if let Some(ref name) = metadata.name {
+            println!("  Title: {}", name);
+        }
+        if let Some(published_at) = metadata.published_at {
+            println!("  Published: {}", published_at.format("%Y-%m-%d %H:%M UTC"));
+        }
+        if !metadata.category.is_empty() {
+            println!("  Categories: {}", metadata.category.join(", "));
+        }
+        if !metadata.profile.is_empty() {
+            println!("  Profile: {}", metadata.profile.join(", "));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_url() {
+        let canonical = normalize_url("https://example.com/2024/01/hello");
+        for variant in [
+            "http://example.com/2024/01/hello",
+            "https://www.Example.com/2024/01/hello/",
+            "https://example.com/2024/01/hello#comments",
+            " https://example.com/2024/01/hello ",
+        ] {
+            assert_eq!(normalize_url(variant), canonical, "{}", variant);
+        }
+
+        assert_ne!(
+            normalize_url("https://example.com/?p=1"),
+            normalize_url("https://example.com/?p=2")
+        );
+        assert_ne!(normalize_url("https://example.com/a"), canonical);
+    }
+}
@@ -0,0 +1,344 @@
+// ABOUTME: Data directory cleanup (`micropub gc`)
+// ABOUTME: Prunes old draft snapshots, leftover temp files, and media sidecars of removed profiles
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::config::{
+    get_data_dir, get_history_dir, get_media_dir, get_scheduled_dir, get_temp_dir, Config,
+    ENV_PROFILE,
+};
+use crate::media_sidecar::profile_dir_name;
+use crate::output::{human_size, json, print_json};
+
+/// Temp files younger than this may still be open in an editor
+const TEMP_GRACE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Where gc looks; the real data directories outside tests
+pub struct Layout {
+    /// The shared drafts folder, then each profile's own (`separate_profile_drafts`)
+    pub drafts: Vec<PathBuf>,
+    pub scheduled: PathBuf,
+    pub history: PathBuf,
+    pub media: PathBuf,
+    /// The data dir's own temp directory, then the system one older versions used
+    pub temp: Vec<PathBuf>,
+}
+
+impl Layout {
+    pub fn data_dir() -> Result<Self> {
+        // Not get_drafts_dir(), which only sees the active profile's folder
+        let shared = get_data_dir()?.join("drafts");
+        let mut drafts = vec![shared.clone()];
+        if let Ok(entries) = fs::read_dir(&shared) {
+            drafts.extend(entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()));
+        }
+        Ok(Self {
+            drafts,
+            scheduled: get_scheduled_dir()?,
+            history: get_history_dir()?,
+            media: get_media_dir()?,
+            temp: vec![get_temp_dir()?, std::env::temp_dir()],
+        })
+    }
+}
+
+/// A file or directory gc removes, and why
+#[derive(Debug, Serialize)]
+pub struct Garbage {
+    pub path: PathBuf,
+    pub reason: String,
+    pub bytes: u64,
+}
+
+fn age(path: &Path, now: SystemTime) -> Duration {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .unwrap_or_default()
+}
+
+fn size(path: &Path) -> u64 {
+    if path.is_dir() {
+        fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|e| size(&e.path())).sum())
+            .unwrap_or(0)
+    } else {
+        fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+fn garbage(path: PathBuf, reason: impl Into<String>) -> Garbage {
+    let bytes = size(&path);
+    Garbage {
+        path,
+        reason: reason.into(),
+        bytes,
+    }
+}
+
+/// Snapshots older than `keep`, except the newest one of a draft that still exists
+fn old_snapshots(layout: &Layout, keep: Duration, now: SystemTime) -> Result<Vec<Garbage>> {
+    let mut found = Vec::new();
+    if !layout.history.exists() {
+        return Ok(found);
+    }
+
+    for entry in fs::read_dir(&layout.history)?.flatten() {
+        let dir = entry.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        let file_name = format!("{}.md", entry.file_name().to_string_lossy());
+        let draft_exists = layout
+            .drafts
+            .iter()
+            .chain([&layout.scheduled])
+            .any(|dir| dir.join(&file_name).exists());
+
+        let mut snapshots: Vec<PathBuf> = fs::read_dir(&dir)?
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("md"))
+            .collect();
+        // Timestamped names sort chronologically
+        snapshots.sort();
+        if draft_exists {
+            snapshots.pop();
+        }
+
+        let reason = if draft_exists {
+            "old snapshot"
+        } else {
+            "old snapshot of a draft that no longer exists"
+        };
+        found.extend(
+            snapshots
+                .into_iter()
+                .filter(|path| age(path, now) > keep)
+                .map(|path| garbage(path, reason)),
+        );
+    }
+    Ok(found)
+}
+
+/// Temp files left behind by an interrupted `update`, MCP upload, or draft save
+fn stale_temp_files(layout: &Layout, now: SystemTime) -> Result<Vec<Garbage>> {
+    let mut found = Vec::new();
+    let entries = layout
+        .temp
+        .iter()
+        .chain(&layout.drafts)
+        .chain([&layout.scheduled])
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let reason = if name.starts_with("micropub-update-") && name.ends_with(".md") {
+            "temp file from an interrupted update"
+        } else if name.starts_with("micropub-upload-") {
+            "temp directory from an interrupted upload"
+        } else if name.starts_with('.') && name.ends_with(".tmp") {
+            "partial write of an interrupted draft save"
+        } else {
+            continue;
+        };
+        let path = entry.path();
+        if age(&path, now) > TEMP_GRACE {
+            found.push(garbage(path, reason));
+        }
+    }
+    Ok(found)
+}
+
+/// Sidecar directories for profiles that are no longer configured
+fn orphaned_sidecars(layout: &Layout, profiles: &HashSet<String>) -> Result<Vec<Garbage>> {
+    let mut found = Vec::new();
+    if !layout.media.exists() {
+        return Ok(found);
+    }
+
+    for entry in fs::read_dir(&layout.media)?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.path().is_dir() && !profiles.contains(&name) {
+            found.push(garbage(
+                entry.path(),
+                format!("media records for removed profile '{}'", name),
+            ));
+        }
+    }
+    Ok(found)
+}
+
+/// Everything gc would remove, given the configured profile names
+pub fn find_garbage(
+    layout: &Layout,
+    profiles: &[String],
+    keep_snapshots: Duration,
+    now: SystemTime,
+) -> Result<Vec<Garbage>> {
+    let profiles: HashSet<String> = profiles
+        .iter()
+        .map(String::as_str)
+        .chain([ENV_PROFILE])
+        .map(profile_dir_name)
+        .collect();
+
+    let mut found = old_snapshots(layout, keep_snapshots, now)?;
+    found.extend(stale_temp_files(layout, now)?);
+    found.extend(orphaned_sidecars(layout, &profiles)?);
+    Ok(found)
+}
+
+/// Delete what `find_garbage` found, then any snapshot directories left empty
+pub fn remove(layout: &Layout, found: &[Garbage]) -> Result<()> {
+    for item in found {
+        if item.path.is_dir() {
+            fs::remove_dir_all(&item.path)?;
+        } else {
+            fs::remove_file(&item.path)?;
+        }
+    }
+
+    if layout.history.exists() {
+        for entry in fs::read_dir(&layout.history)?.flatten() {
+            // Only succeeds for empty directories
+            let _ = fs::remove_dir(entry.path());
+        }
+    }
+    Ok(())
+}
+
+pub fn cmd_gc(keep_days: u64, dry_run: bool) -> Result<()> {
+    let config = Config::load()?;
+    let profiles: Vec<String> = config.profiles.keys().cloned().collect();
+    let layout = Layout::data_dir()?;
+    let keep = Duration::from_secs(keep_days * 24 * 60 * 60);
+    let found = find_garbage(&layout, &profiles, keep, SystemTime::now())?;
+
+    if !dry_run {
+        remove(&layout, &found)?;
+    }
+
+    if json() {
+        return print_json(&found);
+    }
+    if found.is_empty() {
+        println!("Nothing to clean up.");
+        return Ok(());
+    }
+
+    for item in &found {
+        println!("{} ({})", item.path.display(), item.reason);
+    }
+    let total = human_size(found.iter().map(|item| item.bytes).sum());
+    println!();
+    if dry_run {
+        println!("Would remove {} item(s), {}", found.len(), total);
+    } else {
+        println!("Removed {} item(s), {}", found.len(), total);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn layout(root: &Path) -> Layout {
+        let layout = Layout {
+            drafts: vec![root.join("drafts"), root.join("drafts/work.example")],
+            scheduled: root.join("scheduled"),
+            history: root.join("history"),
+            media: root.join("media"),
+            temp: vec![root.join("tmp"), root.join("system-tmp")],
+        };
+        for dir in [&layout.scheduled, &layout.media]
+            .into_iter()
+            .chain(&layout.drafts)
+            .chain(&layout.temp)
+        {
+            fs::create_dir_all(dir).unwrap();
+        }
+        layout
+    }
+
+    fn names(found: &[Garbage]) -> Vec<String> {
+        let mut names: Vec<String> = found
+            .iter()
+            .map(|g| g.path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_keeps_newest_snapshot_of_existing_drafts() {
+        let root = TempDir::new().unwrap();
+        let layout = layout(root.path());
+        fs::write(layout.drafts[0].join("kept.md"), "draft").unwrap();
+        fs::write(layout.drafts[1].join("work.md"), "draft").unwrap();
+        for id in ["kept", "work", "gone"] {
+            let dir = layout.history.join(id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("20240101T000000Z.md"), "old").unwrap();
+            fs::write(dir.join("20240102T000000Z.md"), "newer").unwrap();
+        }
+
+        // Everything is a second old, so a zero retention treats it all as old
+        let later = SystemTime::now() + Duration::from_secs(1);
+        let found = find_garbage(&layout, &[], Duration::ZERO, later).unwrap();
+        let paths: Vec<_> = found.iter().map(|g| g.path.clone()).collect();
+        assert_eq!(paths.len(), 4);
+        assert!(!paths.contains(&layout.history.join("kept/20240102T000000Z.md")));
+        // A draft in a profile's own folder still exists
+        assert!(!paths.contains(&layout.history.join("work/20240102T000000Z.md")));
+
+        // Nothing is older than a day yet
+        assert!(find_garbage(&layout, &[], DAY, later).unwrap().is_empty());
+
+        remove(&layout, &found).unwrap();
+        assert!(!layout.history.join("gone").exists());
+        assert!(layout.history.join("kept/20240102T000000Z.md").exists());
+    }
+
+    #[test]
+    fn test_finds_stale_temp_files_and_orphaned_sidecars() {
+        let root = TempDir::new().unwrap();
+        let layout = layout(root.path());
+        fs::write(layout.temp[0].join("micropub-update-abc.md"), "edit").unwrap();
+        fs::create_dir(layout.temp[1].join("micropub-upload-def")).unwrap();
+        fs::write(layout.temp[1].join("unrelated.md"), "keep").unwrap();
+        fs::write(layout.drafts[0].join(".post.md.0a1b.tmp"), "half").unwrap();
+        fs::create_dir(layout.media.join("kept.example")).unwrap();
+        fs::create_dir(layout.media.join("removed.example")).unwrap();
+        fs::create_dir(layout.media.join(ENV_PROFILE)).unwrap();
+
+        let profiles = ["kept.example".to_string()];
+        let now = SystemTime::now();
+        assert_eq!(
+            names(&find_garbage(&layout, &profiles, DAY, now).unwrap()),
+            ["removed.example"]
+        );
+
+        let two_days_later = now + 2 * DAY;
+        assert_eq!(
+            names(&find_garbage(&layout, &profiles, DAY, two_days_later).unwrap()),
+            [
+                ".post.md.0a1b.tmp",
+                "micropub-update-abc.md",
+                "micropub-upload-def",
+                "removed.example"
+            ]
+        );
+    }
+}
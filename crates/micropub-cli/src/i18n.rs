@@ -0,0 +1,121 @@
+// ABOUTME: Lightweight message catalog for user-visible strings
+// ABOUTME: Resolves the active locale and formats messages with named placeholders
+
+use lazy_static::lazy_static;
+
+/// Default locale, also used when a key is missing from the active catalog
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// English messages. Placeholders use `{name}` syntax.
+const EN: &[(&str, &str)] = &[
+    (
+        "banner.tagline",
+        "Ultra-compliant Micropub CLI for IndieWeb",
+    ),
+    ("banner.authenticated_as", "🔐 Authenticated as: {profile}"),
+    ("banner.quick_commands", "Quick commands:"),
+    ("banner.cmd.tui", "Launch interactive TUI"),
+    ("banner.cmd.draft_new", "Create a new draft"),
+    ("banner.cmd.posts", "List published posts"),
+    ("banner.cmd.whoami", "Show current profile"),
+    (
+        "banner.get_started",
+        "To get started, authenticate with your site:",
+    ),
+    ("banner.more_help", "For more help, run:"),
+    ("publish.uploading", "Uploading {count} media file(s)..."),
+    ("publish.uploading_file", "Uploading: {path}"),
+    ("publish.publishing_to", "Publishing to {domain}..."),
+    ("publish.success", "✓ Published successfully!"),
+    ("publish.url", "URL: {url}"),
+    ("publish.archived", "Draft archived to: {path}"),
+];
+
+/// Look up the catalog for a language code
+fn catalog(lang: &str) -> Option<&'static [(&'static str, &'static str)]> {
+    match lang {
+        "en" => Some(EN),
+        _ => None,
+    }
+}
+
+/// Reduce a POSIX locale like "pt_BR.UTF-8" to its language code ("pt")
+fn language_code(locale: &str) -> String {
+    locale
+        .split(['_', '.', '@', '-'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// Detect the active language from MICROPUB_LANG, then the standard locale variables
+pub fn detect_locale() -> String {
+    ["MICROPUB_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .map(|value| language_code(&value))
+        .find(|lang| !lang.is_empty() && lang != "c" && lang != "posix")
+        .filter(|lang| catalog(lang).is_some())
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+lazy_static! {
+    static ref LOCALE: String = detect_locale();
+}
+
+/// Look up a message in a specific locale, falling back to English and then the key itself
+pub fn lookup(locale: &str, key: &str) -> String {
+    let find = |entries: &[(&str, &str)]| {
+        entries
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.to_string())
+    };
+
+    catalog(locale)
+        .and_then(find)
+        .or_else(|| find(EN))
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Translate a message key in the active locale
+pub fn t(key: &str) -> String {
+    lookup(&LOCALE, key)
+}
+
+/// Translate a message key and substitute `{name}` placeholders
+pub fn tf(key: &str, args: &[(&str, &str)]) -> String {
+    let mut message = t(key);
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), value);
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_code() {
+        assert_eq!(language_code("en_US.UTF-8"), "en");
+        assert_eq!(language_code("pt_BR"), "pt");
+        assert_eq!(language_code("de"), "de");
+        assert_eq!(language_code(""), "");
+    }
+
+    #[test]
+    fn test_lookup_falls_back_to_english_then_key() {
+        assert_eq!(lookup("en", "banner.quick_commands"), "Quick commands:");
+        assert_eq!(lookup("xx", "banner.quick_commands"), "Quick commands:");
+        assert_eq!(lookup("en", "missing.key"), "missing.key");
+    }
+
+    #[test]
+    fn test_tf_substitutes_placeholders() {
+        assert_eq!(
+            tf("publish.url", &[("url", "https://example.com/1")]),
+            "URL: https://example.com/1"
+        );
+    }
+}
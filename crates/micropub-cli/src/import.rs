@@ -0,0 +1,400 @@
+// ABOUTME: `micropub import` turns a Hugo or Jekyll content directory into drafts
+// ABOUTME: Maps frontmatter title, date, and tags, and can backdate-publish each file after asking
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde_json::Value;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::draft::{generate_draft_id, Draft};
+use crate::output::{json, print_json};
+use crate::publish::{cmd_publish, PublishResult};
+
+/// A content file converted to a draft
+#[derive(Debug)]
+pub struct Imported {
+    pub draft: Draft,
+    /// Marked `draft: true` (Hugo) or `published: false` (Jekyll) on the old site,
+    /// so `--publish` leaves it alone
+    pub unpublished: bool,
+}
+
+/// YAML (`---`) or TOML (`+++`) frontmatter as a JSON value, and the body after it;
+/// `None` for files without frontmatter, which neither generator publishes
+fn split_frontmatter(source: &str) -> Result<Option<(Value, String)>> {
+    let mut lines = source.trim_start_matches('\u{feff}').lines();
+    let fence = match lines.next().map(str::trim_end) {
+        Some(fence @ ("---" | "+++")) => fence,
+        _ => return Ok(None),
+    };
+
+    let mut front = Vec::new();
+    let mut closed = false;
+    for line in lines.by_ref() {
+        if line.trim_end() == fence {
+            closed = true;
+            break;
+        }
+        front.push(line);
+    }
+    if !closed {
+        bail!("Frontmatter opened with {} is never closed", fence);
+    }
+    let front = front.join("\n");
+    let body = lines.collect::<Vec<_>>().join("\n");
+
+    let value = if fence == "+++" {
+        let table: toml::Table = toml::from_str(&front).context("Invalid TOML frontmatter")?;
+        toml_to_json(toml::Value::Table(table))
+    } else {
+        serde_yaml::from_str::<Option<Value>>(&front)
+            .context("Invalid YAML frontmatter")?
+            .unwrap_or_default()
+    };
+    Ok(Some((value, body)))
+}
+
+/// TOML values as JSON, with datetimes as their RFC 3339 text
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => i.into(),
+        toml::Value::Float(f) => f.into(),
+        toml::Value::Boolean(b) => b.into(),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+/// Dates as Hugo and Jekyll write them: RFC 3339, `2024-01-15 10:00:00 -0500`,
+/// with no offset (taken as UTC), or a bare day
+fn parse_date(input: &str) -> Option<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(input) {
+        return Some(date.with_timezone(&Utc));
+    }
+    for format in ["%Y-%m-%d %H:%M:%S %z", "%Y-%m-%d %H:%M %z"] {
+        if let Ok(date) = DateTime::parse_from_str(input, format) {
+            return Some(date.with_timezone(&Utc));
+        }
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(input, format) {
+            return Some(date.and_utc());
+        }
+    }
+    let day = NaiveDate::parse_from_str(input.get(..10)?, "%Y-%m-%d").ok()?;
+    Some(day.and_hms_opt(0, 0, 0)?.and_utc())
+}
+
+/// A list field that may be an array or, in Jekyll, a space-separated string
+fn words(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::String(s) => Some(s.trim().to_string()),
+                Value::Null => None,
+                other => Some(other.to_string()),
+            })
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Some(Value::String(s)) => s.split_whitespace().map(str::to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The slug the old site used: `slug:`, the name of a Hugo page bundle
+/// (`my-post/index.md`), or the file name without a Jekyll date prefix
+fn slug_for(path: &Path, front: &Value) -> Option<String> {
+    if let Some(slug) = front.get("slug").and_then(Value::as_str) {
+        return Some(slug.to_string()).filter(|s| !s.trim().is_empty());
+    }
+    let stem = path.file_stem()?.to_str()?;
+    let name = if stem == "index" {
+        path.parent()?.file_name()?.to_str()?
+    } else {
+        stem
+    };
+    let undated = match name.get(..11) {
+        Some(prefix)
+            if prefix.ends_with('-')
+                && NaiveDate::parse_from_str(&prefix[..10], "%Y-%m-%d").is_ok() =>
+        {
+            &name[11..]
+        }
+        _ => name,
+    };
+    Some(undated.to_string()).filter(|s| !s.is_empty())
+}
+
+/// Convert one content file to a draft; `None` if it has no frontmatter
+pub fn convert(path: &Path, source: &str) -> Result<Option<Imported>> {
+    let Some((front, body)) = split_frontmatter(source)? else {
+        return Ok(None);
+    };
+    let text = |key: &str| {
+        front
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+
+    let mut draft = Draft::new(generate_draft_id());
+    draft.metadata.name = text("title");
+    if draft.metadata.name.is_some() {
+        draft.metadata.post_type = "article".to_string();
+    }
+    draft.metadata.summary = text("summary").or_else(|| text("description"));
+    // Jekyll posts can leave the date to their `2024-01-15-title.md` file name
+    draft.metadata.published = text("date")
+        .or_else(|| text("publishDate"))
+        .and_then(|date| parse_date(&date))
+        .or_else(|| parse_date(path.file_name()?.to_str()?));
+    for tag in words(front.get("tags"))
+        .into_iter()
+        .chain(words(front.get("categories")))
+    {
+        if !draft.metadata.category.contains(&tag) {
+            draft.metadata.category.push(tag);
+        }
+    }
+    draft.metadata.slug = slug_for(path, &front);
+    draft.content = body.trim().to_string();
+
+    let unpublished = front.get("draft").and_then(Value::as_bool) == Some(true)
+        || front.get("published").and_then(Value::as_bool) == Some(false);
+    Ok(Some(Imported { draft, unpublished }))
+}
+
+/// Markdown files under `dir`, in path order, skipping hidden directories and
+/// Hugo's `_index.md` section pages
+fn content_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries =
+            fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if name.starts_with('.') || name == "_index.md" {
+                continue;
+            }
+            if path.is_dir() {
+                pending.push(path);
+            } else if matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("md" | "markdown")
+            ) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Ask whether to publish one imported draft: yes, no, or stop asking
+fn confirm_publish(source: &Path, draft: &Draft) -> Result<Option<bool>> {
+    let date = draft
+        .metadata
+        .published
+        .map(|d| d.format(" dated %Y-%m-%d").to_string())
+        .unwrap_or_default();
+    print!("Publish {}{}? [y/N/q] ", source.display(), date);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(match input.trim().to_lowercase().as_str() {
+        "y" | "yes" => Some(true),
+        "q" | "quit" => None,
+        _ => Some(false),
+    })
+}
+
+/// Publish one imported draft through the same profile and duplicate checks as `publish`
+async fn publish_imported(
+    draft_path: &str,
+    allow_duplicate: bool,
+    allow_profile_mismatch: bool,
+) -> Result<PublishResult> {
+    crate::profile_guard::check_before_publish(draft_path, allow_profile_mismatch)?;
+    let allow_duplicate = crate::duplicates::confirm_before_publish(draft_path, allow_duplicate)?;
+    cmd_publish(draft_path, None, allow_duplicate).await
+}
+
+/// Import every markdown file under `dir` as a draft. With `publish`, each one is
+/// then published with its original date, after asking unless `--yes` was passed.
+pub async fn cmd_import(
+    dir: &Path,
+    publish: bool,
+    allow_duplicate: bool,
+    allow_profile_mismatch: bool,
+) -> Result<()> {
+    if !dir.is_dir() {
+        bail!("Not a directory: {}", dir.display());
+    }
+    let yes = crate::prompt::no_input();
+    if publish && !yes && !crate::prompt::can_prompt() {
+        bail!("--publish asks before each file; pass --yes to publish them all without asking");
+    }
+
+    let mut records = Vec::new();
+    let mut asking = true;
+    for path in content_files(dir)? {
+        let source = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let imported = match convert(&path, &source) {
+            Ok(Some(imported)) => imported,
+            Ok(None) => {
+                crate::progress!("Skipped {} (no frontmatter)", path.display());
+                continue;
+            }
+            Err(e) => {
+                crate::progress!("Skipped {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        let draft_path = imported.draft.save()?;
+        let id = imported.draft.id.clone();
+        crate::progress!("Imported {} as {}", path.display(), id);
+
+        let mut url = None;
+        if publish && asking && !imported.unpublished {
+            let go = if yes {
+                true
+            } else {
+                match confirm_publish(&path, &imported.draft)? {
+                    Some(go) => go,
+                    None => {
+                        asking = false;
+                        false
+                    }
+                }
+            };
+            if go {
+                let published = publish_imported(
+                    &draft_path.to_string_lossy(),
+                    allow_duplicate,
+                    allow_profile_mismatch,
+                )
+                .await;
+                match published {
+                    Ok(result) => {
+                        crate::progress!(
+                            "  Published: {}",
+                            result.url.as_deref().unwrap_or("(no URL returned)")
+                        );
+                        url = result.url;
+                    }
+                    Err(e) => crate::progress!("  Failed to publish: {:#}", e),
+                }
+            }
+        }
+        records.push(serde_json::json!({
+            "file": path,
+            "draft_id": id,
+            "unpublished": imported.unpublished,
+            "url": url,
+        }));
+    }
+
+    if json() {
+        return print_json(&records);
+    }
+    let published = records.iter().filter(|r| !r["url"].is_null()).count();
+    if publish {
+        println!(
+            "Imported {} file(s) from {}, published {}",
+            records.len(),
+            dir.display(),
+            published
+        );
+    } else {
+        println!(
+            "Imported {} file(s) from {} as drafts",
+            records.len(),
+            dir.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_hugo_toml_bundle() {
+        let source = "+++\ntitle = \"Hello Hugo\"\ndate = 2024-01-15T10:00:00-05:00\ntags = [\"rust\", \"notes\"]\ncategories = [\"notes\", \"dev\"]\ndraft = true\n+++\n\nBody *text*\n";
+        let imported = convert(Path::new("content/posts/hello/index.md"), source)
+            .unwrap()
+            .unwrap();
+        let meta = &imported.draft.metadata;
+        assert_eq!(meta.name.as_deref(), Some("Hello Hugo"));
+        assert_eq!(meta.post_type, "article");
+        assert_eq!(
+            meta.published.unwrap().to_rfc3339(),
+            "2024-01-15T15:00:00+00:00"
+        );
+        assert_eq!(meta.category, ["rust", "notes", "dev"]);
+        assert_eq!(meta.slug.as_deref(), Some("hello"));
+        assert_eq!(imported.draft.content, "Body *text*");
+        assert!(imported.unpublished);
+    }
+
+    #[test]
+    fn test_convert_jekyll_yaml_post() {
+        let source = "---\nlayout: post\ntags: rust cli\ndescription: Short\n---\nJust a note\n";
+        let imported = convert(Path::new("_posts/2023-06-01-first-post.md"), source)
+            .unwrap()
+            .unwrap();
+        let meta = &imported.draft.metadata;
+        assert_eq!(meta.name, None);
+        assert_eq!(meta.post_type, "note");
+        assert_eq!(meta.summary.as_deref(), Some("Short"));
+        // No date in the frontmatter, so the file name's
+        assert_eq!(
+            meta.published.unwrap().to_rfc3339(),
+            "2023-06-01T00:00:00+00:00"
+        );
+        assert_eq!(meta.category, ["rust", "cli"]);
+        assert_eq!(meta.slug.as_deref(), Some("first-post"));
+        assert!(!imported.unpublished);
+
+        assert!(convert(Path::new("README.md"), "# No frontmatter")
+            .unwrap()
+            .is_none());
+        assert!(convert(Path::new("bad.md"), "---\ntitle: x\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_date_formats() {
+        let parsed = |s| parse_date(s).map(|d| d.to_rfc3339());
+        assert_eq!(
+            parsed("2024-01-15 10:30:00 -0500").as_deref(),
+            Some("2024-01-15T15:30:00+00:00")
+        );
+        assert_eq!(
+            parsed("2024-01-15T10:30:00").as_deref(),
+            Some("2024-01-15T10:30:00+00:00")
+        );
+        assert_eq!(
+            parsed("2024-01-15").as_deref(),
+            Some("2024-01-15T00:00:00+00:00")
+        );
+        assert_eq!(parsed("someday"), None);
+    }
+}
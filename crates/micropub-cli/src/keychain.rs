@@ -0,0 +1,54 @@
+// ABOUTME: Token storage in the OS credential store through the keyring crate
+// ABOUTME: macOS Keychain, Windows Credential Manager, and Secret Service (GNOME Keyring, KWallet)
+
+use anyhow::{Context, Result};
+use keyring::{Entry, Error};
+
+/// Service name entries are stored under
+const SERVICE: &str = "micropub";
+
+fn entry(account: &str) -> Result<Entry> {
+    Entry::new(SERVICE, account).context("Failed to open the credential store")
+}
+
+/// Whether a credential store is reachable; false on headless systems without one
+pub fn available() -> bool {
+    // Secret Service needs a session bus, which SSH sessions and containers usually lack
+    if cfg!(all(unix, not(target_os = "macos")))
+        && std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_none()
+    {
+        return false;
+    }
+    // A lookup that finds nothing still proves the store answers
+    matches!(
+        Entry::new(SERVICE, "availability-check").and_then(|entry| entry.get_password()),
+        Ok(_) | Err(Error::NoEntry)
+    )
+}
+
+/// Look up a stored secret; `None` if there's no entry
+pub fn get(account: &str) -> Result<Option<String>> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok((!secret.is_empty()).then_some(secret)),
+        Err(Error::NoEntry) => Ok(None),
+        Err(e) => Err(e).context("Failed to read from the credential store"),
+    }
+}
+
+/// Store a secret, replacing any existing entry
+pub fn set(account: &str, secret: &str) -> Result<()> {
+    entry(account)?.set_password(secret).with_context(|| {
+        format!(
+            "Credential store refused to save the secret for {}",
+            account
+        )
+    })
+}
+
+/// Delete a stored secret; missing entries aren't an error
+pub fn delete(account: &str) -> Result<()> {
+    match entry(account)?.delete_credential() {
+        Ok(()) | Err(Error::NoEntry) => Ok(()),
+        Err(e) => Err(e).context("Failed to delete from the credential store"),
+    }
+}
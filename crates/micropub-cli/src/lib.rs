@@ -0,0 +1,54 @@
+// ABOUTME: Main library file for micropub CLI
+// ABOUTME: Exports the CLI's modules and re-exports micropub-core's under the same paths
+
+pub mod aliases;
+pub mod archive;
+pub mod auth;
+pub mod changelog;
+pub mod ci;
+pub mod clipboard;
+pub mod completions;
+pub mod config;
+pub mod debug;
+pub mod draft;
+pub mod draft_push;
+pub mod duplicates;
+pub mod editor;
+pub mod export;
+pub mod find;
+pub mod gc;
+pub mod i18n;
+pub mod import;
+pub mod keychain;
+#[cfg(feature = "mcp")]
+pub mod mcp;
+#[cfg(feature = "mcp")]
+pub mod mcp_http;
+#[cfg(feature = "mcp")]
+pub mod mcp_install;
+pub mod notes;
+#[cfg(feature = "auth-server")]
+pub mod oauth;
+pub mod operations;
+pub mod output;
+pub mod pager;
+pub mod photo;
+pub mod profile;
+pub mod profile_guard;
+pub mod prompt;
+pub mod publish;
+pub mod queue;
+pub mod reconcile;
+pub mod retag;
+pub mod schedule;
+pub mod settings;
+pub mod summary;
+#[cfg(feature = "tui")]
+pub mod tui;
+
+pub use micropub_core::{
+    blocking, client, html, http_cache, images, media, media_sidecar, polite, post_notes, retry,
+    server_config, transform, transport,
+};
+
+pub use anyhow::{Error, Result};
@@ -0,0 +1,1171 @@
+// ABOUTME: Main entry point for micropub CLI
+// ABOUTME: Parses commands and dispatches to appropriate handlers
+
+use anyhow::Context;
+use clap::{CommandFactory, Parser, Subcommand, ValueHint};
+use micropub_cli::draft::ResponseKind;
+use micropub_cli::i18n::{t, tf};
+use micropub_cli::publish::DraftEdits;
+use micropub_cli::summary::Summary;
+use micropub_cli::Result;
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::Instant;
+
+#[derive(Parser)]
+#[command(name = "micropub")]
+#[command(version)]
+#[command(about = "Ultra-compliant Micropub CLI", long_about = None)]
+struct Cli {
+    /// Enable verbose output
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Ignore cached server configuration (q=config) and query it again
+    #[arg(long, global = true)]
+    refresh_config: bool,
+
+    /// Profile to use instead of the default (repeat with `publish` to post to several sites)
+    #[arg(long, global = true, value_name = "NAME")]
+    profile: Vec<String>,
+
+    /// Never prompt; assume defaults
+    #[arg(short, long, visible_alias = "no-input", global = true)]
+    yes: bool,
+
+    /// Print machine-readable JSON from list, query, publish, and push commands
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Print listings directly instead of piping them through $PAGER
+    #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// Upload images as they are, skipping the [images] resize/recompress/EXIF pipeline
+    #[arg(long, global = true)]
+    no_process: bool,
+
+    /// CI mode: no prompts, pager, browser, or editor; GitHub Actions annotations and outputs
+    #[arg(long, global = true)]
+    ci: bool,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Authenticate with a Micropub site
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Auth {
+        #[command(subcommand)]
+        command: Option<AuthCommands>,
+        /// Domain to authenticate with
+        #[arg(required = true)]
+        domain: Option<String>,
+        /// OAuth scope (default: "create update delete media")
+        #[arg(long)]
+        scope: Option<String>,
+        /// Use a manually issued token instead of the OAuth flow
+        #[arg(long, conflicts_with_all = ["scope", "token_stdin"])]
+        token: Option<String>,
+        /// Read a manually issued token from stdin
+        #[arg(long, conflicts_with = "scope")]
+        token_stdin: bool,
+        /// Use this micropub endpoint instead of discovering it
+        #[arg(long, value_name = "URL")]
+        micropub_endpoint: Option<String>,
+        /// Use this media endpoint instead of discovering it
+        #[arg(long, value_name = "URL")]
+        media_endpoint: Option<String>,
+        /// Use this authorization endpoint instead of discovering it
+        #[arg(long, value_name = "URL")]
+        authorization_endpoint: Option<String>,
+        /// Use this token endpoint instead of discovering it
+        #[arg(long, value_name = "URL")]
+        token_endpoint: Option<String>,
+    },
+    /// Draft management commands
+    #[command(subcommand)]
+    Draft(DraftCommands),
+    /// Profile management commands
+    #[command(subcommand)]
+    Profile(ProfileCommands),
+    /// Read and change config.toml settings
+    #[command(subcommand)]
+    Config(ConfigCommands),
+    /// Publish a draft
+    Publish {
+        /// Path to draft file
+        #[arg(value_hint = ValueHint::FilePath)]
+        draft: String,
+        /// Print the request that would be sent (uploads and JSON body) without sending it
+        #[arg(long)]
+        dry_run: bool,
+        /// Syndicate to this target (uid or name; repeatable)
+        #[arg(long = "syndicate", value_name = "TARGET")]
+        syndicate: Vec<String>,
+        /// URL slug for the new post, saved to the draft's `slug:`
+        #[arg(long)]
+        slug: Option<String>,
+        /// Publish even if an identical post went out in the last few days
+        #[arg(long)]
+        allow_duplicate: bool,
+        /// Publish without asking when the draft's `profile:` disagrees with
+        /// `--profile` or the default profile
+        #[arg(long)]
+        allow_profile_mismatch: bool,
+    },
+    /// Publish a backdated post
+    Backdate {
+        /// Path to draft file
+        #[arg(value_hint = ValueHint::FilePath)]
+        draft: String,
+        /// Date to publish (ISO 8601 format)
+        #[arg(long)]
+        date: String,
+        /// Print the request that would be sent (uploads and JSON body) without sending it
+        #[arg(long)]
+        dry_run: bool,
+        /// Publish even if an identical post went out in the last few days
+        #[arg(long)]
+        allow_duplicate: bool,
+        /// Publish without asking when the draft's `profile:` disagrees with
+        /// `--profile` or the default profile
+        #[arg(long)]
+        allow_profile_mismatch: bool,
+    },
+    /// Draft a reply to a URL
+    Reply {
+        /// URL being replied to
+        url: String,
+    },
+    /// Draft a like of a URL
+    Like {
+        /// URL being liked
+        url: String,
+    },
+    /// Draft a repost of a URL
+    Repost {
+        /// URL being reposted
+        url: String,
+    },
+    /// Draft a bookmark of a URL
+    Bookmark {
+        /// URL being bookmarked
+        url: String,
+    },
+    /// Upload a photo and publish it as a post in one step
+    Photo {
+        /// Image file to post
+        #[arg(value_hint = ValueHint::FilePath)]
+        file: String,
+        /// Caption for the post
+        caption: Option<String>,
+        /// Alt text describing the photo
+        #[arg(long)]
+        alt: Option<String>,
+        /// Category for the post (repeatable)
+        #[arg(long = "category", value_name = "CATEGORY")]
+        categories: Vec<String>,
+        /// Print the request that would be sent without uploading or sending anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Show one post's properties as stored on the server
+    Get {
+        /// URL of the post
+        url: String,
+        /// Save it as a local draft; publishing the draft updates the post
+        #[arg(long)]
+        to_draft: bool,
+    },
+    /// Update an existing post
+    Update {
+        /// URL of post to update
+        url: String,
+    },
+    /// Delete a post
+    Delete {
+        /// URL of post to delete
+        url: String,
+    },
+    /// Undelete a post
+    Undelete {
+        /// URL of post to undelete
+        url: String,
+    },
+    /// Compare archived posts with the server: deleted, edited elsewhere, or missing URLs
+    Reconcile {
+        /// Mark deleted posts, pull server edits into archives, and fill in matched URLs
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Rename a category on local drafts, or with --server on published posts
+    Retag {
+        /// Category to replace
+        #[arg(long)]
+        from: String,
+        /// Category to use instead
+        #[arg(long)]
+        to: String,
+        /// Update posts on the server instead of local drafts (resumable)
+        #[arg(long)]
+        server: bool,
+        /// Milliseconds to wait between server updates
+        #[arg(long, default_value_t = 1000, value_name = "MS")]
+        delay_ms: u64,
+        /// List what would change without changing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Find the local draft or archive file for a published post URL
+    Find {
+        /// URL of the published post
+        url: String,
+    },
+    /// Check a profile's endpoints, token, scopes, and media endpoint
+    Debug {
+        /// Profile name to debug
+        #[arg(value_name = "PROFILE")]
+        profile_name: String,
+    },
+    /// Show current authenticated user
+    Whoami,
+    /// List published posts
+    Posts {
+        /// Number of posts to show (default: 10)
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+        /// Offset for pagination (default: 0)
+        #[arg(short, long, default_value = "0")]
+        offset: usize,
+        /// Show your private notes under each post (see `micropub notes`)
+        #[arg(long)]
+        notes: bool,
+    },
+    /// Private local notes on published posts, never sent to the server
+    Notes {
+        #[command(subcommand)]
+        command: NotesCommands,
+    },
+    /// List uploaded media files
+    #[command(args_conflicts_with_subcommands = true)]
+    Media {
+        #[command(subcommand)]
+        command: Option<MediaCommands>,
+        /// Number of media items to show (default: 20)
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+        /// Offset for pagination (default: 0)
+        #[arg(short, long, default_value = "0")]
+        offset: usize,
+    },
+    /// Queue a draft to publish later (see `schedule run`)
+    #[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Schedule {
+        #[command(subcommand)]
+        command: Option<ScheduleCommands>,
+        /// Draft ID to schedule
+        #[arg(required = true)]
+        draft: Option<String>,
+        /// When to publish (ISO 8601 format)
+        #[arg(long, required = true)]
+        at: Option<String>,
+    },
+    /// Prune old snapshots, leftover temp files, and media records of removed profiles
+    Gc {
+        /// Show what would be removed without removing it
+        #[arg(long)]
+        dry_run: bool,
+        /// Keep draft snapshots newer than this many days
+        #[arg(long, default_value_t = 30)]
+        keep_days: u64,
+    },
+    /// Write every published post to local files, for backups or a static site generator
+    Export {
+        /// Directory to write the files to (created if missing)
+        #[arg(long, value_hint = ValueHint::DirPath)]
+        dir: PathBuf,
+        /// File format: markdown with frontmatter, or JSON
+        #[arg(long, default_value = "md", value_parser = ["md", "json"])]
+        format: String,
+    },
+    /// Turn a Hugo or Jekyll content directory into drafts
+    Import {
+        /// Content directory to read markdown files from (searched recursively)
+        #[arg(value_hint = ValueHint::DirPath)]
+        dir: PathBuf,
+        /// Publish each imported post with its original date, asking first
+        #[arg(long)]
+        publish: bool,
+        /// With --publish, publish posts identical to one from the last few days
+        #[arg(long, requires = "publish")]
+        allow_duplicate: bool,
+        /// With --publish, publish without asking when a post's `profile:`
+        /// disagrees with `--profile` or the default profile
+        #[arg(long, requires = "publish")]
+        allow_profile_mismatch: bool,
+    },
+    /// Browse published drafts in the archive and restore them to drafts
+    Archive {
+        #[command(subcommand)]
+        command: ArchiveCommands,
+    },
+    /// Requests queued while the server couldn't be reached
+    Queue {
+        #[command(subcommand)]
+        command: QueueCommands,
+    },
+    /// Publish a version's CHANGELOG section as an article (for release CI)
+    PostChangelog {
+        /// Changelog file to read
+        #[arg(long, default_value = "CHANGELOG.md", value_hint = ValueHint::FilePath)]
+        from: String,
+        /// Version whose section to publish (e.g. v1.2.0)
+        #[arg(long)]
+        version: String,
+        /// Post title (default: "Release <version>")
+        #[arg(long)]
+        title: Option<String>,
+        /// Category for the post (repeatable)
+        #[arg(long = "category", value_name = "CATEGORY", default_value = "release")]
+        categories: Vec<String>,
+        /// Print the request that would be sent without sending it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List the server's syndication targets (q=syndicate-to) and cache them
+    SyndicateTargets,
+    /// List categories from the server (q=category, cached) and local drafts
+    Categories {
+        /// Query the server even if the cached list is fresh
+        #[arg(long)]
+        refresh: bool,
+    },
+    /// Print a shell completion script (bash, zsh, and fish complete draft IDs and profiles too)
+    Completions { shell: clap_complete::Shell },
+    /// Print draft IDs or profiles for completion scripts
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[arg(value_parser = ["drafts", "profiles"])]
+        kind: String,
+    },
+    /// Launch interactive TUI (Terminal User Interface)
+    #[cfg(feature = "tui")]
+    Tui,
+    /// Start MCP server (Model Context Protocol)
+    #[cfg(feature = "mcp")]
+    #[command(args_conflicts_with_subcommands = true)]
+    Mcp {
+        #[command(subcommand)]
+        command: Option<McpCommands>,
+        /// Serve over streamable HTTP at /mcp instead of stdio
+        #[arg(long)]
+        http: bool,
+        /// Port for --http
+        #[arg(long, default_value_t = 8808, requires = "http")]
+        port: u16,
+        /// Address for --http; anything but localhost needs [mcp] http_token
+        #[arg(long, default_value = "127.0.0.1", requires = "http")]
+        host: String,
+        /// Only offer tools that read (list, view, whoami, server config); nothing can post or delete
+        #[arg(long)]
+        read_only: bool,
+    },
+}
+
+#[cfg(feature = "mcp")]
+#[derive(Subcommand)]
+enum McpCommands {
+    /// Add the MCP server to an assistant app's config (the old file is backed up)
+    Install {
+        /// App to configure
+        #[arg(value_parser = ["claude", "cursor", "zed"])]
+        client: String,
+        /// Print the config snippet instead of writing it
+        #[arg(long)]
+        print: bool,
+        /// Register the server with --read-only
+        #[arg(long)]
+        read_only: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum AuthCommands {
+    /// Exchange the stored refresh token for a new access token
+    Refresh {
+        /// Profile to refresh (default: active profile)
+        profile_name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ScheduleCommands {
+    /// Publish every scheduled draft whose time has arrived (for cron/systemd)
+    Run,
+    /// List scheduled drafts, soonest first
+    List,
+    /// Take a draft out of the schedule and return it to drafts
+    Cancel {
+        /// Draft ID to unschedule
+        draft: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ArchiveCommands {
+    /// List archived drafts, most recently published first
+    List {
+        /// Filter by category
+        #[arg(long)]
+        category: Option<String>,
+        /// Number of drafts to show (default: 20)
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+        /// Offset for pagination (default: 0)
+        #[arg(short, long, default_value = "0")]
+        offset: usize,
+    },
+    /// Show an archived draft
+    Show {
+        /// Archived draft ID
+        draft: String,
+    },
+    /// Search archived drafts by title, content, or category
+    Search {
+        /// Text to search for
+        query: String,
+    },
+    /// Copy an archived draft back to drafts with its publish status cleared
+    Restore {
+        /// Archived draft ID
+        draft: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum QueueCommands {
+    /// List queued publishes and pushes, oldest first
+    List,
+    /// Send everything in the queue, stopping if the server is still unreachable
+    Flush,
+}
+
+#[derive(Subcommand)]
+enum NotesCommands {
+    /// Attach a note to a post
+    Add {
+        /// URL of the post
+        url: String,
+        /// The note, e.g. "syndicated manually to list X"
+        text: String,
+    },
+    /// Show a post's notes
+    Show {
+        /// URL of the post
+        url: String,
+    },
+    /// List every post that has notes
+    List,
+    /// Remove a post's notes
+    Rm {
+        /// URL of the post
+        url: String,
+        /// Note number from `notes show` (default: all of them)
+        number: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum MediaCommands {
+    /// Upload files to the media endpoint and print their URLs
+    Upload {
+        /// Files to upload
+        #[arg(required = true, value_hint = ValueHint::FilePath)]
+        files: Vec<String>,
+        /// Alt text for the Markdown snippet (implies --markdown)
+        #[arg(long)]
+        alt: Option<String>,
+        /// Print Markdown image snippets instead of bare URLs
+        #[arg(long)]
+        markdown: bool,
+    },
+    /// List recorded uploads that have no alt text
+    Audit,
+}
+
+#[derive(Subcommand)]
+enum DraftCommands {
+    /// Create a new draft
+    New {
+        /// Category for the draft (repeatable; see `micropub categories`)
+        #[arg(long)]
+        category: Vec<String>,
+    },
+    /// Edit an existing draft
+    Edit {
+        /// Draft ID to edit
+        draft_id: String,
+    },
+    /// List all drafts
+    List {
+        /// Filter by category
+        #[arg(long)]
+        category: Option<String>,
+        /// Number of drafts to show per page (default: 10)
+        #[arg(short, long, default_value = "10")]
+        limit: usize,
+        /// Offset for pagination (default: 0)
+        #[arg(short, long, default_value = "0")]
+        offset: usize,
+    },
+    /// Show a draft's content
+    Show {
+        /// Draft ID to show
+        draft_id: String,
+    },
+    /// Check a draft for problems (e.g. missing photo files)
+    Lint {
+        /// Draft ID to check
+        draft_id: String,
+    },
+    /// Validate a draft's frontmatter, content, and media, reporting problems by line
+    Validate {
+        /// Draft ID to validate
+        draft_id: String,
+    },
+    /// Print the properties a draft would be published with, as JSON
+    Props {
+        /// Draft ID
+        draft_id: String,
+    },
+    /// Search drafts by content or metadata
+    Search {
+        /// Search query
+        query: String,
+    },
+    /// List snapshots taken before a draft was overwritten or edited
+    History {
+        /// Draft ID
+        draft_id: String,
+    },
+    /// Restore a draft from a snapshot (the current version is snapshotted first)
+    Restore {
+        /// Draft ID
+        draft_id: String,
+        /// Snapshot number from `draft history` (1 = newest)
+        #[arg(default_value = "1")]
+        number: usize,
+    },
+    /// Push a draft to the server as a server-side draft
+    Push {
+        /// Draft ID to push
+        draft_id: String,
+        /// Backdate the draft (ISO 8601 format)
+        #[arg(long)]
+        backdate: Option<String>,
+        /// Print the request that would be sent (uploads and JSON body) without sending it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Print a setting, e.g. `editor` or `profiles.<name>.license`
+    Get {
+        /// Dotted setting name
+        key: String,
+    },
+    /// Change a setting, checking the value first
+    Set {
+        /// Dotted setting name
+        key: String,
+        /// New value (`true`, `85`, and `["a", "b"]` keep their types)
+        value: String,
+    },
+    /// Print every setting that's set, with tokens masked
+    List,
+    /// Remove a setting so its default applies
+    Unset {
+        /// Dotted setting name
+        key: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileCommands {
+    /// List configured profiles and when they were last used
+    List,
+    /// Show a profile's endpoints and settings
+    Show {
+        /// Profile name
+        name: String,
+    },
+    /// Remove a profile and its stored token
+    Remove {
+        /// Profile name
+        name: String,
+    },
+    /// Make a profile the default
+    SetDefault {
+        /// Profile name
+        name: String,
+    },
+    /// Remove profiles whose tokens haven't been used recently
+    Prune {
+        /// Remove profiles unused for at least this long (e.g. 90d, 12h, 2w)
+        #[arg(long)]
+        unused_for: String,
+        /// Show what would be removed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // A broken config is reported by the command itself; aliases just don't apply
+    let aliases = micropub_cli::config::Config::load()
+        .map(|config| config.aliases)
+        .unwrap_or_default();
+    let mut command = Cli::command();
+    command.build();
+    let args = micropub_cli::aliases::expand(std::env::args_os().collect(), &aliases, |name| {
+        command.find_subcommand(name).is_some()
+    })?;
+    let cli = Cli::parse_from(args);
+    let ci = cli.ci;
+
+    let result = run(cli).await;
+    if ci {
+        // The annotation is the error report; returning it too would print it again
+        if let Err(e) = result {
+            micropub_cli::ci::error(&format!("{:#}", e));
+            std::process::exit(1);
+        }
+    }
+    result
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    if cli.refresh_config {
+        micropub_cli::server_config::invalidate_all()?;
+    }
+
+    micropub_cli::prompt::set_no_input(cli.yes || cli.ci);
+    micropub_cli::pager::set_no_pager(cli.no_pager || cli.ci);
+    micropub_cli::ci::set_ci(cli.ci);
+    micropub_cli::images::set_no_process(cli.no_process);
+    micropub_cli::output::set_json(cli.json);
+    micropub_cli::output::init_logging();
+    micropub_cli::output::init_upload_progress();
+
+    if cli.profile.len() > 1 && !matches!(cli.command, Some(Commands::Publish { .. })) {
+        anyhow::bail!("Only `publish` accepts more than one --profile");
+    }
+    micropub_cli::config::set_profile_overrides(&cli.profile);
+
+    // If no command provided, show help
+    if cli.command.is_none() {
+        let config = micropub_cli::config::Config::load()?;
+
+        println!(
+            r#"
+  ███╗   ███╗██╗ ██████╗██████╗  ██████╗ ██████╗ ██╗   ██╗██████╗
+  ████╗ ████║██║██╔════╝██╔══██╗██╔═══██╗██╔══██╗██║   ██║██╔══██╗
+  ██╔████╔██║██║██║     ██████╔╝██║   ██║██████╔╝██║   ██║██████╔╝
+  ██║╚██╔╝██║██║██║     ██╔══██╗██║   ██║██╔═══╝ ██║   ██║██╔══██╗
+  ██║ ╚═╝ ██║██║╚██████╗██║  ██║╚██████╔╝██║     ╚██████╔╝██████╔╝
+  ╚═╝     ╚═╝╚═╝ ╚═════╝╚═╝  ╚═╝ ╚═════╝ ╚═╝      ╚═════╝ ╚═════╝
+"#
+        );
+        println!("              {}\n", t("banner.tagline"));
+
+        if !config.active_profile_name().is_empty() {
+            println!(
+                "  {}",
+                tf(
+                    "banner.authenticated_as",
+                    &[("profile", &config.active_profile_name())]
+                )
+            );
+            println!("\n  {}", t("banner.quick_commands"));
+            #[cfg(feature = "tui")]
+            println!("    micropub tui              {}", t("banner.cmd.tui"));
+            println!(
+                "    micropub draft new        {}",
+                t("banner.cmd.draft_new")
+            );
+            println!("    micropub posts            {}", t("banner.cmd.posts"));
+            println!("    micropub whoami           {}", t("banner.cmd.whoami"));
+        } else {
+            println!("  {}", t("banner.get_started"));
+            println!("    micropub auth <your-domain.com>");
+        }
+
+        println!("\n  {}", t("banner.more_help"));
+        println!("    micropub --help\n");
+        return Ok(());
+    }
+
+    match cli.command.unwrap() {
+        Commands::Auth {
+            command: Some(AuthCommands::Refresh { profile_name }),
+            ..
+        } => {
+            micropub_cli::auth::cmd_auth_refresh(profile_name.as_deref()).await?;
+            Ok(())
+        }
+        Commands::Auth {
+            command: None,
+            domain,
+            scope,
+            token,
+            token_stdin,
+            micropub_endpoint,
+            media_endpoint,
+            authorization_endpoint,
+            token_endpoint,
+        } => {
+            let domain = domain.context("Domain is required")?;
+            let overrides = micropub_cli::config::EndpointOverrides {
+                micropub: micropub_endpoint,
+                media: media_endpoint,
+                authorization: authorization_endpoint,
+                token: token_endpoint,
+            };
+            if token_stdin {
+                let mut token = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut token)
+                    .context("Failed to read token from stdin")?;
+                micropub_cli::auth::cmd_auth_with_token(&domain, &token, overrides).await?;
+            } else if let Some(token) = token {
+                micropub_cli::auth::cmd_auth_with_token(&domain, &token, overrides).await?;
+            } else {
+                micropub_cli::auth::cmd_auth(&domain, scope.as_deref(), overrides).await?;
+            }
+            Ok(())
+        }
+        Commands::Draft(cmd) => match cmd {
+            DraftCommands::New { category } => {
+                micropub_cli::draft::cmd_new(&category)?;
+                Ok(())
+            }
+            DraftCommands::Edit { draft_id } => {
+                micropub_cli::draft::cmd_edit(&draft_id)?;
+                Ok(())
+            }
+            DraftCommands::List {
+                category,
+                limit,
+                offset,
+            } => {
+                micropub_cli::draft::cmd_list(category.as_deref(), limit, offset)?;
+                Ok(())
+            }
+            DraftCommands::Show { draft_id } => {
+                micropub_cli::draft::cmd_show(&draft_id)?;
+                Ok(())
+            }
+            DraftCommands::Lint { draft_id } => {
+                micropub_cli::draft::cmd_lint(&draft_id)?;
+                Ok(())
+            }
+            DraftCommands::Validate { draft_id } => {
+                micropub_cli::draft::cmd_validate(&draft_id)?;
+                Ok(())
+            }
+            DraftCommands::History { draft_id } => {
+                micropub_cli::draft::history::cmd_history(&draft_id)?;
+                Ok(())
+            }
+            DraftCommands::Restore { draft_id, number } => {
+                micropub_cli::draft::history::cmd_restore(&draft_id, number)?;
+                Ok(())
+            }
+            DraftCommands::Props { draft_id } => {
+                micropub_cli::publish::cmd_props(&draft_id)?;
+                Ok(())
+            }
+            DraftCommands::Search { query } => {
+                micropub_cli::draft::cmd_search(&query)?;
+                Ok(())
+            }
+            DraftCommands::Push {
+                draft_id,
+                backdate,
+                dry_run,
+            } => {
+                use chrono::DateTime;
+                use micropub_cli::draft_push;
+
+                let backdate_parsed = if let Some(date_str) = backdate {
+                    Some(DateTime::parse_from_rfc3339(&date_str)?.with_timezone(&chrono::Utc))
+                } else {
+                    None
+                };
+
+                if dry_run {
+                    return draft_push::cmd_push_draft_dry_run(&draft_id, backdate_parsed).await;
+                }
+
+                let started = Instant::now();
+                let result = draft_push::cmd_push_draft(&draft_id, backdate_parsed).await?;
+                let summary = Summary::for_push(&result, started.elapsed());
+                if !micropub_cli::output::json() {
+                    if result.queued.is_some() {
+                        println!("Draft push queued until the server can be reached");
+                    } else if result.is_update && result.changed.is_empty() {
+                        println!("Draft is already up to date on the server");
+                    } else if result.pending {
+                        println!("Draft accepted by server (pending)");
+                    } else {
+                        println!("Draft pushed to server!");
+                    }
+                    if result.is_update && !result.changed.is_empty() {
+                        println!("  Changed: {}", result.changed.join(", "));
+                    }
+                }
+                micropub_cli::summary::print(&result, &summary)
+            }
+        },
+        Commands::Config(cmd) => match cmd {
+            ConfigCommands::Get { key } => micropub_cli::settings::cmd_config_get(&key),
+            ConfigCommands::Set { key, value } => {
+                micropub_cli::settings::cmd_config_set(&key, &value)
+            }
+            ConfigCommands::List => micropub_cli::settings::cmd_config_list(),
+            ConfigCommands::Unset { key } => micropub_cli::settings::cmd_config_unset(&key),
+        },
+        Commands::Profile(cmd) => match cmd {
+            ProfileCommands::List => {
+                micropub_cli::profile::cmd_profile_list()?;
+                Ok(())
+            }
+            ProfileCommands::Show { name } => micropub_cli::profile::cmd_profile_show(&name),
+            ProfileCommands::Remove { name } => micropub_cli::profile::cmd_profile_remove(&name),
+            ProfileCommands::SetDefault { name } => {
+                micropub_cli::profile::cmd_profile_set_default(&name)
+            }
+            ProfileCommands::Prune {
+                unused_for,
+                dry_run,
+            } => {
+                micropub_cli::profile::cmd_profile_prune(&unused_for, dry_run)?;
+                Ok(())
+            }
+        },
+        Commands::Publish {
+            draft,
+            dry_run,
+            syndicate,
+            slug,
+            allow_duplicate,
+            allow_profile_mismatch,
+        } => {
+            if dry_run {
+                let edits = DraftEdits {
+                    slug: slug.as_deref(),
+                    syndicate: &syndicate,
+                };
+                micropub_cli::publish::cmd_publish_dry_run(&draft, None, &edits).await?;
+            } else {
+                // Ask before anything below saves the draft, so saying no leaves it untouched
+                micropub_cli::profile_guard::check_before_publish(&draft, allow_profile_mismatch)?;
+                let allow_duplicate =
+                    micropub_cli::duplicates::confirm_before_publish(&draft, allow_duplicate)?;
+                if let Some(slug) = &slug {
+                    micropub_cli::publish::set_slug(&draft, slug)?;
+                }
+                micropub_cli::publish::add_syndication_targets(&draft, &syndicate).await?;
+                micropub_cli::publish::pick_categories_if_missing(&draft)?;
+                let started = Instant::now();
+                let result =
+                    micropub_cli::publish::cmd_publish(&draft, None, allow_duplicate).await?;
+                let summary = Summary::for_publish(&result, started.elapsed());
+                micropub_cli::summary::print(&result, &summary)?;
+            }
+            Ok(())
+        }
+        Commands::Backdate {
+            draft,
+            date,
+            dry_run,
+            allow_duplicate,
+            allow_profile_mismatch,
+        } => {
+            use chrono::DateTime;
+            let parsed_date = DateTime::parse_from_rfc3339(&date)
+                .context("Invalid date format. Use ISO 8601 (e.g., 2024-01-15T10:30:00Z)")?
+                .with_timezone(&chrono::Utc);
+            if dry_run {
+                return micropub_cli::publish::cmd_publish_dry_run(
+                    &draft,
+                    Some(parsed_date),
+                    &DraftEdits::default(),
+                )
+                .await;
+            }
+            // Ask before the category picker saves the draft
+            micropub_cli::profile_guard::check_before_publish(&draft, allow_profile_mismatch)?;
+            let allow_duplicate =
+                micropub_cli::duplicates::confirm_before_publish(&draft, allow_duplicate)?;
+            micropub_cli::publish::pick_categories_if_missing(&draft)?;
+            let started = Instant::now();
+            let result =
+                micropub_cli::publish::cmd_publish(&draft, Some(parsed_date), allow_duplicate)
+                    .await?;
+            let summary = Summary::for_publish(&result, started.elapsed());
+            micropub_cli::summary::print(&result, &summary)
+        }
+        Commands::Reply { url } => {
+            micropub_cli::draft::cmd_new_response(ResponseKind::Reply, &url)?;
+            Ok(())
+        }
+        Commands::Like { url } => {
+            micropub_cli::draft::cmd_new_response(ResponseKind::Like, &url)?;
+            Ok(())
+        }
+        Commands::Repost { url } => {
+            micropub_cli::draft::cmd_new_response(ResponseKind::Repost, &url)?;
+            Ok(())
+        }
+        Commands::Bookmark { url } => {
+            micropub_cli::draft::cmd_new_response(ResponseKind::Bookmark, &url)?;
+            Ok(())
+        }
+        Commands::Get { url, to_draft } => micropub_cli::operations::cmd_get(&url, to_draft).await,
+        Commands::Update { url } => {
+            micropub_cli::operations::cmd_update(&url).await?;
+            Ok(())
+        }
+        Commands::Delete { url } => {
+            micropub_cli::operations::cmd_delete(&url).await?;
+            Ok(())
+        }
+        Commands::Undelete { url } => {
+            micropub_cli::operations::cmd_undelete(&url).await?;
+            Ok(())
+        }
+        Commands::Reconcile { fix } => {
+            micropub_cli::reconcile::cmd_reconcile(fix).await?;
+            Ok(())
+        }
+        Commands::Retag {
+            from,
+            to,
+            server,
+            delay_ms,
+            dry_run,
+        } => {
+            micropub_cli::retag::cmd_retag(
+                &from,
+                &to,
+                server,
+                std::time::Duration::from_millis(delay_ms),
+                dry_run,
+            )
+            .await?;
+            Ok(())
+        }
+        Commands::Find { url } => {
+            micropub_cli::find::cmd_find(&url)?;
+            Ok(())
+        }
+        Commands::Debug { profile_name } => {
+            micropub_cli::debug::cmd_debug(&profile_name).await?;
+            Ok(())
+        }
+        Commands::Whoami => {
+            micropub_cli::operations::cmd_whoami().await?;
+            Ok(())
+        }
+        Commands::Posts {
+            limit,
+            offset,
+            notes,
+        } => {
+            micropub_cli::operations::cmd_list_posts(limit, offset, notes).await?;
+            Ok(())
+        }
+        Commands::Notes { command } => match command {
+            NotesCommands::Add { url, text } => micropub_cli::notes::cmd_notes_add(&url, &text),
+            NotesCommands::Show { url } => micropub_cli::notes::cmd_notes_show(&url),
+            NotesCommands::List => micropub_cli::notes::cmd_notes_list(),
+            NotesCommands::Rm { url, number } => {
+                micropub_cli::notes::cmd_notes_remove(&url, number)
+            }
+        },
+        Commands::Photo {
+            file,
+            caption,
+            alt,
+            categories,
+            dry_run,
+        } => {
+            micropub_cli::photo::cmd_photo(
+                &file,
+                caption.as_deref(),
+                alt.as_deref(),
+                &categories,
+                dry_run,
+            )
+            .await?;
+            Ok(())
+        }
+        Commands::Media {
+            command:
+                Some(MediaCommands::Upload {
+                    files,
+                    alt,
+                    markdown,
+                }),
+            ..
+        } => {
+            micropub_cli::operations::cmd_upload_media(&files, alt.as_deref(), markdown).await?;
+            Ok(())
+        }
+        Commands::Media {
+            command: Some(MediaCommands::Audit),
+            ..
+        } => {
+            micropub_cli::operations::cmd_media_audit()?;
+            Ok(())
+        }
+        Commands::Media {
+            command: None,
+            limit,
+            offset,
+        } => {
+            micropub_cli::operations::cmd_list_media(limit, offset).await?;
+            Ok(())
+        }
+        Commands::Schedule {
+            command: Some(cmd), ..
+        } => match cmd {
+            ScheduleCommands::Run => {
+                micropub_cli::schedule::cmd_schedule_run().await?;
+                Ok(())
+            }
+            ScheduleCommands::List => {
+                micropub_cli::schedule::cmd_schedule_list()?;
+                Ok(())
+            }
+            ScheduleCommands::Cancel { draft } => {
+                micropub_cli::schedule::cmd_schedule_cancel(&draft)?;
+                Ok(())
+            }
+        },
+        Commands::Schedule {
+            command: None,
+            draft,
+            at,
+        } => {
+            use chrono::DateTime;
+            let draft = draft.context("Draft ID is required")?;
+            let at = at.context("--at is required")?;
+            let parsed_date = DateTime::parse_from_rfc3339(&at)
+                .context("Invalid date format. Use ISO 8601 (e.g., 2024-01-15T10:30:00Z)")?
+                .with_timezone(&chrono::Utc);
+            micropub_cli::schedule::cmd_schedule(&draft, parsed_date)?;
+            Ok(())
+        }
+        Commands::Gc { dry_run, keep_days } => micropub_cli::gc::cmd_gc(keep_days, dry_run),
+        Commands::Export { dir, format } => micropub_cli::export::cmd_export(&dir, &format).await,
+        Commands::Import {
+            dir,
+            publish,
+            allow_duplicate,
+            allow_profile_mismatch,
+        } => {
+            micropub_cli::import::cmd_import(&dir, publish, allow_duplicate, allow_profile_mismatch)
+                .await
+        }
+        Commands::Archive { command } => match command {
+            ArchiveCommands::List {
+                category,
+                limit,
+                offset,
+            } => micropub_cli::archive::cmd_archive_list(category.as_deref(), limit, offset),
+            ArchiveCommands::Show { draft } => micropub_cli::archive::cmd_archive_show(&draft),
+            ArchiveCommands::Search { query } => micropub_cli::archive::cmd_archive_search(&query),
+            ArchiveCommands::Restore { draft } => {
+                micropub_cli::archive::cmd_archive_restore(&draft)
+            }
+        },
+        Commands::Queue { command } => match command {
+            QueueCommands::List => micropub_cli::queue::cmd_queue_list(),
+            QueueCommands::Flush => micropub_cli::queue::cmd_queue_flush().await,
+        },
+        Commands::PostChangelog {
+            from,
+            version,
+            title,
+            categories,
+            dry_run,
+        } => {
+            micropub_cli::changelog::cmd_post_changelog(
+                &from,
+                &version,
+                title.as_deref(),
+                &categories,
+                dry_run,
+            )
+            .await?;
+            Ok(())
+        }
+        Commands::SyndicateTargets => {
+            micropub_cli::operations::cmd_syndicate_targets().await?;
+            Ok(())
+        }
+        Commands::Categories { refresh } => {
+            micropub_cli::operations::cmd_categories(refresh).await?;
+            Ok(())
+        }
+        Commands::Completions { shell } => {
+            micropub_cli::completions::cmd_completions(shell, Cli::command())?;
+            Ok(())
+        }
+        Commands::Complete { kind } => {
+            micropub_cli::completions::cmd_complete(&kind)?;
+            Ok(())
+        }
+        #[cfg(feature = "tui")]
+        Commands::Tui => {
+            micropub_cli::tui::run().await?;
+            Ok(())
+        }
+        #[cfg(feature = "mcp")]
+        Commands::Mcp {
+            command:
+                Some(McpCommands::Install {
+                    client,
+                    print,
+                    read_only,
+                }),
+            ..
+        } => {
+            micropub_cli::mcp_install::cmd_mcp_install(&client, print, read_only)?;
+            Ok(())
+        }
+        #[cfg(feature = "mcp")]
+        Commands::Mcp {
+            command: None,
+            http,
+            port,
+            host,
+            read_only,
+        } => {
+            if http {
+                micropub_cli::mcp_http::run_http_server(&host, port, read_only).await?;
+            } else {
+                micropub_cli::mcp::run_server(read_only).await?;
+            }
+            Ok(())
+        }
+    }
+}
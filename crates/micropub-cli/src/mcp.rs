@@ -3,7 +3,7 @@
 
 use anyhow::Result;
 use base64::{engine::general_purpose, Engine as _};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SecondsFormat, Utc};
 use rmcp::handler::server::router::prompt::PromptRouter;
 use rmcp::handler::server::router::tool::ToolRouter;
 use rmcp::handler::server::wrapper::Parameters;
@@ -24,8 +24,8 @@ use rmcp::ErrorData as McpError;
 use rmcp::{schemars, RoleServer, ServerHandler, ServiceExt};
 
 use crate::config::Config;
-use crate::draft::Draft;
-use crate::draft_push::validate_draft_id;
+use crate::draft::{Draft, DraftId};
+use crate::duplicates;
 use crate::publish;
 
 /// Parameters for publish_post tool
@@ -39,6 +39,15 @@ pub struct PublishPostArgs {
     /// Optional comma-separated categories
     #[serde(skip_serializing_if = "Option::is_none")]
     pub categories: Option<String>,
+    /// Optional summary for feeds and previews
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Generate a summary from the content when none is given (default: false)
+    #[serde(default)]
+    pub auto_summary: bool,
+    /// Publish even if an identical post went out in the last few days (default: false)
+    #[serde(default)]
+    pub allow_duplicate: bool,
 }
 
 /// Parameters for create_draft tool
@@ -49,6 +58,12 @@ pub struct CreateDraftArgs {
     /// Optional title for the draft
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    /// Optional summary for feeds and previews
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Generate a summary from the content when none is given (default: false)
+    #[serde(default)]
+    pub auto_summary: bool,
 }
 
 /// Parameters for publish_backdate tool
@@ -59,6 +74,9 @@ pub struct PublishBackdateArgs {
     pub draft_id: String,
     /// ISO 8601 formatted date (e.g., 2024-01-15T10:30:00Z)
     pub date: String,
+    /// Publish even if an identical post went out in the last few days (default: false)
+    #[serde(default)]
+    pub allow_duplicate: bool,
 }
 
 /// Parameters for delete_post tool
@@ -69,6 +87,31 @@ pub struct DeletePostArgs {
     pub url: String,
 }
 
+/// Parameters for update_post tool
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct UpdatePostArgs {
+    /// The URL of the post to update
+    #[schemars(url)]
+    pub url: String,
+    /// Optional new content (replaces the existing content)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Optional new title (an empty string removes the title)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Optional comma-separated categories (replaces the existing categories)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<String>,
+}
+
+/// Parameters for undelete_post tool
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct UndeletePostArgs {
+    /// The URL of the deleted post to restore
+    #[schemars(url)]
+    pub url: String,
+}
+
 /// Parameters for list_posts tool
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ListPostsArgs {
@@ -80,6 +123,42 @@ pub struct ListPostsArgs {
     pub offset: usize,
 }
 
+/// Parameters for list_drafts tool
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ListDraftsArgs {
+    /// Number of drafts to retrieve (default: 20)
+    #[serde(default = "default_draft_limit")]
+    pub limit: usize,
+    /// Offset for pagination (default: 0)
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// What search_content looks through
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchScope {
+    /// Local drafts only
+    Drafts,
+    /// Published posts only
+    Posts,
+    #[default]
+    Both,
+}
+
+/// Parameters for search_content tool
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SearchContentArgs {
+    /// Text to look for in titles, content, and categories (case-insensitive)
+    pub query: String,
+    /// Where to search: drafts, posts, or both (default: both)
+    #[serde(default)]
+    pub scope: SearchScope,
+    /// Maximum matches to return for each of drafts and posts (default: 20)
+    #[serde(default = "default_draft_limit")]
+    pub limit: usize,
+}
+
 /// Parameters for view_draft tool
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ViewDraftArgs {
@@ -130,10 +209,40 @@ pub struct PushDraftArgs {
     pub backdate: Option<String>,
 }
 
+/// Parameters for get_server_config tool
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetServerConfigArgs {
+    /// Bypass the local cache and re-query the server (default: false)
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// Longest summary generated by `auto_summary`
+const AUTO_SUMMARY_MAX_CHARS: usize = 280;
+
+/// Pick an explicit summary, or derive one from the content if requested
+fn resolve_summary(summary: Option<String>, auto: bool, content: &str) -> Option<String> {
+    summary
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .or_else(|| {
+            if auto {
+                Some(crate::draft::auto_summary(content, AUTO_SUMMARY_MAX_CHARS))
+                    .filter(|s| !s.is_empty())
+            } else {
+                None
+            }
+        })
+}
+
 fn default_limit() -> usize {
     10
 }
 
+fn default_draft_limit() -> usize {
+    20
+}
+
 fn default_media_limit() -> usize {
     20
 }
@@ -188,11 +297,306 @@ pub struct CategorizedPostPromptArgs {
     pub categories: String,
 }
 
+/// Drafts untouched for this many days are considered stale in the weekly review
+const STALE_DRAFT_DAYS: i64 = 14;
+
+/// How many recent posts the weekly review looks at
+const WEEKLY_REVIEW_POST_LIMIT: usize = 50;
+
+/// Build the posting-stats and stale-draft context for the weekly-review prompt.
+///
+/// `drafts` pairs each draft with when it was last modified.
+pub fn weekly_review_summary(
+    posts: &[crate::operations::PostData],
+    drafts: &[(Draft, DateTime<Utc>)],
+    now: DateTime<Utc>,
+) -> String {
+    let published: Vec<DateTime<Utc>> = posts
+        .iter()
+        .filter_map(|p| DateTime::parse_from_rfc3339(p.published.as_deref()?).ok())
+        .map(|d| d.with_timezone(&Utc))
+        .collect();
+    let in_last = |days: i64| {
+        published
+            .iter()
+            .filter(|d| now.signed_duration_since(**d).num_days() < days)
+            .count()
+    };
+
+    let mut out = String::from("Posting stats:\n");
+    out.push_str(&format!("- Posts in the last 7 days: {}\n", in_last(7)));
+    out.push_str(&format!("- Posts in the last 30 days: {}\n", in_last(30)));
+    match published.iter().max() {
+        Some(last) => out.push_str(&format!(
+            "- Last post: {} ({} days ago)\n",
+            last.format("%Y-%m-%d"),
+            now.signed_duration_since(*last).num_days()
+        )),
+        None => out.push_str("- Last post: none found\n"),
+    }
+
+    let mut category_counts: std::collections::HashMap<&str, usize> =
+        std::collections::HashMap::new();
+    for post in posts {
+        for category in &post.categories {
+            *category_counts.entry(category.as_str()).or_default() += 1;
+        }
+    }
+    let mut top: Vec<(&str, usize)> = category_counts.into_iter().collect();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    if !top.is_empty() {
+        let names: Vec<String> = top
+            .iter()
+            .take(5)
+            .map(|(c, n)| format!("{} ({})", c, n))
+            .collect();
+        out.push_str(&format!("- Top categories: {}\n", names.join(", ")));
+    }
+
+    let stale: Vec<&(Draft, DateTime<Utc>)> = drafts
+        .iter()
+        .filter(|(_, modified)| now.signed_duration_since(*modified).num_days() >= STALE_DRAFT_DAYS)
+        .collect();
+
+    out.push_str(&format!(
+        "\nDrafts: {} total, {} untouched for {}+ days\n",
+        drafts.len(),
+        stale.len(),
+        STALE_DRAFT_DAYS
+    ));
+    for (draft, modified) in stale {
+        let title = draft.metadata.name.as_deref().unwrap_or("[untitled]");
+        out.push_str(&format!(
+            "- {} ({}), last edited {} days ago\n",
+            title,
+            draft.id,
+            now.signed_duration_since(*modified).num_days()
+        ));
+    }
+
+    out
+}
+
+/// Load local drafts with their last-modified times
+async fn drafts_with_mtime() -> Result<Vec<(Draft, DateTime<Utc>)>> {
+    crate::blocking::run(|| {
+        let mut drafts = Vec::new();
+
+        for id in Draft::list_all()? {
+            if let Ok(draft) = Draft::load(&id) {
+                let modified = crate::draft::paths::draft_path(&id)
+                    .ok()
+                    .and_then(|path| std::fs::metadata(path).ok())
+                    .and_then(|m| m.modified().ok())
+                    .map(DateTime::<Utc>::from)
+                    .unwrap_or_else(Utc::now);
+                drafts.push((draft, modified));
+            }
+        }
+
+        Ok(drafts)
+    })
+    .await
+}
+
+/// One page of `list_drafts`, most recently modified first, ending with the
+/// offset of the next page when there is one
+pub fn draft_page(mut drafts: Vec<(Draft, DateTime<Utc>)>, limit: usize, offset: usize) -> String {
+    if drafts.is_empty() {
+        return "No drafts found.".to_string();
+    }
+    drafts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.id.cmp(&b.0.id)));
+
+    let total = drafts.len();
+    let page: Vec<(Draft, DateTime<Utc>)> =
+        drafts.into_iter().skip(offset).take(limit.max(1)).collect();
+    if page.is_empty() {
+        return format!("No drafts at offset {} ({} in total).", offset, total);
+    }
+
+    let mut output = format!(
+        "Drafts {}-{} of {}, most recently modified first:\n",
+        offset + 1,
+        offset + page.len(),
+        total
+    );
+    for (draft, modified) in &page {
+        let title = draft.metadata.name.as_deref().unwrap_or("[untitled]");
+        output.push_str(&format!(
+            "- {} ({}), modified {}\n",
+            title,
+            draft.id,
+            modified.to_rfc3339_opts(SecondsFormat::Secs, true)
+        ));
+    }
+
+    let next = offset + page.len();
+    if next < total {
+        output.push_str(&format!(
+            "\n{} more; call list_drafts with offset {} for the next page.\n",
+            total - next,
+            next
+        ));
+    }
+    output
+}
+
+/// How many of the most recent posts search_content fetches and looks through
+const SEARCH_POST_LIMIT: usize = 200;
+
+/// Posts are fetched for search_content this many at a time
+const SEARCH_PAGE_SIZE: usize = 50;
+
+/// A draft or post found by search_content
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit {
+    pub title: String,
+    /// Draft ID or post URL
+    pub location: String,
+    /// Which of title, content, and category matched
+    pub matched_in: Vec<&'static str>,
+    pub snippet: Option<String>,
+}
+
+impl SearchHit {
+    fn find(
+        title: Option<&str>,
+        location: &str,
+        content: &str,
+        categories: &[String],
+        query: &str,
+    ) -> Option<Self> {
+        let matched_in = crate::draft::matched_fields(title, content, categories, query);
+        if matched_in.is_empty() {
+            return None;
+        }
+        Some(Self {
+            title: title.unwrap_or("[untitled]").to_string(),
+            location: location.to_string(),
+            snippet: crate::draft::match_snippet(content, query),
+            matched_in,
+        })
+    }
+}
+
+/// Drafts whose title, content, or categories contain `query`
+pub fn search_drafts(drafts: &[Draft], query: &str) -> Vec<SearchHit> {
+    drafts
+        .iter()
+        .filter_map(|draft| {
+            SearchHit::find(
+                draft.metadata.name.as_deref(),
+                &draft.id,
+                &draft.content,
+                &draft.metadata.category,
+                query,
+            )
+        })
+        .collect()
+}
+
+/// Posts whose name, content, or categories contain `query`
+pub fn search_posts(posts: &[crate::operations::PostData], query: &str) -> Vec<SearchHit> {
+    posts
+        .iter()
+        .filter_map(|post| {
+            SearchHit::find(
+                post.name.as_deref(),
+                &post.url,
+                &post.content,
+                &post.categories,
+                query,
+            )
+        })
+        .collect()
+}
+
+/// One section of search_content's answer, showing at most `limit` hits
+pub fn format_search_hits(heading: &str, hits: &[SearchHit], limit: usize) -> String {
+    if hits.is_empty() {
+        return format!("{}: no matches\n", heading);
+    }
+
+    let mut output = format!("{} ({} matches):\n", heading, hits.len());
+    for hit in hits.iter().take(limit) {
+        output.push_str(&format!(
+            "- {} ({}) [{}]\n",
+            hit.title,
+            hit.location,
+            hit.matched_in.join(", ")
+        ));
+        if let Some(snippet) = &hit.snippet {
+            output.push_str(&format!("  {}\n", snippet));
+        }
+    }
+    if hits.len() > limit {
+        output.push_str(&format!(
+            "  ...and {} more; narrow the query or raise the limit\n",
+            hits.len() - limit
+        ));
+    }
+    output
+}
+
+/// The active profile's most recent posts, up to `SEARCH_POST_LIMIT`
+async fn recent_posts() -> Result<Vec<crate::operations::PostData>> {
+    let mut posts = Vec::new();
+    while posts.len() < SEARCH_POST_LIMIT {
+        let page = crate::operations::fetch_posts(SEARCH_PAGE_SIZE, posts.len()).await?;
+        let last_page = page.len() < SEARCH_PAGE_SIZE;
+        posts.extend(page);
+        if last_page {
+            break;
+        }
+    }
+    Ok(posts)
+}
+
+/// Fetch the default profile's server config along with its effective media endpoint
+async fn default_server_config(
+    refresh: bool,
+) -> Result<(String, crate::server_config::ServerConfig, Option<String>)> {
+    use anyhow::Context;
+
+    let config = Config::load_async().await?;
+    let (profile_name, profile) = config.active_profile()?;
+    let profile_name = profile_name.to_string();
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured")?;
+    let token = crate::auth::load_fresh_token(&profile_name).await?;
+
+    let server_config =
+        crate::server_config::get_server_config(&profile_name, micropub_endpoint, &token, refresh)
+            .await?;
+
+    // The profile's discovered media endpoint wins over the q=config one, as in whoami
+    let media_endpoint = profile
+        .media_endpoint
+        .clone()
+        .or_else(|| server_config.media_endpoint.clone());
+
+    Ok((profile_name, server_config, media_endpoint))
+}
+
+/// Tools that only read, the ones `micropub mcp --read-only` registers
+pub const READ_ONLY_TOOLS: [&str; 7] = [
+    "list_drafts",
+    "view_draft",
+    "search_content",
+    "list_posts",
+    "list_media",
+    "whoami",
+    "get_server_config",
+];
+
 /// MCP server state
 #[derive(Clone)]
 pub struct MicropubMcp {
     tool_router: ToolRouter<MicropubMcp>,
     prompt_router: PromptRouter<MicropubMcp>,
+    read_only: bool,
 }
 
 impl MicropubMcp {
@@ -201,15 +605,50 @@ impl MicropubMcp {
         Ok(Self {
             tool_router: Self::tool_router(),
             prompt_router: Self::prompt_router(),
+            read_only: false,
         })
     }
+
+    /// A server that can look at posts, drafts, and media but never change them.
+    /// The prompts all end in posting, so they're left out too.
+    pub fn read_only() -> Result<Self> {
+        let mut tool_router = Self::tool_router();
+        for tool in tool_router.list_all() {
+            if !READ_ONLY_TOOLS.contains(&tool.name.as_ref()) {
+                tool_router.remove_route(&tool.name);
+            }
+        }
+        Ok(Self {
+            tool_router,
+            prompt_router: PromptRouter::new(),
+            read_only: true,
+        })
+    }
+
+    /// `read_only()` when `read_only` is set, otherwise `new()`
+    pub fn with_read_only(read_only: bool) -> Result<Self> {
+        if read_only {
+            Self::read_only()
+        } else {
+            Self::new()
+        }
+    }
+
+    /// Names of the tools this server offers
+    pub fn tool_names(&self) -> Vec<String> {
+        self.tool_router
+            .list_all()
+            .into_iter()
+            .map(|tool| tool.name.to_string())
+            .collect()
+    }
 }
 
 #[tool_router]
 impl MicropubMcp {
     /// Create and publish a post immediately
     #[tool(
-        description = "Create and publish a micropub post with optional title and categories. Automatically detects and uploads local image files (e.g., ![alt](~/photo.jpg) or <img src='/path/image.png'>) and replaces them with permanent URLs before publishing."
+        description = "Create and publish a micropub post with optional title, categories, and summary (or auto_summary to derive one from the content). Automatically detects and uploads local image files (e.g., ![alt](~/photo.jpg) or <img src='/path/image.png'>) and replaces them with permanent URLs before publishing."
     )]
     async fn publish_post(
         &self,
@@ -225,6 +664,7 @@ impl MicropubMcp {
 
         // Create a draft first
         let mut draft = Draft::new(uuid::Uuid::new_v4().to_string());
+        draft.metadata.summary = resolve_summary(args.summary, args.auto_summary, &args.content);
         draft.content = args.content;
         draft.metadata.name = args.title;
 
@@ -233,7 +673,30 @@ impl MicropubMcp {
             draft.metadata.category = cats.split(',').map(|s| s.trim().to_string()).collect();
         }
 
-        let draft_path = draft.save().map_err(|e| {
+        // Refuse a likely retry of a post that already went out
+        if !args.allow_duplicate {
+            let candidate = draft.clone();
+            let duplicate = crate::blocking::run(move || duplicates::find(&candidate))
+                .await
+                .map_err(|e| {
+                    McpError::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("Failed to check for duplicates: {}", e),
+                        None,
+                    )
+                })?;
+            if let Some(duplicate) = duplicate {
+                return Err(McpError::invalid_params(
+                    format!(
+                        "Not published: this matches {}. Call again with allow_duplicate: true to publish it anyway",
+                        duplicate.describe(Utc::now())
+                    ),
+                    None,
+                ));
+            }
+        }
+
+        let draft_path = draft.save_async().await.map_err(|e| {
             McpError::new(
                 ErrorCode::INTERNAL_ERROR,
                 format!("Failed to save draft: {}", e),
@@ -250,7 +713,8 @@ impl MicropubMcp {
             )
         })?;
 
-        let uploads = publish::cmd_publish(draft_path_str, None)
+        // Duplicates were checked before the draft was saved
+        let result = publish::cmd_publish(draft_path_str, None, true)
             .await
             .map_err(|e| {
                 McpError::new(
@@ -260,20 +724,47 @@ impl MicropubMcp {
                 )
             })?;
 
-        let mut message = String::from("Post published successfully!");
+        let mut message = match result.queued {
+            Some(_) => result.status("Post published"),
+            None => String::from("Post published successfully!"),
+        };
+        if let Some(ref url) = result.url {
+            message.push_str(&format!("\nURL: {}", url));
+        }
+        for site in &result.other_sites {
+            match (&site.queued, &site.url) {
+                (Some(_), _) => {
+                    message.push_str(&format!(
+                        "\n{}: {}",
+                        site.profile,
+                        site.status("Post published")
+                    ));
+                }
+                (None, Some(url)) => {
+                    message.push_str(&format!("\nURL ({}): {}", site.profile, url))
+                }
+                (None, None) => {}
+            }
+        }
 
-        if !uploads.is_empty() {
+        if !result.uploads.is_empty() {
             message.push_str("\n\nUploaded media:");
-            for (filename, url) in uploads {
+            for (filename, url) in result.uploads {
                 message.push_str(&format!("\n- {} -> {}", filename, url));
             }
         }
 
+        for warning in result.warnings {
+            message.push_str(&format!("\n\nWarning: {}", warning));
+        }
+
         Ok(CallToolResult::success(vec![Content::text(message)]))
     }
 
     /// Create a draft post without publishing
-    #[tool(description = "Create a draft micropub post for later editing and publishing")]
+    #[tool(
+        description = "Create a draft micropub post for later editing and publishing, with an optional summary"
+    )]
     async fn create_draft(
         &self,
         Parameters(args): Parameters<CreateDraftArgs>,
@@ -287,10 +778,11 @@ impl MicropubMcp {
         }
 
         let mut draft = Draft::new(uuid::Uuid::new_v4().to_string());
+        draft.metadata.summary = resolve_summary(args.summary, args.auto_summary, &args.content);
         draft.content = args.content;
         draft.metadata.name = args.title;
 
-        draft.save().map_err(|e| {
+        draft.save_async().await.map_err(|e| {
             McpError::new(
                 ErrorCode::INTERNAL_ERROR,
                 format!("Failed to create draft: {}", e),
@@ -304,10 +796,15 @@ impl MicropubMcp {
         ))]))
     }
 
-    /// List all draft posts
-    #[tool(description = "List all draft micropub posts")]
-    async fn list_drafts(&self) -> Result<CallToolResult, McpError> {
-        let draft_ids = Draft::list_all().map_err(|e| {
+    /// List draft posts a page at a time
+    #[tool(
+        description = "List draft micropub posts with their last-modified times, most recently modified first, with pagination"
+    )]
+    async fn list_drafts(
+        &self,
+        Parameters(args): Parameters<ListDraftsArgs>,
+    ) -> Result<CallToolResult, McpError> {
+        let drafts = drafts_with_mtime().await.map_err(|e| {
             McpError::new(
                 ErrorCode::INTERNAL_ERROR,
                 format!("Failed to list drafts: {}", e),
@@ -315,24 +812,11 @@ impl MicropubMcp {
             )
         })?;
 
-        if draft_ids.is_empty() {
-            return Ok(CallToolResult::success(vec![Content::text(
-                "No drafts found.",
-            )]));
-        }
-
-        let mut output = String::from("Drafts:\n");
-        for id in draft_ids {
-            if let Ok(draft) = Draft::load(&id) {
-                let title = draft
-                    .metadata
-                    .name
-                    .unwrap_or_else(|| "[untitled]".to_string());
-                output.push_str(&format!("- {} ({})\n", title, id));
-            }
-        }
-
-        Ok(CallToolResult::success(vec![Content::text(output)]))
+        Ok(CallToolResult::success(vec![Content::text(draft_page(
+            drafts,
+            args.limit,
+            args.offset,
+        ))]))
     }
 
     /// Publish a draft with a backdated timestamp
@@ -342,7 +826,7 @@ impl MicropubMcp {
         Parameters(args): Parameters<PublishBackdateArgs>,
     ) -> Result<CallToolResult, McpError> {
         // Validate draft_id format to prevent path traversal
-        validate_draft_id(&args.draft_id)
+        let draft_id = DraftId::parse(&args.draft_id)
             .map_err(|e| McpError::invalid_params(format!("Invalid draft ID: {}", e), None))?;
 
         // Parse the date
@@ -359,15 +843,13 @@ impl MicropubMcp {
             .with_timezone(&Utc);
 
         // Load draft path
-        let draft_path = crate::config::get_drafts_dir()
-            .map_err(|e| {
-                McpError::new(
-                    ErrorCode::INTERNAL_ERROR,
-                    format!("Failed to get drafts dir: {}", e),
-                    None,
-                )
-            })?
-            .join(format!("{}.md", args.draft_id));
+        let draft_path = draft_id.draft_path().map_err(|e| {
+            McpError::new(
+                ErrorCode::INTERNAL_ERROR,
+                format!("Failed to get drafts dir: {}", e),
+                None,
+            )
+        })?;
 
         if !draft_path.exists() {
             return Err(McpError::invalid_params(
@@ -385,25 +867,42 @@ impl MicropubMcp {
             )
         })?;
 
-        let uploads = publish::cmd_publish(draft_path_str, Some(parsed_date))
+        let result = publish::cmd_publish(draft_path_str, Some(parsed_date), args.allow_duplicate)
             .await
-            .map_err(|e| {
-                McpError::new(
+            .map_err(|e| match e.downcast_ref::<duplicates::DuplicateError>() {
+                Some(duplicates::DuplicateError(duplicate)) => McpError::invalid_params(
+                    format!(
+                        "Not published: this matches {}. Call again with allow_duplicate: true to publish it anyway",
+                        duplicate.describe(Utc::now())
+                    ),
+                    None,
+                ),
+                None => McpError::new(
                     ErrorCode::INTERNAL_ERROR,
                     format!("Failed to publish: {}", e),
                     None,
-                )
+                ),
             })?;
 
-        let mut message = format!("Post published with backdated timestamp: {}", args.date);
+        let mut message = match result.queued {
+            Some(_) => result.status("Post published"),
+            None => format!("Post published with backdated timestamp: {}", args.date),
+        };
+        if let Some(ref url) = result.url {
+            message.push_str(&format!("\nURL: {}", url));
+        }
 
-        if !uploads.is_empty() {
+        if !result.uploads.is_empty() {
             message.push_str("\n\nUploaded media:");
-            for (filename, url) in uploads {
+            for (filename, url) in result.uploads {
                 message.push_str(&format!("\n- {} -> {}", filename, url));
             }
         }
 
+        for warning in result.warnings {
+            message.push_str(&format!("\n\nWarning: {}", warning));
+        }
+
         Ok(CallToolResult::success(vec![Content::text(message)]))
     }
 
@@ -421,7 +920,7 @@ impl MicropubMcp {
             ));
         }
 
-        crate::operations::cmd_delete(&args.url)
+        crate::operations::delete_post(&args.url)
             .await
             .map_err(|e| {
                 McpError::new(
@@ -437,10 +936,87 @@ impl MicropubMcp {
         ))]))
     }
 
+    /// Update a published post
+    #[tool(
+        description = "Update a published micropub post by URL, replacing its content, title, and/or comma-separated categories. Only the fields given are changed."
+    )]
+    async fn update_post(
+        &self,
+        Parameters(args): Parameters<UpdatePostArgs>,
+    ) -> Result<CallToolResult, McpError> {
+        // Validate URL is not empty
+        if args.url.is_empty() {
+            return Err(McpError::invalid_params(
+                "URL cannot be empty".to_string(),
+                None,
+            ));
+        }
+
+        let categories: Option<Vec<String>> = args.categories.map(|cats| {
+            cats.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        });
+
+        let request = crate::operations::build_update_request(
+            &args.url,
+            args.content.as_deref(),
+            args.title.as_deref(),
+            categories.as_deref(),
+        )
+        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+
+        crate::operations::send_update(&request)
+            .await
+            .map_err(|e| {
+                McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to update post: {}", e),
+                    None,
+                )
+            })?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Post updated: {}",
+            args.url
+        ))]))
+    }
+
+    /// Restore a deleted post
+    #[tool(description = "Restore a previously deleted micropub post by URL")]
+    async fn undelete_post(
+        &self,
+        Parameters(args): Parameters<UndeletePostArgs>,
+    ) -> Result<CallToolResult, McpError> {
+        // Validate URL is not empty
+        if args.url.is_empty() {
+            return Err(McpError::invalid_params(
+                "URL cannot be empty".to_string(),
+                None,
+            ));
+        }
+
+        crate::operations::undelete_post(&args.url)
+            .await
+            .map_err(|e| {
+                McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to undelete post: {}", e),
+                    None,
+                )
+            })?;
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Post restored: {}",
+            args.url
+        ))]))
+    }
+
     /// Get authentication status
     #[tool(description = "Check which micropub account is currently authenticated")]
     async fn whoami(&self) -> Result<CallToolResult, McpError> {
-        let config = Config::load().map_err(|e| {
+        let config = Config::load_async().await.map_err(|e| {
             McpError::new(
                 ErrorCode::INTERNAL_ERROR,
                 format!("Failed to load config: {}", e),
@@ -448,7 +1024,7 @@ impl MicropubMcp {
             )
         })?;
 
-        let profile_name = &config.default_profile;
+        let profile_name = &config.active_profile_name();
         if profile_name.is_empty() {
             return Ok(CallToolResult::success(vec![Content::text(
                 "No profile configured. Run 'micropub auth <domain>' first.",
@@ -476,6 +1052,37 @@ impl MicropubMcp {
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
+    /// Describe what the micropub server supports
+    #[tool(
+        description = "Get the micropub server's capabilities from q=config: media endpoint, syndication targets, supported queries and post types. Check this before uploading media or syndicating."
+    )]
+    async fn get_server_config(
+        &self,
+        Parameters(args): Parameters<GetServerConfigArgs>,
+    ) -> Result<CallToolResult, McpError> {
+        let (profile_name, server_config, media_endpoint) =
+            default_server_config(args.refresh).await.map_err(|e| {
+                McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to get server config: {}", e),
+                    None,
+                )
+            })?;
+
+        let response = serde_json::json!({
+            "profile": profile_name,
+            "media_endpoint": media_endpoint,
+            "supports_media_upload": media_endpoint.is_some(),
+            "syndicate_to": server_config.syndicate_to,
+            "q": server_config.q,
+            "post_types": server_config.post_types,
+        });
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&response).unwrap_or_else(|_| response.to_string()),
+        )]))
+    }
+
     /// List published posts
     #[tool(description = "List published micropub posts with pagination")]
     async fn list_posts(
@@ -502,7 +1109,10 @@ impl MicropubMcp {
         for post in posts {
             let title = post.name.unwrap_or_else(|| "[untitled]".to_string());
             output.push_str(&format!("- {} ({})\n", title, post.url));
-            output.push_str(&format!("  Published: {}\n", post.published));
+            output.push_str(&format!(
+                "  Published: {}\n",
+                post.published.as_deref().unwrap_or("(no date)")
+            ));
             if !post.categories.is_empty() {
                 output.push_str(&format!("  Categories: {}\n", post.categories.join(", ")));
             }
@@ -520,6 +1130,61 @@ impl MicropubMcp {
         Ok(CallToolResult::success(vec![Content::text(output)]))
     }
 
+    /// Search drafts and published posts
+    #[tool(
+        description = "Search drafts and/or published posts (scope: drafts, posts, or both) for text in their titles, content, or categories. Returns matching draft IDs and post URLs with a snippet of the matching line. Posts are searched among the 200 most recent."
+    )]
+    async fn search_content(
+        &self,
+        Parameters(args): Parameters<SearchContentArgs>,
+    ) -> Result<CallToolResult, McpError> {
+        if args.query.trim().is_empty() {
+            return Err(McpError::invalid_params(
+                "Query cannot be empty".to_string(),
+                None,
+            ));
+        }
+        let limit = args.limit.max(1);
+        let mut output = format!("Results for '{}':\n\n", args.query);
+
+        if args.scope != SearchScope::Posts {
+            let drafts = Draft::load_all_async().await.map_err(|e| {
+                McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to load drafts: {}", e),
+                    None,
+                )
+            })?;
+            let hits = search_drafts(&drafts, &args.query);
+            output.push_str(&format_search_hits("Drafts", &hits, limit));
+        }
+
+        if args.scope != SearchScope::Drafts {
+            if args.scope == SearchScope::Both {
+                output.push('\n');
+            }
+            match recent_posts().await {
+                Ok(posts) => {
+                    let hits = search_posts(&posts, &args.query);
+                    output.push_str(&format_search_hits("Posts", &hits, limit));
+                }
+                // Draft matches are still worth returning when the server is unreachable
+                Err(e) if args.scope == SearchScope::Both => {
+                    output.push_str(&format!("Posts: couldn't be searched: {}\n", e));
+                }
+                Err(e) => {
+                    return Err(McpError::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("Failed to fetch posts: {}", e),
+                        None,
+                    ));
+                }
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(output)]))
+    }
+
     /// View a specific draft
     #[tool(description = "View the content of a specific draft")]
     async fn view_draft(
@@ -527,10 +1192,11 @@ impl MicropubMcp {
         Parameters(args): Parameters<ViewDraftArgs>,
     ) -> Result<CallToolResult, McpError> {
         // Validate draft_id format to prevent path traversal
-        validate_draft_id(&args.draft_id)
+        let draft_id = DraftId::parse(&args.draft_id)
             .map_err(|e| McpError::invalid_params(format!("Invalid draft ID: {}", e), None))?;
 
-        let draft = Draft::load(&args.draft_id)
+        let draft = Draft::load_async(draft_id.as_str())
+            .await
             .map_err(|e| McpError::invalid_params(format!("Failed to load draft: {}", e), None))?;
 
         let mut output = String::new();
@@ -556,15 +1222,16 @@ impl MicropubMcp {
         &self,
         Parameters(args): Parameters<ListMediaArgs>,
     ) -> Result<CallToolResult, McpError> {
-        let media_items = crate::operations::fetch_media(args.limit, args.offset)
-            .await
-            .map_err(|e| {
-                McpError::new(
-                    ErrorCode::INTERNAL_ERROR,
-                    format!("Failed to fetch media: {}", e),
-                    None,
-                )
-            })?;
+        let (media_items, server_error) =
+            crate::operations::fetch_media_or_local(args.limit, args.offset)
+                .await
+                .map_err(|e| {
+                    McpError::new(
+                        ErrorCode::INTERNAL_ERROR,
+                        format!("Failed to fetch media: {}", e),
+                        None,
+                    )
+                })?;
 
         if media_items.is_empty() {
             return Ok(CallToolResult::success(vec![Content::text(
@@ -572,13 +1239,22 @@ impl MicropubMcp {
             )]));
         }
 
-        let mut output = String::from("Media files:\n\n");
+        let mut output = match server_error {
+            Some(e) => format!(
+                "The server couldn't list media ({}); these are uploads recorded locally:\n\n",
+                e
+            ),
+            None => String::from("Media files:\n\n"),
+        };
         for item in media_items {
             output.push_str(&format!("- {}\n", item.url));
             if let Some(ref name) = item.name {
                 output.push_str(&format!("  Name: {}\n", name));
             }
-            output.push_str(&format!("  Uploaded: {}\n\n", item.uploaded));
+            output.push_str(&format!(
+                "  Uploaded: {}\n\n",
+                item.uploaded.as_deref().unwrap_or("(no date)")
+            ));
         }
 
         Ok(CallToolResult::success(vec![Content::text(output)]))
@@ -619,7 +1295,7 @@ impl MicropubMcp {
         }
 
         // Get config and profile
-        let config = Config::load().map_err(|e| {
+        let config = Config::load_async().await.map_err(|e| {
             McpError::new(
                 ErrorCode::INTERNAL_ERROR,
                 format!("Failed to load config: {}", e),
@@ -627,7 +1303,7 @@ impl MicropubMcp {
             )
         })?;
 
-        let profile_name = &config.default_profile;
+        let profile_name = &config.active_profile_name();
         if profile_name.is_empty() {
             return Err(McpError::new(
                 ErrorCode::INTERNAL_ERROR,
@@ -652,13 +1328,15 @@ impl MicropubMcp {
             )
         })?;
 
-        let token = crate::config::load_token(profile_name).map_err(|e| {
-            McpError::new(
-                ErrorCode::INTERNAL_ERROR,
-                format!("Failed to load token: {}", e),
-                None,
-            )
-        })?;
+        let token = crate::auth::load_fresh_token(profile_name)
+            .await
+            .map_err(|e| {
+                McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Failed to load token: {}", e),
+                    None,
+                )
+            })?;
 
         // Handle file_path upload
         let (url, filename_str, mime_type) = if let Some(file_path) = args.file_path {
@@ -673,53 +1351,78 @@ impl MicropubMcp {
 
             let mime = mime_guess::from_path(&resolved_path).first_or_octet_stream();
 
-            let url = crate::media::upload_file(media_endpoint, &token, &resolved_path)
-                .await
-                .map_err(|e| {
-                    McpError::new(
-                        ErrorCode::INTERNAL_ERROR,
-                        format!("Upload failed: {}", e),
-                        None,
-                    )
-                })?;
+            let url = crate::media::upload_or_reuse(
+                media_endpoint,
+                &token,
+                &resolved_path,
+                profile_name,
+                args.alt_text.as_deref(),
+            )
+            .await
+            .map(|upload| upload.url)
+            .map_err(|e| {
+                McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Upload failed: {}", e),
+                    None,
+                )
+            })?;
 
             (url, filename, mime.to_string())
         } else if let Some(file_data) = args.file_data {
-            let filename = args.filename.unwrap(); // Already validated above
+            // Already validated above; keep only the final component so a
+            // filename like "../x" can't write outside the temp directory
+            let filename = args
+                .filename
+                .as_deref()
+                .and_then(|f| std::path::Path::new(f).file_name())
+                .and_then(|n| n.to_str())
+                .ok_or_else(|| McpError::invalid_params("Invalid filename".to_string(), None))?
+                .to_string();
 
             // Decode base64
             let decoded = general_purpose::STANDARD.decode(&file_data).map_err(|e| {
                 McpError::invalid_params(format!("Invalid base64 data: {}", e), None)
             })?;
 
-            // Write to temp file
-            let temp_dir = std::env::temp_dir();
+            // Write to a fresh temp directory so the upload keeps its filename
+            let temp_dir =
+                std::env::temp_dir().join(format!("micropub-upload-{}", uuid::Uuid::new_v4()));
             let temp_path = temp_dir.join(&filename);
 
-            std::fs::write(&temp_path, decoded).map_err(|e| {
-                McpError::new(
-                    ErrorCode::INTERNAL_ERROR,
-                    format!("Failed to write temp file: {}", e),
-                    None,
-                )
-            })?;
-
-            let mime = mime_guess::from_path(&temp_path).first_or_octet_stream();
-
-            let url = crate::media::upload_file(media_endpoint, &token, &temp_path)
-                .await
+            std::fs::create_dir(&temp_dir)
+                .and_then(|_| std::fs::write(&temp_path, decoded))
                 .map_err(|e| {
-                    // Clean up temp file
-                    let _ = std::fs::remove_file(&temp_path);
                     McpError::new(
                         ErrorCode::INTERNAL_ERROR,
-                        format!("Upload failed: {}", e),
+                        format!("Failed to write temp file: {}", e),
                         None,
                     )
                 })?;
 
+            let mime = mime_guess::from_path(&temp_path).first_or_octet_stream();
+
+            let url = crate::media::upload_or_reuse(
+                media_endpoint,
+                &token,
+                &temp_path,
+                profile_name,
+                args.alt_text.as_deref(),
+            )
+            .await
+            .map(|upload| upload.url)
+            .map_err(|e| {
+                // Clean up temp file
+                let _ = std::fs::remove_dir_all(&temp_dir);
+                McpError::new(
+                    ErrorCode::INTERNAL_ERROR,
+                    format!("Upload failed: {}", e),
+                    None,
+                )
+            })?;
+
             // Clean up temp file
-            let _ = std::fs::remove_file(&temp_path);
+            let _ = std::fs::remove_dir_all(&temp_dir);
 
             (url, filename, mime.to_string())
         } else {
@@ -728,11 +1431,7 @@ impl MicropubMcp {
 
         // Build response
         let alt_text = args.alt_text.unwrap_or_default();
-        let markdown = if alt_text.is_empty() {
-            format!("![]({})", url)
-        } else {
-            format!("![{}]({})", alt_text, url)
-        };
+        let markdown = crate::operations::markdown_image(&alt_text, &url);
 
         let response = serde_json::json!({
             "url": url,
@@ -755,7 +1454,7 @@ impl MicropubMcp {
         Parameters(args): Parameters<PushDraftArgs>,
     ) -> Result<CallToolResult, McpError> {
         // Validate draft_id format to prevent path traversal
-        validate_draft_id(&args.draft_id)
+        let draft_id = DraftId::parse(&args.draft_id)
             .map_err(|e| McpError::invalid_params(format!("Invalid draft ID: {}", e), None))?;
 
         // Parse backdate if provided
@@ -777,7 +1476,7 @@ impl MicropubMcp {
         };
 
         // Push draft to server
-        let result = crate::draft_push::cmd_push_draft(&args.draft_id, backdate_parsed)
+        let result = crate::draft_push::cmd_push_draft(draft_id.as_str(), backdate_parsed)
             .await
             .map_err(|e| {
                 McpError::new(
@@ -792,6 +1491,8 @@ impl MicropubMcp {
             "url": result.url,
             "is_update": result.is_update,
             "status": "server-draft",
+            "pending": result.pending,
+            "changed": result.changed,
             "uploaded_media": result.uploads.iter().map(|(filename, url)| {
                 serde_json::json!({
                     "filename": filename,
@@ -1049,6 +1750,45 @@ impl MicropubMcp {
         })
     }
 
+    /// Weekly review of posting activity and abandoned drafts
+    #[prompt(
+        name = "weekly-review",
+        description = "Review recent posting activity and stale drafts, then plan the week's posts"
+    )]
+    async fn weekly_review(&self) -> GetPromptResult {
+        let posts = crate::operations::fetch_posts(WEEKLY_REVIEW_POST_LIMIT, 0).await;
+        let drafts = drafts_with_mtime().await.unwrap_or_default();
+
+        let mut context =
+            weekly_review_summary(posts.as_deref().unwrap_or_default(), &drafts, Utc::now());
+        if let Err(e) = posts {
+            context.push_str(&format!("\n(Could not fetch published posts: {})\n", e));
+        }
+
+        GetPromptResult {
+            description: Some("Weekly posting review and plan".to_string()),
+            messages: vec![
+                PromptMessage::new_text(
+                    PromptMessageRole::User,
+                    format!(
+                        "Help me review my week of posting and plan what's next.\n\n{}",
+                        context
+                    ),
+                ),
+                PromptMessage::new_text(
+                    PromptMessageRole::Assistant,
+                    "Here's how I'll approach your weekly review:\n\
+                     1. Summarize how your posting cadence compares to recent weeks\n\
+                     2. Go through the stale drafts and suggest which are worth finishing, merging, or dropping\n\
+                     3. Propose a posting plan for the coming week, mixing revived drafts with new ideas\n\n\
+                     I can open any draft with 'view_draft' and publish or push it when you're ready. \
+                     Which drafts would you like to start with?"
+                        .to_string(),
+                ),
+            ],
+        }
+    }
+
     /// General micropub posting workflow
     #[prompt(
         name = "new-post",
@@ -1082,6 +1822,19 @@ impl MicropubMcp {
 #[prompt_handler(router = self.prompt_router)]
 impl ServerHandler for MicropubMcp {
     fn get_info(&self) -> ServerInfo {
+        if self.read_only {
+            return ServerInfo {
+                protocol_version: ProtocolVersion::V_2024_11_05,
+                capabilities: ServerCapabilities::builder().enable_tools().build(),
+                server_info: Implementation::from_build_env(),
+                instructions: Some(
+                    "Read-only Micropub MCP server: list, view, and search drafts and published \
+                     posts, list uploaded media, check the signed-in account, and read the server config. \
+                     Nothing can be posted, edited, or deleted from here."
+                        .to_string(),
+                ),
+            };
+        }
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
             capabilities: ServerCapabilities::builder()
@@ -1099,6 +1852,8 @@ impl ServerHandler for MicropubMcp {
                  - Drafts remain editable locally and can be re-pushed to update\n\
                  - Use 'publish_post' to change server draft to published status\n\
                  - Supports media upload and backdating when pushing drafts\n\n\
+                 SERVER CAPABILITIES:\n\
+                 - Use 'get_server_config' to see the media endpoint, syndication targets, and supported post types\n\n\
                  All uploads and draft operations require authentication via 'micropub auth <domain>' first."
                     .to_string(),
             ),
@@ -1107,12 +1862,17 @@ impl ServerHandler for MicropubMcp {
 }
 
 /// Run the MCP server
-pub async fn run_server() -> Result<()> {
-    eprintln!("Starting Micropub MCP server...");
+pub async fn run_server(read_only: bool) -> Result<()> {
+    eprintln!(
+        "Starting Micropub MCP server{}...",
+        if read_only { " (read-only)" } else { "" }
+    );
     eprintln!("Ready to receive requests via stdio");
 
     // Create server and serve via stdio
-    let service = MicropubMcp::new()?.serve(stdio()).await?;
+    let service = MicropubMcp::with_read_only(read_only)?
+        .serve(stdio())
+        .await?;
 
     // Wait for shutdown
     service.waiting().await?;
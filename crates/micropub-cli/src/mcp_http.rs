@@ -0,0 +1,187 @@
+// ABOUTME: Streamable HTTP transport for the MCP server (`micropub mcp --http`)
+// ABOUTME: rmcp's streamable-HTTP service on /mcp behind a bearer token, with capped, expiring sessions
+
+use anyhow::{bail, Context, Result};
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::header::{AUTHORIZATION, CONTENT_LENGTH, ORIGIN, WWW_AUTHENTICATE};
+use axum::http::{HeaderValue, Method, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use http_body_util::Limited;
+use rmcp::transport::streamable_http_server::session::local::{LocalSessionManager, SessionConfig};
+use rmcp::transport::streamable_http_server::session::SessionManager;
+use rmcp::transport::streamable_http_server::{StreamableHttpServerConfig, StreamableHttpService};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::mcp::MicropubMcp;
+
+const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Largest JSON-RPC message accepted; uploads go by file path, not in the body
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// How long a request may take to be answered. Tool results arrive on the
+/// response's event stream, which this doesn't cut off.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How many sessions may be open, and how long one may sit unused before it's closed
+#[derive(Debug, Clone, Copy)]
+pub struct SessionLimits {
+    pub max_sessions: usize,
+    pub idle_timeout: Duration,
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self {
+            max_sessions: 32,
+            idle_timeout: Duration::from_secs(30 * 60),
+        }
+    }
+}
+
+/// What every request is checked against before rmcp sees it
+struct Guard {
+    token: Option<String>,
+    limits: SessionLimits,
+    sessions: Arc<LocalSessionManager>,
+}
+
+impl Guard {
+    /// Whether the request carries the configured bearer token. Without one,
+    /// only requests lacking an Origin header (i.e. not from a browser page) pass.
+    fn authorized(&self, req: &Request) -> bool {
+        let Some(token) = &self.token else {
+            return !req.headers().contains_key(ORIGIN);
+        };
+        req.headers()
+            .get(AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .is_some_and(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn status(code: StatusCode, message: &'static str) -> Response {
+    (code, message).into_response()
+}
+
+async fn guard(State(guard): State<Arc<Guard>>, req: Request, next: Next) -> Response {
+    if !guard.authorized(&req) {
+        let mut response = status(StatusCode::UNAUTHORIZED, "Unauthorized");
+        response
+            .headers_mut()
+            .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        return response;
+    }
+
+    let too_large = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok()?.parse::<usize>().ok())
+        .is_some_and(|length| length > MAX_BODY_BYTES);
+    if too_large {
+        return status(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large");
+    }
+
+    let session_id = req
+        .headers()
+        .get(SESSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    if let Some(id) = session_id {
+        let known = guard.sessions.has_session(&id.into()).await;
+        if !known.unwrap_or(false) {
+            return status(StatusCode::NOT_FOUND, "Unknown session");
+        }
+    } else if req.method() == Method::POST
+        // A POST without a session starts one
+        && guard.sessions.sessions.read().await.len() >= guard.limits.max_sessions
+    {
+        return status(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Too many open MCP sessions; end one with DELETE or try again later",
+        );
+    }
+
+    // Chunked bodies carry no length, so the limit is also enforced while reading
+    let req = req.map(|body| Body::new(Limited::new(body, MAX_BODY_BYTES)));
+    match tokio::time::timeout(REQUEST_TIMEOUT, next.run(req)).await {
+        Ok(response) => response,
+        Err(_) => status(StatusCode::GATEWAY_TIMEOUT, "MCP request timed out"),
+    }
+}
+
+/// Serve MCP on `listener` until the process exits; `token` guards every request
+/// and `read_only` limits each session to the read-only tools
+pub async fn serve(listener: TcpListener, token: Option<String>, read_only: bool) -> Result<()> {
+    serve_with_limits(listener, token, read_only, SessionLimits::default()).await
+}
+
+/// `serve` with its own session cap and idle timeout
+pub async fn serve_with_limits(
+    listener: TcpListener,
+    token: Option<String>,
+    read_only: bool,
+    limits: SessionLimits,
+) -> Result<()> {
+    listener.set_nonblocking(true)?;
+    let listener = tokio::net::TcpListener::from_std(listener)?;
+
+    let sessions = Arc::new(LocalSessionManager {
+        sessions: Default::default(),
+        session_config: SessionConfig {
+            keep_alive: Some(limits.idle_timeout),
+            ..Default::default()
+        },
+    });
+    let mcp = StreamableHttpService::new(
+        move || MicropubMcp::with_read_only(read_only).map_err(std::io::Error::other),
+        Arc::clone(&sessions),
+        StreamableHttpServerConfig::default(),
+    );
+    let guard_state = Arc::new(Guard {
+        token,
+        limits,
+        sessions,
+    });
+    let app = Router::new()
+        .route_service("/mcp", mcp)
+        .layer(middleware::from_fn_with_state(guard_state, guard));
+
+    axum::serve(listener, app)
+        .await
+        .context("MCP HTTP server failed")
+}
+
+/// Listen on `host:port` with the token from `[mcp] http_token` in config.toml
+pub async fn run_http_server(host: &str, port: u16, read_only: bool) -> Result<()> {
+    let token = Config::load_async().await?.mcp.http_token;
+    let addr: SocketAddr = format!("{}:{}", host, port)
+        .parse()
+        .with_context(|| format!("Invalid address: {}:{}", host, port))?;
+    if token.is_none() && !addr.ip().is_loopback() {
+        bail!(
+            "Set http_token under [mcp] in config.toml before serving MCP on {}",
+            addr.ip()
+        );
+    }
+
+    let listener =
+        TcpListener::bind(addr).with_context(|| format!("Failed to listen on {}", addr))?;
+    eprintln!(
+        "Starting Micropub MCP server{}...",
+        if read_only { " (read-only)" } else { "" }
+    );
+    eprintln!("Ready to receive requests at http://{}/mcp", addr);
+    serve(listener, token, read_only).await
+}
@@ -0,0 +1,225 @@
+// ABOUTME: `micropub mcp install`: registers the MCP server with assistant apps
+// ABOUTME: Merges a server entry into Claude Desktop, Cursor, or Zed config, keeping a backup
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the entry in the client's server list
+const SERVER_NAME: &str = "micropub";
+
+/// An app that can launch MCP servers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum McpClient {
+    Claude,
+    Cursor,
+    Zed,
+}
+
+impl McpClient {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "claude" => Ok(McpClient::Claude),
+            "cursor" => Ok(McpClient::Cursor),
+            "zed" => Ok(McpClient::Zed),
+            _ => bail!("Unknown MCP client '{}'; use claude, cursor, or zed", name),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            McpClient::Claude => "Claude Desktop",
+            McpClient::Cursor => "Cursor",
+            McpClient::Zed => "Zed",
+        }
+    }
+
+    /// The client's config file on this platform
+    pub fn config_path(self) -> Result<PathBuf> {
+        let home = || dirs::home_dir().context("Could not determine home directory");
+        Ok(match self {
+            McpClient::Claude => dirs::config_dir()
+                .context("Could not determine config directory")?
+                .join("Claude")
+                .join("claude_desktop_config.json"),
+            McpClient::Cursor => home()?.join(".cursor").join("mcp.json"),
+            McpClient::Zed if cfg!(windows) => dirs::config_dir()
+                .context("Could not determine config directory")?
+                .join("Zed")
+                .join("settings.json"),
+            McpClient::Zed => home()?.join(".config").join("zed").join("settings.json"),
+        })
+    }
+
+    /// Key of the object holding server entries
+    fn servers_key(self) -> &'static str {
+        match self {
+            McpClient::Claude | McpClient::Cursor => "mcpServers",
+            McpClient::Zed => "context_servers",
+        }
+    }
+
+    /// The entry that launches `micropub mcp`, with `--profile` when one was chosen
+    pub fn server_entry(self, command: &str, profile: Option<&str>, read_only: bool) -> Value {
+        let mut args: Vec<&str> = Vec::new();
+        if let Some(profile) = profile {
+            args.extend(["--profile", profile]);
+        }
+        args.push("mcp");
+        if read_only {
+            args.push("--read-only");
+        }
+
+        match self {
+            McpClient::Claude | McpClient::Cursor => {
+                json!({ "command": command, "args": args, "env": {} })
+            }
+            McpClient::Zed => {
+                json!({ "source": "custom", "command": command, "args": args, "env": {} })
+            }
+        }
+    }
+
+    /// The config snippet to paste by hand
+    fn snippet(self, entry: &Value) -> String {
+        let snippet = json!({ self.servers_key(): { SERVER_NAME: entry } });
+        serde_json::to_string_pretty(&snippet).unwrap_or_default()
+    }
+}
+
+/// Add the micropub entry to a client's config, replacing an older one and
+/// keeping every other setting
+pub fn merge_entry(existing: &str, client: McpClient, entry: Value) -> Result<String> {
+    let mut config: Value = if existing.trim().is_empty() {
+        Value::Object(Map::new())
+    } else {
+        serde_json::from_str(existing).context("Config file isn't plain JSON")?
+    };
+
+    let servers = config
+        .as_object_mut()
+        .context("Config file isn't a JSON object")?
+        .entry(client.servers_key())
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .with_context(|| format!("`{}` isn't a JSON object", client.servers_key()))?;
+    servers.insert(SERVER_NAME.to_string(), entry);
+
+    Ok(serde_json::to_string_pretty(&config)? + "\n")
+}
+
+/// Write the entry into `path`, first copying an existing file aside.
+/// Returns the backup's path, if there was anything to back up.
+pub fn install(client: McpClient, path: &Path, entry: Value) -> Result<Option<PathBuf>> {
+    let existing = match fs::read_to_string(path) {
+        Ok(contents) => Some(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let merged = merge_entry(existing.as_deref().unwrap_or(""), client, entry.clone())
+        .with_context(|| {
+            format!(
+                "Couldn't update {}; add this to it by hand:\n{}",
+                path.display(),
+                client.snippet(&entry)
+            )
+        })?;
+
+    let backup = match existing {
+        Some(contents) => {
+            let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let backup = path.with_file_name(format!("{}.{}.bak", file_name, stamp));
+            fs::write(&backup, contents)
+                .with_context(|| format!("Failed to write backup {}", backup.display()))?;
+            Some(backup)
+        }
+        None => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            None
+        }
+    };
+
+    fs::write(path, merged).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(backup)
+}
+
+/// Register `micropub mcp` with an assistant app, or just print the snippet
+pub fn cmd_mcp_install(client: &str, print: bool, read_only: bool) -> Result<()> {
+    let client = McpClient::parse(client)?;
+    let command = std::env::current_exe()
+        .and_then(|path| path.canonicalize())
+        .context("Could not determine the path of the micropub binary")?;
+    let profile = crate::config::profile_override();
+    let entry = client.server_entry(&command.to_string_lossy(), profile.as_deref(), read_only);
+
+    if print {
+        println!("{}", client.snippet(&entry));
+        return Ok(());
+    }
+
+    let path = client.config_path()?;
+    let backup = install(client, &path, entry)?;
+
+    println!("✓ Added micropub to {}", path.display());
+    if let Some(backup) = backup {
+        println!("  Previous config saved to {}", backup.display());
+    }
+    println!("Restart {} to connect.", client.name());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_merge_entry_keeps_other_settings() {
+        let existing = r#"{"theme": "dark", "mcpServers": {"other": {"command": "x"}, "micropub": {"command": "old"}}}"#;
+        let entry = McpClient::Claude.server_entry("/bin/micropub", Some("blog.example"), false);
+        let merged: Value =
+            serde_json::from_str(&merge_entry(existing, McpClient::Claude, entry).unwrap())
+                .unwrap();
+
+        assert_eq!(merged["theme"], "dark");
+        assert_eq!(merged["mcpServers"]["other"]["command"], "x");
+        assert_eq!(merged["mcpServers"]["micropub"]["command"], "/bin/micropub");
+        assert_eq!(
+            merged["mcpServers"]["micropub"]["args"],
+            json!(["--profile", "blog.example", "mcp"])
+        );
+
+        let entry = McpClient::Zed.server_entry("/bin/micropub", None, true);
+        let merged: Value =
+            serde_json::from_str(&merge_entry("", McpClient::Zed, entry).unwrap()).unwrap();
+        assert_eq!(merged["context_servers"]["micropub"]["source"], "custom");
+        assert_eq!(
+            merged["context_servers"]["micropub"]["args"],
+            json!(["mcp", "--read-only"])
+        );
+
+        // Comments (allowed in Zed's settings) aren't plain JSON
+        let entry = McpClient::Zed.server_entry("/bin/micropub", None, false);
+        assert!(merge_entry("// settings\n{}", McpClient::Zed, entry).is_err());
+    }
+
+    #[test]
+    fn test_install_backs_up_existing_config() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("mcp.json");
+        let entry = McpClient::Cursor.server_entry("/bin/micropub", None, false);
+
+        assert!(install(McpClient::Cursor, &path, entry.clone())
+            .unwrap()
+            .is_none());
+        let backup = install(McpClient::Cursor, &path, entry).unwrap().unwrap();
+        assert_eq!(
+            fs::read_to_string(&backup).unwrap(),
+            fs::read_to_string(&path).unwrap()
+        );
+    }
+}
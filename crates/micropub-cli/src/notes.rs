@@ -0,0 +1,70 @@
+// ABOUTME: `micropub notes` commands for private local notes on published posts
+// ABOUTME: Notes are shown by `posts --notes` and the TUI preview but never sent anywhere
+
+use anyhow::Result;
+
+use crate::output::{json, print_json};
+use crate::post_notes::{self, PostNote};
+
+/// A note as a line under a post, numbered from 1
+pub fn format_note(number: usize, note: &PostNote) -> String {
+    format!(
+        "{}. {}  ({})",
+        number,
+        note.text,
+        note.added_at.format("%Y-%m-%d")
+    )
+}
+
+pub fn cmd_notes_add(url: &str, text: &str) -> Result<()> {
+    if text.trim().is_empty() {
+        anyhow::bail!("Note is empty");
+    }
+    post_notes::add(url, text.trim())?;
+    println!("✓ Note added to {}", url);
+    Ok(())
+}
+
+pub fn cmd_notes_show(url: &str) -> Result<()> {
+    let notes = post_notes::for_post(url)?;
+    if json() {
+        return print_json(&notes);
+    }
+    if notes.is_empty() {
+        println!("No notes on {}", url);
+        return Ok(());
+    }
+    for (idx, note) in notes.iter().enumerate() {
+        println!("{}", format_note(idx + 1, note));
+    }
+    Ok(())
+}
+
+/// Every post with notes, without contacting the server
+pub fn cmd_notes_list() -> Result<()> {
+    let notes = post_notes::load()?;
+    if json() {
+        return print_json(&notes);
+    }
+    if notes.is_empty() {
+        println!("No posts have notes. Add one with: micropub notes add <url> <text>");
+        return Ok(());
+    }
+    for (url, post_notes) in &notes {
+        println!("{}", url);
+        for (idx, note) in post_notes.iter().enumerate() {
+            println!("   {}", format_note(idx + 1, note));
+        }
+        println!();
+    }
+    Ok(())
+}
+
+pub fn cmd_notes_remove(url: &str, number: Option<usize>) -> Result<()> {
+    match post_notes::remove(url, number)? {
+        0 => println!("No notes on {}", url),
+        1 => println!("✓ Removed 1 note from {}", url),
+        n => println!("✓ Removed {} notes from {}", n, url),
+    }
+    Ok(())
+}
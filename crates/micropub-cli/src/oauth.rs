@@ -0,0 +1,407 @@
+// ABOUTME: Browser sign-in: IndieAuth authorization code flow with PKCE
+// ABOUTME: Runs a local callback server for the redirect; behind the `auth-server` feature
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+use crate::auth::{
+    discover_with_overrides, merge_overrides, request_token, save_credentials, validate_token,
+    TokenGrant,
+};
+use crate::config::{Config, EndpointOverrides, Profile};
+
+/// Discover endpoints from a domain
+async fn discover_endpoints(
+    domain: &str,
+    overrides: &EndpointOverrides,
+) -> Result<(String, String, String)> {
+    let found = discover_with_overrides(domain, overrides).await?;
+
+    let micropub = found
+        .micropub
+        .context("Could not find micropub endpoint in Link headers or HTML")?;
+    let auth = found
+        .authorization
+        .context("Could not find authorization_endpoint in Link headers or HTML")?;
+    let token = found
+        .token
+        .context("Could not find token_endpoint in Link headers or HTML")?;
+
+    Ok((micropub, auth, token))
+}
+
+/// Generate a cryptographically secure PKCE code verifier
+fn generate_code_verifier() -> String {
+    let mut rng = rand::thread_rng();
+    (0..128)
+        .map(|_| {
+            let idx = rng.gen_range(0..62);
+            match idx {
+                0..=25 => (b'A' + idx) as char,
+                26..=51 => (b'a' + (idx - 26)) as char,
+                _ => (b'0' + (idx - 52)) as char,
+            }
+        })
+        .collect()
+}
+
+/// Generate PKCE code challenge from verifier
+fn generate_code_challenge(verifier: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(verifier.as_bytes());
+    let hash = hasher.finalize();
+    URL_SAFE_NO_PAD.encode(hash)
+}
+
+/// Generate a random state parameter
+fn generate_state() -> String {
+    let mut rng = rand::thread_rng();
+    (0..32).map(|_| format!("{:x}", rng.gen::<u8>())).collect()
+}
+
+/// Struct to hold OAuth callback data
+#[derive(Clone)]
+struct OAuthCallback {
+    code: Arc<Mutex<Option<String>>>,
+    state: Arc<Mutex<Option<String>>>,
+    error: Arc<Mutex<Option<String>>>,
+}
+
+/// Handle OAuth callback from authorization server
+async fn handle_callback(
+    req: Request<Body>,
+    callback_data: Arc<OAuthCallback>,
+) -> Result<Response<Body>, Infallible> {
+    let uri = req.uri();
+    let query = uri.query().unwrap_or("");
+
+    let params: HashMap<String, String> = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+
+    if let Some(error) = params.get("error") {
+        *callback_data.error.lock().unwrap() = Some(error.clone());
+
+        let error_desc = params
+            .get("error_description")
+            .map(|s| s.as_str())
+            .unwrap_or("Unknown error");
+
+        let html = format!(
+            r#"<html><body><h1>Authentication Failed</h1><p>Error: {}</p><p>{}</p><p>You can close this window.</p></body></html>"#,
+            error, error_desc
+        );
+
+        return Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "text/html")
+            .body(Body::from(html))
+            .unwrap());
+    }
+
+    if let (Some(code), Some(state)) = (params.get("code"), params.get("state")) {
+        *callback_data.code.lock().unwrap() = Some(code.clone());
+        *callback_data.state.lock().unwrap() = Some(state.clone());
+
+        let html = r#"<html><body><h1>Authentication Successful!</h1><p>You can close this window and return to the terminal.</p><script>window.close();</script></body></html>"#;
+
+        return Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "text/html")
+            .body(Body::from(html))
+            .unwrap());
+    }
+
+    let html =
+        r#"<html><body><h1>Invalid Callback</h1><p>Missing required parameters.</p></body></html>"#;
+    Ok(Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .header("Content-Type", "text/html")
+        .body(Body::from(html))
+        .unwrap())
+}
+
+/// Find and bind to an available port from candidates
+fn find_and_bind_port() -> Result<std::net::TcpListener> {
+    let candidate_ports = [8089, 8090, 8091, 8092, 8093];
+
+    for port in candidate_ports {
+        let addr = SocketAddr::from(([127, 0, 0, 1], port));
+        if let Ok(listener) = std::net::TcpListener::bind(addr) {
+            return Ok(listener);
+        }
+    }
+
+    // Fallback: let OS choose a random available port
+    println!("⚠ All preferred ports (8089-8093) occupied, using OS-assigned random port...");
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .context("Failed to bind to any port, including OS-assigned random port")
+}
+
+/// Start local server to receive OAuth callback
+async fn start_callback_server(
+    callback_data: Arc<OAuthCallback>,
+    listener: std::net::TcpListener,
+) -> Result<()> {
+    // Clone for shutdown signal before moving into make_svc
+    let shutdown_signal = callback_data.clone();
+
+    let make_svc = make_service_fn(move |_conn| {
+        let callback_data = callback_data.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle_callback(req, callback_data.clone())
+            }))
+        }
+    });
+
+    let server = Server::from_tcp(listener)?.serve(make_svc);
+
+    // Run server with graceful shutdown
+    let graceful = server.with_graceful_shutdown(async move {
+        // Wait until we have a code or error
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+            if shutdown_signal.code.lock().unwrap().is_some()
+                || shutdown_signal.error.lock().unwrap().is_some()
+            {
+                break;
+            }
+        }
+    });
+
+    tokio::select! {
+        result = graceful => {
+            result.context("Server error")?;
+        },
+        _ = tokio::time::sleep(tokio::time::Duration::from_secs(300)) => {
+            anyhow::bail!("OAuth callback timeout after 5 minutes");
+        }
+    }
+
+    Ok(())
+}
+
+/// Exchange authorization code for access token
+async fn exchange_code_for_token(
+    token_endpoint: &str,
+    code: &str,
+    code_verifier: &str,
+    redirect_uri: &str,
+    client_id: &str,
+) -> Result<TokenGrant> {
+    let params = [
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("client_id", client_id),
+        ("redirect_uri", redirect_uri),
+        ("code_verifier", code_verifier),
+    ];
+
+    request_token(token_endpoint, &params)
+        .await
+        .context("Failed to exchange authorization code")
+}
+
+/// Validate OAuth scope contains only safe characters
+fn validate_scope(scope: &str) -> Result<()> {
+    if scope.is_empty() {
+        return Ok(()); // Empty scope is valid
+    }
+
+    // Allow alphanumeric, spaces, hyphens, and underscores only
+    if !scope
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-' || c == '_')
+    {
+        anyhow::bail!(
+            "Invalid scope '{}': only alphanumeric characters, spaces, hyphens, and underscores allowed",
+            scope
+        );
+    }
+
+    Ok(())
+}
+
+/// Perform OAuth authentication flow
+pub async fn cmd_auth(
+    domain: &str,
+    scope: Option<&str>,
+    overrides: EndpointOverrides,
+) -> Result<()> {
+    crate::ci::ensure_interactive("Browser sign-in (use --token or MICROPUB_TOKEN)")?;
+
+    // Load config to get client_id (if configured)
+    let mut config = Config::load()?;
+    let overrides = merge_overrides(&config, domain, overrides)?;
+
+    println!("Discovering endpoints for {}...", domain);
+
+    let (micropub_endpoint, auth_endpoint, token_endpoint) =
+        discover_endpoints(domain, &overrides).await?;
+
+    println!("✓ Found micropub endpoint: {}", micropub_endpoint);
+    println!("✓ Found authorization endpoint: {}", auth_endpoint);
+    println!("✓ Found token endpoint: {}", token_endpoint);
+
+    // Find and bind to an available port for the callback server
+    let listener = find_and_bind_port()?;
+    let port = listener.local_addr()?.port();
+    println!("Using port {} for OAuth callback", port);
+
+    // Generate PKCE parameters
+    let code_verifier = generate_code_verifier();
+    let code_challenge = generate_code_challenge(&code_verifier);
+    let state = generate_state();
+
+    // Set up OAuth parameters
+    let redirect_uri = format!("http://127.0.0.1:{}/callback", port);
+    // Use configured client_id or default to GitHub repo URL
+    let client_id = config
+        .client_id
+        .as_deref()
+        .unwrap_or("https://github.com/harperreed/micropub");
+
+    // Build authorization URL
+    let scope = scope.unwrap_or("create update delete media");
+    validate_scope(scope)?;
+
+    // Build "me" parameter - must match the authenticated identity
+    // Use the same scheme detection logic as endpoint discovery
+    let me_param = if domain.starts_with("http://") || domain.starts_with("https://") {
+        // Domain already has scheme, use as-is
+        domain.to_string()
+    } else {
+        // No scheme - use http:// for localhost, https:// for remote
+        let is_localhost = domain.starts_with("localhost")
+            || domain.starts_with("127.0.0.1")
+            || domain.starts_with("::1")
+            || domain.starts_with("[::1]");
+
+        if is_localhost {
+            format!("http://{}", domain)
+        } else {
+            format!("https://{}", domain)
+        }
+    };
+
+    let mut auth_url = Url::parse(&auth_endpoint)?;
+    auth_url
+        .query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", client_id)
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("state", &state)
+        .append_pair("code_challenge", &code_challenge)
+        .append_pair("code_challenge_method", "S256")
+        .append_pair("scope", scope)
+        .append_pair("me", &me_param);
+
+    println!("\nStarting OAuth flow...");
+    println!("Opening your browser to authenticate...");
+    println!();
+
+    // Set up callback receiver
+    let callback_data = Arc::new(OAuthCallback {
+        code: Arc::new(Mutex::new(None)),
+        state: Arc::new(Mutex::new(None)),
+        error: Arc::new(Mutex::new(None)),
+    });
+
+    // Start local callback server in background
+    let callback_data_clone = callback_data.clone();
+    let server_handle =
+        tokio::spawn(async move { start_callback_server(callback_data_clone, listener).await });
+
+    // Open browser
+    if let Err(e) = open::that(auth_url.as_str()) {
+        println!("⚠ Could not open browser automatically: {}", e);
+        println!("Please open this URL manually:");
+        println!("{}", auth_url);
+    }
+
+    println!("\nWaiting for authorization...");
+
+    // Wait for the server to complete (it will shut down automatically after receiving callback)
+    match server_handle.await {
+        Ok(Ok(())) => {
+            // Server completed successfully
+        }
+        Ok(Err(e)) => {
+            anyhow::bail!("OAuth callback server error: {}", e);
+        }
+        Err(e) => {
+            anyhow::bail!("OAuth server task panicked: {}", e);
+        }
+    }
+
+    // Check for error
+    if let Some(error) = callback_data.error.lock().unwrap().clone() {
+        anyhow::bail!("Authorization failed: {}", error);
+    }
+
+    // Extract code and state
+    let code = callback_data
+        .code
+        .lock()
+        .unwrap()
+        .clone()
+        .context("No authorization code received")?;
+    let received_state = callback_data
+        .state
+        .lock()
+        .unwrap()
+        .clone()
+        .context("No state received")?;
+
+    // Verify state matches
+    if received_state != state {
+        anyhow::bail!("State mismatch - possible CSRF attack");
+    }
+
+    println!("✓ Authorization code received");
+    println!("\nExchanging code for access token...");
+
+    // Exchange code for token
+    let grant = exchange_code_for_token(
+        &token_endpoint,
+        &code,
+        &code_verifier,
+        &redirect_uri,
+        client_id,
+    )
+    .await?;
+
+    println!("✓ Access token obtained");
+    if grant.metadata.refresh_token.is_some() {
+        println!("✓ Refresh token obtained");
+    }
+
+    // Validate the token before saving it
+    validate_token(&micropub_endpoint, &grant.access_token).await?;
+
+    // Save profile and token AFTER validation succeeds
+    let profile = Profile {
+        domain: domain.to_string(),
+        micropub_endpoint: Some(micropub_endpoint),
+        media_endpoint: overrides.media.clone(),
+        token_endpoint: Some(token_endpoint),
+        authorization_endpoint: Some(auth_endpoint),
+        transforms: Vec::new(),
+        endpoint_overrides: overrides,
+        request_format: Default::default(),
+        syndicate_to: Vec::new(),
+        polite: false,
+        license: None,
+    };
+    save_credentials(&mut config, &grant, profile).await
+}
@@ -0,0 +1,1073 @@
+// ABOUTME: Post management operations (get, update, delete, undelete, whoami, list)
+// ABOUTME: Handles modifications to existing posts and queries
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::io::Write;
+
+use crate::auth::load_fresh_token;
+use crate::client::{MicropubAction, MicropubClient, MicropubRequest};
+use crate::config::{get_temp_dir, Config, Profile};
+use crate::draft::{generate_draft_id, Draft, ResponseKind};
+use crate::html::{content_as_markdown, markdown_to_html};
+use crate::http_cache;
+use crate::media::{resolve_path, upload_or_reuse};
+use crate::media_sidecar::{self, MediaSidecar};
+use crate::output::{json, print_json};
+use crate::pager::Pager;
+use crate::server_config::{
+    cached_categories, get_categories, get_server_config, query_syndicate_to, SyndicationTarget,
+};
+
+pub async fn cmd_update(post_url: &str) -> Result<()> {
+    let config = Config::load()?;
+
+    let (profile_name, profile) = config.active_profile()?;
+
+    let token = load_fresh_token(profile_name).await?;
+
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured")?;
+
+    // First, fetch the current post content
+    println!("Fetching post: {}", post_url);
+    let source = fetch_source(micropub_endpoint, &token, post_url).await?;
+
+    // Edit the post as a draft file; HTML content is edited as Markdown
+    let original = draft_from_source("update".to_string(), post_url, &source)?;
+    let content_is_html = source
+        .get("properties")
+        .unwrap_or(&source)
+        .get("content")
+        .and_then(|v| v.as_array())
+        .and_then(|arr| arr.first())
+        .and_then(content_as_markdown)
+        .is_some_and(|(_, is_html)| is_html);
+
+    // The post may be private, so it's edited in an owner-only file that's
+    // removed when this returns, whether or not the edit succeeds
+    let mut temp_file = tempfile::Builder::new()
+        .prefix("micropub-update-")
+        .suffix(".md")
+        .tempfile_in(get_temp_dir()?)
+        .context("Failed to create temp file")?;
+    temp_file.write_all(original.to_string()?.as_bytes())?;
+    temp_file.flush()?;
+
+    crate::editor::open_in_editor(temp_file.path())?;
+
+    // Read back the edited content, keeping the file if it no longer parses
+    let edited_content = std::fs::read_to_string(temp_file.path())?;
+    let edited = match Draft::from_string(original.id.clone(), edited_content) {
+        Ok(edited) => edited,
+        Err(e) => {
+            let (_, kept) = temp_file.keep()?;
+            return Err(e.context(format!(
+                "Couldn't parse the edited post; your changes are in {}",
+                kept.display()
+            )));
+        }
+    };
+    drop(temp_file);
+
+    let Some(request) = build_draft_update(post_url, &original, &edited, content_is_html) else {
+        println!("No changes detected.");
+        return Ok(());
+    };
+
+    let micropub_client =
+        MicropubClient::new(micropub_endpoint.clone(), token).with_format(profile.request_format);
+
+    println!("Updating post...");
+    micropub_client.send(&request).await?;
+
+    println!("✓ Post updated successfully!");
+
+    Ok(())
+}
+
+/// Properties that lists can gain values in, so additions are sent with `add`
+const ADDITIVE_PROPERTIES: [&str; 3] = ["category", "photo", "mp-syndicate-to"];
+
+fn insert_strings(properties: &mut Map<String, Value>, name: &str, values: Vec<String>) {
+    if !values.is_empty() {
+        properties.insert(
+            name.to_string(),
+            Value::Array(values.into_iter().map(Value::String).collect()),
+        );
+    }
+}
+
+/// The micropub properties a post edited as a draft can change
+fn editable_properties(draft: &Draft) -> Map<String, Value> {
+    let metadata = &draft.metadata;
+    let mut properties = Map::new();
+
+    let content = draft.content.trim();
+    if !content.is_empty() {
+        insert_strings(&mut properties, "content", vec![content.to_string()]);
+    }
+    insert_strings(
+        &mut properties,
+        "name",
+        metadata.name.iter().cloned().collect(),
+    );
+    insert_strings(
+        &mut properties,
+        "summary",
+        metadata.summary.iter().cloned().collect(),
+    );
+    insert_strings(&mut properties, "category", metadata.category.clone());
+    insert_strings(&mut properties, "photo", metadata.photo.clone());
+    insert_strings(
+        &mut properties,
+        "license",
+        metadata.license.iter().cloned().collect(),
+    );
+    insert_strings(
+        &mut properties,
+        "published",
+        metadata.published.iter().map(|d| d.to_rfc3339()).collect(),
+    );
+    for (property, url) in metadata.response_properties() {
+        insert_strings(&mut properties, property, vec![url.to_string()]);
+    }
+    let post_status = match metadata.status.as_deref() {
+        Some("server-draft") => Some("draft"),
+        Some("published") => Some("published"),
+        _ => None,
+    };
+    insert_strings(
+        &mut properties,
+        "post-status",
+        post_status.into_iter().map(String::from).collect(),
+    );
+    insert_strings(
+        &mut properties,
+        "mp-syndicate-to",
+        metadata.syndicate_to.clone(),
+    );
+
+    properties
+}
+
+/// Update request for the properties that differ between a post's draft before and after
+/// editing: new list values are added, other changes replaced, and cleared properties deleted.
+/// `None` when nothing changed.
+pub fn build_draft_update(
+    post_url: &str,
+    before: &Draft,
+    after: &Draft,
+    content_is_html: bool,
+) -> Option<MicropubRequest> {
+    let old = editable_properties(before);
+    let new = editable_properties(after);
+
+    let mut replace = Map::new();
+    let mut add = Map::new();
+    for (property, value) in &new {
+        if old.get(property) == Some(value) {
+            continue;
+        }
+        let values = |properties: &Map<String, Value>| -> Vec<Value> {
+            match properties.get(property) {
+                Some(Value::Array(values)) => values.clone(),
+                _ => Vec::new(),
+            }
+        };
+        let (old_values, new_values) = (values(&old), values(&new));
+
+        let only_additions = old_values.iter().all(|v| new_values.contains(v));
+        if ADDITIVE_PROPERTIES.contains(&property.as_str()) && only_additions {
+            let added = new_values
+                .into_iter()
+                .filter(|v| !old_values.contains(v))
+                .collect();
+            add.insert(property.clone(), Value::Array(added));
+        } else {
+            replace.insert(property.clone(), value.clone());
+        }
+    }
+
+    if content_is_html {
+        if let Some(Value::Array(content)) = replace.get_mut("content") {
+            let markdown = content[0].as_str().unwrap_or_default().to_string();
+            content[0] = serde_json::json!({ "html": markdown_to_html(&markdown) });
+        }
+    }
+
+    let delete: Vec<String> = old
+        .keys()
+        .filter(|property| !new.contains_key(*property))
+        .cloned()
+        .collect();
+
+    if replace.is_empty() && add.is_empty() && delete.is_empty() {
+        return None;
+    }
+
+    Some(MicropubRequest {
+        action: MicropubAction::Update {
+            replace,
+            add,
+            delete,
+        },
+        properties: Map::new(),
+        url: Some(post_url.to_string()),
+    })
+}
+
+/// Fetch one post's microformats2 source with `q=source&url=`
+async fn fetch_source(micropub_endpoint: &str, token: &str, post_url: &str) -> Result<Value> {
+    let mut url = url::Url::parse(micropub_endpoint).context("Invalid micropub endpoint")?;
+    url.query_pairs_mut()
+        .append_pair("q", "source")
+        .append_pair("url", post_url);
+    http_cache::get_json(url.as_str(), token, "fetch post").await
+}
+
+/// Every value of a property as a string, taking `value` from object values
+fn property_strings(properties: &Value, key: &str) -> Vec<String> {
+    let values = match properties.get(key) {
+        Some(Value::Array(arr)) => arr.iter().collect(),
+        Some(value) => vec![value],
+        None => Vec::new(),
+    };
+    values
+        .into_iter()
+        .filter_map(|v| v.as_str().or_else(|| v.get("value")?.as_str()))
+        .map(String::from)
+        .collect()
+}
+
+/// Turn a `q=source` response into a local draft that remembers the post's URL,
+/// so publishing it again updates the post instead of creating a new one
+pub fn draft_from_source(id: String, post_url: &str, source: &Value) -> Result<Draft> {
+    let properties = source.get("properties").unwrap_or(source);
+    if !properties.is_object() {
+        anyhow::bail!("Post has no properties");
+    }
+
+    let mut draft = Draft::new(id);
+    let metadata = &mut draft.metadata;
+
+    draft.content = first_property_str(properties, "content").unwrap_or_default();
+    metadata.name = first_property_str(properties, "name").filter(|n| !n.trim().is_empty());
+    metadata.summary = first_property_str(properties, "summary");
+    metadata.category = property_strings(properties, "category");
+    metadata.photo = property_strings(properties, "photo");
+    metadata.license = first_property_str(properties, "license");
+    metadata.published = first_property_str(properties, "published")
+        .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+        .map(|date| date.with_timezone(&Utc));
+
+    for kind in ResponseKind::ALL {
+        if let Some(url) = property_strings(properties, kind.property()).pop() {
+            metadata.set_response_target(kind, url);
+        }
+    }
+    metadata.post_type = match ResponseKind::ALL
+        .into_iter()
+        .find(|kind| metadata.response_target(*kind).is_some())
+    {
+        Some(kind) => kind.post_type().to_string(),
+        None if metadata.name.is_some() => "article".to_string(),
+        None => "note".to_string(),
+    };
+
+    metadata.status = Some(
+        if is_server_draft(properties) {
+            "server-draft"
+        } else {
+            "published"
+        }
+        .to_string(),
+    );
+    metadata.url =
+        Some(first_property_str(properties, "url").unwrap_or_else(|| post_url.to_string()));
+
+    Ok(draft)
+}
+
+/// Print one post's properties, or save it as a draft for editing
+pub async fn cmd_get(post_url: &str, to_draft: bool) -> Result<()> {
+    let config = Config::load()?;
+    let (profile_name, profile) = config.active_profile()?;
+    let token = load_fresh_token(profile_name).await?;
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured")?;
+
+    let source = fetch_source(micropub_endpoint, &token, post_url).await?;
+
+    if to_draft {
+        let mut draft = draft_from_source(generate_draft_id(), post_url, &source)?;
+        draft.metadata.profile = vec![profile_name.to_string()];
+        let path = draft.save()?;
+
+        if json() {
+            return print_json(&serde_json::json!({ "draft_id": draft.id, "path": path }));
+        }
+        println!("Draft created: {}", draft.id);
+        println!("Path: {}", path.display());
+        println!(
+            "Edit it, then run `micropub publish {}` to update the post",
+            draft.id
+        );
+        return Ok(());
+    }
+
+    if json() {
+        return print_json(&source);
+    }
+
+    let properties = source
+        .get("properties")
+        .unwrap_or(&source)
+        .as_object()
+        .context("Post has no properties")?;
+    for (key, values) in properties {
+        println!("{}:", key);
+        let values = match values {
+            Value::Array(arr) => arr.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            match content_as_markdown(value) {
+                Some((text, _)) => {
+                    for line in text.lines() {
+                        println!("  {}", line);
+                    }
+                }
+                None => println!("  {}", value),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Build an update request replacing whichever of content, title, and categories are given.
+/// An empty title removes the post's name.
+pub fn build_update_request(
+    post_url: &str,
+    content: Option<&str>,
+    title: Option<&str>,
+    categories: Option<&[String]>,
+) -> Result<MicropubRequest> {
+    let mut replace = Map::new();
+
+    if let Some(content) = content {
+        replace.insert(
+            "content".to_string(),
+            Value::Array(vec![Value::String(content.to_string())]),
+        );
+    }
+    if let Some(title) = title {
+        let name = if title.is_empty() {
+            vec![]
+        } else {
+            vec![Value::String(title.to_string())]
+        };
+        replace.insert("name".to_string(), Value::Array(name));
+    }
+    if let Some(categories) = categories {
+        replace.insert(
+            "category".to_string(),
+            Value::Array(categories.iter().cloned().map(Value::String).collect()),
+        );
+    }
+
+    if replace.is_empty() {
+        anyhow::bail!("Nothing to update: give new content, title, or categories");
+    }
+
+    Ok(MicropubRequest {
+        action: MicropubAction::Update {
+            replace,
+            add: Map::new(),
+            delete: Vec::new(),
+        },
+        properties: Map::new(),
+        url: Some(post_url.to_string()),
+    })
+}
+
+/// Send an update built by `build_update_request` with the active profile
+pub async fn send_update(request: &MicropubRequest) -> Result<()> {
+    let config = Config::load()?;
+    let (profile_name, profile) = config.active_profile()?;
+    let token = load_fresh_token(profile_name).await?;
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured")?;
+
+    MicropubClient::new(micropub_endpoint.clone(), token)
+        .with_format(profile.request_format)
+        .send(request)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn cmd_delete(post_url: &str) -> Result<()> {
+    println!("Deleting post: {}", post_url);
+    delete_post(post_url).await?;
+    println!("✓ Post deleted successfully");
+    Ok(())
+}
+
+pub async fn cmd_undelete(post_url: &str) -> Result<()> {
+    println!("Undeleting post: {}", post_url);
+    undelete_post(post_url).await?;
+    println!("✓ Post undeleted successfully");
+    Ok(())
+}
+
+/// Delete a post on the active profile's server without printing
+pub async fn delete_post(post_url: &str) -> Result<()> {
+    send_url_action(MicropubAction::Delete, post_url).await
+}
+
+/// Undelete a post on the active profile's server without printing
+pub async fn undelete_post(post_url: &str) -> Result<()> {
+    send_url_action(MicropubAction::Undelete, post_url).await
+}
+
+/// Publish a server-side draft (`post-status: draft`) by updating its status
+pub async fn promote_post(post_url: &str) -> Result<()> {
+    send_update(&build_promote_request(post_url)).await
+}
+
+/// The update that sets a post's `post-status` to published
+pub fn build_promote_request(post_url: &str) -> MicropubRequest {
+    let mut replace = Map::new();
+    replace.insert(
+        "post-status".to_string(),
+        Value::Array(vec![Value::String("published".to_string())]),
+    );
+
+    MicropubRequest {
+        action: MicropubAction::Update {
+            replace,
+            add: Map::new(),
+            delete: Vec::new(),
+        },
+        properties: Map::new(),
+        url: Some(post_url.to_string()),
+    }
+}
+
+async fn send_url_action(action: MicropubAction, post_url: &str) -> Result<()> {
+    let config = Config::load()?;
+
+    let (profile_name, profile) = config.active_profile()?;
+
+    let token = load_fresh_token(profile_name).await?;
+
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured")?;
+
+    let request = MicropubRequest {
+        action,
+        properties: Map::new(),
+        url: Some(post_url.to_string()),
+    };
+
+    let client =
+        MicropubClient::new(micropub_endpoint.clone(), token).with_format(profile.request_format);
+    client.send(&request).await?;
+
+    Ok(())
+}
+
+/// Syndication targets for a profile, from the config cache unless empty or `refresh` is set
+pub async fn syndication_targets(
+    profile_name: &str,
+    refresh: bool,
+) -> Result<Vec<SyndicationTarget>> {
+    let mut config = Config::load()?;
+    let profile = config
+        .get_profile(profile_name)
+        .with_context(|| format!("Profile not found: {}", profile_name))?;
+
+    if !refresh && !profile.syndicate_to.is_empty() {
+        return Ok(profile.syndicate_to.clone());
+    }
+
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .clone()
+        .context("No micropub endpoint configured")?;
+    let token = load_fresh_token(profile_name).await?;
+    let targets = query_syndicate_to(&micropub_endpoint, &token).await?;
+
+    if let Some(profile) = config.profiles.get_mut(profile_name) {
+        profile.syndicate_to = targets.clone();
+    }
+    config.save()?;
+
+    Ok(targets)
+}
+
+/// Query the server's syndication targets, cache them, and print them
+pub async fn cmd_syndicate_targets() -> Result<()> {
+    let config = Config::load()?;
+    let (profile_name, _) = config.active_profile()?;
+
+    let targets = syndication_targets(profile_name, true).await?;
+
+    if targets.is_empty() {
+        println!("No syndication targets advertised by the server.");
+        return Ok(());
+    }
+
+    println!("Syndication targets for {}:", profile_name);
+    for target in &targets {
+        match target.name {
+            Some(ref name) => println!("  {} ({})", target.uid, name),
+            None => println!("  {}", target.uid),
+        }
+    }
+
+    Ok(())
+}
+
+/// Categories the server has seen (`q=category`), from the cache unless stale or `refresh` is set
+pub async fn server_categories(profile_name: &str, refresh: bool) -> Result<Vec<String>> {
+    let config = Config::load()?;
+    let profile = config
+        .get_profile(profile_name)
+        .with_context(|| format!("Profile not found: {}", profile_name))?;
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .clone()
+        .context("No micropub endpoint configured")?;
+    let token = load_fresh_token(profile_name).await?;
+
+    get_categories(profile_name, &micropub_endpoint, &token, refresh).await
+}
+
+/// Server categories plus those in local drafts and archived posts, sorted. Falls back to
+/// the cached server list when the server can't be reached.
+pub async fn all_categories(profile_name: &str, refresh: bool) -> Result<Vec<String>> {
+    let server = match server_categories(profile_name, refresh).await {
+        Ok(categories) => categories,
+        Err(_) if !refresh => cached_categories(profile_name),
+        Err(e) => return Err(e),
+    };
+    let mut categories: std::collections::BTreeSet<String> = server.into_iter().collect();
+    categories.extend(crate::draft::known_categories()?);
+    Ok(categories.into_iter().collect())
+}
+
+/// Print every known category, one per line (for scripts and shell completion)
+pub async fn cmd_categories(refresh: bool) -> Result<()> {
+    let config = Config::load()?;
+    let (profile_name, _) = config.active_profile()?;
+    let categories = all_categories(profile_name, refresh).await?;
+
+    if json() {
+        return print_json(&categories);
+    }
+    for category in &categories {
+        println!("{}", category);
+    }
+    Ok(())
+}
+
+pub async fn cmd_whoami() -> Result<()> {
+    let config = Config::load()?;
+
+    let (profile_name, profile) = config.active_profile()?;
+
+    let token = load_fresh_token(profile_name).await?;
+
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured")?;
+
+    // Query the micropub endpoint for user info (cached per profile)
+    let server_config = get_server_config(profile_name, micropub_endpoint, &token, false)
+        .await
+        .context("Failed to get user info")?;
+
+    if json() {
+        return print_json(&serde_json::json!({
+            "profile": profile_name,
+            "domain": profile.domain,
+            "micropub_endpoint": micropub_endpoint,
+            "media_endpoint": profile
+                .media_endpoint
+                .as_ref()
+                .or(server_config.media_endpoint.as_ref()),
+            "syndicate_to": server_config.syndicate_to,
+        }));
+    }
+
+    println!("Authenticated as:");
+    println!("  Profile: {}", profile_name);
+    println!("  Domain: {}", profile.domain);
+    println!("  Micropub endpoint: {}", micropub_endpoint);
+
+    if let Some(media) = profile
+        .media_endpoint
+        .as_ref()
+        .or(server_config.media_endpoint.as_ref())
+    {
+        println!("  Media endpoint: {}", media);
+    }
+
+    if !server_config.syndicate_to.is_empty() {
+        println!("  Syndication targets:");
+        for target in &server_config.syndicate_to {
+            match target.name {
+                Some(ref name) => println!("    - {} ({})", name, target.uid),
+                None => println!("    - {}", target.uid),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch posts from the micropub endpoint and return them as structured data
+pub async fn fetch_posts(limit: usize, offset: usize) -> Result<Vec<PostData>> {
+    let config = Config::load()?;
+
+    let (profile_name, profile) = config.active_profile()?;
+
+    let token = load_fresh_token(profile_name).await?;
+
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured")?;
+
+    let mut url = format!("{}?q=source&limit={}", micropub_endpoint, limit);
+    if offset > 0 {
+        url.push_str(&format!("&offset={}", offset));
+    }
+
+    let data = http_cache::get_json(&url, &token, "list posts").await?;
+
+    Ok(parse_posts(&data))
+}
+
+/// First value of a microformats property as a string.
+///
+/// Accepts `["value"]`, a bare `"value"`, and `{"value": ...}` / `{"html": ...}` objects.
+fn first_property_str(properties: &Value, key: &str) -> Option<String> {
+    let value = properties.get(key)?;
+    let first = match value {
+        Value::Array(arr) => arr.first()?,
+        other => other,
+    };
+    content_as_markdown(first).map(|(text, _)| text)
+}
+
+/// Whether a post's properties mark it as a server-side draft
+fn is_server_draft(properties: &Value) -> bool {
+    property_strings(properties, "post-status")
+        .first()
+        .is_some_and(|status| status == "draft")
+}
+
+/// Parse a `q=source` listing into posts, tolerating missing and nested properties
+pub fn parse_posts(data: &Value) -> Vec<PostData> {
+    let Some(items) = data.get("items").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+
+    items
+        .iter()
+        .filter(|item| item.is_object())
+        .map(|item| {
+            // Some servers return flat items rather than mf2 `properties`
+            let properties = item.get("properties").unwrap_or(item);
+
+            let categories = match properties.get("category") {
+                Some(Value::Array(arr)) => arr
+                    .iter()
+                    .filter_map(|v| content_as_markdown(v).map(|(text, _)| text))
+                    .collect(),
+                Some(Value::String(s)) => vec![s.clone()],
+                _ => Vec::new(),
+            };
+
+            PostData {
+                url: first_property_str(properties, "url")
+                    .unwrap_or_else(|| "(no URL)".to_string()),
+                content: first_property_str(properties, "content").unwrap_or_default(),
+                name: first_property_str(properties, "name").filter(|n| !n.trim().is_empty()),
+                published: first_property_str(properties, "published"),
+                categories,
+                draft: is_server_draft(properties),
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PostData {
+    pub url: String,
+    pub content: String,
+    pub name: Option<String>,
+    /// `None` when the server doesn't say
+    pub published: Option<String>,
+    pub categories: Vec<String>,
+    /// `post-status: draft`: saved on the server but not published
+    pub draft: bool,
+}
+
+/// List posts a page at a time; with `show_notes`, each post's private notes too
+pub async fn cmd_list_posts(limit: usize, offset: usize, show_notes: bool) -> Result<()> {
+    let notes = if show_notes {
+        crate::post_notes::load()?
+    } else {
+        Default::default()
+    };
+
+    if json() {
+        let posts = fetch_posts(limit, offset).await?;
+        if !show_notes {
+            return print_json(&posts);
+        }
+        let mut with_notes = Vec::new();
+        for post in posts {
+            let mut value = serde_json::to_value(&post)?;
+            value["notes"] =
+                serde_json::to_value(notes.get(&post.url).cloned().unwrap_or_default())?;
+            with_notes.push(value);
+        }
+        return print_json(&with_notes);
+    }
+
+    let mut out = Pager::start();
+    let mut current_offset = offset;
+    let mut first_page = true;
+
+    loop {
+        let posts = fetch_posts(limit, current_offset).await?;
+
+        if posts.is_empty() {
+            if first_page {
+                writeln!(out, "No posts found.")?;
+            } else {
+                writeln!(out, "No more posts.")?;
+            }
+            return Ok(());
+        }
+
+        if first_page {
+            writeln!(out, "Recent posts:")?;
+            writeln!(out)?;
+        }
+
+        for (idx, post) in posts.iter().enumerate() {
+            let display_content = post.name.as_ref().unwrap_or(&post.content);
+            let content_preview = if display_content.len() > 80 {
+                format!("{}...", &display_content[..77])
+            } else {
+                display_content.to_string()
+            };
+
+            writeln!(out, "{}. {}", current_offset + idx + 1, content_preview)?;
+            writeln!(out, "   URL: {}", post.url)?;
+            writeln!(
+                out,
+                "   Published: {}",
+                post.published.as_deref().unwrap_or("(no date)")
+            )?;
+            if let Some(post_notes) = notes.get(&post.url) {
+                writeln!(out, "   Notes:")?;
+                for (idx, note) in post_notes.iter().enumerate() {
+                    writeln!(out, "     {}", crate::notes::format_note(idx + 1, note))?;
+                }
+            }
+            writeln!(out)?;
+        }
+
+        // Keep streaming pages into the pager until the user quits it
+        let has_more = posts.len() == limit;
+        if !has_more || !out.is_paging() || out.is_closed() {
+            return Ok(());
+        }
+
+        current_offset += limit;
+        first_page = false;
+    }
+}
+
+/// Fetch media from the micropub endpoint and return them as structured data
+pub async fn fetch_media(limit: usize, offset: usize) -> Result<Vec<MediaData>> {
+    let config = Config::load()?;
+
+    let (profile_name, profile) = config.active_profile()?;
+
+    let token = load_fresh_token(profile_name).await?;
+
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured")?;
+
+    let mut url = format!(
+        "{}?q=source&limit={}&filter=photo",
+        micropub_endpoint, limit
+    );
+    if offset > 0 {
+        url.push_str(&format!("&offset={}", offset));
+    }
+
+    let data = http_cache::get_json(&url, &token, "list media").await?;
+
+    let mut media_items = Vec::new();
+
+    if let Some(items) = data.get("items").and_then(|v| v.as_array()) {
+        for item in items {
+            let properties = item
+                .get("properties")
+                .context("Missing properties in media item")?;
+
+            // Get URL
+            let url = properties
+                .get("url")
+                .and_then(|v| v.as_array())
+                .and_then(|arr| arr.first())
+                .and_then(|v| v.as_str())
+                .or_else(|| {
+                    properties
+                        .get("photo")
+                        .and_then(|v| v.as_array())
+                        .and_then(|arr| arr.first())
+                        .and_then(|v| v.as_str())
+                })
+                .unwrap_or("(no URL)")
+                .to_string();
+
+            // Get published date
+            let uploaded = properties
+                .get("published")
+                .and_then(|v| v.as_array())
+                .and_then(|arr| arr.first())
+                .and_then(|v| v.as_str())
+                .map(String::from);
+
+            // Get name/alt text if available
+            let name = properties
+                .get("name")
+                .and_then(|v| v.as_array())
+                .and_then(|arr| arr.first())
+                .and_then(|v| v.as_str())
+                .map(String::from);
+
+            media_items.push(MediaData {
+                url,
+                name,
+                uploaded,
+            });
+        }
+    }
+
+    Ok(media_items)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaData {
+    pub url: String,
+    pub name: Option<String>,
+    /// `None` when the server doesn't say
+    pub uploaded: Option<String>,
+}
+
+/// A profile's media endpoint, asking the server's `q=config` if none is stored
+pub(crate) async fn media_endpoint_for(
+    profile_name: &str,
+    profile: &Profile,
+    token: &str,
+) -> Result<String> {
+    if let Some(ref endpoint) = profile.media_endpoint {
+        return Ok(endpoint.clone());
+    }
+
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured")?;
+    get_server_config(profile_name, micropub_endpoint, token, false)
+        .await?
+        .media_endpoint
+        .with_context(|| {
+            format!(
+                "No media endpoint found for profile '{}'. Re-authenticate to discover it:\n  micropub auth {}",
+                profile_name, profile.domain
+            )
+        })
+}
+
+/// Upload files to the media endpoint and print their URLs (or Markdown images)
+pub async fn cmd_upload_media(files: &[String], alt: Option<&str>, markdown: bool) -> Result<()> {
+    let config = Config::load()?;
+    let (profile_name, profile) = config.active_profile()?;
+    let token = load_fresh_token(profile_name).await?;
+    let media_endpoint = media_endpoint_for(profile_name, profile, &token).await?;
+
+    // Alt text only means something in a Markdown snippet
+    let markdown = markdown || alt.is_some();
+
+    for file in files {
+        let resolved = resolve_path(file, None)?;
+        // Progress goes to stderr so the URLs can be piped
+        eprintln!("Uploading {}...", resolved.display());
+        let upload = upload_or_reuse(&media_endpoint, &token, &resolved, profile_name, alt).await?;
+        if upload.reused {
+            eprintln!("  Already uploaded; reusing {}", upload.url);
+        }
+        let url = upload.url;
+
+        if markdown {
+            println!("{}", markdown_image(alt.unwrap_or(""), &url));
+        } else {
+            println!("{}", url);
+        }
+    }
+
+    Ok(())
+}
+
+/// List recorded uploads that have no alt text
+pub fn cmd_media_audit() -> Result<()> {
+    let config = Config::load()?;
+    let profile_name = &config.active_profile_name();
+    let missing: Vec<MediaSidecar> = media_sidecar::list(profile_name)?
+        .into_iter()
+        .filter(|sidecar| sidecar.alt.is_none())
+        .collect();
+
+    if json() {
+        return print_json(&missing);
+    }
+    if missing.is_empty() {
+        println!("Every upload recorded for {} has alt text.", profile_name);
+        return Ok(());
+    }
+
+    println!("{} upload(s) without alt text:", missing.len());
+    println!();
+    for sidecar in &missing {
+        println!("{}", sidecar.url);
+        println!("   File: {}", sidecar.original_path);
+        println!(
+            "   Uploaded: {}",
+            sidecar.uploaded_at.format("%Y-%m-%d %H:%M UTC")
+        );
+        println!();
+    }
+    Ok(())
+}
+
+/// A Markdown image snippet, escaping brackets in the alt text
+pub fn markdown_image(alt: &str, url: &str) -> String {
+    let alt = alt.replace('[', "\\[").replace(']', "\\]");
+    format!("![{}]({})", alt, url)
+}
+
+/// Uploads recorded in local sidecars for the active profile, newest first
+pub fn local_media() -> Result<Vec<MediaData>> {
+    let config = Config::load()?;
+    Ok(media_sidecar::list(&config.active_profile_name())?
+        .into_iter()
+        .map(|sidecar| MediaData {
+            name: std::path::Path::new(&sidecar.original_path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string()),
+            uploaded: Some(sidecar.uploaded_at.to_rfc3339()),
+            url: sidecar.url,
+        })
+        .collect())
+}
+
+/// The server's media listing, or the locally recorded uploads when the server
+/// can't list media; the second value is the server's error in that case
+pub async fn fetch_media_or_local(
+    limit: usize,
+    offset: usize,
+) -> Result<(Vec<MediaData>, Option<String>)> {
+    match fetch_media(limit, offset).await {
+        Ok(items) => Ok((items, None)),
+        Err(e) => {
+            let local = local_media()?;
+            if local.is_empty() {
+                return Err(e);
+            }
+            let page = local.into_iter().skip(offset).take(limit).collect();
+            Ok((page, Some(format!("{:#}", e).trim().to_string())))
+        }
+    }
+}
+
+pub async fn cmd_list_media(limit: usize, offset: usize) -> Result<()> {
+    if json() {
+        return print_json(&fetch_media_or_local(limit, offset).await?.0);
+    }
+
+    let mut out = Pager::start();
+    let mut current_offset = offset;
+    let mut first_page = true;
+
+    loop {
+        let (media_items, server_error) = fetch_media_or_local(limit, current_offset).await?;
+        if let (Some(e), true) = (server_error, first_page) {
+            eprintln!(
+                "⚠ Couldn't list media from the server ({}); showing uploads recorded locally",
+                e
+            );
+        }
+
+        if media_items.is_empty() {
+            if first_page {
+                writeln!(out, "No media files found.")?;
+            } else {
+                writeln!(out, "No more media files.")?;
+            }
+            return Ok(());
+        }
+
+        if first_page {
+            writeln!(out, "Recent media uploads:")?;
+            writeln!(out)?;
+        }
+
+        for (idx, item) in media_items.iter().enumerate() {
+            writeln!(out, "{}. {}", current_offset + idx + 1, item.url)?;
+            if let Some(ref n) = item.name {
+                writeln!(out, "   Name: {}", n)?;
+            }
+            writeln!(
+                out,
+                "   Uploaded: {}",
+                item.uploaded.as_deref().unwrap_or("(no date)")
+            )?;
+            writeln!(out)?;
+        }
+
+        // Keep streaming pages into the pager until the user quits it
+        let has_more = media_items.len() == limit;
+        if !has_more || !out.is_paging() || out.is_closed() {
+            return Ok(());
+        }
+
+        current_offset += limit;
+        first_page = false;
+    }
+}
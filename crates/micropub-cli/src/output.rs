@@ -0,0 +1,142 @@
+// ABOUTME: Machine-readable output mode (`--json`) shared by list and query commands
+// ABOUTME: Keeps stdout parseable by sending progress messages to stderr in JSON mode
+
+use anyhow::Result;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use micropub_core::media::{set_upload_observer, UploadProgress};
+use serde::Serialize;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Emit JSON instead of text for the rest of the process (`--json`)
+pub fn set_json(json: bool) {
+    JSON.store(json, Ordering::Relaxed);
+}
+
+pub fn json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Print a value as pretty JSON on stdout
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// A byte count for people, e.g. "2.4 MB"
+pub fn human_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+/// Shows warnings logged by micropub-core (such as retries) on stderr
+struct WarningLogger;
+
+impl log::Log for WarningLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Warn && metadata.target().starts_with("micropub_core")
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("⚠ {}", record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Route micropub-core's log warnings to stderr; call once at startup
+pub fn init_logging() {
+    static LOGGER: WarningLogger = WarningLogger;
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(log::LevelFilter::Warn);
+    }
+}
+
+/// An upload's progress line: name, bar, percentage, size, speed, and time left
+const UPLOAD_TEMPLATE: &str =
+    "  {msg} [{bar:24}] {percent:>3}%  {bytes}/{total_bytes}  {bytes_per_sec}  ETA {eta}";
+
+/// A progress bar for one file's upload, drawn to `target`
+fn upload_bar(progress: &UploadProgress, target: ProgressDrawTarget) -> ProgressBar {
+    let style = ProgressStyle::with_template(UPLOAD_TEMPLATE)
+        .expect("upload template is valid")
+        .progress_chars("#-");
+    let bar = ProgressBar::with_draw_target(Some(progress.total), target)
+        .with_style(style)
+        .with_message(progress.file.clone());
+    // A resumed upload starts part way through
+    bar.set_position(progress.sent);
+    bar.reset_eta();
+    bar
+}
+
+/// Draw a progress bar on stderr during media uploads; call once at startup.
+/// Does nothing unless stderr is a terminal, so pipes and CI logs stay clean.
+pub fn init_upload_progress() {
+    if !std::io::stderr().is_terminal() || crate::ci::enabled() {
+        return;
+    }
+    let current: Mutex<Option<(String, ProgressBar)>> = Mutex::new(None);
+    set_upload_observer(move |progress| {
+        let mut current = current.lock().unwrap_or_else(|e| e.into_inner());
+        match current.as_ref() {
+            // A retry starts the file again, possibly from a resumed offset
+            Some((file, bar)) if *file == progress.file && bar.position() <= progress.sent => {
+                bar.set_position(progress.sent);
+            }
+            _ => {
+                if let Some((_, bar)) = current.take() {
+                    bar.abandon();
+                }
+                let bar = upload_bar(progress, ProgressDrawTarget::stderr());
+                *current = Some((progress.file.clone(), bar));
+            }
+        }
+        if progress.sent >= progress.total {
+            if let Some((_, bar)) = current.take() {
+                bar.finish();
+            }
+        }
+    });
+}
+
+/// Print a progress message: stdout normally, stderr in `--json` mode
+#[macro_export]
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if $crate::output::json() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_bar() {
+        let progress = UploadProgress {
+            file: "video.mp4".to_string(),
+            sent: 5_000_000,
+            total: 10_000_000,
+            elapsed: std::time::Duration::from_secs(2),
+        };
+        let bar = upload_bar(&progress, ProgressDrawTarget::hidden());
+        assert_eq!(bar.length(), Some(10_000_000));
+        assert_eq!(bar.position(), 5_000_000);
+        assert_eq!(bar.message(), "video.mp4");
+    }
+}
@@ -0,0 +1,124 @@
+// ABOUTME: Pipes long listings through $PAGER when stdout is a terminal, like git
+// ABOUTME: Falls back to plain stdout when piped or when --no-pager is given
+
+use is_terminal::IsTerminal;
+use std::io::{self, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::editor::split_command;
+
+static NO_PAGER: AtomicBool = AtomicBool::new(false);
+
+/// Write listings straight to stdout for the rest of the process (`--no-pager`)
+pub fn set_no_pager(no_pager: bool) {
+    NO_PAGER.store(no_pager, Ordering::Relaxed);
+}
+
+/// The pager command from $PAGER (default `less`); `None` for an empty value or `cat`
+pub fn pager_command(env_pager: Option<&str>) -> Option<Vec<String>> {
+    let parts = split_command(env_pager.unwrap_or("less"));
+    match parts.first().map(String::as_str) {
+        None | Some("cat") => None,
+        Some(_) => Some(parts),
+    }
+}
+
+/// Output for a listing: a pager process or stdout
+pub struct Pager {
+    child: Option<Child>,
+    closed: bool,
+}
+
+impl Pager {
+    /// Start the pager if stdout is a terminal and paging isn't disabled
+    pub fn start() -> Self {
+        let child = if NO_PAGER.load(Ordering::Relaxed) || !io::stdout().is_terminal() {
+            None
+        } else {
+            let pager = std::env::var("PAGER").ok();
+            pager_command(pager.as_deref()).and_then(|command| {
+                Command::new(&command[0])
+                    .args(&command[1..])
+                    // Same defaults git uses: quit if one screen, keep colors, don't clear
+                    .env(
+                        "LESS",
+                        std::env::var("LESS").unwrap_or_else(|_| "FRX".into()),
+                    )
+                    .stdin(Stdio::piped())
+                    .spawn()
+                    .ok()
+            })
+        };
+
+        Self {
+            child,
+            closed: false,
+        }
+    }
+
+    /// Whether output is going to a pager, so it's fine to keep fetching pages
+    pub fn is_paging(&self) -> bool {
+        self.child.is_some()
+    }
+
+    /// Whether the user quit the pager; stop producing output
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+}
+
+impl Write for Pager {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.closed {
+            return Ok(buf.len());
+        }
+
+        let result = match self.child.as_mut().and_then(|c| c.stdin.as_mut()) {
+            Some(stdin) => stdin.write(buf),
+            None => io::stdout().write(buf),
+        };
+
+        match result {
+            // Quitting the pager early isn't an error
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(buf.len())
+            }
+            other => other,
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.child.as_mut().and_then(|c| c.stdin.as_mut()) {
+            Some(stdin) => stdin.flush().or(Ok(())),
+            None => io::stdout().flush(),
+        }
+    }
+}
+
+impl Drop for Pager {
+    /// Close the pager's input and wait for the user to quit it
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            drop(child.stdin.take());
+            let _ = child.wait();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(pager_command(None), Some(vec!["less".to_string()]));
+        assert_eq!(
+            pager_command(Some("less -S")),
+            Some(vec!["less".to_string(), "-S".to_string()])
+        );
+        assert_eq!(pager_command(Some("cat")), None);
+        assert_eq!(pager_command(Some("")), None);
+    }
+}
@@ -0,0 +1,173 @@
+// ABOUTME: One-shot photo posts: upload a file and publish it without a draft round trip
+// ABOUTME: Builds an h-entry with the photo, optional caption, alt text, and categories
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use std::time::Instant;
+
+use crate::auth::load_fresh_token;
+use crate::client::{MicropubAction, MicropubClient, MicropubRequest};
+use crate::config::Config;
+use crate::draft::{generate_draft_id, Draft};
+use crate::media::{resolve_path, upload_or_reuse};
+use crate::operations::media_endpoint_for;
+use crate::publish::{placeholder_upload_url, print_dry_run, PublishResult};
+use crate::summary::{self, Summary};
+
+/// Create request for a photo post. Alt text needs a structured photo value,
+/// so it's only included when `structured` (JSON requests) is true.
+pub fn build_photo_request(
+    photo_url: &str,
+    caption: Option<&str>,
+    alt: Option<&str>,
+    categories: &[String],
+    structured: bool,
+) -> MicropubRequest {
+    let mut properties = Map::new();
+
+    let photo = match alt {
+        Some(alt) if structured => json!({ "value": photo_url, "alt": alt }),
+        _ => Value::String(photo_url.to_string()),
+    };
+    properties.insert("photo".to_string(), Value::Array(vec![photo]));
+
+    if let Some(caption) = caption.filter(|c| !c.trim().is_empty()) {
+        properties.insert("content".to_string(), json!([caption]));
+    }
+    if !categories.is_empty() {
+        properties.insert("category".to_string(), json!(categories));
+    }
+
+    MicropubRequest {
+        action: MicropubAction::Create,
+        properties,
+        url: None,
+    }
+}
+
+pub async fn cmd_photo(
+    file: &str,
+    caption: Option<&str>,
+    alt: Option<&str>,
+    categories: &[String],
+    dry_run: bool,
+) -> Result<()> {
+    let started = Instant::now();
+    let config = Config::load()?;
+    let (profile_name, profile) = config.active_profile()?;
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured")?;
+    let path = resolve_path(file, None)?;
+    let structured = profile.request_format.is_json();
+
+    if dry_run {
+        let request = build_photo_request(
+            &placeholder_upload_url(&path),
+            caption,
+            alt,
+            categories,
+            structured,
+        );
+        return print_dry_run(micropub_endpoint, profile.request_format, &[path], &request);
+    }
+
+    let token = load_fresh_token(profile_name).await?;
+    let media_endpoint = media_endpoint_for(profile_name, profile, &token).await?;
+
+    crate::progress!("Uploading {}...", path.display());
+    let photo_url = upload_or_reuse(&media_endpoint, &token, &path, profile_name, alt)
+        .await?
+        .url;
+
+    let mut warnings = Vec::new();
+    if alt.is_some() && !structured {
+        warnings.push("Alt text can't be form-encoded, so it wasn't sent".to_string());
+    }
+
+    let request = build_photo_request(&photo_url, caption, alt, categories, structured);
+    crate::progress!("Publishing to {}...", profile.domain);
+    let response = MicropubClient::new(micropub_endpoint.clone(), token)
+        .with_format(profile.request_format)
+        .send(&request)
+        .await?;
+
+    if response.pending {
+        warnings
+            .push("Server accepted the post for processing; it may not be live yet".to_string());
+    } else if response.url.is_none() {
+        warnings.push("Server did not return a URL for the published post".to_string());
+    }
+
+    // Keep an archive record so `find` and `reconcile` know about the post
+    let mut draft = Draft::new(generate_draft_id());
+    draft.metadata.post_type = "photo".to_string();
+    draft.metadata.photo = vec![photo_url.clone()];
+    draft.metadata.category = categories.to_vec();
+    draft.metadata.status = Some("published".to_string());
+    draft.metadata.url = response.url.clone();
+    draft.metadata.published_at = Some(Utc::now());
+    draft.content = caption.unwrap_or_default().to_string();
+    let archived_path = draft.archive()?;
+
+    crate::progress!("✓ Photo published!");
+    if let Some(ref url) = response.url {
+        crate::progress!("  URL: {}", url);
+        crate::ci::set_output("url", url)?;
+    }
+    for warning in &warnings {
+        crate::ci::warning(warning);
+    }
+
+    let result = PublishResult {
+        url: response.url,
+        was_update: false,
+        archived_path,
+        uploads: vec![(path.display().to_string(), photo_url)],
+        warnings,
+        profile: profile_name.to_string(),
+        other_sites: Vec::new(),
+        queued: None,
+    };
+    summary::print(&result, &Summary::for_publish(&result, started.elapsed()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_photo_request() {
+        let categories = vec!["photos".to_string()];
+        let request = build_photo_request(
+            "https://example.com/media/1.jpg",
+            Some("Sunset"),
+            Some("Orange sky over the bay"),
+            &categories,
+            true,
+        );
+        assert_eq!(
+            request.properties["photo"],
+            json!([{ "value": "https://example.com/media/1.jpg", "alt": "Orange sky over the bay" }])
+        );
+        assert_eq!(request.properties["content"], json!(["Sunset"]));
+        assert_eq!(request.properties["category"], json!(["photos"]));
+
+        // Form requests get a plain URL, and a blank caption is left out
+        let request = build_photo_request(
+            "https://example.com/media/1.jpg",
+            Some("  "),
+            Some("alt"),
+            &[],
+            false,
+        );
+        assert_eq!(
+            request.properties["photo"],
+            json!(["https://example.com/media/1.jpg"])
+        );
+        assert!(!request.properties.contains_key("content"));
+        assert!(request.to_form().is_ok());
+    }
+}
@@ -0,0 +1,397 @@
+// ABOUTME: Profile management commands (list, show, remove, set-default, prune)
+// ABOUTME: Reports per-profile token usage and removes stale site credentials
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+use crate::config::{get_tokens_dir, load_token, remove_token, token_last_used, Config};
+use crate::output::{json, print_json};
+
+/// Parse a human age like "90d", "12h", "2w", or "30m" into a duration
+pub fn parse_age(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let unit_start = input.char_indices().last().map_or(0, |(i, _)| i);
+    let (number, unit) = input.split_at(unit_start);
+    let value: i64 = number
+        .parse()
+        .with_context(|| format!("Invalid duration '{}'. Use e.g. 90d, 12h, 2w", input))?;
+    if value <= 0 {
+        anyhow::bail!("Duration '{}' must be more than zero", input);
+    }
+
+    let age = match unit {
+        "m" => Duration::try_minutes(value),
+        "h" => Duration::try_hours(value),
+        "d" => Duration::try_days(value),
+        "w" => Duration::try_weeks(value),
+        _ => anyhow::bail!("Invalid duration unit in '{}'. Use m, h, d, or w", input),
+    };
+    age.with_context(|| format!("Duration '{}' is too long", input))
+}
+
+/// Last time a profile's token was used, falling back to when it was saved
+fn last_activity(profile_name: &str) -> Option<DateTime<Utc>> {
+    token_last_used(profile_name).or_else(|| {
+        let path = get_tokens_dir()
+            .ok()?
+            .join(format!("{}.token", profile_name));
+        let modified = fs::metadata(path).ok()?.modified().ok()?;
+        Some(DateTime::<Utc>::from(modified))
+    })
+}
+
+/// Format how long ago a timestamp was, e.g. "3 days ago"
+fn format_ago(when: DateTime<Utc>) -> String {
+    let elapsed = Utc::now() - when;
+    if elapsed.num_days() > 0 {
+        format!("{} days ago", elapsed.num_days())
+    } else if elapsed.num_hours() > 0 {
+        format!("{} hours ago", elapsed.num_hours())
+    } else {
+        "just now".to_string()
+    }
+}
+
+/// List configured profiles with their last-used time
+pub fn cmd_profile_list() -> Result<()> {
+    let config = Config::load()?;
+
+    if config.profiles.is_empty() {
+        println!("No profiles configured. Run 'micropub auth <domain>' to add one.");
+        return Ok(());
+    }
+
+    let mut names: Vec<&String> = config.profiles.keys().collect();
+    names.sort();
+
+    println!("Profiles:");
+    for name in names {
+        let profile = &config.profiles[name];
+        let marker = if *name == config.default_profile {
+            "*"
+        } else {
+            " "
+        };
+        println!("{} {}", marker, name);
+        println!("    Domain: {}", profile.domain);
+        match token_last_used(name) {
+            Some(when) => println!(
+                "    Last used: {} ({})",
+                when.format("%Y-%m-%d %H:%M"),
+                format_ago(when)
+            ),
+            None => println!("    Last used: never"),
+        }
+    }
+
+    Ok(())
+}
+
+/// Show one profile's settings and whether a token is stored for it
+pub fn cmd_profile_show(name: &str) -> Result<()> {
+    let config = Config::load()?;
+    let profile = config
+        .profiles
+        .get(name)
+        .with_context(|| format!("Profile not found: {}", name))?;
+    let has_token = load_token(name).is_ok();
+    let last_used = token_last_used(name);
+
+    if json() {
+        return print_json(&serde_json::json!({
+            "name": name,
+            "default": config.default_profile == name,
+            "profile": profile,
+            "has_token": has_token,
+            "last_used": last_used,
+        }));
+    }
+
+    let unset = "(not set)";
+    println!(
+        "{}{}",
+        name,
+        if config.default_profile == name {
+            " (default)"
+        } else {
+            ""
+        }
+    );
+    println!("  Domain: {}", profile.domain);
+    for (label, value) in [
+        ("Micropub endpoint", &profile.micropub_endpoint),
+        ("Media endpoint", &profile.media_endpoint),
+        ("Token endpoint", &profile.token_endpoint),
+        ("Authorization endpoint", &profile.authorization_endpoint),
+        ("License", &profile.license),
+    ] {
+        println!("  {}: {}", label, value.as_deref().unwrap_or(unset));
+    }
+    let overrides = &profile.endpoint_overrides;
+    for (kind, value) in [
+        ("micropub", &overrides.micropub),
+        ("media", &overrides.media),
+        ("authorization", &overrides.authorization),
+        ("token", &overrides.token),
+    ] {
+        if let Some(url) = value {
+            println!("  Overridden {} endpoint: {}", kind, url);
+        }
+    }
+    println!(
+        "  Request format: {}",
+        if profile.request_format.is_json() {
+            "json"
+        } else {
+            "form"
+        }
+    );
+    if profile.polite {
+        println!("  Polite: yes");
+    }
+    if !profile.transforms.is_empty() {
+        println!("  Transforms: {}", profile.transforms.len());
+    }
+    if !profile.syndicate_to.is_empty() {
+        println!("  Syndication targets: {}", profile.syndicate_to.len());
+    }
+    println!("  Token: {}", if has_token { "stored" } else { "none" });
+    match last_used {
+        Some(when) => println!(
+            "  Last used: {} ({})",
+            when.format("%Y-%m-%d %H:%M"),
+            format_ago(when)
+        ),
+        None => println!("  Last used: never"),
+    }
+    Ok(())
+}
+
+/// Remove a profile and its token
+pub fn cmd_profile_remove(name: &str) -> Result<()> {
+    let mut config = Config::load()?;
+    let was_default = config.default_profile == name;
+    if !config.profiles.contains_key(name) {
+        bail!("Profile not found: {}", name);
+    }
+    confirm_remove(
+        &[name.to_string()],
+        crate::prompt::no_input(),
+        crate::prompt::can_prompt(),
+        &mut io::stdin().lock(),
+    )?;
+    config.remove_profile(name);
+    config.save()?;
+    remove_token(name)?;
+    crate::server_config::invalidate(name)?;
+
+    println!("✓ Removed: {}", name);
+    if was_default {
+        println!("It was the default profile; choose another with `micropub profile set-default <name>`.");
+    }
+    Ok(())
+}
+
+/// Check before removing profiles and their tokens: `--yes` goes ahead, a
+/// terminal is asked (reading the answer from `input`), anything else is refused
+fn confirm_remove(
+    names: &[String],
+    no_input: bool,
+    can_prompt: bool,
+    input: &mut impl BufRead,
+) -> Result<()> {
+    if no_input {
+        return Ok(());
+    }
+    let (listed, one) = (names.join(", "), names.len() == 1);
+    if !can_prompt {
+        bail!(
+            "Not removing {} without confirmation; pass --yes to remove {}",
+            listed,
+            if one { "it" } else { "them" }
+        );
+    }
+    print!(
+        "Remove {} and {}? [y/N] ",
+        listed,
+        if one {
+            "its stored token"
+        } else {
+            "their stored tokens"
+        }
+    );
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    if !matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") {
+        bail!("Not removed");
+    }
+    Ok(())
+}
+
+/// Make a profile the default
+pub fn cmd_profile_set_default(name: &str) -> Result<()> {
+    let mut config = Config::load()?;
+    if !config.profiles.contains_key(name) {
+        bail!("Profile not found: {}", name);
+    }
+    config.default_profile = name.to_string();
+    config.save()?;
+    println!("✓ Default profile: {}", name);
+    Ok(())
+}
+
+/// Profiles other than the default last used before `cutoff`, and those with no
+/// record of use at all (keychain tokens leave no file behind), both sorted
+fn stale_profiles(
+    config: &Config,
+    cutoff: DateTime<Utc>,
+    activity: impl Fn(&str) -> Option<DateTime<Utc>>,
+) -> (Vec<String>, Vec<String>) {
+    let mut stale = Vec::new();
+    let mut unknown = Vec::new();
+    for name in config.profiles.keys() {
+        if *name == config.default_profile {
+            continue;
+        }
+        match activity(name) {
+            Some(when) if when < cutoff => stale.push(name.clone()),
+            Some(_) => {}
+            None => unknown.push(name.clone()),
+        }
+    }
+    stale.sort();
+    unknown.sort();
+    (stale, unknown)
+}
+
+/// Remove profiles (and their tokens) that haven't been used within `unused_for`.
+/// Profiles with no recorded use are listed but kept.
+pub fn cmd_profile_prune(unused_for: &str, dry_run: bool) -> Result<()> {
+    let max_age = parse_age(unused_for)?;
+    let cutoff = Utc::now() - max_age;
+    let mut config = Config::load()?;
+
+    let (stale, unknown) = stale_profiles(&config, cutoff, last_activity);
+    if !unknown.is_empty() {
+        println!(
+            "Keeping (no use recorded yet): {}; remove them with `micropub profile remove`",
+            unknown.join(", ")
+        );
+    }
+
+    if stale.is_empty() {
+        println!("No profiles unused for {}.", unused_for);
+        return Ok(());
+    }
+
+    if dry_run {
+        for name in &stale {
+            println!("Would remove: {}", name);
+        }
+        return Ok(());
+    }
+
+    confirm_remove(
+        &stale,
+        crate::prompt::no_input(),
+        crate::prompt::can_prompt(),
+        &mut io::stdin().lock(),
+    )?;
+
+    for name in &stale {
+        config.remove_profile(name);
+        remove_token(name)?;
+        crate::server_config::invalidate(name)?;
+        println!("✓ Removed: {}", name);
+    }
+
+    config.save()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_age_units() {
+        assert_eq!(parse_age("90d").unwrap(), Duration::days(90));
+        assert_eq!(parse_age("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_age("2w").unwrap(), Duration::weeks(2));
+        assert_eq!(parse_age("30m").unwrap(), Duration::minutes(30));
+    }
+
+    #[test]
+    fn test_parse_age_rejects_invalid() {
+        assert!(parse_age("").is_err());
+        assert!(parse_age("90").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("ninety-d").is_err());
+        assert!(parse_age("9é").is_err());
+    }
+
+    #[test]
+    fn test_parse_age_rejects_zero_negative_and_huge() {
+        assert!(parse_age("0d").is_err());
+        assert!(parse_age("-5d").is_err());
+        assert!(parse_age("99999999999999w").is_err());
+    }
+
+    #[test]
+    fn test_confirm_remove_asks_at_terminal() {
+        for (answer, accepted) in [
+            ("y\n", true),
+            ("YES\n", true),
+            ("n\n", false),
+            ("\n", false),
+            ("", false),
+        ] {
+            let mut input = io::Cursor::new(answer);
+            let result = confirm_remove(&["old-blog".to_string()], false, true, &mut input);
+            assert_eq!(result.is_ok(), accepted, "answer {:?}", answer);
+        }
+    }
+
+    #[test]
+    fn test_confirm_remove_without_terminal_needs_yes() {
+        let mut input = io::Cursor::new("y\n");
+        let names = ["old-blog".to_string(), "old-site".to_string()];
+        let err = confirm_remove(&names, false, false, &mut input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Not removing old-blog, old-site without confirmation; pass --yes to remove them"
+        );
+
+        // --yes never reads an answer
+        let mut input = io::Cursor::new("n\n");
+        assert!(confirm_remove(&names, true, false, &mut input).is_ok());
+        assert_eq!(input.position(), 0);
+    }
+
+    #[test]
+    fn test_stale_profiles_keep_unknown_activity() {
+        use crate::config::Profile;
+
+        let mut config = Config {
+            default_profile: "main".to_string(),
+            ..Default::default()
+        };
+        for name in ["main", "old", "recent", "keychain"] {
+            config.upsert_profile(name.to_string(), Profile::default());
+        }
+        let now = Utc::now();
+        let activity = |name: &str| match name {
+            "main" | "old" => Some(now - Duration::days(200)),
+            "recent" => Some(now - Duration::days(1)),
+            _ => None,
+        };
+
+        let (stale, unknown) = stale_profiles(&config, now - Duration::days(90), activity);
+        // The default is never pruned, and no record isn't proof of disuse
+        assert_eq!(stale, ["old"]);
+        assert_eq!(unknown, ["keychain"]);
+    }
+}
@@ -0,0 +1,183 @@
+// ABOUTME: Catches a draft about to go to a different site than the one in use
+// ABOUTME: Asks before publishing when frontmatter `profile:` and `--profile` or the default disagree
+
+use anyhow::{bail, Result};
+use std::io::{self, Write};
+
+use crate::config::{profile_override, Config};
+use crate::draft::{Draft, DraftId};
+
+/// A draft whose `profile:` disagrees with the profile otherwise in use
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// What the draft's frontmatter names
+    pub draft_profile: String,
+    /// The `--profile` choice (or TUI switch), or the default profile
+    pub active_profile: String,
+    /// Whether `--profile` overrides the draft, so the post goes to `active_profile`
+    pub overridden: bool,
+}
+
+impl Mismatch {
+    /// Where the post will go
+    pub fn target(&self) -> &str {
+        if self.overridden {
+            &self.active_profile
+        } else {
+            &self.draft_profile
+        }
+    }
+
+    /// Both profiles with their domains, e.g. "--profile sends this draft to
+    /// blog (blog.example.com), but its frontmatter says profile: work (work.example.com)"
+    pub fn describe(&self, config: &Config) -> String {
+        let site = |name: &str| match config.get_profile(name) {
+            Some(profile) if !profile.domain.is_empty() => {
+                format!("{} ({})", name, profile.domain)
+            }
+            _ => name.to_string(),
+        };
+        if self.overridden {
+            format!(
+                "--profile sends this draft to {}, but its frontmatter says profile: {}",
+                site(&self.active_profile),
+                site(&self.draft_profile)
+            )
+        } else {
+            format!(
+                "This draft's profile: sends it to {}, not the default {}",
+                site(&self.draft_profile),
+                site(&self.active_profile)
+            )
+        }
+    }
+}
+
+/// Compare a draft's `profile:` list with the active profile. Drafts without one,
+/// drafts naming several sites, and environment credentials never mismatch; with
+/// `separate_profile_drafts`, publishing refuses mismatched drafts outright.
+pub fn find_in(config: &Config, draft_profiles: &[String], overridden: bool) -> Option<Mismatch> {
+    if config.env_profile.is_some() || config.separate_profile_drafts {
+        return None;
+    }
+    let draft_profile = draft_profiles.first()?;
+    let active = config.active_profile_name();
+    if active.is_empty() {
+        return None;
+    }
+
+    if overridden {
+        // Every profile passed with --profile has to be one the draft names
+        let targets = config.profiles_for_draft(draft_profiles);
+        let target = targets.iter().find(|t| !draft_profiles.contains(t))?;
+        return Some(Mismatch {
+            draft_profile: draft_profile.clone(),
+            active_profile: target.clone(),
+            overridden: true,
+        });
+    }
+
+    (draft_profiles.len() == 1 && *draft_profile != active).then(|| Mismatch {
+        draft_profile: draft_profile.clone(),
+        active_profile: active,
+        overridden: false,
+    })
+}
+
+/// `find_in` for the current `--profile` choice
+pub fn find(config: &Config, draft: &Draft) -> Option<Mismatch> {
+    find_in(
+        config,
+        &draft.metadata.profile,
+        profile_override().is_some(),
+    )
+}
+
+/// Stop before publishing a draft to a profile other than the one in use unless
+/// `allow` is set or the user confirms at the terminal
+pub fn check_before_publish(draft_path: &str, allow: bool) -> Result<()> {
+    if allow {
+        return Ok(());
+    }
+    let config = Config::load()?;
+    let draft = Draft::load(DraftId::from_path(draft_path)?.as_str())?;
+    let Some(mismatch) = find(&config, &draft) else {
+        return Ok(());
+    };
+    let described = mismatch.describe(&config);
+
+    if !crate::prompt::can_prompt() {
+        bail!(
+            "{}; pass --allow-profile-mismatch to publish it anyway",
+            described
+        );
+    }
+
+    println!("⚠ {}", described);
+    print!("Publish to {}? [y/N] ", mismatch.target());
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+        bail!("Not published");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Profile;
+
+    fn config() -> Config {
+        let mut config = Config {
+            default_profile: "blog".to_string(),
+            ..Default::default()
+        };
+        for (name, domain) in [("blog", "blog.example.com"), ("work", "work.example.com")] {
+            config.upsert_profile(
+                name.to_string(),
+                Profile {
+                    domain: domain.to_string(),
+                    ..Default::default()
+                },
+            );
+        }
+        config
+    }
+
+    fn profiles(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_find_in_flags_draft_profile_other_than_default() {
+        let config = config();
+        assert_eq!(find_in(&config, &[], false), None);
+        assert_eq!(find_in(&config, &profiles(&["blog"]), false), None);
+        // Cross-posting on purpose
+        assert_eq!(find_in(&config, &profiles(&["blog", "work"]), false), None);
+
+        let mismatch = find_in(&config, &profiles(&["work"]), false).unwrap();
+        assert_eq!(mismatch.target(), "work");
+        assert_eq!(
+            mismatch.describe(&config),
+            "This draft's profile: sends it to work (work.example.com), not the default blog (blog.example.com)"
+        );
+    }
+
+    #[test]
+    fn test_describe_override() {
+        let config = config();
+        let mismatch = Mismatch {
+            draft_profile: "work".to_string(),
+            active_profile: "blog".to_string(),
+            overridden: true,
+        };
+        assert_eq!(mismatch.target(), "blog");
+        assert_eq!(
+            mismatch.describe(&config),
+            "--profile sends this draft to blog (blog.example.com), but its frontmatter says profile: work (work.example.com)"
+        );
+    }
+}
@@ -0,0 +1,23 @@
+// ABOUTME: Interactive prompt helpers shared by CLI commands
+// ABOUTME: Honors --yes/--no-input so scripts never block waiting on a TTY
+
+use is_terminal::IsTerminal;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static NO_INPUT: AtomicBool = AtomicBool::new(false);
+
+/// Assume defaults instead of prompting for the rest of the process (`--yes`/`--no-input`)
+pub fn set_no_input(no_input: bool) {
+    NO_INPUT.store(no_input, Ordering::Relaxed);
+}
+
+/// Whether `--yes`/`--no-input` was passed
+pub fn no_input() -> bool {
+    NO_INPUT.load(Ordering::Relaxed)
+}
+
+/// Whether prompts are allowed: not disabled and both stdin and stdout are terminals
+pub fn can_prompt() -> bool {
+    !no_input() && io::stdin().is_terminal() && io::stdout().is_terminal()
+}
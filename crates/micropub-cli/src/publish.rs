@@ -0,0 +1,950 @@
+// ABOUTME: Post publishing functionality
+// ABOUTME: Orchestrates draft loading, media upload, and micropub posting
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use crate::auth::load_fresh_token;
+use crate::client::{MicropubAction, MicropubClient, MicropubRequest, RequestFormat};
+use crate::config::{Config, Profile};
+use crate::draft::{
+    ensure_valid, is_valid_slug, known_categories, lint_with_config, unfamiliar_categories, Draft,
+    DraftId,
+};
+use crate::i18n::{t, tf};
+use crate::media::{
+    alt_text_for, find_media_references, replace_paths, resolve_path, upload_or_reuse,
+};
+use crate::operations::syndication_targets;
+use crate::queue::{enqueue, queued_message, QueueKind};
+use crate::server_config::{cached_categories, get_categories, resolve_syndication_target};
+use crate::transform::apply_transforms;
+use crate::transport::is_connection_error;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PublishResult {
+    /// URL of the published post, if the server returned one
+    pub url: Option<String>,
+    /// True when an existing post or server draft was updated rather than a new post created
+    pub was_update: bool,
+    /// Where the draft was archived after publishing
+    pub archived_path: PathBuf,
+    /// Uploaded media as (filename, url) pairs
+    pub uploads: Vec<(String, String)>,
+    /// Non-fatal issues encountered while publishing
+    pub warnings: Vec<String>,
+    /// Profile the post was published with
+    pub profile: String,
+    /// Results for the other sites when a draft is published to several profiles
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub other_sites: Vec<PublishResult>,
+    /// Offline queue ID when the server couldn't be reached and the request was queued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queued: Option<String>,
+}
+
+impl PublishResult {
+    /// One-line status for this site: `published` and the post's URL, or that it's
+    /// waiting in the offline queue
+    pub fn status(&self, published: &str) -> String {
+        match (&self.queued, &self.url) {
+            (Some(id), _) => format!(
+                "Queued, not published yet (queue ID {}); run `micropub queue flush` once the server can be reached",
+                id
+            ),
+            (None, Some(url)) => format!("{}: {}", published, url),
+            (None, None) => format!("{} successfully!", published),
+        }
+    }
+}
+
+/// Parse a comma-separated list of 1-based choices into indexes
+pub fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>> {
+    let mut indexes = Vec::new();
+
+    for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let choice: usize = part
+            .parse()
+            .with_context(|| format!("Invalid selection: {}", part))?;
+        if choice == 0 || choice > count {
+            bail!("Selection out of range: {}", choice);
+        }
+        if !indexes.contains(&(choice - 1)) {
+            indexes.push(choice - 1);
+        }
+    }
+
+    Ok(indexes)
+}
+
+/// Offer a category picker for an uncategorized draft before it's published.
+///
+/// Only runs when `prompt_for_categories` is enabled and both stdin and stdout
+/// are terminals, so the TUI and MCP paths never block on it.
+pub fn pick_categories_if_missing(draft_path: &str) -> Result<()> {
+    let config = Config::load()?;
+    if !config.prompt_for_categories || !crate::prompt::can_prompt() {
+        return Ok(());
+    }
+
+    let mut draft = Draft::load(DraftId::from_path(draft_path)?.as_str())?;
+    if !draft.metadata.category.is_empty() {
+        return Ok(());
+    }
+
+    let known = known_categories()?;
+    if known.is_empty() {
+        return Ok(());
+    }
+
+    println!("This draft has no categories. Known categories:");
+    for (idx, category) in known.iter().enumerate() {
+        println!("  {}. {}", idx + 1, category);
+    }
+
+    loop {
+        print!("Select categories (e.g. 1,3) or press Enter to skip: ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        match parse_selection(&input, known.len()) {
+            Ok(indexes) if indexes.is_empty() => return Ok(()),
+            Ok(indexes) => {
+                draft.metadata.category = indexes.into_iter().map(|i| known[i].clone()).collect();
+                draft.save()?;
+                println!("Categories: {}", draft.metadata.category.join(", "));
+                return Ok(());
+            }
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+/// Render a draft's content with its profile's transforms, without publishing
+pub fn preview_content(draft_path: &str) -> Result<String> {
+    let draft = Draft::load(DraftId::from_path(draft_path)?.as_str())?;
+    let config = Config::load()?;
+
+    let profile_name = &config.profile_for_draft(draft.metadata.profile());
+    let profile = config
+        .get_profile(profile_name)
+        .context(format!("Profile not found: {}", profile_name))?;
+
+    apply_transforms(&draft.content, &profile.transforms)
+}
+
+/// Add `--syndicate` targets to a draft's `syndicate-to`, resolving names to uids
+pub async fn add_syndication_targets(draft_path: &str, targets: &[String]) -> Result<()> {
+    if targets.is_empty() {
+        return Ok(());
+    }
+
+    let mut draft = Draft::load(DraftId::from_path(draft_path)?.as_str())?;
+    apply_syndication_targets(&mut draft, targets).await?;
+    draft.save()?;
+    Ok(())
+}
+
+/// Resolve `--syndicate` targets by uid or name and add them to `syndicate_to`
+async fn apply_syndication_targets(draft: &mut Draft, targets: &[String]) -> Result<()> {
+    let config = Config::load()?;
+    let draft_profile = draft.metadata.profile().map(str::to_string);
+    let profile_name = &config.profile_for_draft(draft_profile.as_deref());
+    let known = syndication_targets(profile_name, false).await?;
+
+    for target in targets {
+        // A target missing from the cache may be new on the server, so check again
+        let uid = match resolve_syndication_target(&known, target) {
+            Ok(uid) => uid,
+            Err(_) => {
+                let fresh = syndication_targets(profile_name, true).await?;
+                resolve_syndication_target(&fresh, target)?
+            }
+        };
+        if !draft.metadata.syndicate_to.contains(&uid) {
+            draft.metadata.syndicate_to.push(uid);
+        }
+    }
+    Ok(())
+}
+
+fn apply_slug(draft: &mut Draft, slug: &str) -> Result<()> {
+    if !is_valid_slug(slug) {
+        bail!(
+            "Invalid slug '{}': use lowercase letters, digits, and hyphens",
+            slug
+        );
+    }
+    draft.metadata.slug = Some(slug.to_string());
+    Ok(())
+}
+
+/// Set a draft's `slug` from `publish --slug`
+pub fn set_slug(draft_path: &str, slug: &str) -> Result<()> {
+    let mut draft = Draft::load(DraftId::from_path(draft_path)?.as_str())?;
+    apply_slug(&mut draft, slug)?;
+    draft.save()?;
+    Ok(())
+}
+
+/// `publish` flags that change the draft, which a dry run shows in its
+/// request without saving them
+#[derive(Debug, Default)]
+pub struct DraftEdits<'a> {
+    /// `--slug`
+    pub slug: Option<&'a str>,
+    /// `--syndicate` targets, by uid or name
+    pub syndicate: &'a [String],
+}
+
+impl DraftEdits<'_> {
+    async fn apply(&self, draft: &mut Draft) -> Result<()> {
+        if let Some(slug) = self.slug {
+            apply_slug(draft, slug)?;
+        }
+        if !self.syndicate.is_empty() {
+            apply_syndication_targets(draft, self.syndicate).await?;
+        }
+        Ok(())
+    }
+}
+
+/// The draft's `unfamiliar_categories`, checked against the server's category list
+async fn unused_categories(
+    draft: &Draft,
+    profile_name: &str,
+    micropub_endpoint: &str,
+    token: &str,
+    dry_run: bool,
+) -> Vec<String> {
+    if draft.metadata.category.is_empty() {
+        return Vec::new();
+    }
+
+    // A dry run doesn't talk to the server, and a failed query shouldn't stop publishing
+    let server = if dry_run {
+        cached_categories(profile_name)
+    } else {
+        get_categories(profile_name, micropub_endpoint, token, false)
+            .await
+            .unwrap_or_else(|_| cached_categories(profile_name))
+    };
+    unfamiliar_categories(&draft.metadata.category, server)
+}
+
+/// Properties of the post a draft creates, once its transformed content has had
+/// local media paths swapped for `replacements`
+fn create_properties(
+    draft: &Draft,
+    default_license: Option<&String>,
+    final_content: &str,
+    replacements: &[(String, String)],
+    backdate: Option<DateTime<Utc>>,
+) -> Result<Map<String, Value>> {
+    let mut properties = Map::new();
+
+    // Likes and reposts usually have no text of their own
+    if !final_content.trim().is_empty() || draft.metadata.response_properties().is_empty() {
+        properties.insert(
+            "content".to_string(),
+            Value::Array(vec![Value::String(final_content.to_string())]),
+        );
+    }
+
+    for (property, url) in draft.metadata.response_properties() {
+        properties.insert(
+            property.to_string(),
+            Value::Array(vec![Value::String(url.to_string())]),
+        );
+    }
+
+    if let Some(name) = &draft.metadata.name {
+        properties.insert(
+            "name".to_string(),
+            Value::Array(vec![Value::String(name.clone())]),
+        );
+    }
+
+    if let Some(summary) = &draft.metadata.summary {
+        properties.insert(
+            "summary".to_string(),
+            Value::Array(vec![Value::String(summary.clone())]),
+        );
+    }
+
+    if !draft.metadata.category.is_empty() {
+        properties.insert(
+            "category".to_string(),
+            Value::Array(
+                draft
+                    .metadata
+                    .category
+                    .iter()
+                    .map(|c| Value::String(c.clone()))
+                    .collect(),
+            ),
+        );
+    }
+
+    if let Some(license) = draft.metadata.license.as_ref().or(default_license) {
+        properties.insert(
+            "license".to_string(),
+            Value::Array(vec![Value::String(license.clone())]),
+        );
+    }
+
+    if !draft.metadata.photo.is_empty() {
+        // Build photo array: uploaded URLs + remote URLs
+        let mut photo_values: Vec<Value> = Vec::new();
+
+        for photo_path in &draft.metadata.photo {
+            if photo_path.starts_with("http://") || photo_path.starts_with("https://") {
+                // Keep remote URLs as-is
+                photo_values.push(Value::String(photo_path.clone()));
+            } else {
+                // Find the corresponding uploaded URL
+                if let Some((_, url)) = replacements.iter().find(|(local, _)| local == photo_path) {
+                    photo_values.push(Value::String(url.clone()));
+                } else {
+                    bail!("Photo file not found or not uploaded: {}", photo_path);
+                }
+            }
+        }
+
+        properties.insert("photo".to_string(), Value::Array(photo_values));
+    }
+
+    if !draft.metadata.syndicate_to.is_empty() {
+        properties.insert(
+            "mp-syndicate-to".to_string(),
+            Value::Array(
+                draft
+                    .metadata
+                    .syndicate_to
+                    .iter()
+                    .map(|s| Value::String(s.clone()))
+                    .collect(),
+            ),
+        );
+    }
+
+    if let Some(slug) = &draft.metadata.slug {
+        properties.insert(
+            "mp-slug".to_string(),
+            Value::Array(vec![Value::String(slug.clone())]),
+        );
+    }
+
+    // Handle published date (backdate or from draft)
+    let published_date = backdate.or(draft.metadata.published);
+    if let Some(date) = published_date {
+        properties.insert(
+            "published".to_string(),
+            Value::Array(vec![Value::String(date.to_rfc3339())]),
+        );
+    }
+
+    Ok(properties)
+}
+
+/// URL standing in for a file that a dry run would have uploaded
+pub(crate) fn placeholder_upload_url(path: &std::path::Path) -> String {
+    format!("upload://{}", path.display())
+}
+
+/// Print what a dry run would have sent: uploads, endpoint, and request body
+pub(crate) fn print_dry_run(
+    endpoint: &str,
+    format: RequestFormat,
+    uploads: &[PathBuf],
+    request: &MicropubRequest,
+) -> Result<()> {
+    let body: Value = serde_json::from_str(&request.to_json()?)?;
+
+    if crate::output::json() {
+        return crate::output::print_json(&serde_json::json!({
+            "endpoint": endpoint,
+            "format": if format.is_json() { "json" } else { "form" },
+            "uploads": uploads,
+            "body": body,
+        }));
+    }
+
+    println!("Dry run: nothing was uploaded or sent.");
+    if !uploads.is_empty() {
+        println!("\nWould upload {} file(s):", uploads.len());
+        for path in uploads {
+            println!(
+                "  {} (referenced as {})",
+                path.display(),
+                placeholder_upload_url(path)
+            );
+        }
+    }
+    println!(
+        "\nPOST {}{}",
+        endpoint,
+        if format.is_json() {
+            ""
+        } else {
+            " (form-encoded)"
+        }
+    );
+    println!("{}", serde_json::to_string_pretty(&body)?);
+    Ok(())
+}
+
+/// Publish a draft; `allow_duplicate` skips refusing a repeat of a recent post
+pub async fn cmd_publish(
+    draft_path: &str,
+    backdate: Option<DateTime<Utc>>,
+    allow_duplicate: bool,
+) -> Result<PublishResult> {
+    publish_draft(draft_path, backdate, None, allow_duplicate)
+        .await?
+        .context("Draft was not published")
+}
+
+/// Print the request `publish` would send, with `edits` applied, without
+/// uploading or sending anything or saving the draft
+pub async fn cmd_publish_dry_run(
+    draft_path: &str,
+    backdate: Option<DateTime<Utc>>,
+    edits: &DraftEdits<'_>,
+) -> Result<()> {
+    publish_draft(draft_path, backdate, Some(edits), true)
+        .await
+        .map(|_| ())
+}
+
+/// Properties `publish` would send to create a post from `draft` with `profile`:
+/// its transforms applied, its default license filled in, and local media
+/// referenced by `upload://` placeholders instead of being uploaded
+pub fn draft_properties(draft: &Draft, profile: &Profile) -> Result<Map<String, Value>> {
+    let content = apply_transforms(&draft.content, &profile.transforms)?;
+
+    let mut local_paths = find_media_references(&content);
+    for photo_path in &draft.metadata.photo {
+        if !photo_path.starts_with("http://") && !photo_path.starts_with("https://") {
+            local_paths.push(photo_path.clone());
+        }
+    }
+
+    let mut replacements = Vec::new();
+    for local_path in local_paths {
+        let resolved = resolve_path(&local_path, None)?;
+        replacements.push((local_path, placeholder_upload_url(&resolved)));
+    }
+
+    create_properties(
+        draft,
+        profile.license.as_ref(),
+        &replace_paths(&content, &replacements),
+        &replacements,
+        None,
+    )
+}
+
+/// Print the properties a draft would be published with as pretty JSON; a
+/// draft published to several sites gets one map per profile
+pub fn cmd_props(draft_id: &str) -> Result<()> {
+    let config = Config::load()?;
+    let draft = Draft::load(draft_id)?;
+
+    let mut by_profile = Map::new();
+    for profile_name in config.profiles_for_draft(&draft.metadata.profile) {
+        let profile = config
+            .get_profile(&profile_name)
+            .context(format!("Profile not found: {}", profile_name))?;
+        let properties = draft_properties(&draft, profile)?;
+        by_profile.insert(profile_name, Value::Object(properties));
+    }
+
+    let output = if by_profile.len() == 1 {
+        by_profile.into_values().next().unwrap_or_default()
+    } else {
+        Value::Object(by_profile)
+    };
+    println!("{}", serde_json::to_string_pretty(&output)?);
+    Ok(())
+}
+
+/// Publish a draft, or with `dry_run` edits, print what would be sent
+async fn publish_draft(
+    draft_path: &str,
+    backdate: Option<DateTime<Utc>>,
+    dry_run: Option<&DraftEdits<'_>>,
+    allow_duplicate: bool,
+) -> Result<Option<PublishResult>> {
+    let config = Config::load()?;
+
+    // Validate up front so problems are reported by line, not as serde errors mid-publish
+    let id = DraftId::from_path(draft_path)?;
+    let _lock = crate::draft::lock::lock(id.as_str()).await;
+    let (draft, problems) = lint_with_config(id.as_str(), Some(&config))?;
+    ensure_valid(id.as_str(), &problems)?;
+    let mut draft = draft.context("Failed to parse draft")?;
+    if let Some(edits) = dry_run {
+        edits.apply(&mut draft).await?;
+    }
+    config.check_draft_profiles(&draft.metadata.profile)?;
+    crate::duplicates::ensure_not_duplicate(&draft, allow_duplicate)?;
+    let dry_run = dry_run.is_some();
+
+    // Determine which profile(s) to use
+    match config
+        .profiles_for_draft(&draft.metadata.profile)
+        .as_slice()
+    {
+        [profile_name] => publish_to(&config, draft, profile_name, backdate, dry_run).await,
+        profiles => publish_to_each(&config, draft, profiles, backdate, dry_run).await,
+    }
+}
+
+/// Whether publishing sends an update: the draft is a pushed server draft, or a
+/// published post fetched with `get --to-draft`
+fn updates_existing_post(draft: &Draft) -> bool {
+    draft.metadata.url.is_some()
+        && matches!(
+            draft.metadata.status.as_deref(),
+            Some("server-draft") | Some("published")
+        )
+}
+
+/// Archive ID for one site's copy of a draft published to several
+fn site_draft_id(draft_id: &str, profile_name: &str) -> String {
+    let slug: String = profile_name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("{}-{}", draft_id, slug)
+}
+
+/// Publish a draft to several sites, each with its own uploads and archive record.
+/// If a site fails, the draft is kept with only the profiles still to go.
+async fn publish_to_each(
+    config: &Config,
+    draft: Draft,
+    profiles: &[String],
+    backdate: Option<DateTime<Utc>>,
+    dry_run: bool,
+) -> Result<Option<PublishResult>> {
+    if updates_existing_post(&draft) {
+        bail!("This draft updates a post on one site; publish it with a single profile");
+    }
+
+    let mut results = Vec::new();
+    for (i, profile_name) in profiles.iter().enumerate() {
+        crate::progress!("[{}/{}] {}", i + 1, profiles.len(), profile_name);
+
+        let mut site_draft = draft.clone();
+        site_draft.id = site_draft_id(&draft.id, profile_name);
+        site_draft.metadata.profile = vec![profile_name.clone()];
+
+        match publish_to(config, site_draft, profile_name, backdate, dry_run).await {
+            Ok(result) => results.extend(result),
+            // Nothing went out on a dry run, so the draft stays as it is
+            Err(e) if i == 0 || dry_run => return Err(e),
+            Err(e) => {
+                let mut remaining = draft.clone();
+                remaining.metadata.profile = profiles[i..].to_vec();
+                remaining.save()?;
+                let done: Vec<&str> = profiles[..i].iter().map(String::as_str).collect();
+                return Err(e).context(format!(
+                    "Published to {} but not {}. The draft now lists only the remaining profiles; run `micropub publish {}` to retry them",
+                    done.join(", "),
+                    profiles[i..].join(", "),
+                    draft.id
+                ));
+            }
+        }
+    }
+
+    if dry_run {
+        return Ok(None);
+    }
+
+    // Each site has its own archive record, so the original draft is done
+    let draft_file = DraftId::parse(&draft.id)?.draft_path()?;
+    if draft_file.exists() {
+        std::fs::remove_file(&draft_file).context("Failed to remove published draft")?;
+    }
+
+    crate::progress!("Published to {} sites:", results.len());
+    for result in &results {
+        crate::progress!(
+            "  {}: {}",
+            result.profile,
+            result.url.as_deref().unwrap_or("(no URL returned)")
+        );
+    }
+
+    let mut results = results.into_iter();
+    let mut first = results.next().context("No profiles to publish to")?;
+    first.other_sites = results.collect();
+    Ok(Some(first))
+}
+
+/// Publish a draft with one profile and archive it
+async fn publish_to(
+    config: &Config,
+    mut draft: Draft,
+    profile_name: &str,
+    backdate: Option<DateTime<Utc>>,
+    dry_run: bool,
+) -> Result<Option<PublishResult>> {
+    let profile = config
+        .get_profile(profile_name)
+        .context(format!("Profile not found: {}", profile_name))?;
+
+    // Load token (a dry run never talks to the server)
+    let token = if dry_run {
+        String::new()
+    } else {
+        load_fresh_token(profile_name).await?
+    };
+
+    // Fail before uploading anything if a referenced photo is missing
+    let missing_photos = draft.missing_photos();
+    if !missing_photos.is_empty() {
+        bail!("Photo file(s) not found: {}", missing_photos.join(", "));
+    }
+
+    // Apply the profile's content transforms before looking for media
+    let content = apply_transforms(&draft.content, &profile.transforms)?;
+
+    // Collect media references and deduplicate them
+    let mut media_refs_set: HashSet<String> = HashSet::new();
+
+    // Add references from content
+    for ref_path in find_media_references(&content) {
+        media_refs_set.insert(ref_path);
+    }
+
+    // Add local photo references (skip remote URLs)
+    for photo_path in &draft.metadata.photo {
+        if !photo_path.starts_with("http://") && !photo_path.starts_with("https://") {
+            media_refs_set.insert(photo_path.clone());
+        }
+    }
+
+    // Convert to Vec for iteration
+    let media_refs: Vec<String> = media_refs_set.into_iter().collect();
+
+    let mut replacements = Vec::new();
+    let mut uploaded_photo_urls = Vec::new();
+    let mut upload_results = Vec::new();
+    let mut pending_uploads = Vec::new();
+
+    if dry_run {
+        for local_path in &media_refs {
+            let resolved = resolve_path(local_path, None)?;
+            replacements.push((local_path.clone(), placeholder_upload_url(&resolved)));
+            pending_uploads.push(resolved);
+        }
+    } else if !media_refs.is_empty() {
+        let media_endpoint = profile.media_endpoint.as_ref()
+            .context(format!(
+                "No media endpoint found for profile '{}'. Re-authenticate to discover media endpoint:\n  micropub auth {}",
+                profile_name, profile.domain
+            ))?;
+
+        crate::progress!(
+            "{}",
+            tf(
+                "publish.uploading",
+                &[("count", &media_refs.len().to_string())]
+            )
+        );
+
+        for local_path in media_refs {
+            let resolved = resolve_path(&local_path, None)?;
+            crate::progress!(
+                "  {}",
+                tf(
+                    "publish.uploading_file",
+                    &[("path", &resolved.display().to_string())]
+                )
+            );
+
+            let alt = alt_text_for(&content, &local_path);
+            let upload = upload_or_reuse(
+                media_endpoint,
+                &token,
+                &resolved,
+                profile_name,
+                alt.as_deref(),
+            )
+            .await?;
+            let url = upload.url;
+            if upload.reused {
+                crate::progress!("    -> {} (already uploaded)", url);
+            } else {
+                crate::progress!("    -> {}", url);
+            }
+
+            let filename = resolved
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            upload_results.push((filename, url.clone()));
+            replacements.push((local_path.clone(), url.clone()));
+
+            // If this was from photo metadata, save the URL
+            if draft.metadata.photo.contains(&local_path) {
+                uploaded_photo_urls.push(url);
+            }
+        }
+    }
+
+    // Replace local paths with URLs in content
+    let final_content = replace_paths(&content, &replacements);
+
+    let properties = create_properties(
+        &draft,
+        profile.license.as_ref(),
+        &final_content,
+        &replacements,
+        backdate,
+    )?;
+
+    // Check if this draft already exists on the server
+    let is_server_draft = draft.metadata.status.as_deref() == Some("server-draft");
+    let is_existing_post = updates_existing_post(&draft);
+
+    let request = if is_existing_post {
+        // Update the existing post (promoting a server draft to published)
+        let url = draft.metadata.url.clone().unwrap();
+
+        let mut replace = Map::new();
+
+        // Only update content, name, summary, and categories (if present), and post-status
+        replace.insert(
+            "content".to_string(),
+            Value::Array(vec![Value::String(final_content.clone())]),
+        );
+
+        if let Some(name) = &draft.metadata.name {
+            replace.insert(
+                "name".to_string(),
+                Value::Array(vec![Value::String(name.clone())]),
+            );
+        }
+
+        if let Some(summary) = &draft.metadata.summary {
+            replace.insert(
+                "summary".to_string(),
+                Value::Array(vec![Value::String(summary.clone())]),
+            );
+        }
+
+        if !draft.metadata.category.is_empty() {
+            replace.insert(
+                "category".to_string(),
+                Value::Array(
+                    draft
+                        .metadata
+                        .category
+                        .iter()
+                        .map(|c| Value::String(c.clone()))
+                        .collect(),
+                ),
+            );
+        }
+
+        if let Some(license) = &draft.metadata.license {
+            replace.insert(
+                "license".to_string(),
+                Value::Array(vec![Value::String(license.clone())]),
+            );
+        }
+
+        // Change post-status from draft to published
+        if is_server_draft {
+            replace.insert(
+                "post-status".to_string(),
+                Value::Array(vec![Value::String("published".to_string())]),
+            );
+        }
+
+        MicropubRequest {
+            action: MicropubAction::Update {
+                replace,
+                add: Map::new(),
+                delete: Vec::new(),
+            },
+            properties: Map::new(),
+            url: Some(url),
+        }
+    } else {
+        // Create new published post (existing behavior)
+        MicropubRequest {
+            action: MicropubAction::Create,
+            properties,
+            url: None,
+        }
+    };
+
+    // Send request
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_ref()
+        .context("No micropub endpoint configured for this profile")?;
+
+    let category_warnings: Vec<String> =
+        unused_categories(&draft, profile_name, micropub_endpoint, &token, dry_run)
+            .await
+            .into_iter()
+            .map(|category| {
+                format!(
+                    "Category '{}' has never been used before; check it for typos",
+                    category
+                )
+            })
+            .collect();
+
+    if dry_run {
+        for warning in &category_warnings {
+            crate::ci::warning(warning);
+        }
+        print_dry_run(
+            micropub_endpoint,
+            profile.request_format,
+            &pending_uploads,
+            &request,
+        )?;
+        return Ok(None);
+    }
+
+    let client =
+        MicropubClient::new(micropub_endpoint.clone(), token).with_format(profile.request_format);
+
+    crate::progress!(
+        "{}",
+        tf("publish.publishing_to", &[("domain", &profile.domain)])
+    );
+    let response = match client.send(&request).await {
+        Ok(response) => response,
+        Err(e) if is_connection_error(&e) => {
+            // Archive now so the draft isn't published twice; `queue flush` finishes the job
+            let entry = enqueue(
+                QueueKind::Publish,
+                profile_name,
+                &draft.id,
+                &request,
+                Default::default(),
+            )?;
+            let message = queued_message(&entry);
+            crate::ci::warning(&message);
+
+            draft.metadata.status = Some("queued".to_string());
+            draft.metadata.pushed_hashes.clear();
+            let archive_path = draft.archive()?;
+
+            return Ok(Some(PublishResult {
+                url: None,
+                was_update: is_existing_post,
+                archived_path: archive_path,
+                uploads: upload_results,
+                warnings: vec![message],
+                profile: profile_name.to_string(),
+                other_sites: Vec::new(),
+                queued: Some(entry.id),
+            }));
+        }
+        Err(e) => return Err(e),
+    };
+
+    let mut warnings = category_warnings;
+    if response.pending {
+        warnings
+            .push("Server accepted the post for processing; it may not be live yet".to_string());
+    } else if response.url.is_none() && !is_existing_post {
+        warnings.push("Server did not return a URL for the published post".to_string());
+    }
+
+    // Updating an existing post keeps its URL
+    let post_url = response.url.clone().or_else(|| {
+        if is_existing_post {
+            draft.metadata.url.clone()
+        } else {
+            None
+        }
+    });
+
+    // Archive draft with metadata
+    draft.metadata.status = Some("published".to_string());
+    draft.metadata.url = post_url.clone();
+    draft.metadata.published_at = Some(Utc::now());
+    draft.metadata.pushed_hashes.clear();
+
+    let archive_path = draft.archive()?;
+
+    crate::progress!("{}", t("publish.success"));
+    if let Some(ref url) = post_url {
+        crate::progress!("  {}", tf("publish.url", &[("url", url)]));
+        crate::ci::set_output("url", url)?;
+    }
+    crate::progress!(
+        "  {}",
+        tf(
+            "publish.archived",
+            &[("path", &archive_path.display().to_string())]
+        )
+    );
+    for warning in &warnings {
+        crate::ci::warning(warning);
+    }
+
+    Ok(Some(PublishResult {
+        url: post_url,
+        was_update: is_existing_post,
+        archived_path: archive_path,
+        uploads: upload_results,
+        warnings,
+        profile: profile_name.to_string(),
+        other_sites: Vec::new(),
+        queued: None,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_failed_dry_run_leaves_draft_unsaved() {
+        crate::config::use_temp_data_dir();
+        let mut config = Config::default();
+        config.upsert_profile(
+            "blog".to_string(),
+            Profile {
+                domain: "blog.example.com".to_string(),
+                micropub_endpoint: Some("https://blog.example.com/micropub".to_string()),
+                ..Default::default()
+            },
+        );
+        let mut draft = Draft::new(crate::draft::generate_draft_id());
+        draft.content = "Hello".to_string();
+        let profiles = ["blog".to_string(), "gone".to_string()];
+
+        let err = publish_to_each(&config, draft.clone(), &profiles, None, true)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Profile not found: gone"));
+        assert!(!DraftId::parse(&draft.id)
+            .unwrap()
+            .draft_path()
+            .unwrap()
+            .exists());
+    }
+}
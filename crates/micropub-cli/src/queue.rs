@@ -0,0 +1,288 @@
+// ABOUTME: Offline queue for publishes and pushes that couldn't reach the server
+// ABOUTME: Requests wait in the queue/ data directory until `micropub queue flush` sends them
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::auth::load_fresh_token;
+use crate::client::{MicropubClient, MicropubRequest};
+use crate::config::{get_queue_dir, Config};
+use crate::draft::{Draft, DraftId};
+use crate::output::{json, print_json};
+use crate::transport::is_connection_error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueKind {
+    /// `publish`: the draft was archived with `status: queued`
+    Publish,
+    /// `draft push`: the draft stays in drafts until the push goes through
+    Push,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedRequest {
+    pub id: String,
+    pub kind: QueueKind,
+    pub profile: String,
+    pub draft_id: String,
+    pub request: MicropubRequest,
+    pub queued_at: DateTime<Utc>,
+    /// Property hashes to record on the draft once a push is sent
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pushed_hashes: BTreeMap<String, String>,
+    /// Why the last flush didn't send it, if it failed for a reason other than the network
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+fn entry_path(id: &str) -> Result<PathBuf> {
+    Ok(get_queue_dir()?.join(format!("{}.json", DraftId::parse(id)?)))
+}
+
+fn write(entry: &QueuedRequest) -> Result<()> {
+    let json = serde_json::to_string_pretty(entry)?;
+    fs::write(entry_path(&entry.id)?, json).context("Failed to write queued request")
+}
+
+/// Save a request that failed to reach the server
+pub fn enqueue(
+    kind: QueueKind,
+    profile: &str,
+    draft_id: &str,
+    request: &MicropubRequest,
+    pushed_hashes: BTreeMap<String, String>,
+) -> Result<QueuedRequest> {
+    let now = Utc::now();
+    let entry = QueuedRequest {
+        id: now.format("%Y%m%dT%H%M%S%6fZ").to_string(),
+        kind,
+        profile: profile.to_string(),
+        draft_id: draft_id.to_string(),
+        request: request.clone(),
+        queued_at: now,
+        pushed_hashes,
+        last_error: None,
+    };
+    write(&entry)?;
+    Ok(entry)
+}
+
+/// Queued requests, oldest first
+pub fn list() -> Result<Vec<QueuedRequest>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(get_queue_dir()?)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let entry: QueuedRequest = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        entries.push(entry);
+    }
+    entries.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(entries)
+}
+
+/// Message shown when a request is queued instead of sent
+pub fn queued_message(entry: &QueuedRequest) -> String {
+    format!(
+        "Couldn't reach the server, so the request was queued ({}). Run `micropub queue flush` when you're back online",
+        entry.id
+    )
+}
+
+/// Record a sent request on its draft: the archive for a publish, the draft for a push
+fn mark_sent(entry: &QueuedRequest, url: Option<String>) -> Result<()> {
+    let id = DraftId::parse(&entry.draft_id)?;
+    let path = match entry.kind {
+        QueueKind::Publish => id.archive_path()?,
+        QueueKind::Push => id.draft_path()?,
+    };
+    // The draft may have been published or removed since
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return Ok(());
+    };
+    let mut draft = Draft::from_string(entry.draft_id.clone(), contents)?;
+
+    match entry.kind {
+        QueueKind::Publish => {
+            draft.metadata.status = Some("published".to_string());
+            draft.metadata.published_at = Some(Utc::now());
+        }
+        QueueKind::Push => {
+            draft.metadata.status = Some("server-draft".to_string());
+            draft.metadata.pushed_hashes = entry.pushed_hashes.clone();
+        }
+    }
+    if url.is_some() {
+        draft.metadata.url = url;
+    }
+
+    fs::write(&path, draft.to_string()?).context("Failed to update draft after sending")
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FlushReport {
+    /// (queue id, URL) for each request that went through
+    pub sent: Vec<(String, Option<String>)>,
+    /// (queue id, error) for requests the server rejected; they stay queued
+    pub failed: Vec<(String, String)>,
+    /// Requests not tried because the server still can't be reached
+    pub remaining: usize,
+}
+
+/// Send every queued request, oldest first, stopping if the network is still down
+pub async fn flush() -> Result<FlushReport> {
+    let config = Config::load()?;
+    let mut report = FlushReport::default();
+    let entries = list()?;
+
+    for (i, mut entry) in entries.iter().cloned().enumerate() {
+        let result = async {
+            let profile = config
+                .get_profile(&entry.profile)
+                .with_context(|| format!("Profile not found: {}", entry.profile))?;
+            let endpoint = profile
+                .micropub_endpoint
+                .clone()
+                .context("No micropub endpoint configured for this profile")?;
+            let token = load_fresh_token(&entry.profile).await?;
+            MicropubClient::new(endpoint, token)
+                .with_format(profile.request_format)
+                .send(&entry.request)
+                .await
+        }
+        .await;
+
+        match result {
+            Ok(response) => {
+                let url = response.url.or_else(|| entry.request.url.clone());
+                mark_sent(&entry, url.clone())?;
+                fs::remove_file(entry_path(&entry.id)?).context("Failed to remove sent request")?;
+                report.sent.push((entry.id, url));
+            }
+            Err(e) if is_connection_error(&e) => {
+                report.remaining = entries.len() - i;
+                break;
+            }
+            Err(e) => {
+                entry.last_error = Some(format!("{:#}", e));
+                write(&entry)?;
+                report.failed.push((entry.id, format!("{:#}", e)));
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+pub fn cmd_queue_list() -> Result<()> {
+    let entries = list()?;
+    if json() {
+        return print_json(&entries);
+    }
+    if entries.is_empty() {
+        println!("Queue is empty");
+        return Ok(());
+    }
+
+    for entry in &entries {
+        let kind = match entry.kind {
+            QueueKind::Publish => "publish",
+            QueueKind::Push => "push",
+        };
+        println!(
+            "{}  {:<7}  {}  {}  (queued {})",
+            entry.id,
+            kind,
+            entry.profile,
+            entry.draft_id,
+            entry.queued_at.format("%Y-%m-%d %H:%M UTC")
+        );
+        if let Some(ref error) = entry.last_error {
+            println!("    last error: {}", error);
+        }
+    }
+    Ok(())
+}
+
+pub async fn cmd_queue_flush() -> Result<()> {
+    let report = flush().await?;
+    if json() {
+        return print_json(&report);
+    }
+
+    if report.sent.is_empty() && report.failed.is_empty() && report.remaining == 0 {
+        println!("Queue is empty");
+        return Ok(());
+    }
+    for (id, url) in &report.sent {
+        println!("✓ Sent {}  {}", id, url.as_deref().unwrap_or(""));
+    }
+    for (id, error) in &report.failed {
+        println!("✗ {}: {}", id, error);
+    }
+    if report.remaining > 0 {
+        println!(
+            "Still offline; {} request(s) left in the queue",
+            report.remaining
+        );
+    }
+
+    if !report.failed.is_empty() {
+        anyhow::bail!(
+            "{} queued request(s) were rejected; they stay queued",
+            report.failed.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::MicropubAction;
+    use serde_json::{json, Map};
+
+    #[test]
+    fn test_queued_request_round_trips() {
+        let mut replace = Map::new();
+        replace.insert("content".to_string(), json!(["Updated"]));
+        let entry = QueuedRequest {
+            id: "20261016T120000000000Z".to_string(),
+            kind: QueueKind::Push,
+            profile: "example.com".to_string(),
+            draft_id: "abc".to_string(),
+            request: MicropubRequest {
+                action: MicropubAction::Update {
+                    replace,
+                    add: Map::new(),
+                    delete: vec!["summary".to_string()],
+                },
+                properties: Map::new(),
+                url: Some("https://example.com/drafts/1".to_string()),
+            },
+            queued_at: Utc::now(),
+            pushed_hashes: BTreeMap::from([("content".to_string(), "0123".to_string())]),
+            last_error: None,
+        };
+
+        let json = serde_json::to_string(&entry).unwrap();
+        let parsed: QueuedRequest = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.kind, QueueKind::Push);
+        assert_eq!(parsed.request.url, entry.request.url);
+        assert_eq!(
+            parsed.request.to_json().unwrap(),
+            entry.request.to_json().unwrap()
+        );
+        assert_eq!(parsed.pushed_hashes, entry.pushed_hashes);
+    }
+}
@@ -0,0 +1,364 @@
+// ABOUTME: Cross-checks archived drafts against the server's current posts
+// ABOUTME: Reports deleted, externally modified, and URL-less archives, optionally fixing them
+
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use crate::auth::load_fresh_token;
+use crate::config::{get_archive_dir, Config};
+use crate::draft::{load_dir, Draft};
+use crate::find::normalize_url;
+use crate::media::find_media_references;
+use crate::operations::{fetch_posts, parse_posts, PostData};
+use crate::output::{json, print_json};
+use crate::transform::apply_transforms;
+
+/// Posts fetched per `q=source` page while listing the server
+const PAGE_SIZE: usize = 100;
+/// Stop listing after this many pages, for servers that ignore `offset`
+const MAX_PAGES: usize = 200;
+
+#[derive(Debug, Serialize)]
+pub struct Modified {
+    pub path: PathBuf,
+    pub url: String,
+    /// Which of `name`, `category`, and `content` differ
+    pub fields: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Unchecked {
+    pub path: PathBuf,
+    pub url: String,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub checked: usize,
+    /// Archives whose post is gone from the server
+    pub deleted: Vec<(PathBuf, String)>,
+    pub modified: Vec<Modified>,
+    /// Archives with no `url`, and the server post they appear to be if one matched
+    pub missing_url: Vec<(PathBuf, Option<String>)>,
+    /// Archives the server couldn't be asked about
+    pub unchecked: Vec<Unchecked>,
+    /// Fixes written to archive files with `--fix`
+    pub fixed: usize,
+}
+
+impl Report {
+    fn is_clean(&self) -> bool {
+        self.deleted.is_empty()
+            && self.modified.is_empty()
+            && self.missing_url.is_empty()
+            && self.unchecked.is_empty()
+    }
+}
+
+/// Collapse whitespace so reflowed text doesn't count as a change
+fn squash(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Fields that differ between an archived draft and the server's copy.
+/// Content is only compared when it was sent as-is: uploads rewrite local media paths.
+pub fn changed_fields(
+    draft: &Draft,
+    post: &PostData,
+    sent_content: Option<&str>,
+) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+
+    let name = draft
+        .metadata
+        .name
+        .as_deref()
+        .filter(|n| !n.trim().is_empty());
+    if name.map(str::trim) != post.name.as_deref().map(str::trim) {
+        fields.push("name");
+    }
+
+    let local: HashSet<&str> = draft.metadata.category.iter().map(String::as_str).collect();
+    let remote: HashSet<&str> = post.categories.iter().map(String::as_str).collect();
+    if local != remote {
+        fields.push("category");
+    }
+
+    if let Some(sent) = sent_content {
+        if squash(sent) != squash(&post.content) {
+            fields.push("content");
+        }
+    }
+
+    fields
+}
+
+/// Every post the server lists, keyed by normalized URL
+pub async fn list_server_posts() -> Result<HashMap<String, PostData>> {
+    let mut posts = HashMap::new();
+    let mut offset = 0;
+    let page_size = crate::polite::page_size(PAGE_SIZE);
+
+    for _ in 0..MAX_PAGES {
+        let page = fetch_posts(page_size, offset).await?;
+        let count = page.len();
+        let mut new = 0;
+        for post in page {
+            if posts.insert(normalize_url(&post.url), post).is_none() {
+                new += 1;
+            }
+        }
+        // A short page, or one we've already seen, means we're at the end
+        if count < page_size || new == 0 {
+            break;
+        }
+        offset += count;
+    }
+
+    Ok(posts)
+}
+
+/// Look up one post with `q=source&url=`; `None` if the server says it's gone
+async fn fetch_source(micropub_endpoint: &str, token: &str, url: &str) -> Result<Option<PostData>> {
+    let _turn = crate::polite::turn(micropub_endpoint).await;
+    let response = reqwest::Client::new()
+        .get(micropub_endpoint)
+        .query(&[("q", "source"), ("url", url)])
+        .bearer_auth(token)
+        .send()
+        .await
+        .context("Failed to fetch post")?;
+
+    match response.status() {
+        StatusCode::NOT_FOUND | StatusCode::GONE => Ok(None),
+        status if !status.is_success() => anyhow::bail!("HTTP {}", status),
+        _ => {
+            let source: serde_json::Value =
+                response.json().await.context("Failed to parse post data")?;
+            let mut post = parse_posts(&serde_json::json!({ "items": [source] }))
+                .pop()
+                .context("Empty post data")?;
+            post.url = url.to_string();
+            Ok(Some(post))
+        }
+    }
+}
+
+/// Apply the server's name, categories, and (if compared) content to an archive
+fn pull_server_copy(draft: &mut Draft, post: &PostData, fields: &[&str]) {
+    if fields.contains(&"name") {
+        draft.metadata.name = post.name.clone();
+    }
+    if fields.contains(&"category") {
+        draft.metadata.category = post.categories.clone();
+    }
+    if fields.contains(&"content") {
+        draft.content = post.content.clone();
+    }
+}
+
+pub async fn reconcile(fix: bool) -> Result<Report> {
+    let config = Config::load()?;
+    let (profile_name, profile) = config.active_profile()?;
+    let micropub_endpoint = profile
+        .micropub_endpoint
+        .as_deref()
+        .context("No micropub endpoint configured")?;
+    let token = load_fresh_token(profile_name).await?;
+
+    crate::progress!("Listing posts on {}...", profile.domain);
+    let server_posts = list_server_posts().await?;
+
+    let mut report = Report::default();
+    let mut claimed: HashSet<String> = HashSet::new();
+    let mut without_url = Vec::new();
+
+    for (path, mut draft) in load_dir(&get_archive_dir()?)? {
+        // Archives published with another profile belong to another site
+        if draft.metadata.profile().is_some_and(|p| p != profile_name) {
+            continue;
+        }
+        report.checked += 1;
+
+        let Some(url) = draft.metadata.url.clone() else {
+            without_url.push((path, draft));
+            continue;
+        };
+        let key = normalize_url(&url);
+        claimed.insert(key.clone());
+
+        let post = match server_posts.get(&key) {
+            Some(post) => Some(post.clone()),
+            // Listings can be partial, so ask about this post directly
+            None => match fetch_source(micropub_endpoint, &token, &url).await {
+                Ok(post) => post,
+                Err(e) => {
+                    report.unchecked.push(Unchecked {
+                        path,
+                        url,
+                        error: format!("{:#}", e),
+                    });
+                    continue;
+                }
+            },
+        };
+
+        let Some(post) = post else {
+            if fix && draft.metadata.status.as_deref() != Some("deleted") {
+                draft.metadata.status = Some("deleted".to_string());
+                std::fs::write(&path, draft.to_string()?)
+                    .context("Failed to update archived draft")?;
+                report.fixed += 1;
+            }
+            report.deleted.push((path, url));
+            continue;
+        };
+
+        let sent_content = if find_media_references(&draft.content).is_empty() {
+            apply_transforms(&draft.content, &profile.transforms).ok()
+        } else {
+            None
+        };
+        let fields = changed_fields(&draft, &post, sent_content.as_deref());
+        if fields.is_empty() {
+            continue;
+        }
+        if fix {
+            pull_server_copy(&mut draft, &post, &fields);
+            std::fs::write(&path, draft.to_string()?).context("Failed to update archived draft")?;
+            report.fixed += 1;
+        }
+        report.modified.push(Modified { path, url, fields });
+    }
+
+    // Match URL-less archives to unclaimed posts with the same text
+    for (path, mut draft) in without_url {
+        let content = squash(&draft.content);
+        let matches: Vec<&PostData> = server_posts
+            .iter()
+            .filter(|(key, _)| !claimed.contains(*key))
+            .map(|(_, post)| post)
+            .filter(|post| !content.is_empty() && squash(&post.content) == content)
+            .collect();
+
+        let found = match matches.as_slice() {
+            [post] => Some(post.url.clone()),
+            _ => None,
+        };
+        if let (true, Some(url)) = (fix, &found) {
+            claimed.insert(normalize_url(url));
+            draft.metadata.url = Some(url.clone());
+            std::fs::write(&path, draft.to_string()?).context("Failed to update archived draft")?;
+            report.fixed += 1;
+        }
+        report.missing_url.push((path, found));
+    }
+
+    Ok(report)
+}
+
+pub async fn cmd_reconcile(fix: bool) -> Result<()> {
+    let report = reconcile(fix).await?;
+
+    if json() {
+        return print_json(&report);
+    }
+
+    println!("Checked {} archived post(s)", report.checked);
+    if report.is_clean() {
+        println!("✓ Archive matches the server");
+        return Ok(());
+    }
+
+    if !report.deleted.is_empty() {
+        println!("\nDeleted on the server ({}):", report.deleted.len());
+        for (path, url) in &report.deleted {
+            println!("  {}  {}", url, path.display());
+        }
+    }
+    if !report.modified.is_empty() {
+        println!("\nModified outside micropub ({}):", report.modified.len());
+        for modified in &report.modified {
+            println!(
+                "  {}  [{}]  {}",
+                modified.url,
+                modified.fields.join(", "),
+                modified.path.display()
+            );
+        }
+    }
+    if !report.missing_url.is_empty() {
+        println!("\nArchived without a URL ({}):", report.missing_url.len());
+        for (path, found) in &report.missing_url {
+            match found {
+                Some(url) => println!("  {}  (matches {})", path.display(), url),
+                None => println!("  {}", path.display()),
+            }
+        }
+    }
+    if !report.unchecked.is_empty() {
+        println!("\nCouldn't check ({}):", report.unchecked.len());
+        for unchecked in &report.unchecked {
+            println!("  {}  {}", unchecked.url, unchecked.error);
+        }
+    }
+
+    if fix {
+        println!("\n✓ Updated {} archive file(s)", report.fixed);
+    } else {
+        println!(
+            "\nRun with --fix to mark deleted posts, pull server edits, and fill in matched URLs"
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draft::DraftMetadata;
+
+    fn post(name: Option<&str>, categories: &[&str], content: &str) -> PostData {
+        PostData {
+            url: "https://example.com/1".to_string(),
+            content: content.to_string(),
+            name: name.map(String::from),
+            published: Some("2024-01-01".to_string()),
+            categories: categories.iter().map(|c| c.to_string()).collect(),
+            draft: false,
+        }
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let draft = Draft {
+            id: "20240101-a".to_string(),
+            metadata: DraftMetadata {
+                name: Some("Hello".to_string()),
+                category: vec!["a".to_string(), "b".to_string()],
+                ..Default::default()
+            },
+            content: "Hello\nworld".to_string(),
+        };
+
+        // Category order and reflowed whitespace don't count
+        let same = post(Some("Hello"), &["b", "a"], "Hello world");
+        assert!(changed_fields(&draft, &same, Some("Hello\nworld")).is_empty());
+
+        let edited = post(None, &["a"], "Hello there");
+        assert_eq!(
+            changed_fields(&draft, &edited, Some("Hello\nworld")),
+            vec!["name", "category", "content"]
+        );
+        // Content isn't compared when it was rewritten on upload
+        assert_eq!(
+            changed_fields(&draft, &edited, None),
+            vec!["name", "category"]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+// ABOUTME: `micropub retag` renames a category on local drafts or, with --server, on published posts
+// ABOUTME: Server retags are throttled, stop on rate limits, and resume from a progress file
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::client::{MicropubAction, MicropubRequest};
+use crate::config::{get_data_dir, Config};
+use crate::draft::lock::write_atomic;
+use crate::draft::Draft;
+use crate::operations::send_update;
+use crate::transport::is_connection_error;
+
+/// Replace `from` with `to` in a category list, keeping its position and
+/// dropping the duplicate if `to` was already there; `false` if `from` wasn't in it
+pub fn rename_category(categories: &mut Vec<String>, from: &str, to: &str) -> bool {
+    let Some(idx) = categories.iter().position(|c| c == from) else {
+        return false;
+    };
+    if categories.iter().any(|c| c == to) {
+        categories.remove(idx);
+    } else {
+        categories[idx] = to.to_string();
+    }
+    true
+}
+
+/// The update that swaps one category value on a post, leaving its others alone
+pub fn build_retag_request(post_url: &str, from: &str, to: &str) -> MicropubRequest {
+    let mut add = Map::new();
+    add.insert(
+        "category".to_string(),
+        Value::Array(vec![Value::String(to.to_string())]),
+    );
+    let mut delete = Map::new();
+    delete.insert(
+        "category".to_string(),
+        Value::Array(vec![Value::String(from.to_string())]),
+    );
+
+    MicropubRequest {
+        action: MicropubAction::UpdateValues { add, delete },
+        properties: Map::new(),
+        url: Some(post_url.to_string()),
+    }
+}
+
+/// Posts already retagged by an earlier, interrupted run of the same retag
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Progress {
+    done: BTreeSet<String>,
+}
+
+/// One progress file per profile and rename, so unrelated retags don't mix
+fn progress_path(profile: &str, from: &str, to: &str) -> Result<PathBuf> {
+    let digest = Sha256::digest(format!("{}\n{}\n{}", profile, from, to).as_bytes());
+    let key: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    let dir = get_data_dir()?.join("retag");
+    fs::create_dir_all(&dir).context("Failed to create retag progress directory")?;
+    Ok(dir.join(format!("{}.json", key)))
+}
+
+fn load_progress(path: &Path) -> Result<Progress> {
+    if !path.exists() {
+        return Ok(Progress::default());
+    }
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn save_progress(path: &Path, progress: &Progress) -> Result<()> {
+    write_atomic(path, serde_json::to_string_pretty(progress)?.as_bytes())
+}
+
+/// Errors that mean the server wants us to back off, not that this post is bad
+fn should_stop(error: &anyhow::Error) -> bool {
+    is_connection_error(error) || format!("{:#}", error).contains("HTTP 429")
+}
+
+/// Rename a category. Local drafts by default; with `server`, every post on the
+/// active profile's server that has it, `delay` apart.
+pub async fn cmd_retag(
+    from: &str,
+    to: &str,
+    server: bool,
+    delay: Duration,
+    dry_run: bool,
+) -> Result<()> {
+    let (from, to) = (from.trim(), to.trim());
+    if from.is_empty() || to.is_empty() {
+        anyhow::bail!("Categories can't be empty");
+    }
+    if from == to {
+        anyhow::bail!("--from and --to are the same category");
+    }
+
+    if server {
+        retag_server(from, to, delay, dry_run).await
+    } else {
+        retag_drafts(from, to, dry_run).await
+    }
+}
+
+async fn retag_drafts(from: &str, to: &str, dry_run: bool) -> Result<()> {
+    let mut changed = 0;
+    for id in Draft::list_all()? {
+        let _lock = crate::draft::lock::lock(&id).await;
+        let mut draft = Draft::load(&id)?;
+        if !rename_category(&mut draft.metadata.category, from, to) {
+            continue;
+        }
+        if dry_run {
+            println!("Would retag draft {}", id);
+        } else {
+            draft.save()?;
+            println!("✓ Retagged draft {}", id);
+        }
+        changed += 1;
+    }
+
+    if changed == 0 {
+        println!("No drafts have the category '{}'", from);
+    } else if !dry_run {
+        println!("\n{} draft(s) moved from '{}' to '{}'", changed, from, to);
+    }
+    Ok(())
+}
+
+async fn retag_server(from: &str, to: &str, delay: Duration, dry_run: bool) -> Result<()> {
+    let config = Config::load()?;
+    let (profile_name, _) = config.active_profile()?;
+
+    println!("Looking for posts in '{}'...", from);
+    let mut urls: Vec<String> = crate::reconcile::list_server_posts()
+        .await?
+        .into_values()
+        .filter(|post| post.categories.iter().any(|c| c == from))
+        .map(|post| post.url)
+        .collect();
+    urls.sort();
+
+    if urls.is_empty() {
+        println!("No posts have the category '{}'", from);
+        return Ok(());
+    }
+    if dry_run {
+        for url in &urls {
+            println!("Would retag {}", url);
+        }
+        println!(
+            "\n{} post(s) would move from '{}' to '{}'",
+            urls.len(),
+            from,
+            to
+        );
+        return Ok(());
+    }
+
+    let progress_path = progress_path(profile_name, from, to)?;
+    let mut progress = load_progress(&progress_path)?;
+    let (already, pending): (Vec<String>, Vec<String>) = urls
+        .into_iter()
+        .partition(|url| progress.done.contains(url));
+    if !already.is_empty() {
+        println!(
+            "Resuming: {} post(s) were retagged by an earlier run",
+            already.len()
+        );
+    }
+
+    let mut updated = 0;
+    let mut failed: Vec<(String, String)> = Vec::new();
+    let mut stopped = None;
+    for (idx, url) in pending.iter().enumerate() {
+        if idx > 0 {
+            tokio::time::sleep(delay).await;
+        }
+        match send_update(&build_retag_request(url, from, to)).await {
+            Ok(()) => {
+                println!("✓ {}", url);
+                progress.done.insert(url.clone());
+                save_progress(&progress_path, &progress)?;
+                updated += 1;
+            }
+            Err(e) if should_stop(&e) => {
+                stopped = Some(e);
+                break;
+            }
+            Err(e) => {
+                println!("✗ {}: {:#}", url, e);
+                failed.push((url.clone(), format!("{:#}", e)));
+            }
+        }
+    }
+
+    let remaining = pending.len() - updated - failed.len();
+    println!();
+    println!("Retagged '{}' → '{}' on {} post(s)", from, to, updated);
+    if !already.is_empty() {
+        println!("  {} already done by an earlier run", already.len());
+    }
+    if !failed.is_empty() {
+        println!("  {} failed:", failed.len());
+        for (url, error) in &failed {
+            println!("    {}: {}", url, error);
+        }
+    }
+    if let Some(e) = &stopped {
+        println!("  Stopped with {} post(s) left: {:#}", remaining, e);
+    }
+
+    if stopped.is_none() && failed.is_empty() {
+        let _ = fs::remove_file(&progress_path);
+        return Ok(());
+    }
+    anyhow::bail!(
+        "{} post(s) not retagged; run the same command again to resume",
+        remaining + failed.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_category_keeps_position_and_dedupes() {
+        let mut categories = vec!["a".to_string(), "old".to_string(), "b".to_string()];
+        assert!(rename_category(&mut categories, "old", "new"));
+        assert_eq!(categories, ["a", "new", "b"]);
+
+        let mut categories = vec!["old".to_string(), "new".to_string()];
+        assert!(rename_category(&mut categories, "old", "new"));
+        assert_eq!(categories, ["new"]);
+
+        assert!(!rename_category(&mut categories, "missing", "new"));
+    }
+
+    #[test]
+    fn test_retag_request_adds_and_deletes_single_values() {
+        let request = build_retag_request("https://example.com/1", "old", "new");
+        let json: Value = serde_json::from_str(&request.to_json().unwrap()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "action": "update",
+                "url": "https://example.com/1",
+                "add": {"category": ["new"]},
+                "delete": {"category": ["old"]},
+            })
+        );
+    }
+
+    #[test]
+    fn test_progress_round_trips() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("progress.json");
+        assert!(load_progress(&path).unwrap().done.is_empty());
+
+        let mut progress = Progress::default();
+        progress.done.insert("https://example.com/1".to_string());
+        save_progress(&path, &progress).unwrap();
+        assert!(load_progress(&path)
+            .unwrap()
+            .done
+            .contains("https://example.com/1"));
+    }
+}
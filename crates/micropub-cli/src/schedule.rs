@@ -0,0 +1,327 @@
+// ABOUTME: Queue of drafts waiting to be published at a set time
+// ABOUTME: Scheduled drafts live in the scheduled/ data directory with a scheduled-at field
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use crate::config::get_scheduled_dir;
+use crate::draft::{Draft, DraftId};
+use crate::publish::{cmd_publish, PublishResult};
+
+fn read_draft(id: &DraftId, path: &Path) -> Result<Draft> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read scheduled draft: {}", id))?;
+    Draft::from_string(id.to_string(), contents)
+}
+
+fn write_draft(path: &Path, draft: &Draft) -> Result<()> {
+    fs::write(path, draft.to_string()?).context("Failed to write scheduled draft")
+}
+
+/// Move the draft at `draft_path` to `scheduled`, to be published at `at`
+fn enqueue(id: &DraftId, draft_path: &Path, scheduled: &Path, at: DateTime<Utc>) -> Result<()> {
+    let contents = fs::read_to_string(draft_path).context("Failed to read draft file")?;
+    let mut draft = Draft::from_string(id.to_string(), contents)?;
+    draft.metadata.scheduled_at = Some(at);
+
+    write_draft(scheduled, &draft)?;
+    fs::remove_file(draft_path).context("Failed to remove draft after scheduling")
+}
+
+/// Move the queued draft at `scheduled` back to `draft_path`, returning when it was due
+fn dequeue(id: &DraftId, scheduled: &Path, draft_path: &Path) -> Result<Option<DateTime<Utc>>> {
+    if !scheduled.exists() {
+        bail!("No scheduled draft: {}", id);
+    }
+    let mut draft = read_draft(id, scheduled)?;
+    let at = draft.metadata.scheduled_at.take();
+
+    if draft_path.exists() {
+        bail!("A draft named {} already exists", id);
+    }
+    write_draft(draft_path, &draft)?;
+    fs::remove_file(scheduled).context("Failed to remove scheduled draft")?;
+
+    Ok(at)
+}
+
+/// Move a draft into the queue to be published at `at`
+pub fn schedule_draft(draft_id: &str, at: DateTime<Utc>) -> Result<PathBuf> {
+    let id = DraftId::parse(draft_id)?;
+    let scheduled_path = id.scheduled_path()?;
+    enqueue(&id, &id.draft_path()?, &scheduled_path, at)?;
+    Ok(scheduled_path)
+}
+
+/// Load a queued draft
+pub fn load(draft_id: &str) -> Result<Draft> {
+    let id = DraftId::parse(draft_id)?;
+    let path = id.scheduled_path()?;
+    if !path.exists() {
+        bail!("No scheduled draft: {}", id);
+    }
+    read_draft(&id, &path)
+}
+
+/// All queued drafts, soonest first
+pub fn list() -> Result<Vec<Draft>> {
+    list_in(&get_scheduled_dir()?)
+}
+
+fn list_in(dir: &Path) -> Result<Vec<Draft>> {
+    let mut drafts = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("md") {
+            continue;
+        }
+        let Some(id) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| DraftId::parse(s).ok())
+        else {
+            continue;
+        };
+        if let Ok(draft) = read_draft(&id, &path) {
+            drafts.push(draft);
+        }
+    }
+
+    drafts.sort_by_key(|d| d.metadata.scheduled_at);
+    Ok(drafts)
+}
+
+/// Change when a queued draft will be published
+pub fn reschedule(draft_id: &str, at: DateTime<Utc>) -> Result<()> {
+    let mut draft = load(draft_id)?;
+    draft.metadata.scheduled_at = Some(at);
+    write_draft(&DraftId::parse(draft_id)?.scheduled_path()?, &draft)
+}
+
+/// Take a draft out of the queue and put it back with the other drafts
+pub fn unschedule(draft_id: &str) -> Result<PathBuf> {
+    let id = DraftId::parse(draft_id)?;
+    let draft_path = id.draft_path()?;
+    dequeue(&id, &id.scheduled_path()?, &draft_path)?;
+    Ok(draft_path)
+}
+
+/// Publish a queued draft immediately, re-queueing it if publishing fails
+pub async fn publish_now(draft_id: &str) -> Result<PublishResult> {
+    let id = DraftId::parse(draft_id)?;
+    publish_queued(
+        &id,
+        &id.scheduled_path()?,
+        &id.draft_path()?,
+        |path| async move { cmd_publish(&path.to_string_lossy(), None, false).await },
+    )
+    .await
+}
+
+/// Move the draft out of the queue and `publish` it, putting it back at its
+/// old time if that fails
+async fn publish_queued<T, F, Fut>(
+    id: &DraftId,
+    scheduled: &Path,
+    draft_path: &Path,
+    publish: F,
+) -> Result<T>
+where
+    F: FnOnce(PathBuf) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let at = dequeue(id, scheduled, draft_path)?.unwrap_or_else(Utc::now);
+
+    match publish(draft_path.to_path_buf()).await {
+        Ok(result) => Ok(result),
+        Err(e) => {
+            // Keep the draft in the queue rather than silently dropping the schedule
+            enqueue(id, draft_path, scheduled, at)?;
+            Err(e)
+        }
+    }
+}
+
+/// Whether a queued draft's publish time has arrived
+pub fn is_due(draft: &Draft, now: DateTime<Utc>) -> bool {
+    draft.metadata.scheduled_at.is_some_and(|at| at <= now)
+}
+
+/// Queued drafts in `dir` whose time has arrived, soonest first
+fn due_in(dir: &Path, now: DateTime<Utc>) -> Result<Vec<Draft>> {
+    Ok(list_in(dir)?
+        .into_iter()
+        .filter(|d| is_due(d, now))
+        .collect())
+}
+
+pub fn cmd_schedule(draft_id: &str, at: DateTime<Utc>) -> Result<()> {
+    schedule_draft(draft_id, at)?;
+    println!("Scheduled {} for {}", draft_id, at.to_rfc3339());
+    if at <= Utc::now() {
+        println!(
+            "That time has already passed; it will go out on the next `micropub schedule run`."
+        );
+    }
+    Ok(())
+}
+
+pub fn cmd_schedule_list() -> Result<()> {
+    let drafts = list()?;
+    if drafts.is_empty() {
+        println!("No scheduled drafts.");
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    for draft in drafts {
+        let when = draft
+            .metadata
+            .scheduled_at
+            .map(|at| at.to_rfc3339())
+            .unwrap_or_else(|| "(no time)".to_string());
+        let due = if is_due(&draft, now) { " [due]" } else { "" };
+        let title = draft.metadata.name.as_deref().unwrap_or("(untitled)");
+        println!("{}  {}  {}{}", when, draft.id, title, due);
+    }
+    Ok(())
+}
+
+pub fn cmd_schedule_cancel(draft_id: &str) -> Result<()> {
+    let path = unschedule(draft_id)?;
+    println!(
+        "Unscheduled {}; draft is back at {}",
+        draft_id,
+        path.display()
+    );
+    Ok(())
+}
+
+/// Publish every queued draft whose time has arrived (meant for cron/systemd timers)
+pub async fn cmd_schedule_run() -> Result<()> {
+    let due = due_in(&get_scheduled_dir()?, Utc::now())?;
+
+    if due.is_empty() {
+        println!("Nothing due.");
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for draft in due {
+        match publish_now(&draft.id).await {
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Failed to publish {}: {:#}", draft.id, e);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        bail!(
+            "{} scheduled draft(s) failed to publish and remain queued",
+            failed
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    fn queue_draft(dir: &Path, id: &str, at: Option<DateTime<Utc>>) -> PathBuf {
+        let mut draft = Draft::new(id.to_string());
+        draft.metadata.scheduled_at = at;
+        draft.content = format!("Post {}", id);
+        let path = dir.join(format!("{}.md", id));
+        write_draft(&path, &draft).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_due_in_picks_due_drafts_soonest_first() {
+        let dir = TempDir::new().unwrap();
+        let now = Utc::now();
+        queue_draft(dir.path(), "late", Some(now - Duration::minutes(1)));
+        queue_draft(dir.path(), "early", Some(now - Duration::hours(1)));
+        queue_draft(dir.path(), "future", Some(now + Duration::hours(1)));
+        queue_draft(dir.path(), "undated", None);
+        fs::write(dir.path().join("notes.txt"), "not a draft").unwrap();
+        fs::write(dir.path().join("broken.md"), "---\n: [\n").unwrap();
+
+        let ids: Vec<String> = due_in(dir.path(), now)
+            .unwrap()
+            .into_iter()
+            .map(|d| d.id)
+            .collect();
+        assert_eq!(ids, ["early", "late"]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_publish_requeues_draft_at_its_time() {
+        let dir = TempDir::new().unwrap();
+        let at = Utc::now() - Duration::minutes(5);
+        let id = DraftId::parse("queued").unwrap();
+        let scheduled = queue_draft(dir.path(), id.as_str(), Some(at));
+        let draft_path = dir.path().join("drafts-queued.md");
+
+        let err = publish_queued(&id, &scheduled, &draft_path, |path| async move {
+            // The draft is out of the queue while it publishes
+            assert!(path.exists());
+            let draft = read_draft(&DraftId::parse("queued")?, &path)?;
+            assert_eq!(draft.metadata.scheduled_at, None);
+            anyhow::bail!("server said no")
+        })
+        .await
+        .map(|_: ()| ())
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "server said no");
+        assert!(!draft_path.exists());
+        let requeued = read_draft(&id, &scheduled).unwrap();
+        assert_eq!(requeued.metadata.scheduled_at, Some(at));
+        assert_eq!(requeued.content, "Post queued");
+    }
+
+    #[tokio::test]
+    async fn test_published_draft_leaves_queue() {
+        let dir = TempDir::new().unwrap();
+        let id = DraftId::parse("queued").unwrap();
+        let scheduled = queue_draft(dir.path(), id.as_str(), Some(Utc::now()));
+        let draft_path = dir.path().join("drafts-queued.md");
+
+        let published =
+            publish_queued(&id, &scheduled, &draft_path, |path| async move { Ok(path) })
+                .await
+                .unwrap();
+
+        assert_eq!(published, draft_path);
+        assert!(!scheduled.exists());
+        // A second run finds nothing queued
+        assert!(
+            publish_queued(&id, &scheduled, &draft_path, |_| async { Ok(()) })
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_is_due() {
+        let now = Utc::now();
+        let mut draft = Draft::new("20240101-abc".to_string());
+        assert!(!is_due(&draft, now));
+
+        draft.metadata.scheduled_at = Some(now - Duration::minutes(1));
+        assert!(is_due(&draft, now));
+
+        draft.metadata.scheduled_at = Some(now + Duration::minutes(1));
+        assert!(!is_due(&draft, now));
+    }
+}
@@ -0,0 +1,373 @@
+// ABOUTME: `micropub config` reads and changes config.toml settings by dotted key
+// ABOUTME: Values are checked against the config's types before anything is saved
+
+use anyhow::{bail, Context, Result};
+use toml::{Table, Value};
+
+use crate::config::Config;
+use crate::output::{json, print_json};
+
+/// Settings `config set` accepts; `*` stands for a profile or alias name
+const SETTINGS: [&str; 38] = [
+    "default_profile",
+    "editor",
+    "client_id",
+    "prompt_for_categories",
+    "normalize_whitespace",
+    "separate_profile_drafts",
+    "duplicate_window_days",
+    "token_storage",
+    "retry.attempts",
+    "retry.base_delay_ms",
+    "retry.max_delay_ms",
+    "retry.jitter",
+    "upload.stream_threshold_mb",
+    "upload.resume",
+    "images.enabled",
+    "images.max_width",
+    "images.max_height",
+    "images.jpeg_quality",
+    "images.strip_exif",
+    "images.format",
+    "images.quality",
+    "mcp.http_token",
+    "aliases.*",
+    "profiles.*.domain",
+    "profiles.*.micropub_endpoint",
+    "profiles.*.media_endpoint",
+    "profiles.*.token_endpoint",
+    "profiles.*.authorization_endpoint",
+    "profiles.*.request_format",
+    "profiles.*.polite",
+    "profiles.*.license",
+    "profiles.*.endpoint_overrides.micropub",
+    "profiles.*.endpoint_overrides.media",
+    "profiles.*.endpoint_overrides.authorization",
+    "profiles.*.endpoint_overrides.token",
+    // Whole sections, for `unset`
+    "retry",
+    "upload",
+    "images",
+];
+
+/// Whether `path` is one of `SETTINGS`
+fn is_known(path: &str) -> bool {
+    SETTINGS.iter().any(|known| match known.split_once('*') {
+        Some((before, after)) => path
+            .strip_prefix(before)
+            .and_then(|rest| rest.strip_suffix(after))
+            .is_some_and(|name| !name.is_empty()),
+        None => *known == path,
+    })
+}
+
+/// The config as a TOML table, the shape config.toml has
+fn to_table(config: &Config) -> Result<Table> {
+    Table::try_from(config).context("Failed to serialize config")
+}
+
+fn from_table(table: Table) -> Result<Config> {
+    let config: Config = Value::Table(table).try_into()?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Split the first key off a dotted path, preferring the longest key `table`
+/// already has, so profile names with dots (`profiles.micro.blog.domain`) work
+fn split_key<'a>(table: &Table, path: &'a str) -> (&'a str, Option<&'a str>) {
+    let existing = table
+        .keys()
+        .filter(|key| {
+            path == key.as_str()
+                || path
+                    .strip_prefix(key.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+        .max_by_key(|key| key.len());
+    match existing {
+        Some(key) if key.len() < path.len() => (&path[..key.len()], Some(&path[key.len() + 1..])),
+        Some(_) => (path, None),
+        None => match path.split_once('.') {
+            Some((key, rest)) => (key, Some(rest)),
+            None => (path, None),
+        },
+    }
+}
+
+/// The value at a dotted path
+pub fn get<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    match split_key(table, path) {
+        (key, None) => table.get(key),
+        (key, Some(rest)) => get(table.get(key)?.as_table()?, rest),
+    }
+}
+
+/// Put `value` at a dotted path, creating tables on the way
+fn set(table: &mut Table, path: &str, value: Value) -> Result<()> {
+    match split_key(table, path) {
+        (key, None) => {
+            table.insert(key.to_string(), value);
+            Ok(())
+        }
+        (key, Some(rest)) => {
+            let child = table
+                .entry(key.to_string())
+                .or_insert_with(|| Value::Table(Table::new()));
+            match child.as_table_mut() {
+                Some(child) => set(child, rest, value),
+                None => bail!("{} is a value, not a section", key),
+            }
+        }
+    }
+}
+
+/// Remove the value at a dotted path, returning it
+fn unset(table: &mut Table, path: &str) -> Option<Value> {
+    match split_key(table, path) {
+        (key, None) => table.remove(key),
+        (key, Some(rest)) => unset(table.get_mut(key)?.as_table_mut()?, rest),
+    }
+}
+
+/// A command-line value as TOML: `true`, `85`, and `["a", "b"]` keep their
+/// types, anything else is a string
+fn parse_value(raw: &str) -> Value {
+    format!("value = {}", raw)
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// `config` with `path` set to `raw`, checked by reading it back as a `Config`.
+/// A value that doesn't fit as parsed is tried again as a string, so
+/// `aliases.p 42` still works.
+pub fn with_value(config: &Config, path: &str, raw: &str) -> Result<Config> {
+    if !is_known(path) {
+        bail!("Unknown setting: {}", path);
+    }
+    if let Some(rest) = path.strip_prefix("profiles.") {
+        let exists = config.profiles.keys().any(|name| {
+            rest.strip_prefix(name.as_str())
+                .is_some_and(|rest| rest.starts_with('.'))
+        });
+        if !exists {
+            bail!(
+                "No such profile in {}; add profiles with `micropub auth`",
+                path
+            );
+        }
+    }
+    if path == "default_profile" && !config.profiles.contains_key(raw) {
+        bail!(
+            "Profile not found: {}; add profiles with `micropub auth`",
+            raw
+        );
+    }
+    let mut last_error = None;
+    for value in [parse_value(raw), Value::String(raw.to_string())] {
+        let mut table = to_table(config)?;
+        set(&mut table, path, value)?;
+        match from_table(table) {
+            Ok(updated) => return Ok(updated),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error
+        .expect("at least one value was tried")
+        .context(format!("Invalid value for {}", path)))
+}
+
+/// Every leaf value as `(dotted.key, value)`, in key order
+pub fn flatten(table: &Table) -> Vec<(String, Value)> {
+    let mut entries = Vec::new();
+    for (key, value) in table {
+        match value {
+            Value::Table(child) => {
+                for (rest, value) in flatten(child) {
+                    entries.push((format!("{}.{}", key, rest), value));
+                }
+            }
+            value => entries.push((key.clone(), value.clone())),
+        }
+    }
+    entries
+}
+
+/// Whether a key holds a credential that `config list` shouldn't print
+fn is_secret(key: &str) -> bool {
+    key == "mcp.http_token"
+}
+
+/// Strings without quotes; everything else as TOML writes it
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Table(table) => toml::to_string_pretty(table)
+            .unwrap_or_default()
+            .trim_end()
+            .to_string(),
+        other => other.to_string(),
+    }
+}
+
+pub fn cmd_config_get(key: &str) -> Result<()> {
+    let table = to_table(&Config::load()?)?;
+    let Some(value) = get(&table, key) else {
+        if !is_known(key) {
+            bail!("Unknown setting: {}", key);
+        }
+        bail!("{} is not set; the default applies", key);
+    };
+    if json() {
+        return print_json(value);
+    }
+    println!("{}", display(value));
+    Ok(())
+}
+
+pub fn cmd_config_set(key: &str, raw: &str) -> Result<()> {
+    with_value(&Config::load()?, key, raw)?.save()?;
+    println!("✓ {} = {}", key, raw);
+    Ok(())
+}
+
+pub fn cmd_config_unset(key: &str) -> Result<()> {
+    if !is_known(key) {
+        bail!("Unknown setting: {}", key);
+    }
+    let config = Config::load()?;
+    if key
+        .strip_prefix("profiles.")
+        .is_some_and(|name| config.profiles.contains_key(name))
+    {
+        bail!(
+            "{} is a whole profile; remove it with `micropub profile remove`",
+            key
+        );
+    }
+    let mut table = to_table(&config)?;
+    if unset(&mut table, key).is_none() {
+        println!("{} is not set.", key);
+        return Ok(());
+    }
+    let config = from_table(table).with_context(|| format!("{} can't be unset", key))?;
+    config.save()?;
+    println!("✓ Unset {}", key);
+    Ok(())
+}
+
+/// Print every setting; tokens are masked
+pub fn cmd_config_list() -> Result<()> {
+    let entries: Vec<(String, Value)> = flatten(&to_table(&Config::load()?)?)
+        .into_iter()
+        .map(|(key, value)| {
+            let value = if is_secret(&key) {
+                Value::String("********".to_string())
+            } else {
+                value
+            };
+            (key, value)
+        })
+        .collect();
+
+    if json() {
+        let map: serde_json::Map<String, serde_json::Value> = entries
+            .into_iter()
+            .map(|(key, value)| Ok((key, serde_json::to_value(value)?)))
+            .collect::<Result<_>>()?;
+        return print_json(&map);
+    }
+    for (key, value) in &entries {
+        println!("{} = {}", key, value);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Profile;
+
+    fn config() -> Config {
+        let mut config = Config {
+            default_profile: "micro.blog".to_string(),
+            ..Default::default()
+        };
+        config.upsert_profile(
+            "micro.blog".to_string(),
+            Profile {
+                domain: "micro.blog".to_string(),
+                ..Default::default()
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn test_get_handles_dotted_profile_names() {
+        let table = to_table(&config()).unwrap();
+        assert_eq!(
+            get(&table, "profiles.micro.blog.domain").and_then(Value::as_str),
+            Some("micro.blog")
+        );
+        // Sections left at their defaults aren't written out
+        assert!(get(&table, "images.jpeg_quality").is_none());
+        assert!(get(&table, "profiles.other.domain").is_none());
+    }
+
+    #[test]
+    fn test_with_value_keeps_types_and_rejects_bad_values() {
+        let config = config();
+
+        let updated = with_value(&config, "editor", "code --wait").unwrap();
+        assert_eq!(updated.editor.as_deref(), Some("code --wait"));
+
+        let updated = with_value(&config, "images.jpeg_quality", "70").unwrap();
+        assert_eq!(updated.images.jpeg_quality, 70);
+
+        let updated = with_value(&config, "profiles.micro.blog.polite", "true").unwrap();
+        assert!(updated.profiles["micro.blog"].polite);
+
+        // A number where a string belongs is kept as a string
+        let updated = with_value(&config, "aliases.p", "42").unwrap();
+        assert_eq!(updated.aliases["p"], "42");
+
+        assert!(with_value(&config, "images.jpeg_quality", "high").is_err());
+        assert!(with_value(&config, "editr", "vim").is_err());
+        assert!(with_value(&config, "profiles.micro.blog.domian", "x").is_err());
+        assert!(with_value(&config, "profiles.other.license", "x").is_err());
+        assert!(with_value(&config, "token_storage", "vault").is_err());
+    }
+
+    #[test]
+    fn test_default_profile_must_exist() {
+        let config = config();
+        assert!(with_value(&config, "default_profile", "micro.blog").is_ok());
+        let err = with_value(&config, "default_profile", "mirco.blog").unwrap_err();
+        assert!(err.to_string().contains("Profile not found"));
+    }
+
+    #[test]
+    fn test_unset_rejects_unknown_settings() {
+        let err = cmd_config_unset("editr").unwrap_err();
+        assert!(err.to_string().contains("Unknown setting"));
+    }
+
+    #[test]
+    fn test_flatten_and_unset() {
+        let mut table =
+            to_table(&with_value(&config(), "mcp.http_token", "s3cret").unwrap()).unwrap();
+        let keys: Vec<String> = flatten(&table).into_iter().map(|(k, _)| k).collect();
+        assert!(keys.contains(&"profiles.micro.blog.domain".to_string()));
+        assert!(keys.contains(&"mcp.http_token".to_string()));
+        assert!(is_secret("mcp.http_token"));
+
+        assert!(unset(&mut table, "mcp.http_token").is_some());
+        assert!(unset(&mut table, "mcp.http_token").is_none());
+        assert!(from_table(table.clone()).is_ok());
+
+        unset(&mut table, "default_profile");
+        assert!(from_table(table).is_err());
+    }
+}
@@ -0,0 +1,217 @@
+// ABOUTME: Wrap-up block printed when publish, backdate, and draft push finish
+// ABOUTME: Lists the request sent, resulting URLs, archive paths, uploads with sizes, and time taken
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::draft_push::PushResult;
+use crate::media_sidecar;
+use crate::output::{human_size, json, print_json};
+use crate::publish::PublishResult;
+
+/// One post created or updated on a site
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PostSummary {
+    pub profile: String,
+    /// `create` or `update`
+    pub request: &'static str,
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived_path: Option<PathBuf>,
+    /// Offline queue ID when the request is waiting for the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queued: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UploadSummary {
+    pub file: String,
+    pub url: String,
+    /// Size of the local file, when it's still where it was uploaded from
+    pub bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+    pub posts: Vec<PostSummary>,
+    pub uploads: Vec<UploadSummary>,
+    pub warnings: Vec<String>,
+    pub elapsed_ms: u128,
+}
+
+/// Sizes of a profile's recorded uploads by URL, from the files they came from
+fn upload_sizes(profile: &str) -> HashMap<String, u64> {
+    media_sidecar::list(profile)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|sidecar| {
+            let bytes = std::fs::metadata(&sidecar.original_path).ok()?.len();
+            Some((sidecar.url, bytes))
+        })
+        .collect()
+}
+
+fn uploads(profile: &str, uploads: &[(String, String)]) -> Vec<UploadSummary> {
+    if uploads.is_empty() {
+        return Vec::new();
+    }
+    let sizes = upload_sizes(profile);
+    uploads
+        .iter()
+        .map(|(file, url)| UploadSummary {
+            file: file.clone(),
+            url: url.clone(),
+            bytes: sizes.get(url).copied(),
+        })
+        .collect()
+}
+
+impl Summary {
+    /// Summary of a publish, with one post per site it went to
+    pub fn for_publish(result: &PublishResult, elapsed: Duration) -> Self {
+        let mut summary = Summary {
+            posts: Vec::new(),
+            uploads: Vec::new(),
+            warnings: Vec::new(),
+            elapsed_ms: elapsed.as_millis(),
+        };
+        for site in std::iter::once(result).chain(&result.other_sites) {
+            summary.posts.push(PostSummary {
+                profile: site.profile.clone(),
+                request: if site.was_update { "update" } else { "create" },
+                url: site.url.clone(),
+                archived_path: Some(site.archived_path.clone()),
+                queued: site.queued.clone(),
+            });
+            summary
+                .uploads
+                .extend(uploads(&site.profile, &site.uploads));
+            summary.warnings.extend(site.warnings.iter().cloned());
+        }
+        summary
+    }
+
+    /// Summary of a `draft push`, which leaves the draft in place
+    pub fn for_push(result: &PushResult, elapsed: Duration) -> Self {
+        Summary {
+            posts: vec![PostSummary {
+                profile: result.profile.clone(),
+                request: if result.is_update { "update" } else { "create" },
+                url: result.url.clone(),
+                archived_path: None,
+                queued: result.queued.clone(),
+            }],
+            uploads: uploads(&result.profile, &result.uploads),
+            warnings: Vec::new(),
+            elapsed_ms: elapsed.as_millis(),
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut lines = vec!["── Summary ──".to_string()];
+        for post in &self.posts {
+            let url = match (&post.url, &post.queued) {
+                (_, Some(id)) => format!("queued ({})", id),
+                (Some(url), None) => url.clone(),
+                (None, None) => "(no URL returned)".to_string(),
+            };
+            lines.push(format!("  {} on {}: {}", post.request, post.profile, url));
+            if let Some(path) = &post.archived_path {
+                lines.push(format!("    archived to {}", path.display()));
+            }
+        }
+
+        if !self.uploads.is_empty() {
+            let total: u64 = self.uploads.iter().filter_map(|u| u.bytes).sum();
+            lines.push(format!(
+                "  {} file(s) uploaded, {}",
+                self.uploads.len(),
+                human_size(total)
+            ));
+            for upload in &self.uploads {
+                let size = upload
+                    .bytes
+                    .map(human_size)
+                    .unwrap_or_else(|| "?".to_string());
+                lines.push(format!("    {} ({}) -> {}", upload.file, size, upload.url));
+            }
+        }
+
+        for warning in &self.warnings {
+            lines.push(format!("  ⚠ {}", warning));
+        }
+        lines.push(format!("  took {:.1}s", self.elapsed_ms as f64 / 1000.0));
+        lines.join("\n")
+    }
+}
+
+/// Finish a command: with `--json`, `result` with the summary under `summary`;
+/// otherwise the summary block
+pub fn print<T: Serialize>(result: &T, summary: &Summary) -> Result<()> {
+    if json() {
+        let mut value = serde_json::to_value(result)?;
+        if let Some(object) = value.as_object_mut() {
+            object.insert("summary".to_string(), serde_json::to_value(summary)?);
+        }
+        return print_json(&value);
+    }
+    println!("\n{}", summary.render());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_lists_posts_uploads_and_time() {
+        let summary = Summary {
+            posts: vec![
+                PostSummary {
+                    profile: "blog".to_string(),
+                    request: "create",
+                    url: Some("https://example.com/1".to_string()),
+                    archived_path: Some(PathBuf::from("/data/archive/a.md")),
+                    queued: None,
+                },
+                PostSummary {
+                    profile: "work".to_string(),
+                    request: "create",
+                    url: None,
+                    archived_path: None,
+                    queued: Some("q1".to_string()),
+                },
+            ],
+            uploads: vec![
+                UploadSummary {
+                    file: "a.jpg".to_string(),
+                    url: "https://example.com/a.jpg".to_string(),
+                    bytes: Some(2048),
+                },
+                UploadSummary {
+                    file: "b.jpg".to_string(),
+                    url: "https://example.com/b.jpg".to_string(),
+                    bytes: None,
+                },
+            ],
+            warnings: vec!["slow server".to_string()],
+            elapsed_ms: 2300,
+        };
+
+        let expected = [
+            "── Summary ──",
+            "  create on blog: https://example.com/1",
+            "    archived to /data/archive/a.md",
+            "  create on work: queued (q1)",
+            "  2 file(s) uploaded, 2.0 KB",
+            "    a.jpg (2.0 KB) -> https://example.com/a.jpg",
+            "    b.jpg (?) -> https://example.com/b.jpg",
+            "  ⚠ slow server",
+            "  took 2.3s",
+        ];
+        assert_eq!(summary.render(), expected.join("\n"));
+    }
+}
@@ -0,0 +1,1689 @@
+// ABOUTME: Application state and event handling for TUI
+// ABOUTME: Manages tabs, items, selections, and user actions
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use super::chips;
+use super::content_editor::ContentEditor;
+use super::fetch::{Fetch, PageMode};
+use super::log::EventLog;
+use super::palette::{Palette, PaletteAction};
+use super::session::Session;
+use super::tags::TagEditor;
+use super::thumbnail::{self, Protocol, Thumbnail};
+use crate::config::Config;
+use crate::draft::{known_categories_async, lint_all_async, Draft, DraftId, LintWarning};
+use crate::media::UploadProgress;
+use crate::operations::{MediaData, PostData};
+use crate::publish::PublishResult;
+
+/// Posts and media are fetched this many at a time
+const PAGE_SIZE: usize = 20;
+/// Media previews kept in memory before the cache starts over
+const MAX_THUMBNAILS: usize = 16;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tab {
+    #[default]
+    Drafts,
+    Posts,
+    Media,
+    Scheduled,
+    Archive,
+}
+
+#[derive(Debug, Clone)]
+pub struct DraftItem {
+    pub id: String,
+    pub title: String,
+    pub post_type: String,
+    pub categories: Vec<String>,
+    /// Lint problems found when the draft was loaded
+    pub warnings: Vec<LintWarning>,
+    /// Body text, kept for search
+    pub content: String,
+}
+
+impl DraftItem {
+    /// Whether any problem would make publishing fail
+    pub fn has_errors(&self) -> bool {
+        self.warnings.iter().any(|w| w.kind.is_error())
+    }
+
+    /// Case-insensitive match on title, content, or categories
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.title.to_lowercase().contains(&query)
+            || self.content.to_lowercase().contains(&query)
+            || self
+                .categories
+                .iter()
+                .any(|c| c.to_lowercase().contains(&query))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PostItem {
+    pub url: String,
+    pub content: String,
+    pub name: Option<String>,
+    pub published: String,
+    pub categories: Vec<String>,
+    /// Saved on the server with `post-status: draft`
+    pub draft: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct MediaItem {
+    pub url: String,
+    pub name: Option<String>,
+    pub uploaded: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduledItem {
+    pub id: String,
+    pub title: String,
+    pub scheduled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchivedItem {
+    pub id: String,
+    pub title: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub url: Option<String>,
+}
+
+/// The selected media item's image preview
+pub enum MediaPreview<'a> {
+    Loading,
+    Ready(&'a Thumbnail),
+    Failed(&'a str),
+}
+
+pub enum ConfirmationAction {
+    DeleteDraft(String),
+    PublishDraft(String),
+    BackdateDraft(String),
+    RescheduleDraft(String),
+    PublishScheduled(String),
+    CancelScheduled(String),
+    RestoreArchived(String),
+    DeletePost(String),
+    UndeletePost(String),
+    PromotePost(String),
+    None,
+}
+
+/// A publish running in the background, so the screen keeps updating with
+/// upload progress
+struct Publishing {
+    handle: JoinHandle<Result<PublishResult>>,
+    /// Start of the status message on success, e.g. "Draft published"
+    done: &'static str,
+    /// Whether it came from the Scheduled tab, whose list needs reloading
+    scheduled: bool,
+}
+
+pub struct App {
+    pub current_tab: Tab,
+    /// Drafts matching the search filter; what the list shows
+    pub drafts: Vec<DraftItem>,
+    all_drafts: Vec<DraftItem>,
+    /// Draft search filter (`/`)
+    pub search: String,
+    pub searching: bool,
+    /// Category chip filtering the drafts list (`[`/`]`)
+    pub draft_category: Option<String>,
+    /// Posts in the selected category chip; what the list shows
+    pub posts: Vec<PostItem>,
+    all_posts: Vec<PostItem>,
+    pub post_category: Option<String>,
+    pub media: Vec<MediaItem>,
+    pub scheduled: Vec<ScheduledItem>,
+    /// Published drafts in archive/, newest first
+    pub archived: Vec<ArchivedItem>,
+    pub selected_draft: usize,
+    pub selected_post: usize,
+    pub selected_media: usize,
+    pub selected_scheduled: usize,
+    pub selected_archived: usize,
+    pub preview_content: Option<String>,
+    /// Lines scrolled down in the preview pane; reset when the preview changes
+    pub preview_scroll: u16,
+    pub error_message: Option<String>,
+    pub status_message: Option<String>,
+    pub confirmation_action: ConfirmationAction,
+    pub quit_requested: bool,
+    pub date_input: String,
+    /// Open command palette (`:`), if any
+    pub palette: Option<Palette>,
+    /// Errors and status messages from this session
+    pub log: EventLog,
+    pub show_log: bool,
+    /// Category editor for the selected draft (`t`), if open
+    pub tag_editor: Option<TagEditor>,
+    /// Built-in title and content editor for the selected draft (`i`), if open
+    pub content_editor: Option<ContentEditor>,
+    /// Parent of every background fetch; cancelled on quit
+    cancel: CancellationToken,
+    posts_fetch: Option<Fetch<PostData>>,
+    media_fetch: Option<Fetch<MediaData>>,
+    categories_fetch: Option<Fetch<String>>,
+    /// Categories from the server's `q=category`, offered by the category editor
+    server_categories: Vec<String>,
+    posts_exhausted: bool,
+    /// Most recently deleted post, which `u` offers to restore
+    last_deleted_post: Option<String>,
+    media_exhausted: bool,
+    publishing: Option<Publishing>,
+    /// Latest progress of the current media upload, set from the upload task
+    upload_progress: Arc<Mutex<Option<UploadProgress>>>,
+    /// How the Media tab draws image previews
+    pub image_protocol: Protocol,
+    /// Downloaded previews by URL, or why there isn't one
+    thumbnails: HashMap<String, std::result::Result<Arc<Thumbnail>, String>>,
+    thumbnail_fetch: Option<(String, JoinHandle<Result<Thumbnail>>)>,
+}
+
+impl App {
+    pub async fn new() -> Result<Self> {
+        let mut app = App {
+            current_tab: Tab::Drafts,
+            drafts: Vec::new(),
+            all_drafts: Vec::new(),
+            search: String::new(),
+            searching: false,
+            draft_category: None,
+            posts: Vec::new(),
+            all_posts: Vec::new(),
+            post_category: None,
+            media: Vec::new(),
+            scheduled: Vec::new(),
+            archived: Vec::new(),
+            selected_draft: 0,
+            selected_post: 0,
+            selected_media: 0,
+            selected_scheduled: 0,
+            selected_archived: 0,
+            preview_content: None,
+            preview_scroll: 0,
+            error_message: None,
+            status_message: None,
+            confirmation_action: ConfirmationAction::None,
+            quit_requested: false,
+            date_input: String::new(),
+            palette: None,
+            log: EventLog::default(),
+            show_log: false,
+            tag_editor: None,
+            content_editor: None,
+            cancel: CancellationToken::new(),
+            posts_fetch: None,
+            media_fetch: None,
+            categories_fetch: None,
+            server_categories: Vec::new(),
+            posts_exhausted: false,
+            last_deleted_post: None,
+            media_exhausted: false,
+            publishing: None,
+            upload_progress: Arc::default(),
+            image_protocol: Protocol::detect(),
+            thumbnails: HashMap::new(),
+            thumbnail_fetch: None,
+        };
+
+        app.load_drafts().await?;
+        app.load_posts(PageMode::Load);
+        app.load_media(PageMode::Load);
+        app.load_categories();
+        app.load_scheduled()?;
+        app.load_archived()?;
+        app.restore_session(&Session::load());
+        app.update_preview();
+        Ok(app)
+    }
+
+    /// Whether a page of posts or media is being fetched for `tab`
+    pub fn is_loading(&self, tab: &Tab) -> bool {
+        match tab {
+            Tab::Posts => self.posts_fetch.is_some(),
+            Tab::Media => self.media_fetch.is_some(),
+            Tab::Drafts | Tab::Scheduled | Tab::Archive => false,
+        }
+    }
+
+    /// Show media upload progress in the status bar instead of on stderr
+    pub fn watch_uploads(&self) {
+        let progress = Arc::clone(&self.upload_progress);
+        crate::media::set_upload_observer(move |update| {
+            *progress.lock().unwrap_or_else(|e| e.into_inner()) = Some(update.clone());
+        });
+    }
+
+    pub fn is_loading_preview(&self) -> bool {
+        self.thumbnail_fetch.is_some()
+    }
+
+    pub fn is_publishing(&self) -> bool {
+        self.publishing.is_some()
+    }
+
+    /// "Uploading photo.jpg 45%" while a publish is sending media
+    pub fn upload_status(&self) -> Option<String> {
+        self.publishing.as_ref()?;
+        let progress = self.upload_progress.lock().ok()?.clone()?;
+        Some(format!(
+            "Uploading {} {}%",
+            progress.file,
+            progress.percent()
+        ))
+    }
+
+    /// Publish in the background; `poll_fetches` reports the result
+    fn start_publishing(
+        &mut self,
+        done: &'static str,
+        scheduled: bool,
+        publish: impl std::future::Future<Output = Result<PublishResult>> + Send + 'static,
+    ) {
+        *self
+            .upload_progress
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = None;
+        self.publishing = Some(Publishing {
+            handle: tokio::spawn(publish),
+            done,
+            scheduled,
+        });
+    }
+
+    async fn finish_publishing(&mut self, publishing: Publishing) -> Result<()> {
+        let result = match publishing.handle.await {
+            Ok(result) => result,
+            Err(e) => Err(e.into()),
+        };
+        match result {
+            Ok(result) => {
+                self.status_message = Some(result.status(publishing.done));
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to publish: {}", e));
+            }
+        }
+        if publishing.scheduled {
+            self.load_scheduled()?;
+        } else {
+            self.load_drafts().await?;
+            if self.selected_draft >= self.drafts.len() && self.selected_draft > 0 {
+                self.selected_draft -= 1;
+            }
+        }
+        self.update_preview();
+        Ok(())
+    }
+
+    /// Apply background fetches that have finished; call once per frame
+    pub async fn poll_fetches(&mut self) {
+        if let Some(publishing) = self.publishing.take_if(|p| p.handle.is_finished()) {
+            if let Err(e) = self.finish_publishing(publishing).await {
+                self.error_message = Some(format!("Failed to reload: {}", e));
+            }
+        }
+        if let Some(fetch) = self.posts_fetch.take_if(|f| f.is_finished()) {
+            let mode = fetch.mode;
+            match fetch.result().await {
+                Ok(posts) => self.apply_posts(mode, posts),
+                Err(e) => self.error_message = Some(format!("Failed to load posts: {}", e)),
+            }
+        }
+        if let Some(fetch) = self.media_fetch.take_if(|f| f.is_finished()) {
+            let mode = fetch.mode;
+            match fetch.result().await {
+                Ok(media) => self.apply_media(mode, media),
+                Err(e) => self.error_message = Some(format!("Failed to load media: {}", e)),
+            }
+        }
+        if let Some((url, handle)) = self
+            .thumbnail_fetch
+            .take_if(|(_, handle)| handle.is_finished())
+        {
+            let thumbnail = match handle.await {
+                Ok(Ok(thumbnail)) => Ok(Arc::new(thumbnail)),
+                Ok(Err(e)) => Err(format!("{:#}", e)),
+                Err(e) => Err(e.to_string()),
+            };
+            if self.thumbnails.len() >= MAX_THUMBNAILS {
+                self.thumbnails.clear();
+            }
+            self.thumbnails.insert(url, thumbnail);
+        }
+        // Completions are a nicety; without the server, the cached list will do
+        if let Some(fetch) = self.categories_fetch.take_if(|f| f.is_finished()) {
+            if let Ok(categories) = fetch.result().await {
+                self.server_categories = categories;
+            }
+        }
+    }
+
+    /// Cancel every in-flight fetch, e.g. on quit
+    pub fn cancel_fetches(&mut self) {
+        self.cancel.cancel();
+        self.posts_fetch = None;
+        self.media_fetch = None;
+        self.categories_fetch = None;
+        if let Some((_, handle)) = self.thumbnail_fetch.take() {
+            handle.abort();
+        }
+    }
+
+    /// Start downloading the preview of an image URL unless it's cached, replacing
+    /// the download of a previously selected one
+    fn request_thumbnail(&mut self, url: &str) {
+        if self.image_protocol == Protocol::Off
+            || !thumbnail::is_previewable(url)
+            || self.thumbnails.contains_key(url)
+            || self.thumbnail_fetch.as_ref().is_some_and(|(u, _)| u == url)
+        {
+            return;
+        }
+        if let Some((_, handle)) = self.thumbnail_fetch.take() {
+            handle.abort();
+        }
+        let handle = tokio::spawn(Thumbnail::fetch(url.to_string()));
+        self.thumbnail_fetch = Some((url.to_string(), handle));
+    }
+
+    /// The selected media item's URL and preview, if it's an image
+    pub fn media_preview(&self) -> Option<(&str, MediaPreview<'_>)> {
+        if self.current_tab != Tab::Media || self.image_protocol == Protocol::Off {
+            return None;
+        }
+        let url = self.media.get(self.selected_media)?.url.as_str();
+        let preview = match self.thumbnails.get(url) {
+            Some(Ok(thumbnail)) => MediaPreview::Ready(thumbnail),
+            Some(Err(e)) => MediaPreview::Failed(e),
+            None if self.thumbnail_fetch.as_ref().is_some_and(|(u, _)| u == url) => {
+                MediaPreview::Loading
+            }
+            None => return None,
+        };
+        Some((url, preview))
+    }
+
+    /// Return to the tab and selections from a previous launch
+    fn restore_session(&mut self, session: &Session) {
+        self.current_tab = session.tab.clone();
+        if !session.draft_search.is_empty() || session.draft_category.is_some() {
+            self.search = session.draft_search.clone();
+            self.draft_category = session.draft_category.clone();
+            self.apply_search();
+        }
+        if let Some(index) = session
+            .selected_draft
+            .as_ref()
+            .and_then(|id| self.drafts.iter().position(|d| &d.id == id))
+        {
+            self.selected_draft = index;
+        }
+        // Posts and media are still loading; their pages clamp these on arrival
+        self.selected_post = session.selected_post;
+        self.selected_media = session.selected_media;
+        self.selected_scheduled = session
+            .selected_scheduled
+            .min(self.scheduled.len().saturating_sub(1));
+        self.selected_archived = session
+            .selected_archived
+            .min(self.archived.len().saturating_sub(1));
+    }
+
+    /// Current tab and selections, to be restored next launch
+    pub fn session(&self) -> Session {
+        Session {
+            tab: self.current_tab.clone(),
+            selected_draft: self.drafts.get(self.selected_draft).map(|d| d.id.clone()),
+            draft_search: self.search.clone(),
+            draft_category: self.draft_category.clone(),
+            selected_post: self.selected_post,
+            selected_media: self.selected_media,
+            selected_scheduled: self.selected_scheduled,
+            selected_archived: self.selected_archived,
+        }
+    }
+
+    async fn load_drafts(&mut self) -> Result<()> {
+        self.all_drafts.clear();
+
+        for (id, draft, warnings) in lint_all_async().await? {
+            match draft {
+                Some(draft) => {
+                    let title = draft
+                        .metadata
+                        .name
+                        .unwrap_or_else(|| "[untitled]".to_string());
+                    self.all_drafts.push(DraftItem {
+                        id: id.clone(),
+                        title,
+                        post_type: draft.metadata.post_type.clone(),
+                        categories: draft.metadata.category.clone(),
+                        warnings,
+                        content: draft.content,
+                    });
+                }
+                // Keep unparseable drafts visible so their problems can be fixed
+                None => self.all_drafts.push(DraftItem {
+                    id: id.clone(),
+                    title: id.clone(),
+                    post_type: "?".to_string(),
+                    categories: Vec::new(),
+                    warnings,
+                    content: String::new(),
+                }),
+            }
+        }
+
+        self.apply_search();
+        Ok(())
+    }
+
+    /// Rebuild the visible drafts from the search filter and category chip,
+    /// keeping the selection if it still matches
+    fn apply_search(&mut self) {
+        let selected_id = self.drafts.get(self.selected_draft).map(|d| d.id.clone());
+
+        self.drafts = self
+            .all_drafts
+            .iter()
+            .filter(|d| self.search.is_empty() || d.matches(&self.search))
+            .filter(|d| in_category(&d.categories, self.draft_category.as_deref()))
+            .cloned()
+            .collect();
+
+        self.selected_draft = selected_id
+            .and_then(|id| self.drafts.iter().position(|d| d.id == id))
+            .unwrap_or_else(|| self.selected_draft.min(self.drafts.len().saturating_sub(1)));
+    }
+
+    /// Categories of the loaded drafts or posts, for the chips above the list
+    pub fn category_chips(&self) -> Vec<String> {
+        match self.current_tab {
+            Tab::Drafts => chips::collect(self.all_drafts.iter().map(|d| d.categories.as_slice())),
+            Tab::Posts => chips::collect(self.all_posts.iter().map(|p| p.categories.as_slice())),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The selected chip on the current tab; `None` shows everything
+    pub fn category_filter(&self) -> Option<&str> {
+        match self.current_tab {
+            Tab::Drafts => self.draft_category.as_deref(),
+            Tab::Posts => self.post_category.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Select the next (or previous) category chip and filter the list to it
+    pub fn step_category(&mut self, forward: bool) {
+        let chips = self.category_chips();
+        let next = chips::step(&chips, self.category_filter(), forward);
+        match self.current_tab {
+            Tab::Drafts => {
+                self.draft_category = next;
+                self.apply_search();
+            }
+            Tab::Posts => {
+                self.post_category = next;
+                self.apply_post_filter();
+            }
+            _ => return,
+        }
+        self.update_preview();
+    }
+
+    /// Rebuild the visible posts from the category chip, keeping the selection if it still matches
+    fn apply_post_filter(&mut self) {
+        let selected_url = self.posts.get(self.selected_post).map(|p| p.url.clone());
+
+        self.posts = self
+            .all_posts
+            .iter()
+            .filter(|p| in_category(&p.categories, self.post_category.as_deref()))
+            .cloned()
+            .collect();
+
+        self.selected_post = selected_url
+            .and_then(|url| self.posts.iter().position(|p| p.url == url))
+            .unwrap_or_else(|| self.selected_post.min(self.posts.len().saturating_sub(1)));
+    }
+
+    pub fn start_search(&mut self) {
+        if self.current_tab == Tab::Drafts {
+            self.searching = true;
+        }
+    }
+
+    /// Leave the search input, keeping the filter applied
+    pub fn finish_search(&mut self) {
+        self.searching = false;
+    }
+
+    pub fn clear_search(&mut self) {
+        self.searching = false;
+        if !self.search.is_empty() {
+            self.search.clear();
+            self.apply_search();
+            self.update_preview();
+        }
+    }
+
+    pub fn add_search_char(&mut self, c: char) {
+        self.search.push(c);
+        self.apply_search();
+        self.update_preview();
+    }
+
+    pub fn delete_search_char(&mut self) {
+        self.search.pop();
+        self.apply_search();
+        self.update_preview();
+    }
+
+    fn load_scheduled(&mut self) -> Result<()> {
+        self.scheduled = crate::schedule::list()?
+            .into_iter()
+            .map(|draft| ScheduledItem {
+                id: draft.id,
+                title: draft
+                    .metadata
+                    .name
+                    .unwrap_or_else(|| "[untitled]".to_string()),
+                scheduled_at: draft.metadata.scheduled_at,
+            })
+            .collect();
+
+        if self.selected_scheduled >= self.scheduled.len() {
+            self.selected_scheduled = self.scheduled.len().saturating_sub(1);
+        }
+
+        Ok(())
+    }
+
+    fn load_archived(&mut self) -> Result<()> {
+        self.archived = crate::archive::list()?
+            .into_iter()
+            .map(|draft| ArchivedItem {
+                id: draft.id,
+                title: draft
+                    .metadata
+                    .name
+                    .unwrap_or_else(|| "[untitled]".to_string()),
+                published_at: draft.metadata.published_at,
+                url: draft.metadata.url,
+            })
+            .collect();
+
+        if self.selected_archived >= self.archived.len() {
+            self.selected_archived = self.archived.len().saturating_sub(1);
+        }
+
+        Ok(())
+    }
+
+    /// Start fetching the active profile's categories, showing the cached list meanwhile
+    fn load_categories(&mut self) {
+        let Ok(config) = crate::config::Config::load() else {
+            return;
+        };
+        let profile_name = config.active_profile_name();
+        self.server_categories = crate::server_config::cached_categories(&profile_name);
+        self.categories_fetch = Some(Fetch::spawn(&self.cancel, PageMode::Load, async move {
+            crate::operations::server_categories(&profile_name, false).await
+        }));
+    }
+
+    /// Start fetching the first page of posts; it replaces the list when it arrives
+    fn load_posts(&mut self, mode: PageMode) {
+        let page_size = crate::polite::page_size(PAGE_SIZE);
+        self.posts_fetch = Some(Fetch::spawn(
+            &self.cancel,
+            mode,
+            crate::operations::fetch_posts(page_size, 0),
+        ));
+    }
+
+    /// Start fetching the page after the loaded posts, unless a fetch is running
+    fn fetch_more_posts(&mut self) {
+        if self.posts_fetch.is_some() {
+            return;
+        }
+        let page_size = crate::polite::page_size(PAGE_SIZE);
+        self.posts_fetch = Some(Fetch::spawn(
+            &self.cancel,
+            PageMode::Append,
+            crate::operations::fetch_posts(page_size, self.all_posts.len()),
+        ));
+    }
+
+    fn apply_posts(&mut self, mode: PageMode, posts: Vec<PostData>) {
+        self.posts_exhausted = posts.len() < crate::polite::page_size(PAGE_SIZE);
+        let before = if mode == PageMode::Append {
+            self.posts.len()
+        } else {
+            self.all_posts.clear();
+            0
+        };
+        self.all_posts
+            .extend(posts.into_iter().map(|post| PostItem {
+                url: post.url,
+                content: post.content,
+                name: post.name,
+                published: post.published.unwrap_or_else(|| "(no date)".to_string()),
+                categories: post.categories,
+                draft: post.draft,
+            }));
+        self.apply_post_filter();
+
+        if mode == PageMode::Append {
+            // Move onto the first new post
+            if self.posts.len() > before {
+                self.selected_post = before;
+            }
+        } else {
+            self.selected_post = self.selected_post.min(self.posts.len().saturating_sub(1));
+        }
+        if mode == PageMode::Refresh {
+            self.status_message = Some("Posts refreshed".to_string());
+        }
+        self.update_preview();
+    }
+
+    /// Start fetching the first page of media; it replaces the list when it arrives
+    fn load_media(&mut self, mode: PageMode) {
+        let page_size = crate::polite::page_size(PAGE_SIZE);
+        self.media_fetch = Some(Fetch::spawn(
+            &self.cancel,
+            mode,
+            crate::operations::fetch_media(page_size, 0),
+        ));
+    }
+
+    /// Start fetching the page after the loaded media, unless a fetch is running
+    fn fetch_more_media(&mut self) {
+        if self.media_fetch.is_some() {
+            return;
+        }
+        let page_size = crate::polite::page_size(PAGE_SIZE);
+        self.media_fetch = Some(Fetch::spawn(
+            &self.cancel,
+            PageMode::Append,
+            crate::operations::fetch_media(page_size, self.media.len()),
+        ));
+    }
+
+    fn apply_media(&mut self, mode: PageMode, media_items: Vec<MediaData>) {
+        self.media_exhausted = media_items.len() < crate::polite::page_size(PAGE_SIZE);
+        let before = if mode == PageMode::Append {
+            self.media.len()
+        } else {
+            self.media.clear();
+            0
+        };
+        self.media
+            .extend(media_items.into_iter().map(|media| MediaItem {
+                url: media.url,
+                name: media.name,
+                uploaded: media.uploaded.unwrap_or_else(|| "(no date)".to_string()),
+            }));
+
+        if mode == PageMode::Append {
+            if self.media.len() > before {
+                self.selected_media = before;
+            }
+        } else {
+            self.selected_media = self.selected_media.min(self.media.len().saturating_sub(1));
+        }
+        if mode == PageMode::Refresh {
+            self.status_message = Some("Media refreshed".to_string());
+        }
+        self.update_preview();
+    }
+
+    /// Cancel fetches for tabs other than the current one, and reload the
+    /// current tab if an earlier cancel left it empty
+    fn switched_tab(&mut self) {
+        if self.current_tab != Tab::Posts {
+            self.posts_fetch = None;
+        } else if self.posts_fetch.is_none() && self.all_posts.is_empty() && !self.posts_exhausted {
+            self.load_posts(PageMode::Load);
+        }
+        if self.current_tab != Tab::Media {
+            self.media_fetch = None;
+        } else if self.media_fetch.is_none() && self.media.is_empty() && !self.media_exhausted {
+            self.load_media(PageMode::Load);
+        }
+        self.update_preview();
+    }
+
+    pub async fn refresh(&mut self) -> Result<()> {
+        self.status_message = Some("Refreshing...".to_string());
+        match self.current_tab {
+            Tab::Drafts => {
+                self.load_drafts().await?;
+                self.update_preview();
+                self.status_message = Some("Drafts refreshed".to_string());
+            }
+            // These report back once the page arrives
+            Tab::Posts => self.load_posts(PageMode::Refresh),
+            Tab::Media => self.load_media(PageMode::Refresh),
+            Tab::Scheduled => {
+                self.load_scheduled()?;
+                self.update_preview();
+                self.status_message = Some("Schedule refreshed".to_string());
+            }
+            Tab::Archive => {
+                self.load_archived()?;
+                self.update_preview();
+                self.status_message = Some("Archive refreshed".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn next_tab(&mut self) {
+        self.current_tab = match self.current_tab {
+            Tab::Drafts => Tab::Posts,
+            Tab::Posts => Tab::Media,
+            Tab::Media => Tab::Scheduled,
+            Tab::Scheduled => Tab::Archive,
+            Tab::Archive => Tab::Drafts,
+        };
+        self.switched_tab();
+    }
+
+    pub fn previous_tab(&mut self) {
+        self.current_tab = match self.current_tab {
+            Tab::Drafts => Tab::Archive,
+            Tab::Posts => Tab::Drafts,
+            Tab::Media => Tab::Posts,
+            Tab::Scheduled => Tab::Media,
+            Tab::Archive => Tab::Scheduled,
+        };
+        self.switched_tab();
+    }
+
+    pub fn next_item(&mut self) {
+        match self.current_tab {
+            Tab::Drafts => {
+                if !self.drafts.is_empty() {
+                    self.selected_draft = (self.selected_draft + 1) % self.drafts.len();
+                    self.update_preview();
+                }
+            }
+            Tab::Posts => {
+                if self.selected_post + 1 >= self.posts.len() && !self.posts_exhausted {
+                    if !self.posts.is_empty() {
+                        self.fetch_more_posts();
+                    }
+                } else if !self.posts.is_empty() {
+                    self.selected_post = (self.selected_post + 1) % self.posts.len();
+                    self.update_preview();
+                }
+            }
+            Tab::Media => {
+                if self.selected_media + 1 >= self.media.len() && !self.media_exhausted {
+                    if !self.media.is_empty() {
+                        self.fetch_more_media();
+                    }
+                } else if !self.media.is_empty() {
+                    self.selected_media = (self.selected_media + 1) % self.media.len();
+                    self.update_preview();
+                }
+            }
+            Tab::Scheduled => {
+                if !self.scheduled.is_empty() {
+                    self.selected_scheduled = (self.selected_scheduled + 1) % self.scheduled.len();
+                    self.update_preview();
+                }
+            }
+            Tab::Archive => {
+                if !self.archived.is_empty() {
+                    self.selected_archived = (self.selected_archived + 1) % self.archived.len();
+                    self.update_preview();
+                }
+            }
+        }
+    }
+
+    pub fn previous_item(&mut self) {
+        match self.current_tab {
+            Tab::Drafts => {
+                if !self.drafts.is_empty() {
+                    self.selected_draft = if self.selected_draft == 0 {
+                        self.drafts.len() - 1
+                    } else {
+                        self.selected_draft - 1
+                    };
+                    self.update_preview();
+                }
+            }
+            Tab::Posts => {
+                if !self.posts.is_empty() {
+                    self.selected_post = if self.selected_post == 0 {
+                        self.posts.len() - 1
+                    } else {
+                        self.selected_post - 1
+                    };
+                    self.update_preview();
+                }
+            }
+            Tab::Media => {
+                if !self.media.is_empty() {
+                    self.selected_media = if self.selected_media == 0 {
+                        self.media.len() - 1
+                    } else {
+                        self.selected_media - 1
+                    };
+                    self.update_preview();
+                }
+            }
+            Tab::Scheduled => {
+                if !self.scheduled.is_empty() {
+                    self.selected_scheduled = if self.selected_scheduled == 0 {
+                        self.scheduled.len() - 1
+                    } else {
+                        self.selected_scheduled - 1
+                    };
+                    self.update_preview();
+                }
+            }
+            Tab::Archive => {
+                if !self.archived.is_empty() {
+                    self.selected_archived = if self.selected_archived == 0 {
+                        self.archived.len() - 1
+                    } else {
+                        self.selected_archived - 1
+                    };
+                    self.update_preview();
+                }
+            }
+        }
+    }
+
+    fn update_preview(&mut self) {
+        self.preview_content = None;
+        self.preview_scroll = 0;
+
+        if self.current_tab == Tab::Drafts && !self.drafts.is_empty() {
+            if let Some(draft_item) = self.drafts.get(self.selected_draft) {
+                let mut preview = String::new();
+                for warning in &draft_item.warnings {
+                    preview.push_str(&format!("⚠ {}\n", warning));
+                }
+                if !preview.is_empty() {
+                    preview.push('\n');
+                }
+
+                // Show the raw file when it can't be parsed
+                let body = match Draft::load(&draft_item.id) {
+                    Ok(draft) => draft.to_string().ok(),
+                    Err(_) => crate::draft::paths::draft_path(&draft_item.id)
+                        .ok()
+                        .and_then(|path| std::fs::read_to_string(path).ok()),
+                };
+                if let Some(body) = body {
+                    preview.push_str(&body);
+                    self.preview_content = Some(preview);
+                }
+            }
+        } else if self.current_tab == Tab::Posts && !self.posts.is_empty() {
+            if let Some(post_item) = self.posts.get(self.selected_post) {
+                let mut preview = String::new();
+
+                if let Some(ref name) = post_item.name {
+                    preview.push_str(&format!("Title: {}\n\n", name));
+                }
+
+                preview.push_str(&format!("URL: {}\n", post_item.url));
+                preview.push_str(&format!("Published: {}\n", post_item.published));
+                if post_item.draft {
+                    preview.push_str("Status: draft ([P] to publish)\n");
+                }
+
+                if !post_item.categories.is_empty() {
+                    preview.push_str(&format!(
+                        "Categories: {}\n",
+                        post_item.categories.join(", ")
+                    ));
+                }
+
+                // Private notes are only ever stored locally
+                let notes = crate::post_notes::for_post(&post_item.url).unwrap_or_default();
+                if !notes.is_empty() {
+                    preview.push_str("\nNotes:\n");
+                    for (idx, note) in notes.iter().enumerate() {
+                        preview
+                            .push_str(&format!("  {}\n", crate::notes::format_note(idx + 1, note)));
+                    }
+                }
+
+                preview.push_str("\n---\n\n");
+                preview.push_str(&post_item.content);
+
+                self.preview_content = Some(preview);
+            }
+        } else if self.current_tab == Tab::Media && !self.media.is_empty() {
+            if let Some(media_item) = self.media.get(self.selected_media) {
+                let mut preview = String::new();
+
+                preview.push_str(&format!("URL: {}\n", media_item.url));
+                preview.push_str(&format!("Uploaded: {}\n", media_item.uploaded));
+
+                if let Some(ref name) = media_item.name {
+                    preview.push_str(&format!("\nName/Alt Text:\n{}\n", name));
+                }
+
+                let url = media_item.url.clone();
+                self.preview_content = Some(preview);
+                self.request_thumbnail(&url);
+            }
+        } else if self.current_tab == Tab::Scheduled && !self.scheduled.is_empty() {
+            if let Some(item) = self.scheduled.get(self.selected_scheduled) {
+                if let Ok(draft) = crate::schedule::load(&item.id) {
+                    if let Ok(content) = draft.to_string() {
+                        self.preview_content = Some(content);
+                    }
+                }
+            }
+        } else if self.current_tab == Tab::Archive && !self.archived.is_empty() {
+            if let Some(item) = self.archived.get(self.selected_archived) {
+                if let Ok(draft) = crate::archive::load(&item.id) {
+                    if let Ok(content) = draft.to_string() {
+                        self.preview_content = Some(content);
+                    }
+                }
+            }
+        }
+    }
+
+    pub async fn select_item(&mut self) -> Result<()> {
+        // For now, selection just updates preview (already done by navigation)
+        self.status_message = Some("Item selected".to_string());
+        Ok(())
+    }
+
+    pub async fn publish_draft(&mut self) -> Result<()> {
+        if self.is_publishing() {
+            self.status_message = Some("Still publishing the last draft...".to_string());
+            return Ok(());
+        }
+        if self.current_tab == Tab::Scheduled {
+            if let Some(item) = self.scheduled.get(self.selected_scheduled) {
+                self.confirmation_action = ConfirmationAction::PublishScheduled(item.id.clone());
+                self.status_message = Some("Publish scheduled draft now? (y/n)".to_string());
+            }
+            return Ok(());
+        }
+
+        if self.current_tab != Tab::Drafts || self.drafts.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(draft_item) = self.drafts.get(self.selected_draft) {
+            let draft = Draft::load(&draft_item.id).ok();
+            let mismatch = draft.as_ref().and_then(|draft| {
+                let config = Config::load().ok()?;
+                let mismatch = crate::profile_guard::find(&config, draft)?;
+                Some((mismatch.describe(&config), mismatch.target().to_string()))
+            });
+            let duplicate = draft
+                .as_ref()
+                .and_then(|draft| crate::duplicates::find(draft).ok().flatten());
+            self.status_message = Some(match (mismatch, duplicate) {
+                (Some((described, target)), _) => {
+                    format!("⚠ {}. Publish to {}? (y/n)", described, target)
+                }
+                (None, Some(duplicate)) => format!(
+                    "⚠ Matches {}. Publish again? (y/n)",
+                    duplicate.describe(Utc::now())
+                ),
+                (None, None) => "Publish draft? (y/n)".to_string(),
+            });
+            self.confirmation_action = ConfirmationAction::PublishDraft(draft_item.id.clone());
+        }
+
+        Ok(())
+    }
+
+    pub fn edit_item(&mut self) -> Result<Option<String>> {
+        match self.current_tab {
+            Tab::Drafts => {
+                if let Some(draft_item) = self.drafts.get(self.selected_draft) {
+                    // Return draft ID for TUI to handle suspend/resume
+                    Ok(Some(draft_item.id.clone()))
+                } else {
+                    Ok(None)
+                }
+            }
+            _ => {
+                self.error_message = Some("Edit not available for this view".to_string());
+                Ok(None)
+            }
+        }
+    }
+
+    pub async fn delete_item(&mut self) -> Result<()> {
+        if self.current_tab == Tab::Posts {
+            if let Some(post) = self.posts.get(self.selected_post) {
+                self.confirmation_action = ConfirmationAction::DeletePost(post.url.clone());
+                self.status_message = Some(format!("Delete {} from the server? (y/n)", post.url));
+            }
+            return Ok(());
+        }
+
+        if self.current_tab != Tab::Drafts || self.drafts.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(draft_item) = self.drafts.get(self.selected_draft) {
+            self.confirmation_action = ConfirmationAction::DeleteDraft(draft_item.id.clone());
+            self.status_message = Some("Delete draft? (y/n)".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub async fn backdate_draft(&mut self) -> Result<()> {
+        if self.current_tab != Tab::Drafts || self.drafts.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(draft_item) = self.drafts.get(self.selected_draft) {
+            self.confirmation_action = ConfirmationAction::BackdateDraft(draft_item.id.clone());
+            self.date_input.clear();
+            self.status_message =
+                Some("Enter date (ISO 8601, e.g., 2024-01-15T10:30:00Z):".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Prompt for a new publish time for the selected scheduled draft
+    pub fn reschedule_item(&mut self) {
+        if self.current_tab != Tab::Scheduled {
+            return;
+        }
+
+        if let Some(item) = self.scheduled.get(self.selected_scheduled) {
+            self.confirmation_action = ConfirmationAction::RescheduleDraft(item.id.clone());
+            // Start from the current time so small adjustments are easy
+            self.date_input = item
+                .scheduled_at
+                .map(|at| at.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+                .unwrap_or_default();
+            self.status_message =
+                Some("Enter new time (ISO 8601, e.g., 2024-01-15T10:30:00Z):".to_string());
+        }
+    }
+
+    /// Ask to take the selected draft out of the schedule queue
+    pub fn cancel_scheduled(&mut self) {
+        if self.current_tab != Tab::Scheduled {
+            return;
+        }
+
+        if let Some(item) = self.scheduled.get(self.selected_scheduled) {
+            self.confirmation_action = ConfirmationAction::CancelScheduled(item.id.clone());
+            self.status_message = Some("Cancel schedule and return to drafts? (y/n)".to_string());
+        }
+    }
+
+    /// Ask to copy the selected archived draft back to drafts
+    pub fn restore_archived(&mut self) {
+        if self.current_tab != Tab::Archive {
+            return;
+        }
+
+        if let Some(item) = self.archived.get(self.selected_archived) {
+            self.confirmation_action = ConfirmationAction::RestoreArchived(item.id.clone());
+            self.status_message = Some("Restore to drafts as unpublished? (y/n)".to_string());
+        }
+    }
+
+    /// Copy the selected post, media, or archived post URL, showing it instead
+    /// when there's no clipboard
+    pub fn copy_url(&mut self) {
+        if !matches!(self.current_tab, Tab::Posts | Tab::Media | Tab::Archive) {
+            return;
+        }
+
+        match self.selected_url() {
+            Some(url) => match crate::clipboard::copy(&url) {
+                Ok(()) => self.status_message = Some(format!("Copied {}", url)),
+                Err(e) => self.status_message = Some(format!("{}; URL: {}", e, url)),
+            },
+            None => self.error_message = Some("No URL for this item".to_string()),
+        }
+    }
+
+    /// Ask to undelete the last post deleted here, or else the selected post
+    pub fn undelete_post(&mut self) {
+        if self.current_tab != Tab::Posts {
+            return;
+        }
+
+        let url = self
+            .last_deleted_post
+            .clone()
+            .or_else(|| self.posts.get(self.selected_post).map(|p| p.url.clone()));
+        if let Some(url) = url {
+            self.status_message = Some(format!("Undelete {}? (y/n)", url));
+            self.confirmation_action = ConfirmationAction::UndeletePost(url);
+        }
+    }
+
+    /// Ask to publish the selected post if it's a server-side draft
+    pub fn promote_post(&mut self) {
+        if self.current_tab != Tab::Posts {
+            return;
+        }
+
+        match self.posts.get(self.selected_post) {
+            Some(post) if post.draft => {
+                self.status_message = Some(format!("Publish {}? (y/n)", post.url));
+                self.confirmation_action = ConfirmationAction::PromotePost(post.url.clone());
+            }
+            Some(_) => self.error_message = Some("This post is already published".to_string()),
+            None => {}
+        }
+    }
+
+    pub fn new_draft(&mut self) -> Result<String> {
+        // Generate new draft ID and return it for TUI to handle
+        Ok(crate::draft::generate_draft_id())
+    }
+
+    pub async fn reload_and_select_draft(&mut self, draft_id: &str) -> Result<()> {
+        // Reload drafts
+        self.load_drafts().await?;
+
+        // Find and select the new draft
+        if let Some(index) = self.drafts.iter().position(|d| d.id == draft_id) {
+            self.selected_draft = index;
+            self.update_preview();
+            self.status_message = Some(format!("Draft created: {}", draft_id));
+
+            // Surface broken photo paths now rather than at publish time
+            let warnings = &self.drafts[index].warnings;
+            if !warnings.is_empty() {
+                self.error_message = Some(
+                    warnings
+                        .iter()
+                        .map(|w| w.to_string())
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                );
+            }
+        } else {
+            self.error_message = Some("Draft created but not found in list".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn confirm_quit(&mut self) -> bool {
+        if !self.quit_requested {
+            self.quit_requested = true;
+            self.status_message = Some("Press 'q' again to quit".to_string());
+            false
+        } else {
+            self.cancel_fetches();
+            true
+        }
+    }
+
+    pub fn awaiting_confirmation(&self) -> bool {
+        !matches!(self.confirmation_action, ConfirmationAction::None)
+    }
+
+    pub async fn confirm_action(&mut self) -> Result<()> {
+        match &self.confirmation_action {
+            ConfirmationAction::PublishDraft(draft_id) => {
+                self.status_message = Some("Publishing...".to_string());
+
+                // Load the draft and publish it
+                let draft_path = DraftId::parse(draft_id)?.draft_path()?;
+                let draft_path_str = draft_path.to_string_lossy().to_string();
+
+                // The confirmation prompt already warned about a duplicate
+                self.start_publishing("Draft published", false, async move {
+                    crate::publish::cmd_publish(&draft_path_str, None, true).await
+                });
+            }
+            ConfirmationAction::BackdateDraft(draft_id) => {
+                // Parse the date from date_input
+                match DateTime::parse_from_rfc3339(&self.date_input) {
+                    Ok(parsed_date) => {
+                        self.status_message = Some("Publishing with backdate...".to_string());
+                        let parsed_date_utc = parsed_date.with_timezone(&Utc);
+
+                        let draft_path = DraftId::parse(draft_id)?.draft_path()?;
+                        let draft_path_str = draft_path.to_string_lossy().to_string();
+
+                        self.start_publishing("Draft published with backdate", false, async move {
+                            crate::publish::cmd_publish(
+                                &draft_path_str,
+                                Some(parsed_date_utc),
+                                false,
+                            )
+                            .await
+                        });
+                    }
+                    Err(_) => {
+                        self.error_message = Some(
+                            "Invalid date format. Use ISO 8601 (e.g., 2024-01-15T10:30:00Z)"
+                                .to_string(),
+                        );
+                    }
+                }
+            }
+            ConfirmationAction::DeleteDraft(draft_id) => {
+                let draft_path = DraftId::parse(draft_id)?.draft_path()?;
+                match std::fs::remove_file(&draft_path) {
+                    Ok(_) => {
+                        self.status_message = Some("Draft deleted".to_string());
+                        self.load_drafts().await?;
+                        if self.selected_draft >= self.drafts.len() && self.selected_draft > 0 {
+                            self.selected_draft -= 1;
+                        }
+                        self.update_preview();
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to delete: {}", e));
+                    }
+                }
+            }
+            ConfirmationAction::RescheduleDraft(draft_id) => {
+                match DateTime::parse_from_rfc3339(&self.date_input) {
+                    Ok(at) => match crate::schedule::reschedule(draft_id, at.with_timezone(&Utc)) {
+                        Ok(()) => {
+                            self.status_message = Some(format!(
+                                "Rescheduled for {}",
+                                at.with_timezone(&Utc).format("%Y-%m-%d %H:%M UTC")
+                            ));
+                            self.load_scheduled()?;
+                            self.update_preview();
+                        }
+                        Err(e) => {
+                            self.error_message = Some(format!("Failed to reschedule: {}", e));
+                        }
+                    },
+                    Err(_) => {
+                        self.error_message = Some(
+                            "Invalid date format. Use ISO 8601 (e.g., 2024-01-15T10:30:00Z)"
+                                .to_string(),
+                        );
+                    }
+                }
+            }
+            ConfirmationAction::PublishScheduled(draft_id) => {
+                self.status_message = Some("Publishing...".to_string());
+                let draft_id = draft_id.clone();
+                self.start_publishing("Scheduled draft published", true, async move {
+                    crate::schedule::publish_now(&draft_id).await
+                });
+            }
+            ConfirmationAction::CancelScheduled(draft_id) => {
+                match crate::schedule::unschedule(draft_id) {
+                    Ok(_) => {
+                        self.status_message =
+                            Some("Schedule cancelled; moved to drafts".to_string());
+                        self.load_scheduled()?;
+                        self.load_drafts().await?;
+                        self.update_preview();
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to cancel: {}", e));
+                    }
+                }
+            }
+            ConfirmationAction::RestoreArchived(draft_id) => {
+                match crate::archive::restore(draft_id) {
+                    Ok(_) => {
+                        self.status_message = Some(format!("Restored {} to drafts", draft_id));
+                        self.load_drafts().await?;
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to restore: {}", e));
+                    }
+                }
+            }
+            ConfirmationAction::DeletePost(url) => {
+                match crate::operations::delete_post(url).await {
+                    Ok(()) => {
+                        self.status_message = Some(format!("Post deleted: {} ([u] to undo)", url));
+                        self.last_deleted_post = Some(url.clone());
+                        self.load_posts(PageMode::Load);
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to delete post: {}", e));
+                    }
+                }
+            }
+            ConfirmationAction::UndeletePost(url) => {
+                match crate::operations::undelete_post(url).await {
+                    Ok(()) => {
+                        self.status_message = Some(format!("Post undeleted: {}", url));
+                        self.last_deleted_post = None;
+                        self.load_posts(PageMode::Load);
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to undelete post: {}", e));
+                    }
+                }
+            }
+            ConfirmationAction::PromotePost(url) => {
+                match crate::operations::promote_post(url).await {
+                    Ok(()) => {
+                        self.status_message = Some(format!("Post published: {}", url));
+                        self.load_posts(PageMode::Load);
+                    }
+                    Err(e) => {
+                        self.error_message = Some(format!("Failed to publish post: {}", e));
+                    }
+                }
+            }
+            ConfirmationAction::None => {}
+        }
+
+        self.confirmation_action = ConfirmationAction::None;
+        self.date_input.clear();
+        Ok(())
+    }
+
+    pub fn cancel_action(&mut self) {
+        self.confirmation_action = ConfirmationAction::None;
+        self.date_input.clear();
+        self.status_message = Some("Action cancelled".to_string());
+    }
+
+    /// Scroll the preview down `lines`, stopping at its last line
+    pub fn scroll_preview_down(&mut self, lines: u16) {
+        let last = self
+            .preview_content
+            .as_ref()
+            .map_or(0, |preview| preview.lines().count().saturating_sub(1));
+        let last = u16::try_from(last).unwrap_or(u16::MAX);
+        self.preview_scroll = self.preview_scroll.saturating_add(lines).min(last);
+    }
+
+    pub fn scroll_preview_up(&mut self, lines: u16) {
+        self.preview_scroll = self.preview_scroll.saturating_sub(lines);
+    }
+
+    /// Open the built-in editor on the selected draft's title and content
+    pub async fn edit_inline(&mut self) {
+        if self.current_tab != Tab::Drafts {
+            self.error_message = Some("Edit not available for this view".to_string());
+            return;
+        }
+        let Some(item) = self.drafts.get(self.selected_draft) else {
+            return;
+        };
+        match Draft::load_async(&item.id).await {
+            Ok(draft) => {
+                self.content_editor = Some(ContentEditor::new(
+                    draft.id,
+                    draft.metadata.name.as_deref(),
+                    &draft.content,
+                ))
+            }
+            Err(e) => self.error_message = Some(format!("Failed to load draft: {}", e)),
+        }
+    }
+
+    pub fn discard_inline_edit(&mut self) {
+        if let Some(editor) = self.content_editor.take() {
+            if editor.is_modified() {
+                self.status_message = Some("Changes discarded".to_string());
+            }
+        }
+    }
+
+    /// Write the edited title and content to the draft, keeping its frontmatter
+    pub async fn save_inline_edit(&mut self) -> Result<()> {
+        let Some(editor) = self.content_editor.take() else {
+            return Ok(());
+        };
+        if !editor.is_modified() {
+            return Ok(());
+        }
+
+        let _lock = crate::draft::lock::lock(&editor.draft_id).await;
+        let mut draft = match Draft::load_async(&editor.draft_id).await {
+            Ok(draft) => draft,
+            Err(e) => {
+                self.error_message = Some(format!("Failed to load draft: {}", e));
+                self.content_editor = Some(editor);
+                return Ok(());
+            }
+        };
+        draft.metadata.name = editor.name();
+        draft.content = editor.content();
+        if let Err(e) = draft.save_async().await {
+            self.error_message = Some(format!("Failed to save draft: {}", e));
+            self.content_editor = Some(editor);
+            return Ok(());
+        }
+
+        self.reload_and_select_draft(&editor.draft_id).await?;
+        self.status_message = Some("Draft saved".to_string());
+        Ok(())
+    }
+
+    /// Open the category editor for the selected draft
+    pub async fn edit_tags(&mut self) {
+        if self.current_tab != Tab::Drafts {
+            return;
+        }
+
+        if let Some(item) = self.drafts.get(self.selected_draft) {
+            let mut known = known_categories_async().await.unwrap_or_default();
+            known.extend(self.server_categories.iter().cloned());
+            known.sort();
+            known.dedup();
+            self.tag_editor = Some(TagEditor::new(item.id.clone(), &item.categories, known));
+        }
+    }
+
+    pub fn cancel_tags(&mut self) {
+        self.tag_editor = None;
+    }
+
+    /// Write the edited categories to the draft's frontmatter
+    pub async fn save_tags(&mut self) -> Result<()> {
+        let Some(editor) = self.tag_editor.take() else {
+            return Ok(());
+        };
+
+        let _lock = crate::draft::lock::lock(&editor.draft_id).await;
+        let mut draft = match Draft::load_async(&editor.draft_id).await {
+            Ok(draft) => draft,
+            Err(e) => {
+                self.error_message = Some(format!("Failed to load draft: {}", e));
+                return Ok(());
+            }
+        };
+        draft.metadata.category = editor.categories();
+        if let Err(e) = draft.save_async().await {
+            self.error_message = Some(format!("Failed to save categories: {}", e));
+            return Ok(());
+        }
+
+        self.load_drafts().await?;
+        if let Some(index) = self.drafts.iter().position(|d| d.id == editor.draft_id) {
+            self.selected_draft = index;
+        }
+        self.update_preview();
+        self.status_message = Some(if draft.metadata.category.is_empty() {
+            "Categories cleared".to_string()
+        } else {
+            format!("Categories: {}", draft.metadata.category.join(", "))
+        });
+        Ok(())
+    }
+
+    pub fn toggle_log(&mut self) {
+        self.show_log = !self.show_log;
+        self.log.scroll = 0;
+    }
+
+    pub fn clear_error(&mut self) {
+        self.error_message = None;
+        self.status_message = None;
+        self.quit_requested = false;
+    }
+
+    pub fn awaiting_date_input(&self) -> bool {
+        matches!(
+            self.confirmation_action,
+            ConfirmationAction::BackdateDraft(_) | ConfirmationAction::RescheduleDraft(_)
+        )
+    }
+
+    pub fn open_palette(&mut self) {
+        let config = Config::load().unwrap_or_default();
+        let mut profiles: Vec<String> = config.profiles.keys().cloned().collect();
+        profiles.sort();
+        self.palette = Some(Palette::new(&profiles, &config.active_profile_name()));
+    }
+
+    pub fn close_palette(&mut self) {
+        self.palette = None;
+    }
+
+    /// Run the action selected in the palette and close it
+    pub async fn run_palette_action(&mut self) -> Result<()> {
+        let Some(action) = self.palette.take().and_then(|p| p.selected_action()) else {
+            return Ok(());
+        };
+
+        match action {
+            PaletteAction::Publish => {
+                self.publish_draft().await?;
+                if !self.awaiting_confirmation() {
+                    self.error_message = Some("Select a draft to publish".to_string());
+                }
+            }
+            PaletteAction::Backdate => {
+                self.backdate_draft().await?;
+                if !self.awaiting_confirmation() {
+                    self.error_message = Some("Select a draft to backdate".to_string());
+                }
+            }
+            PaletteAction::Push => self.push_draft().await?,
+            PaletteAction::SwitchProfile(name) => {
+                crate::config::set_profile_override(&name);
+                self.refresh_all().await?;
+                self.status_message = Some(format!("Switched to profile {}", name));
+            }
+            PaletteAction::OpenUrl => match self.selected_url() {
+                Some(url) => match open::that(&url) {
+                    Ok(()) => self.status_message = Some(format!("Opened {}", url)),
+                    Err(e) => self.error_message = Some(format!("Failed to open URL: {}", e)),
+                },
+                None => self.error_message = Some("No URL for this item".to_string()),
+            },
+            PaletteAction::RefreshAll => {
+                self.refresh_all().await?;
+                self.status_message = Some("Everything refreshed".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reload every tab, e.g. after switching profiles
+    async fn refresh_all(&mut self) -> Result<()> {
+        self.load_drafts().await?;
+        self.load_posts(PageMode::Load);
+        self.load_media(PageMode::Load);
+        self.load_categories();
+        self.load_scheduled()?;
+        self.load_archived()?;
+        self.selected_post = 0;
+        self.selected_media = 0;
+        self.update_preview();
+        Ok(())
+    }
+
+    /// Push the selected draft to the server as a server-side draft
+    async fn push_draft(&mut self) -> Result<()> {
+        let Some(draft_id) = (self.current_tab == Tab::Drafts)
+            .then(|| self.drafts.get(self.selected_draft))
+            .flatten()
+            .map(|d| d.id.clone())
+        else {
+            self.error_message = Some("Select a draft to push".to_string());
+            return Ok(());
+        };
+
+        self.status_message = Some("Pushing...".to_string());
+        match crate::draft_push::cmd_push_draft(&draft_id, None).await {
+            Ok(result) => {
+                self.status_message = Some(match result.url {
+                    Some(url) if !result.pending => format!("Draft pushed: {}", url),
+                    _ => "Server accepted the draft; its URL isn't available yet".to_string(),
+                });
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to push: {}", e));
+            }
+        }
+        self.load_drafts().await?;
+        self.update_preview();
+        Ok(())
+    }
+
+    /// URL of the selected post, media item, or pushed draft
+    fn selected_url(&self) -> Option<String> {
+        match self.current_tab {
+            Tab::Posts => self.posts.get(self.selected_post).map(|p| p.url.clone()),
+            Tab::Media => self.media.get(self.selected_media).map(|m| m.url.clone()),
+            Tab::Drafts => self
+                .drafts
+                .get(self.selected_draft)
+                .and_then(|d| Draft::load(&d.id).ok())
+                .and_then(|d| d.metadata.url),
+            Tab::Scheduled => self
+                .scheduled
+                .get(self.selected_scheduled)
+                .and_then(|d| crate::schedule::load(&d.id).ok())
+                .and_then(|d| d.metadata.url),
+            Tab::Archive => self
+                .archived
+                .get(self.selected_archived)
+                .and_then(|d| d.url.clone()),
+        }
+    }
+
+    pub fn add_date_char(&mut self, c: char) {
+        self.date_input.push(c);
+    }
+
+    pub fn delete_date_char(&mut self) {
+        self.date_input.pop();
+    }
+}
+
+/// Whether an item with `categories` passes a category chip filter
+fn in_category(categories: &[String], filter: Option<&str>) -> bool {
+    filter.is_none_or(|filter| categories.iter().any(|c| c == filter))
+}
@@ -0,0 +1,68 @@
+// ABOUTME: Category chips above the Drafts and Posts lists for browsing by topic
+// ABOUTME: Collects the categories in use and steps the selected chip with [ and ]
+
+use std::collections::HashMap;
+
+/// Every category used by `items`, most used first, then alphabetically
+pub fn collect<'a>(items: impl IntoIterator<Item = &'a [String]>) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for categories in items {
+        for category in categories {
+            *counts.entry(category.as_str()).or_default() += 1;
+        }
+    }
+    let mut chips: Vec<(&str, usize)> = counts.into_iter().collect();
+    chips.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    chips.into_iter().map(|(c, _)| c.to_string()).collect()
+}
+
+/// The chip after (or before) `current`, where `None` is the "all" chip in
+/// front of the others; wraps around at either end
+pub fn step(chips: &[String], current: Option<&str>, forward: bool) -> Option<String> {
+    // Position 0 is "all", chip i is at i + 1
+    let positions = chips.len() + 1;
+    let at = current
+        .and_then(|c| chips.iter().position(|chip| chip == c))
+        .map_or(0, |i| i + 1);
+    let next = if forward {
+        (at + 1) % positions
+    } else {
+        (at + positions - 1) % positions
+    };
+    next.checked_sub(1).map(|i| chips[i].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_collect_orders_by_use() {
+        let items = [
+            strings(&["travel", "photo"]),
+            strings(&["rust"]),
+            strings(&["photo"]),
+            strings(&[]),
+        ];
+        assert_eq!(
+            collect(items.iter().map(Vec::as_slice)),
+            ["photo", "rust", "travel"]
+        );
+    }
+
+    #[test]
+    fn test_step_cycles_through_all() {
+        let chips = strings(&["a", "b"]);
+        assert_eq!(step(&chips, None, true).as_deref(), Some("a"));
+        assert_eq!(step(&chips, Some("a"), true).as_deref(), Some("b"));
+        assert_eq!(step(&chips, Some("b"), true), None);
+        assert_eq!(step(&chips, None, false).as_deref(), Some("b"));
+        // A filter whose category is gone starts over from "all"
+        assert_eq!(step(&chips, Some("gone"), true).as_deref(), Some("a"));
+        assert_eq!(step(&[], None, true), None);
+    }
+}
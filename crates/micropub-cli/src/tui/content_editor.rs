@@ -0,0 +1,234 @@
+// ABOUTME: Built-in editor for a draft's title and content, for quick edits without $EDITOR
+// ABOUTME: A title line plus a multi-line buffer with a cursor; Ctrl-S saves and Esc discards
+
+/// Which part of the draft the cursor is in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Title,
+    Content,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContentEditor {
+    pub draft_id: String,
+    pub title: String,
+    lines: Vec<String>,
+    pub focus: Field,
+    /// Cursor line in the content
+    pub row: usize,
+    /// Cursor position in the title or current line, in chars
+    pub col: usize,
+    original: (String, String),
+}
+
+/// Byte offset of char `col` in `s`, or its end
+fn byte_index(s: &str, col: usize) -> usize {
+    s.char_indices().nth(col).map_or(s.len(), |(i, _)| i)
+}
+
+impl ContentEditor {
+    pub fn new(draft_id: String, title: Option<&str>, content: &str) -> Self {
+        let title = title.unwrap_or_default().to_string();
+        let lines: Vec<String> = content.split('\n').map(str::to_string).collect();
+        Self {
+            draft_id,
+            original: (title.clone(), content.to_string()),
+            col: title.chars().count(),
+            title,
+            lines,
+            focus: Field::Title,
+            row: 0,
+        }
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn content(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// The title to save; an empty one removes it
+    pub fn name(&self) -> Option<String> {
+        let title = self.title.trim();
+        (!title.is_empty()).then(|| title.to_string())
+    }
+
+    pub fn is_modified(&self) -> bool {
+        (self.title.as_str(), self.content().as_str())
+            != (self.original.0.as_str(), self.original.1.as_str())
+    }
+
+    /// The text the cursor is in
+    fn current(&mut self) -> &mut String {
+        match self.focus {
+            Field::Title => &mut self.title,
+            Field::Content => &mut self.lines[self.row],
+        }
+    }
+
+    fn current_len(&mut self) -> usize {
+        self.current().chars().count()
+    }
+
+    /// Move between the title and the content
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            Field::Title => Field::Content,
+            Field::Content => Field::Title,
+        };
+        self.col = self.col.min(self.current_len());
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        let col = self.col;
+        let line = self.current();
+        let at = byte_index(line, col);
+        line.insert(at, c);
+        self.col += 1;
+    }
+
+    /// Split the line at the cursor; from the title, go to the start of the content
+    pub fn newline(&mut self) {
+        if self.focus == Field::Title {
+            self.focus = Field::Content;
+            self.row = 0;
+            self.col = 0;
+            return;
+        }
+        let line = &mut self.lines[self.row];
+        let rest = line.split_off(byte_index(line, self.col));
+        self.lines.insert(self.row + 1, rest);
+        self.row += 1;
+        self.col = 0;
+    }
+
+    /// Delete the char before the cursor, joining lines at the start of one
+    pub fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let col = self.col;
+            let line = self.current();
+            let at = byte_index(line, col);
+            line.remove(at);
+        } else if self.focus == Field::Content && self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.lines[self.row].chars().count();
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    /// Delete the char under the cursor, joining the next line at the end of one
+    pub fn delete(&mut self) {
+        let col = self.col;
+        if col < self.current_len() {
+            let line = self.current();
+            let at = byte_index(line, col);
+            line.remove(at);
+        } else if self.focus == Field::Content && self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        }
+    }
+
+    pub fn left(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+        } else if self.focus == Field::Content && self.row > 0 {
+            self.row -= 1;
+            self.col = self.lines[self.row].chars().count();
+        }
+    }
+
+    pub fn right(&mut self) {
+        if self.col < self.current_len() {
+            self.col += 1;
+        } else if self.focus == Field::Content && self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    /// Up a line, or from the first line into the title
+    pub fn up(&mut self) {
+        if self.focus == Field::Title {
+            return;
+        }
+        if self.row == 0 {
+            self.focus = Field::Title;
+        } else {
+            self.row -= 1;
+        }
+        self.col = self.col.min(self.current_len());
+    }
+
+    /// Down a line, or from the title into the content
+    pub fn down(&mut self) {
+        match self.focus {
+            Field::Title => self.focus = Field::Content,
+            Field::Content if self.row + 1 < self.lines.len() => self.row += 1,
+            Field::Content => return,
+        }
+        self.col = self.col.min(self.current_len());
+    }
+
+    pub fn home(&mut self) {
+        self.col = 0;
+    }
+
+    pub fn end(&mut self) {
+        self.col = self.current_len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editing_content_across_lines() {
+        let mut editor = ContentEditor::new("d".to_string(), None, "héllo\nworld");
+        assert!(!editor.is_modified());
+
+        editor.down();
+        editor.right();
+        editor.right();
+        editor.newline();
+        assert_eq!(editor.content(), "hé\nllo\nworld");
+
+        editor.backspace();
+        assert_eq!(editor.content(), "héllo\nworld");
+        editor.end();
+        editor.delete();
+        assert_eq!(editor.content(), "hélloworld");
+        editor.insert_char(' ');
+        assert_eq!(editor.content(), "héllo world");
+        assert!(editor.is_modified());
+    }
+
+    #[test]
+    fn test_title_edits_and_navigation() {
+        let mut editor = ContentEditor::new("d".to_string(), Some("Post"), "body");
+        assert_eq!(editor.focus, Field::Title);
+        editor.insert_char('s');
+        assert_eq!(editor.name().as_deref(), Some("Posts"));
+
+        // Enter in the title moves to the content instead of adding a line
+        editor.newline();
+        assert_eq!(
+            (editor.focus, editor.row, editor.col),
+            (Field::Content, 0, 0)
+        );
+        assert_eq!(editor.content(), "body");
+
+        editor.up();
+        assert_eq!(editor.focus, Field::Title);
+        editor.home();
+        for _ in 0..5 {
+            editor.delete();
+        }
+        assert_eq!(editor.name(), None);
+    }
+}
@@ -0,0 +1,63 @@
+// ABOUTME: Background page fetches for the TUI's Posts and Media tabs
+// ABOUTME: Keeps requests off the event loop so tab switches and quit can cancel them
+
+use anyhow::{anyhow, Result};
+use std::future::Future;
+use tokio::task::JoinHandle;
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+/// What a fetched page does to the list it belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageMode {
+    /// Replace the list, e.g. on launch or after switching profiles
+    Load,
+    /// Replace the list and say so in the status bar (`r`)
+    Refresh,
+    /// Add the next page to the end of the list
+    Append,
+}
+
+/// A page of items being fetched in the background; dropping it cancels the request
+pub struct Fetch<T> {
+    handle: JoinHandle<Result<Vec<T>>>,
+    _cancel: DropGuard,
+    pub mode: PageMode,
+}
+
+impl<T: Send + 'static> Fetch<T> {
+    /// Run `request` on its own task until it finishes or `parent` is cancelled
+    pub fn spawn<F>(parent: &CancellationToken, mode: PageMode, request: F) -> Self
+    where
+        F: Future<Output = Result<Vec<T>>> + Send + 'static,
+    {
+        let cancel = parent.child_token();
+        let token = cancel.clone();
+        let handle = tokio::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => Err(anyhow!("Request cancelled")),
+                result = request => result,
+            }
+        });
+        Fetch {
+            handle,
+            _cancel: cancel.drop_guard(),
+            mode,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// The fetched page; waits if the request is still running
+    pub async fn result(self) -> Result<Vec<T>> {
+        let Fetch {
+            handle,
+            _cancel: guard,
+            ..
+        } = self;
+        let page = handle.await?;
+        drop(guard);
+        page
+    }
+}
@@ -0,0 +1,117 @@
+// ABOUTME: Session log of errors and status messages for the TUI
+// ABOUTME: Keeps messages around after the status bar moves on, viewable with `L`
+
+use chrono::{DateTime, Local};
+
+/// Oldest entries are dropped past this many
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogLevel {
+    Info,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub at: DateTime<Local>,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+pub struct EventLog {
+    entries: Vec<LogEntry>,
+    last_error: Option<String>,
+    last_status: Option<String>,
+    /// Lines scrolled down from the newest entry
+    pub scroll: u16,
+}
+
+impl EventLog {
+    pub fn push(&mut self, level: LogLevel, message: impl Into<String>) {
+        self.entries.push(LogEntry {
+            at: Local::now(),
+            level,
+            message: message.into(),
+        });
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// Record the status bar's messages if they changed since the last call
+    pub fn sync(&mut self, error: Option<&String>, status: Option<&String>) {
+        if error != self.last_error.as_ref() {
+            self.last_error = error.cloned();
+            if let Some(message) = error {
+                self.push(LogLevel::Error, message.clone());
+            }
+        }
+        if status != self.last_status.as_ref() {
+            self.last_status = status.cloned();
+            if let Some(message) = status {
+                self.push(LogLevel::Info, message.clone());
+            }
+        }
+    }
+
+    /// Entries, newest first
+    pub fn newest_first(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter().rev()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn scroll_down(&mut self) {
+        if (self.scroll as usize) + 1 < self.entries.len() {
+            self.scroll += 1;
+        }
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_records_each_new_message_once() {
+        let mut log = EventLog::default();
+        let err = "Failed to load posts".to_string();
+        let status = "Refreshed".to_string();
+
+        log.sync(Some(&err), None);
+        log.sync(Some(&err), None);
+        log.sync(None, Some(&status));
+        log.sync(Some(&err), Some(&status));
+
+        let levels: Vec<_> = log.newest_first().map(|e| e.level).collect();
+        assert_eq!(
+            levels,
+            vec![LogLevel::Error, LogLevel::Info, LogLevel::Error]
+        );
+    }
+
+    #[test]
+    fn test_scroll_is_bounded() {
+        let mut log = EventLog::default();
+        log.scroll_up();
+        assert_eq!(log.scroll, 0);
+
+        log.push(LogLevel::Info, "one");
+        log.push(LogLevel::Info, "two");
+        log.scroll_down();
+        log.scroll_down();
+        assert_eq!(log.scroll, 1);
+    }
+}
@@ -0,0 +1,193 @@
+// ABOUTME: Basic markdown styling for the TUI preview pane
+// ABOUTME: Bold headings, indented list bullets, colored links, and dimmed frontmatter and code
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Style a preview as markdown. A `---` block before any other text is draft
+/// frontmatter and is dimmed; lines starting with ⚠ (lint warnings) don't count as text.
+pub fn render(text: &str) -> Vec<Line<'static>> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines = Vec::new();
+    let mut seen_text = false;
+    let mut in_frontmatter = false;
+    let mut in_code = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if in_frontmatter {
+            in_frontmatter = line.trim_end() != "---";
+            lines.push(Line::styled(line.to_string(), dim));
+            continue;
+        }
+        if line.trim_end() == "---" && !seen_text {
+            in_frontmatter = true;
+            seen_text = true;
+            lines.push(Line::styled(line.to_string(), dim));
+            continue;
+        }
+        if !trimmed.is_empty() && !trimmed.starts_with('⚠') {
+            seen_text = true;
+        }
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            lines.push(Line::styled(line.to_string(), dim));
+        } else if in_code {
+            lines.push(Line::styled(
+                line.to_string(),
+                Style::default().fg(Color::Yellow),
+            ));
+        } else if let Some(heading) = heading(line) {
+            lines.push(Line::styled(
+                heading.to_string(),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        } else if let Some((indent, marker, rest)) = list_item(line) {
+            let mut spans = vec![Span::raw(format!("{}  {} ", indent, marker))];
+            spans.extend(inline(rest));
+            lines.push(Line::from(spans));
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            lines.push(Line::styled(
+                format!("│ {}", quote.trim_start()),
+                Style::default()
+                    .fg(Color::Gray)
+                    .add_modifier(Modifier::ITALIC),
+            ));
+        } else {
+            lines.push(Line::from(inline(line)));
+        }
+    }
+    lines
+}
+
+/// Text of an ATX heading (`# Title`), without its hashes
+fn heading(line: &str) -> Option<&str> {
+    let hashes = line.len() - line.trim_start_matches('#').len();
+    if !(1..=6).contains(&hashes) {
+        return None;
+    }
+    let rest = &line[hashes..];
+    if rest.is_empty() {
+        return Some("");
+    }
+    rest.strip_prefix(' ')
+        .map(|text| text.trim_end_matches('#').trim())
+}
+
+/// Indentation, bullet to show, and text of a `-`, `*`, `+`, or `1.` list item
+fn list_item(line: &str) -> Option<(&str, String, &str)> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(rest) = trimmed.strip_prefix(bullet) {
+            return Some((indent, "•".to_string(), rest));
+        }
+    }
+    let digits = trimmed.len()
+        - trimmed
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    if digits > 0 {
+        if let Some(rest) = trimmed[digits..].strip_prefix(". ") {
+            return Some((indent, format!("{}.", &trimmed[..digits]), rest));
+        }
+    }
+    None
+}
+
+/// Spans for one line of text, with `[text](url)` links colored
+fn inline(text: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        let Some(link) = parse_link(&rest[open..]) else {
+            spans.push(Span::raw(rest[..=open].to_string()));
+            rest = &rest[open + 1..];
+            continue;
+        };
+        if open > 0 {
+            spans.push(Span::raw(rest[..open].to_string()));
+        }
+        spans.push(Span::styled(
+            link.text.to_string(),
+            Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::UNDERLINED),
+        ));
+        spans.push(Span::styled(
+            format!(" ({})", link.url),
+            Style::default().fg(Color::DarkGray),
+        ));
+        rest = &rest[open + link.len..];
+    }
+    if !rest.is_empty() {
+        spans.push(Span::raw(rest.to_string()));
+    }
+    spans
+}
+
+struct Link<'a> {
+    text: &'a str,
+    url: &'a str,
+    /// Bytes the whole `[text](url)` takes up
+    len: usize,
+}
+
+/// A `[text](url)` link at the start of `s`
+fn parse_link(s: &str) -> Option<Link<'_>> {
+    let close = s.find("](")?;
+    let text = &s[1..close];
+    if text.contains('[') || text.contains(']') {
+        return None;
+    }
+    let after = &s[close + 2..];
+    let end = after.find(')')?;
+    Some(Link {
+        text,
+        url: &after[..end],
+        len: close + 2 + end + 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_headings_lists_and_links() {
+        let lines = render("# Title #\n- one\n  2. two\nSee [docs](https://e.com) now\n#tag");
+        let rendered: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(
+            rendered,
+            [
+                "Title",
+                "  • one",
+                "    2. two",
+                "See docs (https://e.com) now",
+                "#tag"
+            ]
+        );
+        assert!(lines[0].style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(lines[3].spans[1].style.fg, Some(Color::Blue));
+    }
+
+    #[test]
+    fn test_frontmatter_and_code_are_not_styled_as_markdown() {
+        let lines = render("⚠ warning\n---\ntype: note\n---\n\n```\n# not a heading\n```\n---");
+        let rendered: Vec<String> = lines.iter().map(text).collect();
+        assert_eq!(rendered[6], "# not a heading");
+        assert_eq!(lines[2].style.fg, Some(Color::DarkGray));
+        assert_eq!(lines[6].style.fg, Some(Color::Yellow));
+        // A rule after the content isn't frontmatter
+        assert_eq!(lines[8].style.fg, None);
+    }
+}
@@ -0,0 +1,407 @@
+// ABOUTME: Terminal User Interface (TUI) module for micropub
+// ABOUTME: Provides interactive interface for managing drafts, posts, and media
+
+mod app;
+mod chips;
+mod content_editor;
+mod fetch;
+mod log;
+mod markdown;
+mod palette;
+mod session;
+mod tags;
+mod thumbnail;
+mod ui;
+
+use anyhow::Result;
+use crossterm::{
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    layout::Rect,
+    Terminal,
+};
+use std::io;
+use std::time::Duration;
+
+pub use app::App;
+use app::{MediaPreview, Tab};
+use thumbnail::Placement;
+
+/// Restore the terminal before printing a panic, so a crash doesn't leave
+/// the shell in raw mode on the alternate screen
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(
+            io::stdout(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            crossterm::cursor::Show
+        );
+        default_hook(info);
+    }));
+}
+
+/// Run the TUI application
+pub async fn run() -> Result<()> {
+    crate::ci::ensure_interactive("The TUI")?;
+    install_panic_hook();
+
+    // Setup terminal
+    enable_raw_mode()?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    // Create app state
+    let mut app = App::new().await?;
+    app.watch_uploads();
+
+    // Run the app
+    let res = run_app(&mut terminal, &mut app).await;
+
+    // Restore terminal
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+
+    if let Err(e) = app.session().save() {
+        eprintln!("Warning: {:#}", e);
+    }
+
+    res
+}
+
+/// How often the screen updates while posts or media load in the background
+const FRAME: Duration = Duration::from_millis(50);
+
+/// Preview lines scrolled by PgUp/PgDn, and by Ctrl-u/Ctrl-d
+const PREVIEW_PAGE: u16 = 20;
+const PREVIEW_HALF_PAGE: u16 = PREVIEW_PAGE / 2;
+
+/// Main event loop
+async fn run_app<B: Backend + io::Write>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+    let mut images = Placement::new(app.image_protocol);
+    loop {
+        app.poll_fetches().await;
+        app.log
+            .sync(app.error_message.as_ref(), app.status_message.as_ref());
+        let mut image_area = None;
+        terminal.draw(|f| image_area = ui::draw(f, app))?;
+        show_image(terminal, app, &mut images, image_area)?;
+
+        // Keep drawing while a fetch or publish runs, so its page or upload
+        // progress shows without a key press
+        let fetching = app.is_loading(&Tab::Posts)
+            || app.is_loading(&Tab::Media)
+            || app.is_loading_preview()
+            || app.is_publishing();
+        if fetching && !event::poll(FRAME)? {
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                // Handle log pane scrolling
+                if app.show_log {
+                    match key.code {
+                        KeyCode::Char('L') | KeyCode::Esc | KeyCode::Char('q') => app.toggle_log(),
+                        KeyCode::Char('j') | KeyCode::Down => app.log.scroll_down(),
+                        KeyCode::Char('k') | KeyCode::Up => app.log.scroll_up(),
+                        _ => {}
+                    }
+                } else if app.searching {
+                    match key.code {
+                        KeyCode::Enter => app.finish_search(),
+                        KeyCode::Esc => app.clear_search(),
+                        KeyCode::Down => app.next_item(),
+                        KeyCode::Up => app.previous_item(),
+                        KeyCode::Backspace => app.delete_search_char(),
+                        KeyCode::Char(c) => app.add_search_char(c),
+                        _ => {}
+                    }
+                } else if let Some(editor) = app.content_editor.as_mut() {
+                    match key.code {
+                        KeyCode::Char('s') if ctrl => app.save_inline_edit().await?,
+                        KeyCode::Esc => app.discard_inline_edit(),
+                        KeyCode::Char(_) if ctrl => {}
+                        KeyCode::Char(c) => editor.insert_char(c),
+                        KeyCode::Enter => editor.newline(),
+                        KeyCode::Tab | KeyCode::BackTab => editor.toggle_focus(),
+                        KeyCode::Backspace => editor.backspace(),
+                        KeyCode::Delete => editor.delete(),
+                        KeyCode::Left => editor.left(),
+                        KeyCode::Right => editor.right(),
+                        KeyCode::Up => editor.up(),
+                        KeyCode::Down => editor.down(),
+                        KeyCode::Home => editor.home(),
+                        KeyCode::End => editor.end(),
+                        _ => {}
+                    }
+                } else if let Some(editor) = app.tag_editor.as_mut() {
+                    match key.code {
+                        KeyCode::Enter => app.save_tags().await?,
+                        KeyCode::Esc => app.cancel_tags(),
+                        KeyCode::Tab => editor.complete(),
+                        KeyCode::Backspace => editor.pop_char(),
+                        KeyCode::Char(c) => editor.push_char(c),
+                        _ => {}
+                    }
+                } else if let Some(palette) = app.palette.as_mut() {
+                    match key.code {
+                        KeyCode::Enter => app.run_palette_action().await?,
+                        KeyCode::Esc => app.close_palette(),
+                        KeyCode::Down => palette.next(),
+                        KeyCode::Up => palette.previous(),
+                        KeyCode::Backspace => palette.pop_char(),
+                        KeyCode::Char(c) => palette.push_char(c),
+                        _ => {}
+                    }
+                } else if app.awaiting_date_input() {
+                    match key.code {
+                        KeyCode::Enter => {
+                            app.confirm_action().await?;
+                        }
+                        KeyCode::Esc => {
+                            app.cancel_action();
+                        }
+                        KeyCode::Backspace => {
+                            app.delete_date_char();
+                        }
+                        KeyCode::Char(c) => {
+                            app.add_date_char(c);
+                        }
+                        _ => {}
+                    }
+                } else {
+                    // Normal key handling
+                    match key.code {
+                        KeyCode::Char('q') if app.confirm_quit() => {
+                            return Ok(());
+                        }
+                        KeyCode::Char('j') | KeyCode::Down => app.next_item(),
+                        KeyCode::Char('k') | KeyCode::Up => app.previous_item(),
+                        KeyCode::PageDown => app.scroll_preview_down(PREVIEW_PAGE),
+                        KeyCode::PageUp => app.scroll_preview_up(PREVIEW_PAGE),
+                        KeyCode::Char('d') if ctrl => app.scroll_preview_down(PREVIEW_HALF_PAGE),
+                        KeyCode::Char('u') if ctrl => app.scroll_preview_up(PREVIEW_HALF_PAGE),
+                        KeyCode::Char(']') => app.step_category(true),
+                        KeyCode::Char('[') => app.step_category(false),
+                        KeyCode::Tab => app.next_tab(),
+                        KeyCode::BackTab => app.previous_tab(),
+                        KeyCode::Enter => app.select_item().await?,
+                        KeyCode::Char('y') if app.awaiting_confirmation() => {
+                            app.confirm_action().await?;
+                        }
+                        KeyCode::Char('n') if app.awaiting_confirmation() => {
+                            app.cancel_action();
+                        }
+                        KeyCode::Char('p') => app.publish_draft().await?,
+                        KeyCode::Char('e') => {
+                            // Suspend TUI to edit draft
+                            match app.edit_item() {
+                                Ok(Some(draft_id)) => {
+                                    if let Err(e) =
+                                        suspend_and_edit_draft(terminal, &draft_id).await
+                                    {
+                                        app.error_message =
+                                            Some(format!("Failed to edit draft: {}", e));
+                                    } else {
+                                        // Reload drafts and select the edited one
+                                        if let Err(e) = app.reload_and_select_draft(&draft_id).await
+                                        {
+                                            app.error_message =
+                                                Some(format!("Failed to reload drafts: {}", e));
+                                        }
+                                    }
+                                }
+                                Ok(None) => {
+                                    // No draft to edit, error already set in edit_item
+                                }
+                                Err(e) => {
+                                    app.error_message =
+                                        Some(format!("Failed to get draft for editing: {}", e));
+                                }
+                            }
+                        }
+                        KeyCode::Char('i') => app.edit_inline().await,
+                        KeyCode::Char('d') => app.delete_item().await?,
+                        KeyCode::Char('u') if app.current_tab == Tab::Archive => {
+                            app.restore_archived()
+                        }
+                        KeyCode::Char('u') => app.undelete_post(),
+                        KeyCode::Char('P') => app.promote_post(),
+                        KeyCode::Char('t') if app.current_tab == Tab::Scheduled => {
+                            app.reschedule_item()
+                        }
+                        KeyCode::Char('t') => app.edit_tags().await,
+                        KeyCode::Char('c') if app.current_tab == Tab::Scheduled => {
+                            app.cancel_scheduled()
+                        }
+                        KeyCode::Char('c') => app.copy_url(),
+                        KeyCode::Char('b') => app.backdate_draft().await?,
+                        KeyCode::Char('n') => {
+                            // Suspend TUI to create new draft
+                            match app.new_draft() {
+                                Ok(draft_id) => {
+                                    if let Err(e) =
+                                        suspend_and_create_draft(terminal, &draft_id).await
+                                    {
+                                        app.error_message =
+                                            Some(format!("Failed to create draft: {}", e));
+                                    } else {
+                                        // Reload drafts and select the new one
+                                        if let Err(e) = app.reload_and_select_draft(&draft_id).await
+                                        {
+                                            app.error_message =
+                                                Some(format!("Failed to reload drafts: {}", e));
+                                        }
+                                    }
+                                }
+                                Err(e) => {
+                                    app.error_message =
+                                        Some(format!("Failed to generate draft ID: {}", e));
+                                }
+                            }
+                        }
+                        KeyCode::Char('r') => app.refresh().await?,
+                        KeyCode::Char(':') => app.open_palette(),
+                        KeyCode::Char('/') => app.start_search(),
+                        KeyCode::Char('L') => app.toggle_log(),
+                        KeyCode::Esc => {
+                            app.clear_error();
+                            app.clear_search();
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Put the selected media item's image over `area` with the terminal's graphics
+/// protocol, or take the shown one down when there's no area for it
+fn show_image<B: Backend + io::Write>(
+    terminal: &mut Terminal<B>,
+    app: &App,
+    images: &mut Placement,
+    area: Option<Rect>,
+) -> Result<()> {
+    let wanted = area
+        .zip(app.media_preview())
+        .and_then(|(area, (url, preview))| match preview {
+            MediaPreview::Ready(thumbnail) => Some((url, thumbnail, area)),
+            _ => None,
+        });
+    if !images.changes(wanted.map(|(url, _, area)| (url, area))) {
+        return Ok(());
+    }
+    if images.needs_repaint() {
+        terminal.clear()?;
+        terminal.draw(|f| {
+            ui::draw(f, app);
+        })?;
+    }
+    images.show(terminal.backend_mut(), wanted)
+}
+
+/// Suspend the TUI, edit an existing draft, then resume TUI
+async fn suspend_and_edit_draft<B: Backend + io::Write>(
+    terminal: &mut Terminal<B>,
+    draft_id: &str,
+) -> Result<()> {
+    use crate::draft::DraftId;
+    use crate::editor::open_in_editor;
+
+    let path = DraftId::parse(draft_id)?.existing_draft_path()?;
+    crate::draft::history::snapshot(draft_id, &path)?;
+
+    // Suspend TUI
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+
+    // Open in editor
+    let result = open_in_editor(&path);
+    if result.is_err() {
+        // Resume TUI even on error
+        enable_raw_mode()?;
+        execute!(
+            terminal.backend_mut(),
+            EnterAlternateScreen,
+            EnableMouseCapture
+        )?;
+        return result;
+    }
+
+    // Resume TUI
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+
+    Ok(())
+}
+
+/// Suspend the TUI, create a draft and open editor, then resume TUI
+async fn suspend_and_create_draft<B: Backend + io::Write>(
+    terminal: &mut Terminal<B>,
+    draft_id: &str,
+) -> Result<()> {
+    use crate::draft::Draft;
+    use crate::editor::open_in_editor;
+
+    // Suspend TUI
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+
+    // Create and save initial draft
+    let draft = Draft::new(draft_id.to_string());
+    let path = draft.save()?;
+
+    // Open in editor
+    let result = open_in_editor(&path);
+    if result.is_err() {
+        // Resume TUI even on error
+        enable_raw_mode()?;
+        execute!(
+            terminal.backend_mut(),
+            EnterAlternateScreen,
+            EnableMouseCapture
+        )?;
+        return result;
+    }
+
+    // Resume TUI
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+
+    Ok(())
+}
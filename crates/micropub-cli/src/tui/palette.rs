@@ -0,0 +1,171 @@
+// ABOUTME: Command palette for the TUI, opened with ':'
+// ABOUTME: Fuzzy-filters a list of less common actions so they don't each need a key
+
+/// An action that can be run from the palette
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteAction {
+    Publish,
+    Push,
+    Backdate,
+    SwitchProfile(String),
+    OpenUrl,
+    RefreshAll,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteEntry {
+    pub label: String,
+    pub action: PaletteAction,
+}
+
+impl PaletteEntry {
+    fn new(label: impl Into<String>, action: PaletteAction) -> Self {
+        Self {
+            label: label.into(),
+            action,
+        }
+    }
+}
+
+/// Palette state: every available entry plus the current query and selection
+pub struct Palette {
+    entries: Vec<PaletteEntry>,
+    pub query: String,
+    pub selected: usize,
+}
+
+impl Palette {
+    /// Build the palette, offering a switch to each profile other than the active one
+    pub fn new(profiles: &[String], active_profile: &str) -> Self {
+        let mut entries = vec![
+            PaletteEntry::new("Publish draft", PaletteAction::Publish),
+            PaletteEntry::new("Push draft to server", PaletteAction::Push),
+            PaletteEntry::new("Backdate draft", PaletteAction::Backdate),
+            PaletteEntry::new("Open URL in browser", PaletteAction::OpenUrl),
+            PaletteEntry::new("Refresh all", PaletteAction::RefreshAll),
+        ];
+        for profile in profiles.iter().filter(|p| p.as_str() != active_profile) {
+            entries.push(PaletteEntry::new(
+                format!("Switch profile: {}", profile),
+                PaletteAction::SwitchProfile(profile.clone()),
+            ));
+        }
+
+        Self {
+            entries,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Entries matching the query, best match first
+    pub fn matches(&self) -> Vec<&PaletteEntry> {
+        let mut scored: Vec<(i64, usize, &PaletteEntry)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| fuzzy_score(&self.query, &entry.label).map(|s| (s, i, entry)))
+            .collect();
+        // Stable on the original order for equal scores
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        scored.into_iter().map(|(_, _, entry)| entry).collect()
+    }
+
+    /// The action under the cursor, if anything matches
+    pub fn selected_action(&self) -> Option<PaletteAction> {
+        self.matches()
+            .get(self.selected)
+            .map(|entry| entry.action.clone())
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.query.push(c);
+        self.selected = 0;
+    }
+
+    pub fn pop_char(&mut self) {
+        self.query.pop();
+        self.selected = 0;
+    }
+
+    pub fn next(&mut self) {
+        let count = self.matches().len();
+        if count > 0 {
+            self.selected = (self.selected + 1) % count;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let count = self.matches().len();
+        if count > 0 {
+            self.selected = if self.selected == 0 {
+                count - 1
+            } else {
+                self.selected - 1
+            };
+        }
+    }
+}
+
+/// Score `text` against `query` as a case-insensitive subsequence match.
+/// Consecutive and word-start matches score higher; `None` means no match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last_match: Option<usize> = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = (pos..text.len()).find(|&i| text[i] == q)?;
+
+        score += 1;
+        if last_match.is_some_and(|last| last + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        last_match = Some(found);
+        pos = found + 1;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("pub", "Publish draft").is_some());
+        assert!(fuzzy_score("pd", "Publish draft").is_some());
+        assert!(fuzzy_score("xyz", "Publish draft").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        // Consecutive matches beat scattered ones
+        assert!(fuzzy_score("pu", "Push draft") > fuzzy_score("pu", "Open URL"));
+    }
+
+    #[test]
+    fn test_palette_filters_and_skips_active_profile() {
+        let profiles = vec!["a.example".to_string(), "b.example".to_string()];
+        let mut palette = Palette::new(&profiles, "a.example");
+
+        assert!(!palette
+            .matches()
+            .iter()
+            .any(|e| e.action == PaletteAction::SwitchProfile("a.example".to_string())));
+
+        for c in "switch".chars() {
+            palette.push_char(c);
+        }
+        assert_eq!(
+            palette.selected_action(),
+            Some(PaletteAction::SwitchProfile("b.example".to_string()))
+        );
+
+        palette.query = "refresh".to_string();
+        assert_eq!(palette.selected_action(), Some(PaletteAction::RefreshAll));
+    }
+}
@@ -0,0 +1,73 @@
+// ABOUTME: Remembers TUI state (tab, selections, search) between launches
+// ABOUTME: Stored as tui_session.json in the data directory
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use super::app::Tab;
+use crate::config::get_data_dir;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub tab: Tab,
+    /// Draft ID rather than index, so new or deleted drafts don't shift it
+    pub selected_draft: Option<String>,
+    /// Draft search filter (`/`)
+    pub draft_search: String,
+    /// Category chip filtering the drafts list
+    pub draft_category: Option<String>,
+    pub selected_post: usize,
+    pub selected_media: usize,
+    pub selected_scheduled: usize,
+    pub selected_archived: usize,
+}
+
+fn session_path() -> Result<PathBuf> {
+    Ok(get_data_dir()?.join("tui_session.json"))
+}
+
+impl Session {
+    /// Load the last session, falling back to defaults if it's missing or unreadable
+    pub fn load() -> Self {
+        session_path()
+            .and_then(|path| Ok(fs::read_to_string(path)?))
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(session_path()?, contents).context("Failed to save TUI session")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_round_trip_and_defaults() {
+        let session = Session {
+            tab: Tab::Scheduled,
+            selected_draft: Some("20240101-abc".to_string()),
+            draft_search: "rust".to_string(),
+            draft_category: Some("travel".to_string()),
+            selected_post: 3,
+            selected_media: 1,
+            selected_scheduled: 2,
+            selected_archived: 0,
+        };
+        let json = serde_json::to_string(&session).unwrap();
+        assert!(json.contains("\"scheduled\""));
+        assert_eq!(serde_json::from_str::<Session>(&json).unwrap(), session);
+
+        // Older or partial files still load
+        let partial: Session = serde_json::from_str(r#"{"tab": "posts"}"#).unwrap();
+        assert_eq!(partial.tab, Tab::Posts);
+        assert_eq!(partial.selected_draft, None);
+    }
+}
@@ -0,0 +1,115 @@
+// ABOUTME: Inline category editor for the selected draft in the TUI
+// ABOUTME: Comma-separated input with Tab completion from known categories
+
+/// How many completions to show under the input
+const MAX_SUGGESTIONS: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct TagEditor {
+    pub draft_id: String,
+    pub input: String,
+    known: Vec<String>,
+}
+
+impl TagEditor {
+    pub fn new(draft_id: String, current: &[String], known: Vec<String>) -> Self {
+        let mut input = current.join(", ");
+        if !input.is_empty() {
+            input.push_str(", ");
+        }
+        Self {
+            draft_id,
+            input,
+            known,
+        }
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.input.push(c);
+    }
+
+    pub fn pop_char(&mut self) {
+        self.input.pop();
+    }
+
+    /// The category currently being typed (after the last comma)
+    fn fragment(&self) -> &str {
+        self.input
+            .rsplit(',')
+            .next()
+            .unwrap_or_default()
+            .trim_start()
+    }
+
+    /// Known categories starting with the current fragment that aren't already entered
+    pub fn suggestions(&self) -> Vec<&str> {
+        let fragment = self.fragment().to_lowercase();
+        let entered = self.categories();
+        self.known
+            .iter()
+            .filter(|k| k.to_lowercase().starts_with(&fragment))
+            .filter(|k| !entered.contains(k))
+            .take(MAX_SUGGESTIONS)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Replace the current fragment with the first suggestion
+    pub fn complete(&mut self) {
+        let Some(suggestion) = self.suggestions().first().map(|s| s.to_string()) else {
+            return;
+        };
+        let keep = self.input.len() - self.fragment().len();
+        self.input.truncate(keep);
+        self.input.push_str(&suggestion);
+        self.input.push_str(", ");
+    }
+
+    /// Parsed categories, trimmed and de-duplicated in order
+    pub fn categories(&self) -> Vec<String> {
+        let mut categories: Vec<String> = Vec::new();
+        for category in self.input.split(',').map(str::trim) {
+            if !category.is_empty() && !categories.iter().any(|c| c == category) {
+                categories.push(category.to_string());
+            }
+        }
+        categories
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(current: &[&str]) -> TagEditor {
+        let current: Vec<String> = current.iter().map(|s| s.to_string()).collect();
+        let known = ["rust", "ruby", "travel", "photos"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        TagEditor::new("20240101-abc".to_string(), &current, known)
+    }
+
+    #[test]
+    fn test_completion_replaces_fragment() {
+        let mut editor = editor(&["travel"]);
+        assert_eq!(editor.input, "travel, ");
+
+        editor.push_char('R');
+        editor.push_char('u');
+        assert_eq!(editor.suggestions(), vec!["rust", "ruby"]);
+
+        editor.complete();
+        assert_eq!(editor.input, "travel, rust, ");
+        assert_eq!(editor.categories(), vec!["travel", "rust"]);
+        // Already-entered categories aren't suggested again
+        assert!(!editor.suggestions().contains(&"rust"));
+    }
+
+    #[test]
+    fn test_categories_trims_and_dedups() {
+        let mut editor = editor(&[]);
+        editor.input = " a ,b,, a,c ".to_string();
+        assert_eq!(editor.categories(), vec!["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,355 @@
+// ABOUTME: Image previews for the TUI's Media tab
+// ABOUTME: Drawn with the kitty graphics protocol or sixel where the terminal has them, else half blocks
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use image::imageops::FilterType;
+use image::{ImageFormat, RgbImage};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Images larger than this aren't downloaded for a preview
+const MAX_DOWNLOAD_BYTES: usize = 25 * 1024 * 1024;
+/// Thumbnails are kept at most this many pixels on a side
+const THUMBNAIL_SIZE: u32 = 512;
+
+/// How the terminal can show images
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+    /// Two pixels per cell with `▀` and true colors; works anywhere
+    Blocks,
+    Off,
+}
+
+impl Protocol {
+    /// `MICROPUB_TUI_IMAGES` (kitty, sixel, blocks, or off) if set, otherwise a
+    /// guess from the terminal's environment variables
+    pub fn detect() -> Self {
+        let var = |name| std::env::var(name).unwrap_or_default();
+        match var("MICROPUB_TUI_IMAGES").to_lowercase().as_str() {
+            "kitty" => return Self::Kitty,
+            "sixel" => return Self::Sixel,
+            "blocks" => return Self::Blocks,
+            "off" | "none" => return Self::Off,
+            _ => {}
+        }
+
+        let (term, program) = (var("TERM"), var("TERM_PROGRAM"));
+        // tmux and screen swallow graphics escapes unless set up to pass them through
+        if !var("TMUX").is_empty() || term.starts_with("screen") {
+            Self::Blocks
+        } else if !var("KITTY_WINDOW_ID").is_empty()
+            || term.contains("kitty")
+            || ["ghostty", "WezTerm"].contains(&program.as_str())
+        {
+            Self::Kitty
+        } else if term.starts_with("foot") || term.contains("mlterm") || program == "iTerm.app" {
+            Self::Sixel
+        } else {
+            Self::Blocks
+        }
+    }
+
+    /// Whether the image is written to the terminal after drawing, rather than
+    /// drawn into ratatui's buffer
+    pub fn is_graphics(self) -> bool {
+        matches!(self, Self::Kitty | Self::Sixel)
+    }
+}
+
+/// Whether a media URL looks like an image that can be previewed
+pub fn is_previewable(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    matches!(
+        ImageFormat::from_path(path),
+        Ok(ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)
+    )
+}
+
+/// A downloaded image, scaled down to preview size
+pub struct Thumbnail(RgbImage);
+
+impl Thumbnail {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let image = image::load_from_memory(bytes).context("Not an image this preview can read")?;
+        Ok(Self(
+            image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).to_rgb8(),
+        ))
+    }
+
+    /// Download and decode the image at `url`
+    pub async fn fetch(url: String) -> Result<Self> {
+        let response = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?;
+        if response
+            .content_length()
+            .is_some_and(|len| len as usize > MAX_DOWNLOAD_BYTES)
+        {
+            anyhow::bail!("Image is too large to preview");
+        }
+        let bytes = response.bytes().await?;
+        if bytes.len() > MAX_DOWNLOAD_BYTES {
+            anyhow::bail!("Image is too large to preview");
+        }
+        crate::blocking::run(move || Self::from_bytes(&bytes)).await
+    }
+
+    /// Columns and rows the image takes up when fit into `cols` x `rows`,
+    /// taking a cell to be twice as tall as it is wide
+    pub fn fit(&self, cols: u16, rows: u16) -> (u16, u16) {
+        let (width, height) = (self.0.width().max(1) as f64, self.0.height().max(1) as f64);
+        let scale = (cols as f64 / width).min(rows as f64 * 2.0 / height);
+        let fit_cols = (width * scale).round().clamp(1.0, cols.max(1) as f64);
+        let fit_rows = (height * scale / 2.0).ceil().clamp(1.0, rows.max(1) as f64);
+        (fit_cols as u16, fit_rows as u16)
+    }
+
+    /// The image as half-block characters, fit into `cols` x `rows`
+    pub fn blocks(&self, cols: u16, rows: u16) -> Vec<Line<'static>> {
+        let (cols, rows) = self.fit(cols, rows);
+        let scaled =
+            image::imageops::resize(&self.0, cols as u32, rows as u32 * 2, FilterType::Triangle);
+        let rgb = |x, y| {
+            let pixel = scaled.get_pixel(x, y);
+            Color::Rgb(pixel[0], pixel[1], pixel[2])
+        };
+        (0..rows as u32)
+            .map(|row| {
+                let spans: Vec<Span> = (0..cols as u32)
+                    .map(|x| {
+                        Span::styled(
+                            "▀",
+                            Style::default().fg(rgb(x, row * 2)).bg(rgb(x, row * 2 + 1)),
+                        )
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// Kitty graphics escapes that show the image over `cols` x `rows` cells at
+    /// the cursor, sent as PNG in 4096-byte chunks
+    fn kitty(&self, cols: u16, rows: u16) -> Result<String> {
+        let mut png = Vec::new();
+        self.0
+            .write_to(&mut io::Cursor::new(&mut png), ImageFormat::Png)?;
+        let data = base64::engine::general_purpose::STANDARD.encode(png);
+
+        let mut out = String::new();
+        let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+        for (idx, chunk) in chunks.iter().enumerate() {
+            let more = u8::from(idx + 1 < chunks.len());
+            let chunk = std::str::from_utf8(chunk)?;
+            if idx == 0 {
+                // q=2 keeps the terminal from replying into our key events; C=1 leaves the cursor alone
+                write!(
+                    out,
+                    "\x1b_Ga=T,f=100,q=2,C=1,c={},r={},m={};{}\x1b\\",
+                    cols, rows, more, chunk
+                )?;
+            } else {
+                write!(out, "\x1b_Gm={};{}\x1b\\", more, chunk)?;
+            }
+        }
+        Ok(out)
+    }
+
+    /// A sixel image `width` x `height` pixels, in a 6x6x6 color cube
+    fn sixel(&self, width: u32, height: u32) -> String {
+        let scaled = image::imageops::resize(&self.0, width, height, FilterType::Triangle);
+        let level = |v: u8| (v as usize * 5 + 127) / 255;
+        let colors: Vec<usize> = scaled
+            .pixels()
+            .map(|p| level(p[0]) * 36 + level(p[1]) * 6 + level(p[2]))
+            .collect();
+
+        let mut out = format!("\x1bPq\"1;1;{};{}", width, height);
+        for color in 0..216 {
+            let percent = |v: usize| v * 100 / 5;
+            let _ = write!(
+                out,
+                "#{};2;{};{};{}",
+                color,
+                percent(color / 36),
+                percent(color / 6 % 6),
+                percent(color % 6)
+            );
+        }
+
+        let (width, height) = (width as usize, height as usize);
+        for band in (0..height).step_by(6) {
+            let rows = band..(band + 6).min(height);
+            let used: BTreeSet<usize> = rows
+                .clone()
+                .flat_map(|y| colors[y * width..(y + 1) * width].iter().copied())
+                .collect();
+            for (n, color) in used.iter().enumerate() {
+                if n > 0 {
+                    out.push('$');
+                }
+                let _ = write!(out, "#{}", color);
+                let sixels = (0..width).map(|x| {
+                    let bits = rows
+                        .clone()
+                        .filter(|y| colors[y * width + x] == *color)
+                        .fold(0u8, |bits, y| bits | 1 << (y - band));
+                    char::from(63 + bits)
+                });
+                push_runs(&mut out, sixels);
+            }
+            out.push('-');
+        }
+        out.push_str("\x1b\\");
+        out
+    }
+}
+
+/// Append sixel characters, run-length encoding repeats
+fn push_runs(out: &mut String, sixels: impl Iterator<Item = char>) {
+    let mut run: Option<(char, usize)> = None;
+    let flush = |out: &mut String, (c, n): (char, usize)| {
+        if n > 3 {
+            let _ = write!(out, "!{}{}", n, c);
+        } else {
+            out.extend(std::iter::repeat_n(c, n));
+        }
+    };
+    for c in sixels {
+        run = match run {
+            Some((prev, n)) if prev == c => Some((prev, n + 1)),
+            Some(prev) => {
+                flush(out, prev);
+                Some((c, 1))
+            }
+            None => Some((c, 1)),
+        };
+    }
+    if let Some(last) = run {
+        flush(out, last);
+    }
+}
+
+/// Shows one image at a time with a graphics protocol, redrawing only when the
+/// image or its place changes
+pub struct Placement {
+    pub protocol: Protocol,
+    shown: Option<(String, Rect)>,
+}
+
+impl Placement {
+    pub fn new(protocol: Protocol) -> Self {
+        Self {
+            protocol,
+            shown: None,
+        }
+    }
+
+    /// Whether showing `wanted` means replacing what's on screen
+    pub fn changes(&self, wanted: Option<(&str, Rect)>) -> bool {
+        self.shown.as_ref().map(|(url, area)| (url.as_str(), *area)) != wanted
+    }
+
+    /// Whether the screen has to be repainted before the change, because
+    /// sixel pixels stay until the cells under them are written again
+    pub fn needs_repaint(&self) -> bool {
+        self.protocol == Protocol::Sixel && self.shown.is_some()
+    }
+
+    /// Remove the shown image and draw `wanted`, fit into its area
+    pub fn show(
+        &mut self,
+        out: &mut impl Write,
+        wanted: Option<(&str, &Thumbnail, Rect)>,
+    ) -> Result<()> {
+        if self.protocol == Protocol::Kitty && self.shown.is_some() {
+            write!(out, "\x1b_Ga=d,q=2\x1b\\")?;
+        }
+        self.shown = None;
+
+        if let Some((url, thumbnail, area)) = wanted {
+            let (cols, rows) = thumbnail.fit(area.width, area.height);
+            let image = match self.protocol {
+                Protocol::Kitty => thumbnail.kitty(cols, rows)?,
+                Protocol::Sixel => {
+                    let (cell_width, cell_height) = cell_size();
+                    thumbnail.sixel(cols as u32 * cell_width, rows as u32 * cell_height)
+                }
+                Protocol::Blocks | Protocol::Off => return Ok(()),
+            };
+            crossterm::queue!(out, crossterm::cursor::MoveTo(area.x, area.y))?;
+            out.write_all(image.as_bytes())?;
+            self.shown = Some((url.to_string(), area));
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// A cell's size in pixels, or a typical 10x20 when the terminal doesn't say
+fn cell_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns).max(1) as u32,
+            (size.height / size.rows).max(1) as u32,
+        ),
+        _ => (10, 20),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+
+    fn thumbnail(width: u32, height: u32) -> Thumbnail {
+        Thumbnail(RgbImage::from_pixel(width, height, Rgb([255, 0, 0])))
+    }
+
+    #[test]
+    fn test_fit_keeps_aspect_ratio_in_cells() {
+        // A square image is twice as many columns as rows
+        assert_eq!(thumbnail(100, 100).fit(40, 40), (40, 20));
+        assert_eq!(thumbnail(100, 100).fit(40, 10), (20, 10));
+        assert_eq!(thumbnail(400, 100).fit(40, 40), (40, 5));
+    }
+
+    #[test]
+    fn test_blocks_use_two_pixels_per_cell() {
+        let lines = thumbnail(4, 4).blocks(4, 4);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].spans.len(), 4);
+        assert_eq!(lines[0].spans[0].style.fg, Some(Color::Rgb(255, 0, 0)));
+    }
+
+    #[test]
+    fn test_sixel_run_length_encodes_bands() {
+        let sixel = thumbnail(10, 12).sixel(10, 12);
+        assert!(sixel.starts_with("\x1bPq\"1;1;10;12"));
+        assert!(sixel.ends_with("\x1b\\"));
+        // Pure red is color 180; each full band is ten `~` sixels
+        assert_eq!(sixel.matches("#180!10~-").count(), 2);
+    }
+
+    #[test]
+    fn test_previewable_urls() {
+        assert!(is_previewable("https://example.com/a/photo.JPG?w=100"));
+        assert!(is_previewable("https://example.com/b.webp"));
+        assert!(!is_previewable("https://example.com/c.mp4"));
+        assert!(!is_previewable("https://example.com/"));
+    }
+}
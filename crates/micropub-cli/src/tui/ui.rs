@@ -0,0 +1,873 @@
+// ABOUTME: UI rendering for TUI using ratatui
+// ABOUTME: Draws the interface with tabs, lists, and preview panes
+
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+    Frame,
+};
+
+use super::app::{App, MediaPreview, Tab};
+use super::content_editor::Field;
+use super::log::LogLevel;
+use super::markdown;
+
+/// Draw the whole screen; returns where the selected image should go when a
+/// graphics protocol draws it over the screen afterwards
+pub fn draw(f: &mut Frame, app: &App) -> Option<Rect> {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Tab bar
+            Constraint::Min(0),    // Main content
+            Constraint::Length(3), // Status/help bar
+        ])
+        .split(f.area());
+
+    draw_tabs(f, app, chunks[0]);
+    let image_area = draw_main_content(f, app, chunks[1]);
+    draw_status_bar(f, app, chunks[2]);
+
+    if app.palette.is_some() {
+        draw_palette(f, app);
+    }
+    if app.tag_editor.is_some() {
+        draw_tag_editor(f, app);
+    }
+    if app.content_editor.is_some() {
+        draw_content_editor(f, app);
+    }
+    if app.show_log {
+        draw_log(f, app);
+    }
+
+    // Popups would sit under the image
+    let popup = app.palette.is_some()
+        || app.tag_editor.is_some()
+        || app.content_editor.is_some()
+        || app.show_log;
+    image_area.filter(|_| !popup)
+}
+
+/// Popup with the comma-separated category input and completions
+fn draw_tag_editor(f: &mut Frame, app: &App) {
+    let Some(ref editor) = app.tag_editor else {
+        return;
+    };
+
+    let suggestions = editor.suggestions();
+    let area = f.area();
+    let width = area.width.clamp(20, 60);
+    let height = (suggestions.len() as u16 + 4).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 3,
+        width,
+        height,
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Green)),
+            Span::styled(
+                &editor.input,
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("_", Style::default().fg(Color::White)),
+        ]),
+        Line::from(""),
+    ];
+    for (i, suggestion) in suggestions.iter().enumerate() {
+        let style = if i == 0 {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        lines.push(Line::from(Span::styled(*suggestion, style)));
+    }
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Categories ([Tab] complete, Enter save, Esc cancel)"),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
+
+/// Popup with the draft's title line above its content, scrolled to the cursor
+fn draw_content_editor(f: &mut Frame, app: &App) {
+    let Some(ref editor) = app.content_editor else {
+        return;
+    };
+
+    let area = f.area();
+    let popup = Rect {
+        x: area.x + area.width / 20,
+        y: area.y + area.height / 20,
+        width: area.width - area.width / 10,
+        height: area.height - area.height / 10,
+    };
+    f.render_widget(Clear, popup);
+
+    let modified = if editor.is_modified() { " *" } else { "" };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(popup);
+    let focused = |field| {
+        if editor.focus == field {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        }
+    };
+
+    let title = Paragraph::new(editor.title.as_str()).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(focused(Field::Title))
+            .title(format!(
+                "{}{} (Ctrl-S save, Esc discard, Tab switch)",
+                editor.draft_id, modified
+            )),
+    );
+    f.render_widget(title, chunks[0]);
+
+    // Keep the cursor line in view
+    let visible = chunks[1].height.saturating_sub(2) as usize;
+    let scroll = (editor.row + 1).saturating_sub(visible);
+    let lines: Vec<Line> = editor
+        .lines()
+        .iter()
+        .skip(scroll)
+        .take(visible)
+        .map(|line| Line::from(line.as_str()))
+        .collect();
+    let content = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(focused(Field::Content))
+            .title("Content"),
+    );
+    f.render_widget(content, chunks[1]);
+
+    let (field_area, row) = match editor.focus {
+        Field::Title => (chunks[0], 0),
+        Field::Content => (chunks[1], editor.row - scroll),
+    };
+    let col = (editor.col as u16).min(field_area.width.saturating_sub(3));
+    f.set_cursor_position((field_area.x + 1 + col, field_area.y + 1 + row as u16));
+}
+
+/// Session log of errors and status messages, newest first
+fn draw_log(f: &mut Frame, app: &App) {
+    let area = f.area();
+    let popup = Rect {
+        x: area.x + area.width / 10,
+        y: area.y + area.height / 10,
+        width: area.width - area.width / 5,
+        height: area.height - area.height / 5,
+    };
+
+    let lines: Vec<Line> = if app.log.is_empty() {
+        vec![Line::from(Span::styled(
+            "Nothing logged yet",
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        app.log
+            .newest_first()
+            .map(|entry| {
+                let (label, color) = match entry.level {
+                    LogLevel::Error => ("ERROR ", Color::Red),
+                    LogLevel::Info => ("info  ", Color::Cyan),
+                };
+                Line::from(vec![
+                    Span::styled(
+                        entry.at.format("%H:%M:%S ").to_string(),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(label, Style::default().fg(color)),
+                    Span::raw(entry.message.as_str()),
+                ])
+            })
+            .collect()
+    };
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "Log ({}) [j/k] scroll [L/Esc] close",
+            app.log.len()
+        )))
+        .scroll((app.log.scroll, 0));
+
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
+
+/// Centered popup listing the palette's matching actions under the query
+fn draw_palette(f: &mut Frame, app: &App) {
+    let Some(ref palette) = app.palette else {
+        return;
+    };
+
+    let matches = palette.matches();
+    let area = f.area();
+    let width = area.width.clamp(20, 60);
+    let height = (matches.len() as u16 + 4).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 3,
+        width,
+        height,
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(": ", Style::default().fg(Color::Green)),
+            Span::styled(
+                &palette.query,
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled("_", Style::default().fg(Color::White)),
+        ]),
+        Line::from(""),
+    ];
+    if matches.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No matching commands",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for (i, entry) in matches.iter().enumerate() {
+        let style = if i == palette.selected {
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(Span::styled(entry.label.as_str(), style)));
+    }
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Commands (↑/↓, Enter, Esc)"),
+    );
+
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
+
+fn draw_tabs(f: &mut Frame, app: &App, area: Rect) {
+    let titles = vec![
+        "[1] Drafts",
+        "[2] Posts",
+        "[3] Media",
+        "[4] Scheduled",
+        "[5] Archive",
+    ];
+    let selected = match app.current_tab {
+        Tab::Drafts => 0,
+        Tab::Posts => 1,
+        Tab::Media => 2,
+        Tab::Scheduled => 3,
+        Tab::Archive => 4,
+    };
+
+    let tabs = Tabs::new(titles)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Micropub Manager"),
+        )
+        .select(selected)
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_widget(tabs, area);
+}
+
+/// Returns the area for a graphics-protocol image, on the Media tab
+fn draw_main_content(f: &mut Frame, app: &App, area: Rect) -> Option<Rect> {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+
+    match app.current_tab {
+        Tab::Drafts => {
+            let list = draw_category_chips(f, app, chunks[0]);
+            draw_drafts_list(f, app, list);
+            draw_preview(f, app, chunks[1]);
+        }
+        Tab::Posts => {
+            let list = draw_category_chips(f, app, chunks[0]);
+            draw_posts_list(f, app, list);
+            draw_preview(f, app, chunks[1]);
+        }
+        Tab::Media => {
+            draw_media_list(f, app, chunks[0]);
+            return draw_media_preview(f, app, chunks[1]);
+        }
+        Tab::Scheduled => {
+            draw_scheduled_list(f, app, chunks[0]);
+            draw_preview(f, app, chunks[1]);
+        }
+        Tab::Archive => {
+            draw_archive_list(f, app, chunks[0]);
+            draw_preview(f, app, chunks[1]);
+        }
+    }
+    None
+}
+
+/// Chips for the categories in the list, with the selected one highlighted,
+/// above the list; returns the area left for the list
+fn draw_category_chips(f: &mut Frame, app: &App, area: Rect) -> Rect {
+    let chips = app.category_chips();
+    if chips.is_empty() {
+        return area;
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(area);
+
+    let selected = app.category_filter();
+    let labels: Vec<(String, bool)> = std::iter::once(("all".to_string(), selected.is_none()))
+        .chain(
+            chips
+                .iter()
+                .map(|c| (c.clone(), Some(c.as_str()) == selected)),
+        )
+        .collect();
+
+    // Drop chips from the front until the selected one fits
+    let width = chunks[0].width.saturating_sub(2) as usize;
+    let chip_width = |label: &str| label.chars().count() + 3;
+    let selected_at = labels.iter().position(|(_, on)| *on).unwrap_or(0);
+    let mut first = 0;
+    while first < selected_at
+        && labels[first..=selected_at]
+            .iter()
+            .map(|(label, _)| chip_width(label))
+            .sum::<usize>()
+            > width
+    {
+        first += 1;
+    }
+
+    let mut spans = Vec::new();
+    if first > 0 {
+        spans.push(Span::styled("… ", Style::default().fg(Color::DarkGray)));
+    }
+    for (label, on) in &labels[first..] {
+        let style = if *on {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Blue)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Blue)
+        };
+        spans.push(Span::styled(format!(" {} ", label), style));
+        spans.push(Span::raw(" "));
+    }
+
+    let paragraph = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray))
+            .title("Category ([ ])"),
+    );
+    f.render_widget(paragraph, chunks[0]);
+    chunks[1]
+}
+
+fn draw_drafts_list(f: &mut Frame, app: &App, area: Rect) {
+    let area = if app.searching || !app.search.is_empty() {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(area);
+        draw_search_bar(f, app, chunks[0]);
+        chunks[1]
+    } else {
+        area
+    };
+
+    let items: Vec<ListItem> = app
+        .drafts
+        .iter()
+        .enumerate()
+        .map(|(i, draft)| {
+            let categories = if draft.categories.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", draft.categories.join(", "))
+            };
+
+            // Status dot: red blocks publishing, yellow is a warning, green is clean
+            let status_color = if draft.has_errors() {
+                Color::Red
+            } else if !draft.warnings.is_empty() {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+
+            let mut spans = vec![
+                Span::styled("● ", Style::default().fg(status_color)),
+                Span::raw(&draft.title),
+                Span::styled(
+                    format!(" ({})", draft.post_type),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(categories, Style::default().fg(Color::Blue)),
+            ];
+
+            let mut badges: Vec<_> = draft.warnings.iter().map(|w| w.kind).collect();
+            badges.dedup();
+            for kind in badges {
+                let color = if kind.is_error() {
+                    Color::Red
+                } else {
+                    Color::Yellow
+                };
+                spans.push(Span::styled(
+                    format!(" ⚠{}", kind.badge()),
+                    Style::default().fg(color),
+                ));
+            }
+
+            let content = vec![Line::from(spans)];
+
+            let style = if i == app.selected_draft {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            ListItem::new(content).style(style)
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Drafts ({})", app.drafts.len())),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    f.render_widget(list, area);
+}
+
+/// Search input above the drafts list; highlighted while typing
+fn draw_search_bar(f: &mut Frame, app: &App, area: Rect) {
+    let border_color = if app.searching {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    };
+
+    let mut spans = vec![
+        Span::styled("/", Style::default().fg(Color::Green)),
+        Span::raw(&app.search),
+    ];
+    if app.searching {
+        spans.push(Span::styled("_", Style::default().fg(Color::White)));
+    }
+
+    let paragraph = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border_color))
+            .title("Search"),
+    );
+
+    f.render_widget(paragraph, area);
+}
+
+fn draw_posts_list(f: &mut Frame, app: &App, area: Rect) {
+    let mut items: Vec<ListItem> = app
+        .posts
+        .iter()
+        .enumerate()
+        .map(|(i, post)| {
+            // Show published date and name/preview
+            let date_part = post.published.get(..10).unwrap_or(&post.published); // YYYY-MM-DD
+
+            let content_part = if let Some(ref name) = post.name {
+                name.clone()
+            } else {
+                // No name - try to extract from URL, fallback to [no title]
+                post.url
+                    .split('/')
+                    .next_back()
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| String::from("[no title]"))
+            };
+
+            let mut display = format!("{} - {}", date_part, content_part);
+
+            if !post.categories.is_empty() {
+                display.push_str(&format!(" [{}]", post.categories.join(", ")));
+            }
+
+            let style = if i == app.selected_post {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            if post.draft {
+                let badge = Span::styled("[draft] ", Style::default().fg(Color::Magenta));
+                return ListItem::new(Line::from(vec![badge, Span::styled(display, style)]));
+            }
+            ListItem::new(display).style(style)
+        })
+        .collect();
+
+    if app.is_loading(&Tab::Posts) {
+        items.push(ListItem::new(Span::styled(
+            "loading…",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Posts ({})", app.posts.len())),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    // Stateful so the list scrolls with the selection as pages are appended
+    let mut state = ListState::default().with_selected(Some(app.selected_post));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_media_list(f: &mut Frame, app: &App, area: Rect) {
+    let mut items: Vec<ListItem> = app
+        .media
+        .iter()
+        .enumerate()
+        .map(|(i, media)| {
+            // Show upload date and filename/name
+            let date_part = if media.uploaded.len() >= 10 {
+                &media.uploaded[..10] // YYYY-MM-DD
+            } else {
+                &media.uploaded
+            };
+
+            let display_name = if let Some(ref name) = media.name {
+                if name.is_empty() {
+                    // Try to extract filename from URL
+                    media
+                        .url
+                        .split('/')
+                        .next_back()
+                        .unwrap_or("[unknown]")
+                        .to_string()
+                } else {
+                    name.clone()
+                }
+            } else {
+                // Try to extract filename from URL
+                media
+                    .url
+                    .split('/')
+                    .next_back()
+                    .unwrap_or("[unknown]")
+                    .to_string()
+            };
+
+            let display = format!("{} - {}", date_part, display_name);
+
+            let style = if i == app.selected_media {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            ListItem::new(display).style(style)
+        })
+        .collect();
+
+    if app.is_loading(&Tab::Media) {
+        items.push(ListItem::new(Span::styled(
+            "loading…",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Media ({})", app.media.len())),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        );
+
+    // Stateful so the list scrolls with the selection as pages are appended
+    let mut state = ListState::default().with_selected(Some(app.selected_media));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_scheduled_list(f: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .scheduled
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let when = item
+                .scheduled_at
+                .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|| "[no time]".to_string());
+
+            let content = vec![Line::from(vec![
+                Span::styled(when, Style::default().fg(Color::Cyan)),
+                Span::raw(" - "),
+                Span::raw(&item.title),
+            ])];
+
+            let style = if i == app.selected_scheduled {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            ListItem::new(content).style(style)
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Scheduled ({})", app.scheduled.len())),
+    );
+
+    f.render_widget(list, area);
+}
+
+fn draw_archive_list(f: &mut Frame, app: &App, area: Rect) {
+    let items: Vec<ListItem> = app
+        .archived
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let when = item
+                .published_at
+                .map(|at| at.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "[no date]".to_string());
+
+            let content = vec![Line::from(vec![
+                Span::styled(when, Style::default().fg(Color::Cyan)),
+                Span::raw(" - "),
+                Span::raw(&item.title),
+            ])];
+
+            let style = if i == app.selected_archived {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+
+            ListItem::new(content).style(style)
+        })
+        .collect();
+
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Archive ({})", app.archived.len())),
+    );
+
+    f.render_widget(list, area);
+}
+
+fn draw_preview(f: &mut Frame, app: &App, area: Rect) {
+    let content = if let Some(ref preview) = app.preview_content {
+        preview.clone()
+    } else if app.current_tab == Tab::Scheduled {
+        "Nothing scheduled.".to_string()
+    } else if app.current_tab == Tab::Archive {
+        "Nothing archived yet. Published drafts show up here.".to_string()
+    } else if app.drafts.is_empty() {
+        "No drafts found.\n\nCreate a new draft with: micropub draft new".to_string()
+    } else {
+        "No preview available".to_string()
+    };
+
+    let title = if app.preview_scroll > 0 {
+        format!("Preview (line {})", app.preview_scroll + 1)
+    } else {
+        "Preview".to_string()
+    };
+    let paragraph = Paragraph::new(markdown::render(&content))
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false })
+        .scroll((app.preview_scroll, 0));
+
+    f.render_widget(paragraph, area);
+}
+
+/// The media item's details above its image. Half blocks are drawn here; for
+/// kitty and sixel the image area is left empty and returned.
+fn draw_media_preview(f: &mut Frame, app: &App, area: Rect) -> Option<Rect> {
+    let Some((_, preview)) = app.media_preview() else {
+        draw_preview(f, app, area);
+        return None;
+    };
+
+    let text_height = app
+        .preview_content
+        .as_deref()
+        .map_or(1, |text| text.lines().count() as u16)
+        + 2;
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(text_height), Constraint::Min(0)])
+        .split(area);
+    draw_preview(f, app, chunks[0]);
+
+    let block = Block::default().borders(Borders::ALL).title("Image");
+    let inner = block.inner(chunks[1]);
+    f.render_widget(block, chunks[1]);
+
+    let dim = Style::default().fg(Color::DarkGray);
+    match preview {
+        MediaPreview::Loading => {
+            f.render_widget(Paragraph::new(Span::styled("Loading preview…", dim)), inner);
+            None
+        }
+        MediaPreview::Failed(error) => {
+            f.render_widget(
+                Paragraph::new(Span::styled(format!("No preview: {}", error), dim))
+                    .wrap(Wrap { trim: false }),
+                inner,
+            );
+            None
+        }
+        MediaPreview::Ready(_) if app.image_protocol.is_graphics() => Some(inner),
+        MediaPreview::Ready(thumbnail) => {
+            f.render_widget(
+                Paragraph::new(thumbnail.blocks(inner.width, inner.height)),
+                inner,
+            );
+            None
+        }
+    }
+}
+
+fn draw_status_bar(f: &mut Frame, app: &App, area: Rect) {
+    let help_text = if app.searching {
+        "[Enter] Keep filter  [Esc] Clear  [↑/↓] Move"
+    } else if app.awaiting_date_input() {
+        "[Enter] Submit  [Esc] Cancel  [Backspace] Delete"
+    } else if app.awaiting_confirmation() {
+        "[y] Yes  [n] No"
+    } else {
+        match app.current_tab {
+            Tab::Drafts => {
+                "[p]ublish [b]ackdate [e]dit [i]nline edit [t]ags [d]elete [n]ew [/]search [[/]] category [r]efresh [:] commands [L]og [q]uit"
+            }
+            Tab::Posts => "[c]opy URL [P]ublish draft [d]elete [u]ndelete [[/]] category [r]efresh [:] commands [L]og [q]uit",
+            Tab::Media => "[c]opy URL [r]efresh [:] commands [L]og [q]uit",
+            Tab::Scheduled => "[t]ime [p]ublish now [c]ancel [r]efresh [:] commands [L]og [q]uit",
+            Tab::Archive => "[u] restore to drafts [c]opy URL [r]efresh [:] commands [L]og [q]uit",
+        }
+    };
+
+    let text = if let Some(ref error) = app.error_message {
+        vec![
+            Line::from(vec![
+                Span::styled(
+                    "Error: ",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(error),
+            ]),
+            Line::from(Span::styled(
+                "[Esc] to dismiss",
+                Style::default().fg(Color::DarkGray),
+            )),
+        ]
+    } else if app.awaiting_date_input() {
+        let prompt = app.status_message.as_deref().unwrap_or("");
+        vec![
+            Line::from(vec![Span::styled(
+                prompt,
+                Style::default().fg(Color::Yellow),
+            )]),
+            Line::from(vec![
+                Span::styled("> ", Style::default().fg(Color::Green)),
+                Span::styled(
+                    &app.date_input,
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled("_", Style::default().fg(Color::White)),
+            ]),
+        ]
+    } else if let Some(upload) = app.upload_status() {
+        vec![Line::from(vec![
+            Span::styled("Status: ", Style::default().fg(Color::Green)),
+            Span::raw(upload),
+        ])]
+    } else if let Some(ref status) = app.status_message {
+        vec![Line::from(vec![
+            Span::styled("Status: ", Style::default().fg(Color::Green)),
+            Span::raw(status),
+        ])]
+    } else {
+        vec![Line::from(Span::styled(
+            help_text,
+            Style::default().fg(Color::Gray),
+        ))]
+    };
+
+    let paragraph = Paragraph::new(text).block(Block::default().borders(Borders::ALL));
+
+    f.render_widget(paragraph, area);
+}
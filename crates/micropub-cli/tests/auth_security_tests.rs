@@ -1,7 +1,7 @@
 // ABOUTME: Security-focused tests for authentication flows
 // ABOUTME: Tests for PKCE, scope validation, token handling, and port management
 
-use micropub::config::Config;
+use micropub_cli::config::Config;
 use std::collections::HashMap;
 use std::net::TcpListener;
 use url::Url;
@@ -394,6 +394,7 @@ fn test_client_id_validation_exists_in_config() {
         editor: None,
         client_id: Some("https://github.com/user/repo".to_string()),
         profiles: HashMap::new(),
+        ..Default::default()
     };
 
     assert!(valid_config.validate().is_ok());
@@ -403,6 +404,7 @@ fn test_client_id_validation_exists_in_config() {
         editor: None,
         client_id: Some("not-a-url".to_string()),
         profiles: HashMap::new(),
+        ..Default::default()
     };
 
     assert!(invalid_config.validate().is_err());
@@ -0,0 +1,16 @@
+// ABOUTME: Helpers shared by the CLI integration tests
+// ABOUTME: Keeps drafts and caches they create out of the real data directory
+
+use micropub_cli::config::DATA_DIR_ENV;
+use std::sync::OnceLock;
+use tempfile::TempDir;
+
+/// Point `MICROPUB_DATA_DIR` at a temp directory shared by this test binary
+pub fn use_temp_data_dir() {
+    static DIR: OnceLock<TempDir> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = TempDir::new().expect("Failed to create temp data dir");
+        std::env::set_var(DATA_DIR_ENV, dir.path());
+        dir
+    });
+}
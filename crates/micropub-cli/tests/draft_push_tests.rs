@@ -1,23 +1,33 @@
 // ABOUTME: Tests for draft push functionality
 // ABOUTME: Validates pushing drafts to server with post-status: draft
 
-use micropub::draft_push::{validate_draft_id, PushResult};
-use micropub::media::{find_media_references, replace_paths};
+mod common;
+
+use micropub_cli::draft_push::{validate_draft_id, PushResult};
+use micropub_cli::media::{find_media_references, replace_paths};
 
 #[test]
 fn test_push_result_structure() {
     let result = PushResult {
-        url: "https://example.com/posts/draft-123".to_string(),
+        url: Some("https://example.com/posts/draft-123".to_string()),
         is_update: false,
         uploads: vec![(
             "photo.jpg".to_string(),
             "https://example.com/media/abc.jpg".to_string(),
         )],
+        pending: false,
+        changed: vec!["content".to_string()],
+        queued: None,
+        profile: "example.com".to_string(),
     };
 
-    assert_eq!(result.url, "https://example.com/posts/draft-123");
+    assert_eq!(
+        result.url.as_deref(),
+        Some("https://example.com/posts/draft-123")
+    );
     assert!(!result.is_update);
     assert_eq!(result.uploads.len(), 1);
+    assert!(!result.pending);
 }
 
 #[test]
@@ -57,7 +67,7 @@ fn test_replace_paths_in_content() {
 
 #[test]
 fn test_micropub_request_includes_draft_status() {
-    use micropub::client::{MicropubAction, MicropubRequest};
+    use micropub_cli::client::{MicropubAction, MicropubRequest};
     use serde_json::{Map, Value};
 
     let mut properties = Map::new();
@@ -85,7 +95,7 @@ fn test_micropub_request_includes_draft_status() {
 
 #[test]
 fn test_micropub_update_request_structure() {
-    use micropub::client::{MicropubAction, MicropubRequest};
+    use micropub_cli::client::{MicropubAction, MicropubRequest};
     use serde_json::{Map, Value};
 
     let mut replace = Map::new();
@@ -120,7 +130,7 @@ fn test_micropub_update_request_structure() {
 
 #[test]
 fn test_update_request_maintains_post_status_draft() {
-    use micropub::client::{MicropubAction, MicropubRequest};
+    use micropub_cli::client::{MicropubAction, MicropubRequest};
     use serde_json::{Map, Value};
 
     // Build properties map with post-status
@@ -188,7 +198,8 @@ fn test_update_request_maintains_post_status_draft() {
 
 #[tokio::test]
 async fn test_cmd_push_draft_requires_valid_draft_id() {
-    let result = micropub::draft_push::cmd_push_draft("nonexistent", None).await;
+    common::use_temp_data_dir();
+    let result = micropub_cli::draft_push::cmd_push_draft("nonexistent", None).await;
     assert!(result.is_err());
     // Will fail with "Draft not found" from Draft::load
 }
@@ -241,20 +252,29 @@ fn test_cli_draft_push_command_exists() {
 // Task 6: Tests for publishing server drafts
 #[test]
 fn test_detect_server_draft_from_metadata() {
-    use micropub::draft::DraftMetadata;
+    use micropub_cli::draft::DraftMetadata;
 
     // Server draft: has URL and status="server-draft"
     let server_draft = DraftMetadata {
         post_type: "note".to_string(),
         name: None,
+        summary: None,
+        in_reply_to: None,
+        like_of: None,
+        repost_of: None,
+        bookmark_of: None,
         published: None,
         category: Vec::new(),
         syndicate_to: Vec::new(),
-        profile: None,
+        profile: Vec::new(),
         photo: Vec::new(),
+        license: None,
+        slug: None,
         status: Some("server-draft".to_string()),
         url: Some("https://example.com/posts/draft-123".to_string()),
         published_at: None,
+        scheduled_at: None,
+        pushed_hashes: Default::default(),
     };
 
     let is_server_draft =
@@ -265,14 +285,23 @@ fn test_detect_server_draft_from_metadata() {
     let local_draft = DraftMetadata {
         post_type: "note".to_string(),
         name: None,
+        summary: None,
+        in_reply_to: None,
+        like_of: None,
+        repost_of: None,
+        bookmark_of: None,
         published: None,
         category: Vec::new(),
         syndicate_to: Vec::new(),
-        profile: None,
+        profile: Vec::new(),
         photo: Vec::new(),
+        license: None,
+        slug: None,
         status: None,
         url: None,
         published_at: None,
+        scheduled_at: None,
+        pushed_hashes: Default::default(),
     };
 
     let is_local =
@@ -282,7 +311,7 @@ fn test_detect_server_draft_from_metadata() {
 
 #[test]
 fn test_update_request_for_publishing_server_draft() {
-    use micropub::client::{MicropubAction, MicropubRequest};
+    use micropub_cli::client::{MicropubAction, MicropubRequest};
     use serde_json::{Map, Value};
 
     // Build UPDATE request to publish a server draft
@@ -370,22 +399,31 @@ fn test_update_request_for_publishing_server_draft() {
 // Integration test for complete publish workflow
 #[test]
 fn test_publish_workflow_local_vs_server_draft() {
-    use micropub::client::{MicropubAction, MicropubRequest};
-    use micropub::draft::DraftMetadata;
+    use micropub_cli::client::{MicropubAction, MicropubRequest};
+    use micropub_cli::draft::DraftMetadata;
     use serde_json::{Map, Value};
 
     // Test 1: Local draft (no URL) → CREATE request
     let local_metadata = DraftMetadata {
         post_type: "note".to_string(),
         name: None,
+        summary: None,
+        in_reply_to: None,
+        like_of: None,
+        repost_of: None,
+        bookmark_of: None,
         published: None,
         category: Vec::new(),
         syndicate_to: Vec::new(),
-        profile: None,
+        profile: Vec::new(),
         photo: Vec::new(),
+        license: None,
+        slug: None,
         status: None,
         url: None,
         published_at: None,
+        scheduled_at: None,
+        pushed_hashes: Default::default(),
     };
 
     let is_server_draft =
@@ -417,14 +455,23 @@ fn test_publish_workflow_local_vs_server_draft() {
     let server_metadata = DraftMetadata {
         post_type: "note".to_string(),
         name: None,
+        summary: None,
+        in_reply_to: None,
+        like_of: None,
+        repost_of: None,
+        bookmark_of: None,
         published: None,
         category: Vec::new(),
         syndicate_to: Vec::new(),
-        profile: None,
+        profile: Vec::new(),
         photo: Vec::new(),
+        license: None,
+        slug: None,
         status: Some("server-draft".to_string()),
         url: Some("https://example.com/posts/draft-123".to_string()),
         published_at: None,
+        scheduled_at: None,
+        pushed_hashes: Default::default(),
     };
 
     let is_server_draft =
@@ -466,19 +513,28 @@ fn test_publish_workflow_local_vs_server_draft() {
 // Task 7: Additional tests for draft push workflow
 #[test]
 fn test_draft_metadata_has_required_fields() {
-    use micropub::draft::DraftMetadata;
+    use micropub_cli::draft::DraftMetadata;
 
     let metadata = DraftMetadata {
         post_type: "note".to_string(),
         name: None,
+        summary: None,
+        in_reply_to: None,
+        like_of: None,
+        repost_of: None,
+        bookmark_of: None,
         published: None,
         category: Vec::new(),
         syndicate_to: Vec::new(),
-        profile: None,
+        profile: Vec::new(),
         photo: Vec::new(),
+        license: None,
+        slug: None,
         status: Some("server-draft".to_string()),
         url: Some("https://example.com/posts/draft-123".to_string()),
         published_at: None,
+        scheduled_at: None,
+        pushed_hashes: Default::default(),
     };
 
     assert_eq!(metadata.status, Some("server-draft".to_string()));
@@ -490,7 +546,7 @@ fn test_draft_metadata_has_required_fields() {
 
 #[test]
 fn test_is_update_logic() {
-    use micropub::draft::DraftMetadata;
+    use micropub_cli::draft::DraftMetadata;
 
     let metadata_new = DraftMetadata::default();
     assert!(metadata_new.url.is_none());
@@ -562,3 +618,35 @@ fn test_validate_draft_id_accepts_valid_ids() {
     assert!(validate_draft_id("draft-123_test").is_ok());
     assert!(validate_draft_id("A1B2C3").is_ok());
 }
+
+#[test]
+fn test_changed_properties_since_last_push() {
+    use micropub_cli::draft_push::{changed_properties, property_hashes};
+    use serde_json::{json, Map, Value};
+
+    let props = |pairs: &[(&str, Value)]| -> Map<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    };
+
+    let pushed = property_hashes(&props(&[
+        ("content", json!(["Hello"])),
+        ("name", json!(["Title"])),
+        ("category", json!(["a"])),
+    ]));
+    let current = property_hashes(&props(&[
+        ("content", json!(["Hello, world"])),
+        ("name", json!(["Title"])),
+        ("summary", json!(["New"])),
+    ]));
+
+    let (changed, removed) = changed_properties(&pushed, &current);
+    assert_eq!(changed, vec!["content", "summary"]);
+    assert_eq!(removed, vec!["category"]);
+
+    // Nothing to send when nothing changed
+    let (changed, removed) = changed_properties(&current, &current);
+    assert!(changed.is_empty() && removed.is_empty());
+}
@@ -0,0 +1,41 @@
+// ABOUTME: Tests for the draft listing and display commands
+// ABOUTME: Runs against an empty drafts directory in a temp data dir
+
+mod common;
+
+#[test]
+fn test_cmd_list_formats_output() {
+    common::use_temp_data_dir();
+    let result = micropub_cli::draft::cmd_list(None, 10, 0);
+    assert!(result.is_ok(), "cmd_list should succeed");
+}
+
+#[test]
+fn test_cmd_show_error_missing_draft() {
+    common::use_temp_data_dir();
+    // Test cmd_show with a draft ID that is very unlikely to exist
+    let result = micropub_cli::draft::cmd_show("nonexistent-draft-id-12345678");
+    assert!(result.is_err(), "cmd_show should fail for missing draft");
+
+    // Verify the error message contains useful information
+    if let Err(e) = result {
+        let error_msg = format!("{:?}", e);
+        assert!(
+            error_msg.contains("Failed to read draft file")
+                || error_msg.contains("No such file or directory"),
+            "Error should mention file not found"
+        );
+    }
+}
+
+#[test]
+fn test_cmd_list_empty_directory() {
+    // This test validates that cmd_list handles cases where
+    // the drafts directory might be empty or newly created
+    common::use_temp_data_dir();
+    let result = micropub_cli::draft::cmd_list(None, 10, 0);
+    assert!(
+        result.is_ok(),
+        "cmd_list should succeed even with no drafts"
+    );
+}
@@ -1,5 +1,5 @@
-use micropub::config::Config;
-use micropub::draft::{generate_draft_id, Draft};
+use micropub_cli::config::Config;
+use micropub_cli::draft::{generate_draft_id, Draft};
 
 #[test]
 #[ignore] // DISABLED: Test writes to production data directory - needs refactoring to use temp dirs
@@ -23,7 +23,7 @@ fn test_draft_lifecycle() {
 #[test]
 #[ignore] // DISABLED: Test writes to production config file - needs refactoring to use temp dirs
 fn test_config_roundtrip() {
-    use micropub::config::Profile;
+    use micropub_cli::config::{EndpointOverrides, Profile};
     use std::collections::HashMap;
 
     // TODO: Refactor config module to support dependency injection of config path
@@ -34,6 +34,7 @@ fn test_config_roundtrip() {
         editor: Some("vim".to_string()),
         client_id: None,
         profiles: HashMap::new(),
+        ..Default::default()
     };
 
     config.upsert_profile(
@@ -44,6 +45,12 @@ fn test_config_roundtrip() {
             media_endpoint: None,
             token_endpoint: None,
             authorization_endpoint: None,
+            transforms: Vec::new(),
+            endpoint_overrides: EndpointOverrides::default(),
+            request_format: Default::default(),
+            syndicate_to: Vec::new(),
+            polite: false,
+            license: None,
         },
     );
 
@@ -1,7 +1,9 @@
 // ABOUTME: Tests for MCP (Model Context Protocol) server implementation
 // ABOUTME: Validates MCP tool functionality and server initialization
 
-use micropub::mcp::MicropubMcp;
+#![cfg(feature = "mcp")]
+
+use micropub_cli::mcp::{MicropubMcp, READ_ONLY_TOOLS};
 
 #[test]
 fn test_mcp_server_creation() {
@@ -10,6 +12,18 @@ fn test_mcp_server_creation() {
     assert!(server.is_ok(), "MCP server should initialize successfully");
 }
 
+#[test]
+fn test_read_only_server_offers_only_read_tools() {
+    let full = MicropubMcp::new().unwrap().tool_names();
+    assert!(full.iter().any(|name| name == "publish_post"));
+
+    let mut tools = MicropubMcp::read_only().unwrap().tool_names();
+    tools.sort();
+    let mut expected: Vec<String> = READ_ONLY_TOOLS.iter().map(|s| s.to_string()).collect();
+    expected.sort();
+    assert_eq!(tools, expected);
+}
+
 #[tokio::test]
 async fn test_whoami_returns_profile_info() {
     // RED: Test the whoami tool
@@ -64,6 +78,19 @@ async fn test_delete_post_tool() {
     // TODO: Implement actual delete_post tool test
 }
 
+#[test]
+fn test_undelete_post_args() {
+    use micropub_cli::mcp::UndeletePostArgs;
+
+    let args: UndeletePostArgs =
+        serde_json::from_value(serde_json::json!({"url": "https://example.com/post/1"}))
+            .expect("Should parse undelete args");
+    assert_eq!(args.url, "https://example.com/post/1");
+
+    let missing: Result<UndeletePostArgs, _> = serde_json::from_value(serde_json::json!({}));
+    assert!(missing.is_err());
+}
+
 #[test]
 fn test_upload_media_requires_file_path_or_data() {
     // Missing both file_path and file_data should fail validation
@@ -606,3 +633,317 @@ fn test_push_draft_error_messages_are_user_friendly() {
         assert!(error.len() < 200);
     }
 }
+
+#[test]
+fn test_weekly_review_summary() {
+    use chrono::{Duration, TimeZone, Utc};
+    use micropub_cli::draft::Draft;
+    use micropub_cli::mcp::weekly_review_summary;
+    use micropub_cli::operations::PostData;
+
+    let now = Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap();
+    let post = |days_ago: i64, categories: &[&str]| PostData {
+        url: format!("https://example.com/{}", days_ago),
+        content: String::new(),
+        name: None,
+        published: Some((now - Duration::days(days_ago)).to_rfc3339()),
+        categories: categories.iter().map(|c| c.to_string()).collect(),
+        draft: false,
+    };
+    let posts = vec![
+        post(1, &["rust"]),
+        post(3, &["rust", "life"]),
+        post(20, &[]),
+    ];
+
+    let mut stale = Draft::new("old-draft".to_string());
+    stale.metadata.name = Some("Abandoned idea".to_string());
+    let fresh = Draft::new("new-draft".to_string());
+    let drafts = vec![
+        (stale, now - Duration::days(30)),
+        (fresh, now - Duration::days(1)),
+    ];
+
+    let summary = weekly_review_summary(&posts, &drafts, now);
+    assert!(summary.contains("Posts in the last 7 days: 2"));
+    assert!(summary.contains("Posts in the last 30 days: 3"));
+    assert!(summary.contains("Last post: 2024-06-14 (1 days ago)"));
+    assert!(summary.contains("Top categories: rust (2), life (1)"));
+    assert!(summary.contains("2 total, 1 untouched"));
+    assert!(summary.contains("Abandoned idea (old-draft), last edited 30 days ago"));
+    assert!(!summary.contains("new-draft"));
+}
+
+#[test]
+fn test_get_server_config_args_default_to_cached() {
+    use micropub_cli::mcp::GetServerConfigArgs;
+
+    let args: GetServerConfigArgs = serde_json::from_str("{}").expect("Should parse empty args");
+    assert!(!args.refresh);
+
+    let args: GetServerConfigArgs =
+        serde_json::from_str(r#"{"refresh": true}"#).expect("Should parse refresh");
+    assert!(args.refresh);
+}
+
+#[test]
+fn test_publish_post_args_summary() {
+    use micropub_cli::mcp::PublishPostArgs;
+
+    let args: PublishPostArgs = serde_json::from_str(r#"{"content": "Hello"}"#).unwrap();
+    assert!(args.summary.is_none());
+    assert!(!args.auto_summary);
+
+    let args: PublishPostArgs =
+        serde_json::from_str(r#"{"content": "Hello", "summary": "Hi", "auto_summary": true}"#)
+            .unwrap();
+    assert_eq!(args.summary.as_deref(), Some("Hi"));
+    assert!(args.auto_summary);
+}
+
+fn initialize_request() -> serde_json::Value {
+    serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": {},
+            "clientInfo": {"name": "test", "version": "1"}
+        }
+    })
+}
+
+/// A POST to the streamable HTTP endpoint, accepting JSON or an event stream
+fn mcp_post(client: &reqwest::Client, url: &str) -> reqwest::RequestBuilder {
+    client
+        .post(url)
+        .header("accept", "application/json, text/event-stream")
+}
+
+/// The JSON-RPC message in a response, which arrives as a server-sent event
+async fn sse_message(response: reqwest::Response) -> serde_json::Value {
+    let body = response.text().await.unwrap();
+    let data = body
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(str::trim)
+        .find(|data| !data.is_empty())
+        .unwrap_or_else(|| panic!("no event in {:?}", body));
+    serde_json::from_str(data).unwrap()
+}
+
+#[tokio::test]
+async fn test_http_transport_requires_token_and_keeps_sessions() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    tokio::spawn(micropub_cli::mcp_http::serve(
+        listener,
+        Some("secret".to_string()),
+        false,
+    ));
+
+    let client = reqwest::Client::new();
+    let response = mcp_post(&client, &url)
+        .json(&initialize_request())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+
+    let response = mcp_post(&client, &url)
+        .bearer_auth("secret")
+        .json(&initialize_request())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let session = response.headers()["mcp-session-id"].clone();
+    let body = sse_message(response).await;
+    assert_eq!(body["id"], 1);
+    assert!(body["result"]["serverInfo"].is_object());
+
+    let response = mcp_post(&client, &url)
+        .bearer_auth("secret")
+        .header("mcp-session-id", session.clone())
+        .json(&serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 202);
+
+    let response = mcp_post(&client, &url)
+        .bearer_auth("secret")
+        .header("mcp-session-id", session.clone())
+        .json(&serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}))
+        .send()
+        .await
+        .unwrap();
+    let body = sse_message(response).await;
+    let tools = body["result"]["tools"].as_array().unwrap();
+    assert!(tools.iter().any(|t| t["name"] == "create_draft"));
+
+    // GET opens the stream for server-initiated messages
+    let response = client
+        .get(&url)
+        .bearer_auth("secret")
+        .header("accept", "text/event-stream")
+        .header("mcp-session-id", session)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/event-stream"));
+    drop(response);
+
+    let response = mcp_post(&client, &url)
+        .bearer_auth("secret")
+        .header("mcp-session-id", "nope")
+        .json(&serde_json::json!({"jsonrpc": "2.0", "id": 3, "method": "tools/list"}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    let response = mcp_post(&client, &url)
+        .bearer_auth("secret")
+        .header("content-type", "application/json")
+        .body(vec![b' '; 5 * 1024 * 1024])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 413);
+}
+
+#[tokio::test]
+async fn test_http_transport_caps_and_expires_sessions() {
+    use micropub_cli::mcp_http::{serve_with_limits, SessionLimits};
+    use std::time::Duration;
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+    tokio::spawn(serve_with_limits(
+        listener,
+        None,
+        true,
+        SessionLimits {
+            max_sessions: 1,
+            idle_timeout: Duration::from_millis(300),
+        },
+    ));
+
+    let client = reqwest::Client::new();
+    let list = serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"});
+
+    let response = mcp_post(&client, &url)
+        .json(&initialize_request())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let first = response.headers()["mcp-session-id"].clone();
+    sse_message(response).await;
+
+    // Only one session may be open at a time
+    let response = mcp_post(&client, &url)
+        .json(&initialize_request())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 503);
+
+    // Once the first sits idle past the timeout, it's gone and there's room again
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let response = mcp_post(&client, &url)
+        .header("mcp-session-id", first)
+        .json(&list)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+    let response = mcp_post(&client, &url)
+        .json(&initialize_request())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+#[test]
+fn test_draft_page_sorts_by_modified_and_points_to_next_page() {
+    use chrono::{Duration, TimeZone, Utc};
+    use micropub_cli::draft::Draft;
+    use micropub_cli::mcp::{draft_page, ListDraftsArgs};
+
+    let now = Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap();
+    let drafts: Vec<(Draft, _)> = (0..3)
+        .map(|i| {
+            let mut draft = Draft::new(format!("draft-{}", i));
+            draft.metadata.name = Some(format!("Draft {}", i));
+            (draft, now - Duration::days(i))
+        })
+        .collect();
+
+    let first = draft_page(drafts.clone(), 2, 0);
+    assert!(first.starts_with("Drafts 1-2 of 3"));
+    assert!(first.contains("- Draft 0 (draft-0), modified 2024-06-15T12:00:00Z"));
+    assert!(first.contains("Draft 1 (draft-1)"));
+    assert!(first.contains("call list_drafts with offset 2"));
+
+    let last = draft_page(drafts.clone(), 2, 2);
+    assert!(last.contains("Draft 2 (draft-2), modified 2024-06-13T12:00:00Z"));
+    assert!(!last.contains("offset"));
+
+    assert!(draft_page(drafts, 2, 5).contains("No drafts at offset 5"));
+    assert_eq!(draft_page(Vec::new(), 2, 0), "No drafts found.");
+
+    let args: ListDraftsArgs = serde_json::from_str("{}").unwrap();
+    assert_eq!((args.limit, args.offset), (20, 0));
+}
+
+#[test]
+fn test_search_content_matches_drafts_and_posts() {
+    use micropub_cli::draft::Draft;
+    use micropub_cli::mcp::SearchScope;
+    use micropub_cli::mcp::{format_search_hits, search_drafts, search_posts, SearchContentArgs};
+    use micropub_cli::operations::PostData;
+
+    let mut rust = Draft::new("rust-notes".to_string());
+    rust.metadata.name = Some("Learning Rust".to_string());
+    rust.content = "Intro\nBorrowing in RUST is fun\n".to_string();
+    let mut cooking = Draft::new("cooking".to_string());
+    cooking.metadata.category = vec!["food".to_string()];
+
+    let hits = search_drafts(&[rust, cooking], "rust");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].location, "rust-notes");
+    assert_eq!(hits[0].matched_in, vec!["title", "content"]);
+    assert_eq!(hits[0].snippet.as_deref(), Some("Borrowing in RUST is fun"));
+
+    let post = PostData {
+        url: "https://example.com/1".to_string(),
+        content: "Dinner".to_string(),
+        name: None,
+        published: Some("2024-01-01T00:00:00Z".to_string()),
+        categories: vec!["rusty-pans".to_string()],
+        draft: false,
+    };
+    let hits = search_posts(&[post.clone(), post], "rust");
+    assert_eq!(hits[0].matched_in, vec!["category"]);
+    assert_eq!(hits[0].snippet, None);
+
+    let text = format_search_hits("Posts", &hits, 1);
+    assert!(text.starts_with("Posts (2 matches):"));
+    assert!(text.contains("- [untitled] (https://example.com/1) [category]"));
+    assert!(text.contains("...and 1 more"));
+    assert_eq!(format_search_hits("Drafts", &[], 5), "Drafts: no matches\n");
+
+    let args: SearchContentArgs = serde_json::from_str(r#"{"query": "rust"}"#).unwrap();
+    assert_eq!((args.scope, args.limit), (SearchScope::Both, 20));
+    let args: SearchContentArgs =
+        serde_json::from_str(r#"{"query": "rust", "scope": "posts"}"#).unwrap();
+    assert_eq!(args.scope, SearchScope::Posts);
+}
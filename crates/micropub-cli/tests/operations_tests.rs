@@ -0,0 +1,243 @@
+// ABOUTME: Tests for post parsing and media upload helpers in the operations module
+// ABOUTME: Covers the different property shapes servers return from q=source
+
+use micropub_cli::client::MicropubAction;
+use micropub_cli::operations::{
+    build_draft_update, build_promote_request, build_update_request, draft_from_source,
+    markdown_image, parse_posts,
+};
+use serde_json::json;
+
+#[test]
+fn test_parse_posts_plain_strings() {
+    let data = json!({
+        "items": [{
+            "type": ["h-entry"],
+            "properties": {
+                "url": ["https://example.com/1"],
+                "content": ["Hello"],
+                "name": ["Title"],
+                "published": ["2024-01-15T10:30:00Z"],
+                "category": ["rust", "cli"]
+            }
+        }]
+    });
+
+    let posts = parse_posts(&data);
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0].url, "https://example.com/1");
+    assert_eq!(posts[0].content, "Hello");
+    assert_eq!(posts[0].name.as_deref(), Some("Title"));
+    assert_eq!(posts[0].categories, vec!["rust", "cli"]);
+}
+
+#[test]
+fn test_parse_posts_nested_content_objects() {
+    let data = json!({
+        "items": [
+            {"properties": {
+                "url": ["https://example.com/html"],
+                "content": [{"html": "<p>Hi <em>there</em></p>", "value": "Hi there"}]
+            }},
+            {"properties": {
+                "url": ["https://example.com/value"],
+                "content": [{"value": "Just text"}]
+            }}
+        ]
+    });
+
+    let posts = parse_posts(&data);
+    assert_eq!(posts.len(), 2);
+    assert_eq!(posts[0].content, "Hi *there*");
+    assert_eq!(posts[1].content, "Just text");
+}
+
+#[test]
+fn test_parse_posts_missing_properties() {
+    let data = json!({
+        "items": [
+            {"properties": {}},
+            {"url": "https://example.com/flat", "content": "Flat item", "name": ""},
+            "not an object"
+        ]
+    });
+
+    let posts = parse_posts(&data);
+    assert_eq!(posts.len(), 2);
+    assert_eq!(posts[0].url, "(no URL)");
+    assert_eq!(posts[0].published, None);
+    assert!(posts[0].content.is_empty());
+    assert_eq!(posts[1].url, "https://example.com/flat");
+    assert_eq!(posts[1].content, "Flat item");
+    assert!(posts[1].name.is_none());
+}
+
+#[test]
+fn test_parse_posts_marks_server_drafts() {
+    let data = json!({
+        "items": [
+            {"properties": {"url": ["https://example.com/d"], "post-status": ["draft"]}},
+            {"properties": {"url": ["https://example.com/p"], "post-status": ["published"]}},
+            {"properties": {"url": ["https://example.com/n"]}}
+        ]
+    });
+
+    let drafts: Vec<bool> = parse_posts(&data).iter().map(|p| p.draft).collect();
+    assert_eq!(drafts, vec![true, false, false]);
+}
+
+#[test]
+fn test_parse_posts_without_items() {
+    assert!(parse_posts(&json!({})).is_empty());
+}
+
+#[test]
+fn test_markdown_image() {
+    assert_eq!(
+        markdown_image("A cat", "https://example.com/cat.jpg"),
+        "![A cat](https://example.com/cat.jpg)"
+    );
+    assert_eq!(
+        markdown_image("see [1]", "https://example.com/a.png"),
+        "![see \\[1\\]](https://example.com/a.png)"
+    );
+    assert_eq!(markdown_image("", "u"), "![](u)");
+}
+
+#[test]
+fn test_build_update_request_replaces_given_fields() {
+    let categories = vec!["rust".to_string()];
+    let request = build_update_request(
+        "https://example.com/1",
+        Some("Fixed typo"),
+        Some(""),
+        Some(&categories),
+    )
+    .unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&request.to_json().unwrap()).unwrap();
+    assert_eq!(json["action"], "update");
+    assert_eq!(json["url"], "https://example.com/1");
+    assert_eq!(json["replace"]["content"], json!(["Fixed typo"]));
+    assert_eq!(json["replace"]["name"], json!([]));
+    assert_eq!(json["replace"]["category"], json!(["rust"]));
+}
+
+#[test]
+fn test_build_promote_request_publishes_post() {
+    let request = build_promote_request("https://example.com/1");
+
+    let json: serde_json::Value = serde_json::from_str(&request.to_json().unwrap()).unwrap();
+    assert_eq!(json["action"], "update");
+    assert_eq!(json["url"], "https://example.com/1");
+    assert_eq!(json["replace"], json!({"post-status": ["published"]}));
+}
+
+#[test]
+fn test_build_update_request_requires_a_change() {
+    assert!(build_update_request("https://example.com/1", None, None, None).is_err());
+}
+
+#[test]
+fn test_draft_from_source_keeps_post_url() {
+    let source = json!({
+        "type": ["h-entry"],
+        "properties": {
+            "content": [{"html": "<p>Hi <em>there</em></p>", "value": "Hi there"}],
+            "name": ["Title"],
+            "category": ["rust"],
+            "photo": [{"value": "https://example.com/a.jpg", "alt": "A"}],
+            "license": ["CC-BY-4.0"],
+            "published": ["2024-01-15T10:30:00+02:00"]
+        }
+    });
+
+    let draft = draft_from_source("abc".to_string(), "https://example.com/1", &source).unwrap();
+    assert_eq!(draft.content, "Hi *there*");
+    assert_eq!(draft.metadata.post_type, "article");
+    assert_eq!(draft.metadata.category, vec!["rust"]);
+    assert_eq!(draft.metadata.photo, vec!["https://example.com/a.jpg"]);
+    assert_eq!(draft.metadata.license.as_deref(), Some("CC-BY-4.0"));
+    assert_eq!(draft.metadata.status.as_deref(), Some("published"));
+    assert_eq!(draft.metadata.url.as_deref(), Some("https://example.com/1"));
+    assert_eq!(
+        draft.metadata.published.unwrap().to_rfc3339(),
+        "2024-01-15T08:30:00+00:00"
+    );
+}
+
+#[test]
+fn test_draft_from_source_server_draft_reply() {
+    let source = json!({
+        "properties": {
+            "content": ["Agreed"],
+            "in-reply-to": ["https://other.example/post"],
+            "post-status": ["draft"]
+        }
+    });
+
+    let draft = draft_from_source("abc".to_string(), "https://example.com/2", &source).unwrap();
+    assert_eq!(draft.metadata.post_type, "reply");
+    assert_eq!(
+        draft.metadata.in_reply_to.as_deref(),
+        Some("https://other.example/post")
+    );
+    assert_eq!(draft.metadata.status.as_deref(), Some("server-draft"));
+}
+
+#[test]
+fn test_build_draft_update_diffs_properties() {
+    let source = json!({
+        "properties": {
+            "content": ["Hello"],
+            "name": ["Title: with \"quotes\""],
+            "summary": ["Short"],
+            "category": ["rust"],
+            "post-status": ["draft"]
+        }
+    });
+    let url = "https://example.com/1";
+    let before = draft_from_source("a".to_string(), url, &source).unwrap();
+
+    assert!(build_draft_update(url, &before, &before.clone(), false).is_none());
+
+    let mut after = before.clone();
+    after.content = "Hello again".to_string();
+    after.metadata.summary = None;
+    after.metadata.category.push("cli".to_string());
+    after.metadata.photo = vec!["https://example.com/a.jpg".to_string()];
+    after.metadata.status = Some("published".to_string());
+
+    let request = build_draft_update(url, &before, &after, true).unwrap();
+    let MicropubAction::Update {
+        replace,
+        add,
+        delete,
+    } = request.action
+    else {
+        panic!("expected an update");
+    };
+
+    assert_eq!(replace["content"], json!([{"html": "<p>Hello again</p>"}]));
+    assert_eq!(replace["post-status"], json!(["published"]));
+    assert!(!replace.contains_key("name"));
+    assert_eq!(add["category"], json!(["cli"]));
+    assert_eq!(add["photo"], json!(["https://example.com/a.jpg"]));
+    assert_eq!(delete, vec!["summary"]);
+}
+
+#[test]
+fn test_build_draft_update_replaces_removed_list_values() {
+    let source = json!({"properties": {"content": ["Hi"], "category": ["a", "b"]}});
+    let url = "https://example.com/1";
+    let before = draft_from_source("a".to_string(), url, &source).unwrap();
+    let mut after = before.clone();
+    after.metadata.category = vec!["b".to_string()];
+
+    let request = build_draft_update(url, &before, &after, false).unwrap();
+    let MicropubAction::Update { replace, add, .. } = request.action else {
+        panic!("expected an update");
+    };
+    assert_eq!(replace["category"], json!(["b"]));
+    assert!(add.is_empty());
+}
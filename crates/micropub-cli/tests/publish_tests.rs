@@ -0,0 +1,121 @@
+// ABOUTME: Tests for post publishing functionality
+// ABOUTME: Validates the structured result returned by cmd_publish
+
+mod common;
+
+use micropub_cli::publish::PublishResult;
+use std::path::PathBuf;
+
+fn published(profile: &str, url: Option<&str>) -> PublishResult {
+    PublishResult {
+        url: url.map(String::from),
+        was_update: false,
+        archived_path: PathBuf::from(format!("/tmp/archive/{}.md", profile)),
+        uploads: Vec::new(),
+        warnings: Vec::new(),
+        profile: profile.to_string(),
+        other_sites: Vec::new(),
+        queued: None,
+    }
+}
+
+#[test]
+fn test_publish_result_json_for_scripts() {
+    let mut result = published("blog", Some("https://example.com/posts/hello"));
+    result.uploads = vec![(
+        "photo.jpg".to_string(),
+        "https://example.com/media/abc.jpg".to_string(),
+    )];
+
+    // Single-site results leave out cross-posting and queue fields
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "url": "https://example.com/posts/hello",
+            "was_update": false,
+            "archived_path": "/tmp/archive/blog.md",
+            "uploads": [["photo.jpg", "https://example.com/media/abc.jpg"]],
+            "warnings": [],
+            "profile": "blog",
+        })
+    );
+
+    let mut queued = published("work", None);
+    queued.queued = Some("q1".to_string());
+    result.other_sites.push(queued);
+    let json = serde_json::to_value(&result).unwrap();
+    assert_eq!(json["other_sites"][0]["profile"], "work");
+    assert_eq!(json["other_sites"][0]["url"], serde_json::Value::Null);
+    assert_eq!(json["other_sites"][0]["queued"], "q1");
+}
+
+#[test]
+fn test_publish_status_line() {
+    assert_eq!(
+        published("blog", Some("https://example.com/posts/1")).status("Draft published"),
+        "Draft published: https://example.com/posts/1"
+    );
+    assert_eq!(
+        published("blog", None).status("Draft published"),
+        "Draft published successfully!"
+    );
+
+    // A queued post isn't published, whatever the caller expected
+    let mut queued = published("blog", None);
+    queued.queued = Some("q1".to_string());
+    let status = queued.status("Draft published");
+    assert!(!status.contains("published successfully"));
+    assert!(status.contains("queue ID q1"));
+    assert!(status.contains("micropub queue flush"));
+}
+
+#[tokio::test]
+async fn test_cmd_publish_requires_valid_draft_id() {
+    common::use_temp_data_dir();
+    let result = micropub_cli::publish::cmd_publish("../etc/passwd", None, false).await;
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_parse_selection() {
+    use micropub_cli::publish::parse_selection;
+
+    assert_eq!(parse_selection("1, 3", 3).unwrap(), vec![0, 2]);
+    assert_eq!(parse_selection("2,2", 3).unwrap(), vec![1]);
+    assert!(parse_selection("", 3).unwrap().is_empty());
+    assert!(parse_selection("4", 3).is_err());
+    assert!(parse_selection("0", 3).is_err());
+    assert!(parse_selection("abc", 3).is_err());
+}
+
+#[test]
+fn test_draft_properties_apply_profile_settings() {
+    use micropub_cli::config::Profile;
+    use micropub_cli::draft::Draft;
+    use micropub_cli::publish::draft_properties;
+    use micropub_cli::transform::Transform;
+
+    let mut draft = Draft::new("props".to_string());
+    draft.metadata.name = Some("Hello".to_string());
+    draft.metadata.category = vec!["rust".to_string()];
+    draft.metadata.slug = Some("hello".to_string());
+    draft.content = "Hi<!-- private -->\n".to_string();
+
+    let profile = Profile {
+        license: Some("CC-BY-4.0".to_string()),
+        transforms: vec![Transform::StripComments],
+        ..Default::default()
+    };
+
+    let properties = draft_properties(&draft, &profile).unwrap();
+    assert_eq!(properties["content"], serde_json::json!(["Hi"]));
+    assert_eq!(properties["name"], serde_json::json!(["Hello"]));
+    assert_eq!(properties["category"], serde_json::json!(["rust"]));
+    assert_eq!(properties["license"], serde_json::json!(["CC-BY-4.0"]));
+    assert_eq!(properties["mp-slug"], serde_json::json!(["hello"]));
+
+    draft.metadata.license = Some("CC0-1.0".to_string());
+    let properties = draft_properties(&draft, &profile).unwrap();
+    assert_eq!(properties["license"], serde_json::json!(["CC0-1.0"]));
+}
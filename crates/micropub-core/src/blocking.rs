@@ -0,0 +1,30 @@
+// ABOUTME: Runs synchronous file I/O on tokio's blocking thread pool
+// ABOUTME: Backs the async draft and config APIs used from the TUI event loop and MCP handlers
+
+use anyhow::{Context, Result};
+
+/// Run `f` on the blocking pool so it doesn't stall the async runtime's workers
+pub async fn run<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .context("Background file task failed")?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_returns_result_and_errors() {
+        assert_eq!(run(|| Ok(2 + 2)).await.unwrap(), 4);
+
+        let err = run(|| -> Result<()> { anyhow::bail!("disk full") })
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "disk full");
+    }
+}
@@ -0,0 +1,648 @@
+// ABOUTME: Micropub HTTP client for API interactions
+// ABOUTME: Handles requests, responses, and endpoint communication
+
+use anyhow::{Context, Result};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::retry::{send_with_retry, RetryPolicy};
+use crate::transport::{default_transport, HttpRequest, HttpResponse, Transport};
+
+/// How create/delete/undelete requests are encoded on the wire
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RequestFormat {
+    /// JSON, falling back to form encoding if a create is rejected
+    #[default]
+    Json,
+    /// Always `application/x-www-form-urlencoded`
+    Form,
+}
+
+impl RequestFormat {
+    pub fn is_json(&self) -> bool {
+        *self == RequestFormat::Json
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MicropubAction {
+    Create,
+    Update {
+        replace: Map<String, Value>,
+        add: Map<String, Value>,
+        delete: Vec<String>,
+    },
+    /// An update that adds and removes single values (`"delete": {"category": ["old"]}`),
+    /// leaving each property's other values alone
+    UpdateValues {
+        add: Map<String, Value>,
+        delete: Map<String, Value>,
+    },
+    Delete,
+    Undelete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MicropubRequest {
+    pub action: MicropubAction,
+    pub properties: Map<String, Value>,
+    pub url: Option<String>,
+}
+
+impl MicropubRequest {
+    /// Serialize to JSON string
+    pub fn to_json(&self) -> Result<String> {
+        let mut obj = serde_json::Map::new();
+
+        match &self.action {
+            MicropubAction::Create => {
+                obj.insert(
+                    "type".to_string(),
+                    Value::Array(vec![Value::String("h-entry".to_string())]),
+                );
+                obj.insert(
+                    "properties".to_string(),
+                    Value::Object(self.properties.clone()),
+                );
+            }
+            MicropubAction::Update {
+                replace,
+                add,
+                delete,
+            } => {
+                obj.insert("action".to_string(), Value::String("update".to_string()));
+                let url = self
+                    .url
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("URL required for update action"))?;
+                obj.insert("url".to_string(), Value::String(url.clone()));
+
+                if !replace.is_empty() {
+                    obj.insert("replace".to_string(), Value::Object(replace.clone()));
+                }
+                if !add.is_empty() {
+                    obj.insert("add".to_string(), Value::Object(add.clone()));
+                }
+                if !delete.is_empty() {
+                    obj.insert(
+                        "delete".to_string(),
+                        Value::Array(delete.iter().map(|s| Value::String(s.clone())).collect()),
+                    );
+                }
+            }
+            MicropubAction::UpdateValues { add, delete } => {
+                obj.insert("action".to_string(), Value::String("update".to_string()));
+                let url = self
+                    .url
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("URL required for update action"))?;
+                obj.insert("url".to_string(), Value::String(url.clone()));
+
+                if !add.is_empty() {
+                    obj.insert("add".to_string(), Value::Object(add.clone()));
+                }
+                if !delete.is_empty() {
+                    obj.insert("delete".to_string(), Value::Object(delete.clone()));
+                }
+            }
+            MicropubAction::Delete => {
+                obj.insert("action".to_string(), Value::String("delete".to_string()));
+                let url = self
+                    .url
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("URL required for delete action"))?;
+                obj.insert("url".to_string(), Value::String(url.clone()));
+            }
+            MicropubAction::Undelete => {
+                obj.insert("action".to_string(), Value::String("undelete".to_string()));
+                let url = self
+                    .url
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("URL required for undelete action"))?;
+                obj.insert("url".to_string(), Value::String(url.clone()));
+            }
+        }
+
+        serde_json::to_string_pretty(&obj).context("Failed to serialize request")
+    }
+
+    /// Whether sending this twice is harmless: everything but creates
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self.action, MicropubAction::Create)
+    }
+
+    /// Serialize to form fields (`h=entry&content=...&category[]=...`)
+    pub fn to_form(&self) -> Result<Vec<(String, String)>> {
+        let mut fields = Vec::new();
+
+        match &self.action {
+            MicropubAction::Create => {
+                fields.push(("h".to_string(), "entry".to_string()));
+
+                for (name, value) in &self.properties {
+                    let values = match value {
+                        Value::Array(values) => values.as_slice(),
+                        other => std::slice::from_ref(other),
+                    };
+                    let key = if values.len() > 1 {
+                        format!("{}[]", name)
+                    } else {
+                        name.clone()
+                    };
+
+                    for value in values {
+                        let value = match value {
+                            Value::String(s) => s.clone(),
+                            Value::Number(n) => n.to_string(),
+                            Value::Bool(b) => b.to_string(),
+                            _ => anyhow::bail!(
+                                "Property '{}' has structured values and can't be form-encoded",
+                                name
+                            ),
+                        };
+                        fields.push((key.clone(), value));
+                    }
+                }
+            }
+            MicropubAction::Update { .. } | MicropubAction::UpdateValues { .. } => {
+                anyhow::bail!("Update requests can't be form-encoded; the server must accept JSON")
+            }
+            MicropubAction::Delete | MicropubAction::Undelete => {
+                let action = if matches!(self.action, MicropubAction::Delete) {
+                    "delete"
+                } else {
+                    "undelete"
+                };
+                let url = self
+                    .url
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("URL required for {} action", action))?;
+                fields.push(("action".to_string(), action.to_string()));
+                fields.push(("url".to_string(), url.clone()));
+            }
+        }
+
+        Ok(fields)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MicropubResponse {
+    pub url: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+    /// True when the server accepted the request (202) but the post isn't available yet
+    #[serde(default)]
+    pub pending: bool,
+}
+
+/// How many times to poll a 202 Accepted Location before reporting it as pending
+const ACCEPTED_POLL_ATTEMPTS: u32 = 5;
+
+/// Delay between polls of a 202 Accepted Location
+const ACCEPTED_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+pub struct MicropubClient {
+    transport: Arc<dyn Transport>,
+    endpoint: String,
+    token: String,
+    format: RequestFormat,
+    retry: RetryPolicy,
+}
+
+impl MicropubClient {
+    pub fn new(endpoint: String, token: String) -> Self {
+        Self {
+            transport: default_transport(),
+            endpoint,
+            token,
+            format: RequestFormat::Json,
+            retry: RetryPolicy::configured(),
+        }
+    }
+
+    /// Send requests through `transport` instead of the default reqwest client
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Use the given retry policy instead of the configured one
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Use the given request encoding (from the profile's `request_format`)
+    pub fn with_format(mut self, format: RequestFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Send a micropub request
+    pub async fn send(&self, request: &MicropubRequest) -> Result<MicropubResponse> {
+        // Updates only exist in the JSON syntax
+        if self.format == RequestFormat::Form
+            && !matches!(
+                request.action,
+                MicropubAction::Update { .. } | MicropubAction::UpdateValues { .. }
+            )
+        {
+            return self.send_form(request).await;
+        }
+
+        let json = request.to_json()?;
+        let http_request = HttpRequest::post(&self.endpoint, json)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Content-Type", "application/json");
+
+        let response = send_with_retry(
+            &self.retry,
+            request.is_idempotent(),
+            "Micropub request",
+            &self.endpoint,
+            || self.transport.send(http_request.clone()),
+        )
+        .await
+        .context("Failed to send request to micropub endpoint")?;
+
+        // Some servers (older WordPress plugins) only accept form-encoded creates
+        let status = StatusCode::from_u16(response.status)?;
+        if matches!(request.action, MicropubAction::Create)
+            && matches!(
+                status,
+                StatusCode::BAD_REQUEST | StatusCode::UNSUPPORTED_MEDIA_TYPE
+            )
+            && request.to_form().is_ok()
+        {
+            let rejection = response.text();
+            return self.send_form(request).await.with_context(|| {
+                format!(
+                    "JSON create was rejected (HTTP {}: {}) and the form-encoded retry failed",
+                    status,
+                    rejection.trim()
+                )
+            });
+        }
+
+        self.handle_response(response).await
+    }
+
+    /// Send a micropub request as `application/x-www-form-urlencoded`
+    pub async fn send_form(&self, request: &MicropubRequest) -> Result<MicropubResponse> {
+        let fields = request.to_form()?;
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(&fields)
+            .finish();
+        let http_request = HttpRequest::post(&self.endpoint, body)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Content-Type", "application/x-www-form-urlencoded");
+
+        let response = send_with_retry(
+            &self.retry,
+            request.is_idempotent(),
+            "Micropub request",
+            &self.endpoint,
+            || self.transport.send(http_request.clone()),
+        )
+        .await
+        .context("Failed to send request to micropub endpoint")?;
+
+        self.handle_response(response).await
+    }
+
+    async fn handle_response(&self, response: HttpResponse) -> Result<MicropubResponse> {
+        let status = StatusCode::from_u16(response.status)?;
+
+        // Get Location header for successful creates
+        let location = response.header_value("location").map(str::to_string);
+
+        let body = response.text();
+
+        if status == StatusCode::ACCEPTED {
+            // Server will process the request asynchronously. The Location (if any)
+            // is where the post will appear, so poll it briefly before giving up.
+            // Until a poll succeeds it may only be a status URL, not the post's.
+            let mut url = None;
+            if let Some(status_url) = location.as_deref().and_then(|l| self.on_endpoint_origin(l)) {
+                if self.poll_until_available(&status_url).await {
+                    url = Some(status_url);
+                }
+            }
+
+            Ok(MicropubResponse {
+                pending: url.is_none(),
+                url,
+                error: None,
+                error_description: None,
+            })
+        } else if status.is_success() {
+            Ok(MicropubResponse {
+                url: location,
+                error: None,
+                error_description: None,
+                pending: false,
+            })
+        } else {
+            // Try to parse error response
+            let error_response: Result<MicropubResponse, _> = serde_json::from_str(&body);
+
+            let error_msg = if let Ok(err) = error_response {
+                format_error_message(&err.error, &err.error_description)
+            } else {
+                format!("HTTP {}: {}", status, body)
+            };
+
+            anyhow::bail!(error_msg);
+        }
+    }
+
+    /// `location` resolved against the endpoint, if it's on the endpoint's origin.
+    /// Polls send the token, so they never go to another host or scheme.
+    fn on_endpoint_origin(&self, location: &str) -> Option<String> {
+        let endpoint = url::Url::parse(&self.endpoint).ok()?;
+        let url = endpoint.join(location).ok()?;
+        (url.origin() == endpoint.origin()).then(|| url.to_string())
+    }
+
+    /// Poll a URL returned with 202 Accepted until it resolves or attempts run out
+    async fn poll_until_available(&self, url: &str) -> bool {
+        for _ in 0..ACCEPTED_POLL_ATTEMPTS {
+            tokio::time::sleep(ACCEPTED_POLL_INTERVAL).await;
+
+            let _turn = crate::polite::turn(url).await;
+            let request =
+                HttpRequest::get(url).header("Authorization", format!("Bearer {}", self.token));
+
+            if let Ok(response) = self.transport.send(request).await {
+                if response.is_success() {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+}
+
+fn format_error_message(error: &Option<String>, description: &Option<String>) -> String {
+    let error_code = error.as_deref().unwrap_or("unknown_error");
+    let desc = description.as_deref().unwrap_or("No description provided");
+
+    match error_code {
+        "insufficient_scope" => {
+            format!(
+                "Insufficient permissions: {}\n\nRe-authenticate with: micropub auth <domain>",
+                desc
+            )
+        }
+        "invalid_request" => {
+            format!(
+                "Invalid request: {}\n\nCheck your draft format and try again",
+                desc
+            )
+        }
+        "unauthorized" => {
+            format!(
+                "Unauthorized: {}\n\nYour token may be expired. Re-authenticate with: micropub auth <domain>",
+                desc
+            )
+        }
+        _ => {
+            format!("Micropub error ({}): {}", error_code, desc)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_request() {
+        let req = MicropubRequest {
+            action: MicropubAction::Delete,
+            properties: Map::new(),
+            url: Some("https://example.com/post/1".to_string()),
+        };
+
+        let json = req.to_json().unwrap();
+        assert!(json.contains("delete"));
+        assert!(json.contains("example.com"));
+    }
+
+    #[test]
+    fn test_create_form_encoding() {
+        let mut properties = Map::new();
+        properties.insert("content".to_string(), serde_json::json!(["Hello"]));
+        properties.insert("category".to_string(), serde_json::json!(["a", "b"]));
+
+        let req = MicropubRequest {
+            action: MicropubAction::Create,
+            properties,
+            url: None,
+        };
+
+        let fields = req.to_form().unwrap();
+        let fields: Vec<(&str, &str)> = fields
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("h", "entry"),
+                ("category[]", "a"),
+                ("category[]", "b"),
+                ("content", "Hello"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_form_encoding_rejects_structured_values() {
+        let mut properties = Map::new();
+        properties.insert(
+            "content".to_string(),
+            serde_json::json!([{"html": "<p>Hi</p>"}]),
+        );
+        let req = MicropubRequest {
+            action: MicropubAction::Create,
+            properties,
+            url: None,
+        };
+        assert!(req.to_form().is_err());
+
+        let update = MicropubRequest {
+            action: MicropubAction::Update {
+                replace: Map::new(),
+                add: Map::new(),
+                delete: Vec::new(),
+            },
+            properties: Map::new(),
+            url: Some("https://example.com/1".to_string()),
+        };
+        assert!(update.to_form().is_err());
+    }
+
+    fn create_request() -> MicropubRequest {
+        let mut properties = Map::new();
+        properties.insert("content".to_string(), serde_json::json!(["Hello"]));
+        MicropubRequest {
+            action: MicropubAction::Create,
+            properties,
+            url: None,
+        }
+    }
+
+    async fn json_rejected(server: &mut mockito::Server) -> mockito::Mock {
+        server
+            .mock("POST", "/micropub")
+            .match_header("content-type", "application/json")
+            .with_status(400)
+            .with_body("expected form fields")
+            .expect(1)
+            .create_async()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_rejected_json_create_is_retried_as_form() {
+        let mut server = mockito::Server::new_async().await;
+        let json = json_rejected(&mut server).await;
+        let form = server
+            .mock("POST", "/micropub")
+            .match_header("content-type", "application/x-www-form-urlencoded")
+            .match_body("h=entry&content=Hello")
+            .with_status(201)
+            .with_header("Location", "https://example.com/posts/1")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = MicropubClient::new(format!("{}/micropub", server.url()), "t".into());
+        let response = client.send(&create_request()).await.unwrap();
+
+        assert_eq!(response.url.as_deref(), Some("https://example.com/posts/1"));
+        json.assert_async().await;
+        form.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_failed_form_retry_keeps_json_rejection() {
+        let mut server = mockito::Server::new_async().await;
+        let json = json_rejected(&mut server).await;
+        let form = server
+            .mock("POST", "/micropub")
+            .match_header("content-type", "application/x-www-form-urlencoded")
+            .with_status(400)
+            .with_body(r#"{"error": "invalid_request", "error_description": "no content"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = MicropubClient::new(format!("{}/micropub", server.url()), "t".into());
+        let err = format!("{:#}", client.send(&create_request()).await.unwrap_err());
+
+        assert!(
+            err.contains("HTTP 400 Bad Request: expected form fields"),
+            "{}",
+            err
+        );
+        assert!(err.contains("no content"), "{}", err);
+        json.assert_async().await;
+        form.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_accepted_create_is_pending_until_its_url_resolves() {
+        let mut server = mockito::Server::new_async().await;
+        let accepted = server
+            .mock("POST", "/micropub")
+            .with_status(202)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = MicropubClient::new(format!("{}/micropub", server.url()), "t".into());
+        let response = client.send(&create_request()).await.unwrap();
+        assert!(response.pending);
+        assert_eq!(response.url, None);
+        accepted.assert_async().await;
+
+        // A relative Location is polled on the endpoint's host
+        let post_url = format!("{}/posts/1", server.url());
+        server
+            .mock("POST", "/micropub")
+            .with_status(202)
+            .with_header("Location", "/posts/1")
+            .create_async()
+            .await;
+        let post = server
+            .mock("GET", "/posts/1")
+            .match_header("authorization", "Bearer t")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let response = client.send(&create_request()).await.unwrap();
+        assert!(!response.pending);
+        assert_eq!(response.url, Some(post_url));
+        post.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_accepted_location_on_another_origin_is_not_polled() {
+        let mut server = mockito::Server::new_async().await;
+        let mut elsewhere = mockito::Server::new_async().await;
+        let status = elsewhere
+            .mock("GET", mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/micropub")
+            .with_status(202)
+            .with_header("Location", &format!("{}/status/1", elsewhere.url()))
+            .create_async()
+            .await;
+
+        let client = MicropubClient::new(format!("{}/micropub", server.url()), "t".into());
+        let response = client.send(&create_request()).await.unwrap();
+
+        // The token stays home, and a status URL isn't reported as the post
+        assert!(response.pending);
+        assert_eq!(response.url, None);
+        status.assert_async().await;
+    }
+
+    #[test]
+    fn test_on_endpoint_origin() {
+        let client = MicropubClient::new("https://example.com/micropub".into(), "t".into());
+        assert_eq!(
+            client.on_endpoint_origin("/posts/1").as_deref(),
+            Some("https://example.com/posts/1")
+        );
+        assert!(client.on_endpoint_origin("https://example.com/p").is_some());
+        assert!(client.on_endpoint_origin("http://example.com/p").is_none());
+        assert!(client
+            .on_endpoint_origin("https://example.com:8443/p")
+            .is_none());
+        assert!(client
+            .on_endpoint_origin("https://evil.example/p")
+            .is_none());
+    }
+
+    #[test]
+    fn test_response_pending_defaults_to_false() {
+        let response: MicropubResponse =
+            serde_json::from_str(r#"{"error": "invalid_request"}"#).unwrap();
+        assert!(!response.pending);
+    }
+}
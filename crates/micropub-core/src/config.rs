@@ -0,0 +1,680 @@
+// ABOUTME: Configuration management for micropub
+// ABOUTME: Handles XDG directories, config file parsing, and profile management
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+use url::Url;
+
+use crate::client::RequestFormat;
+use crate::images::ImageSettings;
+use crate::media::UploadSettings;
+use crate::retry::RetryPolicy;
+use crate::server_config::SyndicationTarget;
+use crate::transform::Transform;
+
+/// Get the XDG config directory for micropub
+pub fn get_config_dir() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir()
+        .context("Could not determine config directory")?
+        .join("micropub");
+
+    fs::create_dir_all(&config_dir).context("Failed to create config directory")?;
+
+    Ok(config_dir)
+}
+
+/// Environment variable naming a directory to use instead of the XDG data
+/// directory, for a portable setup or to keep tests away from real drafts
+pub const DATA_DIR_ENV: &str = "MICROPUB_DATA_DIR";
+
+/// Get the data directory for micropub: `$MICROPUB_DATA_DIR`, or the XDG one
+pub fn get_data_dir() -> Result<PathBuf> {
+    let data_dir = match std::env::var_os(DATA_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => dirs::data_dir()
+            .context("Could not determine data directory")?
+            .join("micropub"),
+    };
+
+    fs::create_dir_all(&data_dir).context("Failed to create data directory")?;
+
+    Ok(data_dir)
+}
+
+/// Point `MICROPUB_DATA_DIR` at a temp directory shared by this test process,
+/// so tests never touch real drafts or caches
+#[cfg(test)]
+pub(crate) fn use_temp_data_dir() {
+    static DIR: OnceLock<tempfile::TempDir> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = tempfile::TempDir::new().expect("Failed to create temp data dir");
+        std::env::set_var(DATA_DIR_ENV, dir.path());
+        dir
+    });
+}
+
+/// `dir/<profile>` for the active profile when `separate_profile_drafts` is on.
+/// An unreadable config.toml is an error rather than a fall back to the shared
+/// folder, which would mix one site's drafts into another's.
+fn scoped_to_profile(dir: PathBuf) -> Result<PathBuf> {
+    Ok(match Config::load()?.drafts_profile() {
+        Some(profile) => dir.join(crate::media_sidecar::profile_dir_name(&profile)),
+        None => dir,
+    })
+}
+
+/// Get the drafts directory, the active profile's own with `separate_profile_drafts`
+pub fn get_drafts_dir() -> Result<PathBuf> {
+    let drafts_dir = scoped_to_profile(get_data_dir()?.join("drafts"))?;
+    fs::create_dir_all(&drafts_dir)?;
+    Ok(drafts_dir)
+}
+
+/// Get the archive directory, the active profile's own with `separate_profile_drafts`
+pub fn get_archive_dir() -> Result<PathBuf> {
+    let archive_dir = scoped_to_profile(get_data_dir()?.join("archive"))?;
+    fs::create_dir_all(&archive_dir)?;
+    Ok(archive_dir)
+}
+
+/// Get the directory for requests waiting for the network to come back
+pub fn get_queue_dir() -> Result<PathBuf> {
+    let queue_dir = get_data_dir()?.join("queue");
+    fs::create_dir_all(&queue_dir)?;
+    Ok(queue_dir)
+}
+
+/// Get the directory for drafts queued for scheduled publishing
+pub fn get_scheduled_dir() -> Result<PathBuf> {
+    let scheduled_dir = get_data_dir()?.join("scheduled");
+    fs::create_dir_all(&scheduled_dir)?;
+    Ok(scheduled_dir)
+}
+
+/// Get the directory for draft snapshots taken before overwrites
+pub fn get_history_dir() -> Result<PathBuf> {
+    let history_dir = get_data_dir()?.join("history");
+    fs::create_dir_all(&history_dir)?;
+    Ok(history_dir)
+}
+
+/// Get the directory for sidecar records of uploaded media
+pub fn get_media_dir() -> Result<PathBuf> {
+    let media_dir = get_data_dir()?.join("media");
+    fs::create_dir_all(&media_dir)?;
+    Ok(media_dir)
+}
+
+/// Get the directory for private scratch files, like posts being edited by `update`
+pub fn get_temp_dir() -> Result<PathBuf> {
+    let temp_dir = get_data_dir()?.join("tmp");
+    fs::create_dir_all(&temp_dir)?;
+    Ok(temp_dir)
+}
+
+/// Get the cache directory
+pub fn get_cache_dir() -> Result<PathBuf> {
+    let cache_dir = get_data_dir()?.join("cache");
+    fs::create_dir_all(&cache_dir)?;
+    Ok(cache_dir)
+}
+
+/// Get the tokens directory
+pub fn get_tokens_dir() -> Result<PathBuf> {
+    let tokens_dir = get_data_dir()?.join("tokens");
+    fs::create_dir_all(&tokens_dir)?;
+    Ok(tokens_dir)
+}
+
+static PROFILE_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Use this profile instead of `default_profile` for the rest of the process
+/// (`--profile`, or switching profiles in the TUI)
+pub fn set_profile_override(name: &str) {
+    if let Ok(mut current) = PROFILE_OVERRIDE.write() {
+        *current = Some(name.to_string());
+    }
+}
+
+/// The profile selected with `--profile` (or a TUI switch), if any
+pub fn profile_override() -> Option<String> {
+    PROFILE_OVERRIDE
+        .read()
+        .ok()
+        .and_then(|current| current.clone())
+}
+
+static PUBLISH_PROFILES: OnceLock<Vec<String>> = OnceLock::new();
+
+/// `--profile a --profile b`: publish to every named profile. The first one is the
+/// active profile for everything else.
+pub fn set_profile_overrides(names: &[String]) {
+    if let Some(first) = names.first() {
+        set_profile_override(first);
+    }
+    if names.len() > 1 {
+        let _ = PUBLISH_PROFILES.set(names.to_vec());
+    }
+}
+
+/// Name commands see for credentials taken from `MICROPUB_ENDPOINT`/`MICROPUB_TOKEN`
+pub const ENV_PROFILE: &str = "environment";
+
+/// Build a profile from `MICROPUB_ENDPOINT` (and optional `MICROPUB_MEDIA_ENDPOINT`).
+/// Both the endpoint and `MICROPUB_TOKEN` must be set.
+fn env_profile_from(
+    endpoint: Option<String>,
+    token: Option<String>,
+    media_endpoint: Option<String>,
+) -> Result<Option<Profile>> {
+    let (Some(endpoint), Some(_)) = (
+        endpoint.filter(|e| !e.is_empty()),
+        token.filter(|t| !t.is_empty()),
+    ) else {
+        return Ok(None);
+    };
+
+    let url = Url::parse(&endpoint).context("MICROPUB_ENDPOINT must be a valid URL")?;
+    Ok(Some(Profile {
+        domain: url.host_str().unwrap_or_default().to_string(),
+        micropub_endpoint: Some(endpoint),
+        media_endpoint: media_endpoint.filter(|m| !m.is_empty()),
+        ..Default::default()
+    }))
+}
+
+/// Environment credentials apply unless `--profile` names a configured profile
+fn uses_env_profile(profile_override: Option<&str>) -> bool {
+    profile_override.is_none_or(|name| name == ENV_PROFILE)
+}
+
+fn env_profile() -> Result<Option<Profile>> {
+    if !uses_env_profile(profile_override().as_deref()) {
+        return Ok(None);
+    }
+    env_profile_from(
+        std::env::var("MICROPUB_ENDPOINT").ok(),
+        std::env::var("MICROPUB_TOKEN").ok(),
+        std::env::var("MICROPUB_MEDIA_ENDPOINT").ok(),
+    )
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Config {
+    pub default_profile: String,
+    pub editor: Option<String>,
+    pub client_id: Option<String>,
+    /// Offer a category picker when publishing a draft with no categories
+    #[serde(default)]
+    pub prompt_for_categories: bool,
+    /// Normalize line endings and trailing whitespace when drafts are saved
+    #[serde(default)]
+    pub normalize_whitespace: bool,
+    /// Keep drafts and the archive in a folder per profile, so each site only
+    /// sees its own
+    #[serde(default)]
+    pub separate_profile_drafts: bool,
+    /// Days back `publish` looks for an identical post before asking to
+    /// publish again (default 7; 0 turns the check off)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_window_days: Option<u32>,
+    pub profiles: HashMap<String, Profile>,
+    /// Retry/backoff for transient failures when publishing and uploading
+    #[serde(default, skip_serializing_if = "RetryPolicy::is_default")]
+    pub retry: RetryPolicy,
+    /// `[upload]` settings: when to stream large files and whether to resume
+    #[serde(default, skip_serializing_if = "UploadSettings::is_default")]
+    pub upload: UploadSettings,
+    /// `[images]` pre-processing: size limits, JPEG quality, and EXIF stripping
+    #[serde(default, skip_serializing_if = "ImageSettings::is_default")]
+    pub images: ImageSettings,
+    /// Where tokens are stored: `file` (default) or `keychain`
+    #[serde(default, skip_serializing_if = "TokenStorage::is_file")]
+    pub token_storage: TokenStorage,
+    /// `[mcp]` settings for `micropub mcp --http`
+    #[serde(default, skip_serializing_if = "McpConfig::is_default")]
+    pub mcp: McpConfig,
+    /// `[aliases]`: custom command names and the command lines they stand for
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    /// Credentials from the environment; when set they replace every profile
+    /// unless `--profile` picks one
+    #[serde(skip)]
+    pub env_profile: Option<Profile>,
+}
+
+/// Backend for access tokens and their refresh details
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStorage {
+    /// `<profile>.token` files readable only by the current user
+    #[default]
+    File,
+    /// The OS credential store, falling back to files where there isn't one
+    Keychain,
+}
+
+impl TokenStorage {
+    pub fn is_file(&self) -> bool {
+        *self == TokenStorage::File
+    }
+}
+
+/// `[mcp]` settings from config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct McpConfig {
+    /// Bearer token HTTP clients must send; required to listen beyond localhost
+    pub http_token: Option<String>,
+}
+
+impl McpConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Profile {
+    pub domain: String,
+    pub micropub_endpoint: Option<String>,
+    pub media_endpoint: Option<String>,
+    pub token_endpoint: Option<String>,
+    pub authorization_endpoint: Option<String>,
+    /// Content rewrite rules applied when publishing with this profile
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<Transform>,
+    /// Endpoints set with `micropub auth --*-endpoint`, which win over discovery
+    #[serde(default, skip_serializing_if = "EndpointOverrides::is_empty")]
+    pub endpoint_overrides: EndpointOverrides,
+    /// `form` for servers that reject JSON creates; JSON falls back to form on 400/415
+    #[serde(default, skip_serializing_if = "RequestFormat::is_json")]
+    pub request_format: RequestFormat,
+    /// Syndication targets last returned by `q=syndicate-to`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub syndicate_to: Vec<SyndicationTarget>,
+    /// Throttle requests for small servers: one at a time, spaced out, smaller pages
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub polite: bool,
+    /// License sent with posts whose draft doesn't set `license:`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+}
+
+/// Manually configured endpoints for sites whose discovery markup is broken
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct EndpointOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub micropub: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorization: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl EndpointOverrides {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Combine with previously stored overrides, preferring our own values
+    pub fn or(self, stored: &EndpointOverrides) -> Self {
+        Self {
+            micropub: self.micropub.or_else(|| stored.micropub.clone()),
+            media: self.media.or_else(|| stored.media.clone()),
+            authorization: self.authorization.or_else(|| stored.authorization.clone()),
+            token: self.token.or_else(|| stored.token.clone()),
+        }
+    }
+}
+
+impl Config {
+    /// Days `publish` looks back for an identical post; 0 when the check is off
+    pub fn duplicate_window_days(&self) -> u32 {
+        self.duplicate_window_days.unwrap_or(7)
+    }
+
+    /// The profile whose drafts folder is in use, when drafts are kept per profile
+    pub fn drafts_profile(&self) -> Option<String> {
+        Some(self.active_profile_name()).filter(|_| self.separate_profile_drafts)
+    }
+
+    /// With drafts kept per profile, refuse a draft whose `profile:` leaves out
+    /// the site whose folder it's in. A list that includes it, for publishing
+    /// to several sites, is fine.
+    pub fn check_draft_profiles(&self, draft_profiles: &[String]) -> Result<()> {
+        let Some(active) = self.drafts_profile() else {
+            return Ok(());
+        };
+        if !draft_profiles.is_empty() && !draft_profiles.contains(&active) {
+            anyhow::bail!(
+                "This draft is in {}'s drafts but sets profile: {}; \
+                 move it to that profile's drafts or remove the profile line",
+                active,
+                draft_profiles.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Validate configuration values
+    pub fn validate(&self) -> Result<()> {
+        // Validate client_id is a valid URL if provided
+        if let Some(ref client_id) = self.client_id {
+            Url::parse(client_id)
+                .context("client_id must be a valid URL (e.g., 'https://github.com/user/repo')")?;
+        }
+        Ok(())
+    }
+
+    /// Load config from file, or create default if not exists
+    pub fn load() -> Result<Self> {
+        let config_path = get_config_dir()?.join("config.toml");
+
+        let mut config = if config_path.exists() {
+            let contents =
+                fs::read_to_string(&config_path).context("Failed to read config file")?;
+            let config: Config =
+                toml::from_str(&contents).context("Failed to parse config file")?;
+            config.validate()?;
+            config
+        } else {
+            // Return default config
+            Config::default()
+        };
+
+        config.env_profile = env_profile()?;
+        Ok(config)
+    }
+
+    /// `load` without blocking the async runtime
+    pub async fn load_async() -> Result<Self> {
+        crate::blocking::run(Self::load).await
+    }
+
+    /// Save config to file, readable only by the current user since it can hold
+    /// the MCP HTTP token
+    pub fn save(&self) -> Result<()> {
+        let config_path = get_config_dir()?.join("config.toml");
+        let contents = toml::to_string_pretty(self).context("Failed to serialize config")?;
+        crate::draft::lock::write_private(&config_path, contents.as_bytes())
+            .context("Failed to write config file")
+    }
+
+    /// Get a profile by name
+    pub fn get_profile(&self, name: &str) -> Option<&Profile> {
+        match self.env_profile {
+            Some(ref profile) if name == ENV_PROFILE => Some(profile),
+            _ => self.profiles.get(name),
+        }
+    }
+
+    /// Name of the profile commands should use: environment credentials if set,
+    /// then `--profile`, then the default
+    pub fn active_profile_name(&self) -> String {
+        if self.env_profile.is_some() {
+            return ENV_PROFILE.to_string();
+        }
+        profile_override().unwrap_or_else(|| self.default_profile.clone())
+    }
+
+    /// Profile to publish a draft with: environment credentials, `--profile`,
+    /// then the draft's own, then the default
+    pub fn profile_for_draft(&self, draft_profile: Option<&str>) -> String {
+        if self.env_profile.is_some() {
+            return ENV_PROFILE.to_string();
+        }
+        profile_override()
+            .or_else(|| draft_profile.map(str::to_string))
+            .unwrap_or_else(|| self.default_profile.clone())
+    }
+
+    /// Every profile to publish a draft with: several from repeated `--profile` flags
+    /// or a `profile:` list, otherwise the single one from `profile_for_draft`
+    pub fn profiles_for_draft(&self, draft_profiles: &[String]) -> Vec<String> {
+        if self.env_profile.is_some() {
+            return vec![ENV_PROFILE.to_string()];
+        }
+        if let Some(names) = PUBLISH_PROFILES.get() {
+            return names.clone();
+        }
+        if profile_override().is_none() {
+            let mut names: Vec<String> = Vec::new();
+            for name in draft_profiles {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+            if names.len() > 1 {
+                return names;
+            }
+        }
+
+        let first = draft_profiles.first().map(String::as_str);
+        vec![self.profile_for_draft(first)]
+    }
+
+    /// Resolve the active profile, failing if none is configured
+    pub fn active_profile(&self) -> Result<(&str, &Profile)> {
+        let name = self.active_profile_name();
+        if name.is_empty() {
+            anyhow::bail!("No profile configured. Run 'micropub auth <domain>' first");
+        }
+        // Borrow the name from the config so it outlives the override lookup
+        match self.env_profile {
+            Some(ref profile) if name == ENV_PROFILE => Ok((ENV_PROFILE, profile)),
+            _ => self
+                .profiles
+                .get_key_value(&name)
+                .map(|(name, profile)| (name.as_str(), profile))
+                .with_context(|| format!("Profile not found: {}", name)),
+        }
+    }
+
+    /// Add or update a profile
+    pub fn upsert_profile(&mut self, name: String, profile: Profile) {
+        self.profiles.insert(name, profile);
+    }
+
+    /// Remove a profile, clearing the default if it pointed at it
+    pub fn remove_profile(&mut self, name: &str) -> Option<Profile> {
+        if self.default_profile == name {
+            self.default_profile.clear();
+        }
+        self.profiles.remove(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_profile() {
+        let some = |s: &str| Some(s.to_string());
+
+        let profile = env_profile_from(some("https://example.com/micropub"), some("tok"), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(profile.domain, "example.com");
+        assert_eq!(
+            profile.micropub_endpoint.as_deref(),
+            Some("https://example.com/micropub")
+        );
+
+        // Both the endpoint and the token are required
+        assert!(
+            env_profile_from(some("https://example.com/micropub"), None, None)
+                .unwrap()
+                .is_none()
+        );
+        assert!(env_profile_from(None, some("tok"), None).unwrap().is_none());
+        assert!(env_profile_from(some("not a url"), some("tok"), None).is_err());
+
+        let config = Config {
+            env_profile: Some(profile),
+            default_profile: "main".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.active_profile_name(), ENV_PROFILE);
+        assert_eq!(config.profile_for_draft(Some("other")), ENV_PROFILE);
+        assert!(config.active_profile().is_ok());
+
+        // An explicit --profile wins over the environment
+        assert!(uses_env_profile(None));
+        assert!(uses_env_profile(Some(ENV_PROFILE)));
+        assert!(!uses_env_profile(Some("main")));
+    }
+
+    #[test]
+    fn test_config_serialization() {
+        let mut config = Config {
+            default_profile: "test".to_string(),
+            editor: Some("vim".to_string()),
+            client_id: None,
+            profiles: HashMap::new(),
+            ..Default::default()
+        };
+
+        config.upsert_profile(
+            "test".to_string(),
+            Profile {
+                domain: "example.com".to_string(),
+                micropub_endpoint: Some("https://example.com/micropub".to_string()),
+                media_endpoint: None,
+                token_endpoint: None,
+                authorization_endpoint: None,
+                transforms: Vec::new(),
+                endpoint_overrides: EndpointOverrides::default(),
+                request_format: RequestFormat::default(),
+                syndicate_to: Vec::new(),
+                polite: false,
+                license: None,
+            },
+        );
+
+        let toml = toml::to_string(&config).unwrap();
+        assert!(toml.contains("example.com"));
+        // The default file storage isn't written out
+        assert!(!toml.contains("token_storage"));
+    }
+
+    #[test]
+    fn test_token_storage_setting() {
+        let config: Config =
+            toml::from_str("default_profile = \"\"\ntoken_storage = \"keychain\"\n[profiles]\n")
+                .unwrap();
+        assert_eq!(config.token_storage, TokenStorage::Keychain);
+
+        let config: Config = toml::from_str("default_profile = \"\"\n[profiles]\n").unwrap();
+        assert_eq!(config.token_storage, TokenStorage::File);
+    }
+
+    #[test]
+    fn test_active_profile_resolution() {
+        let mut config = Config::default();
+        assert!(config.active_profile().is_err());
+
+        config.default_profile = "main".to_string();
+        assert!(config.active_profile().is_err());
+
+        config.upsert_profile("main".to_string(), Profile::default());
+        let (name, _) = config.active_profile().unwrap();
+        assert_eq!(name, "main");
+        assert_eq!(config.profile_for_draft(Some("other")), "other");
+        assert_eq!(config.profile_for_draft(None), "main");
+    }
+
+    #[test]
+    fn test_separate_profile_drafts() {
+        let mut config = Config {
+            default_profile: "work".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(config.drafts_profile(), None);
+        assert!(config.check_draft_profiles(&["blog".to_string()]).is_ok());
+
+        config.separate_profile_drafts = true;
+        assert_eq!(config.drafts_profile().as_deref(), Some("work"));
+        assert!(config.check_draft_profiles(&[]).is_ok());
+        assert!(config.check_draft_profiles(&["work".to_string()]).is_ok());
+        assert!(config
+            .check_draft_profiles(&["blog".to_string(), "work".to_string()])
+            .is_ok());
+        let err = config
+            .check_draft_profiles(&["blog".to_string(), "notes".to_string()])
+            .unwrap_err();
+        assert!(err.to_string().contains("sets profile: blog, notes"));
+    }
+
+    #[test]
+    fn test_validate_valid_client_id() {
+        let config = Config {
+            default_profile: "test".to_string(),
+            editor: None,
+            client_id: Some("https://github.com/user/repo".to_string()),
+            profiles: HashMap::new(),
+            ..Default::default()
+        };
+
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_invalid_client_id() {
+        let config = Config {
+            default_profile: "test".to_string(),
+            editor: None,
+            client_id: Some("not-a-url".to_string()),
+            profiles: HashMap::new(),
+            ..Default::default()
+        };
+
+        let result = config.validate();
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("client_id must be a valid URL"));
+    }
+
+    #[test]
+    fn test_validate_no_client_id() {
+        let config = Config {
+            default_profile: "test".to_string(),
+            editor: None,
+            client_id: None,
+            profiles: HashMap::new(),
+            ..Default::default()
+        };
+
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_endpoint_overrides_merge() {
+        let stored = EndpointOverrides {
+            micropub: Some("https://old.example/micropub".to_string()),
+            media: Some("https://old.example/media".to_string()),
+            ..Default::default()
+        };
+        let merged = EndpointOverrides {
+            micropub: Some("https://new.example/micropub".to_string()),
+            ..Default::default()
+        }
+        .or(&stored);
+
+        assert_eq!(
+            merged.micropub.as_deref(),
+            Some("https://new.example/micropub")
+        );
+        assert_eq!(merged.media.as_deref(), Some("https://old.example/media"));
+        assert!(merged.token.is_none());
+        assert!(EndpointOverrides::default().is_empty());
+    }
+}
@@ -0,0 +1,164 @@
+// ABOUTME: Snapshots of drafts taken before they're overwritten or edited
+// ABOUTME: Stored as history/<draft-id>/<timestamp>.md in the data directory, newest kept
+
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Draft, DraftId};
+use crate::config::get_history_dir;
+
+/// Snapshots kept per draft; older ones are pruned
+const MAX_SNAPSHOTS: usize = 20;
+
+fn snapshot_dir(id: &DraftId) -> Result<PathBuf> {
+    Ok(get_history_dir()?.join(id.as_str()))
+}
+
+/// A draft's snapshots, newest first
+pub fn list(draft_id: &str) -> Result<Vec<PathBuf>> {
+    list_in(&snapshot_dir(&DraftId::parse(draft_id)?)?)
+}
+
+/// Snapshots in `dir`, newest first
+fn list_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("md"))
+        .collect();
+    // Timestamped names sort chronologically
+    snapshots.sort();
+    snapshots.reverse();
+    Ok(snapshots)
+}
+
+/// Copy the file at `path` into the draft's history, unless it matches the latest snapshot
+pub fn snapshot(draft_id: &str, path: &Path) -> Result<Option<PathBuf>> {
+    snapshot_in(&snapshot_dir(&DraftId::parse(draft_id)?)?, path)
+}
+
+fn snapshot_in(dir: &Path, path: &Path) -> Result<Option<PathBuf>> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+
+    let existing = list_in(dir)?;
+    if let Some(latest) = existing.first() {
+        if fs::read_to_string(latest).ok().as_deref() == Some(contents.as_str()) {
+            return Ok(None);
+        }
+    }
+
+    fs::create_dir_all(dir).context("Failed to create draft history directory")?;
+    let snapshot_path = dir.join(format!("{}.md", Utc::now().format("%Y%m%dT%H%M%S%.6fZ")));
+    fs::write(&snapshot_path, contents).context("Failed to write draft snapshot")?;
+
+    for old in existing.iter().skip(MAX_SNAPSHOTS - 1) {
+        let _ = fs::remove_file(old);
+    }
+
+    Ok(Some(snapshot_path))
+}
+
+/// Timestamp part of a snapshot's file name
+pub fn snapshot_label(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Put a snapshot back (1 = newest) and return its path; the current version is snapshotted first
+pub fn restore(draft_id: &str, number: usize) -> Result<PathBuf> {
+    let (path, draft) = load_snapshot(&list(draft_id)?, draft_id, number)?;
+    draft.save()?;
+    Ok(path)
+}
+
+/// Snapshot `number` (1 = newest) of `snapshots`, parsed as the draft
+fn load_snapshot(snapshots: &[PathBuf], draft_id: &str, number: usize) -> Result<(PathBuf, Draft)> {
+    let Some(path) = number.checked_sub(1).and_then(|i| snapshots.get(i)) else {
+        bail!(
+            "No snapshot {} of {} (it has {})",
+            number,
+            draft_id,
+            snapshots.len()
+        );
+    };
+
+    let contents = fs::read_to_string(path).context("Failed to read snapshot")?;
+    let draft = Draft::from_string(draft_id.to_string(), contents)?;
+    Ok((path.clone(), draft))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn draft_text(content: &str) -> String {
+        format!("---\ntype: note\n---\n\n{}\n", content)
+    }
+
+    #[test]
+    fn test_snapshot_and_restore_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let history = dir.path().join("history");
+        let draft = dir.path().join("draft.md");
+
+        fs::write(&draft, draft_text("First")).unwrap();
+        assert!(snapshot_in(&history, &draft).unwrap().is_some());
+        // Unchanged since the last snapshot
+        assert!(snapshot_in(&history, &draft).unwrap().is_none());
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        fs::write(&draft, draft_text("Second")).unwrap();
+        snapshot_in(&history, &draft).unwrap();
+
+        let snapshots = list_in(&history).unwrap();
+        assert_eq!(snapshots.len(), 2);
+        let (path, restored) = load_snapshot(&snapshots, "draft", 2).unwrap();
+        assert_eq!(path, snapshots[1]);
+        assert_eq!(restored.content.trim(), "First");
+        assert_eq!(
+            load_snapshot(&snapshots, "draft", 1)
+                .unwrap()
+                .1
+                .content
+                .trim(),
+            "Second"
+        );
+
+        let err = load_snapshot(&snapshots, "draft", 3).unwrap_err();
+        assert_eq!(err.to_string(), "No snapshot 3 of draft (it has 2)");
+        assert!(load_snapshot(&snapshots, "draft", 0).is_err());
+        // A missing draft file isn't snapshotted
+        assert!(snapshot_in(&history, &dir.path().join("gone.md"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_snapshots_are_pruned_to_newest() {
+        let dir = TempDir::new().unwrap();
+        let history = dir.path().join("history");
+        let draft = dir.path().join("draft.md");
+
+        for n in 0..MAX_SNAPSHOTS + 5 {
+            fs::write(&draft, draft_text(&format!("Version {}", n))).unwrap();
+            snapshot_in(&history, &draft).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        let snapshots = list_in(&history).unwrap();
+        assert_eq!(snapshots.len(), MAX_SNAPSHOTS);
+        let newest = fs::read_to_string(&snapshots[0]).unwrap();
+        let oldest = fs::read_to_string(snapshots.last().unwrap()).unwrap();
+        assert!(newest.contains(&format!("Version {}", MAX_SNAPSHOTS + 4)));
+        assert!(oldest.contains("Version 5"));
+    }
+}
@@ -0,0 +1,126 @@
+// ABOUTME: Per-draft async locks and atomic file writes
+// ABOUTME: Keeps concurrent MCP calls, TUI actions, and CLI runs from interleaving draft writes
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+use uuid::Uuid;
+
+/// One lock per draft ID, created on first use
+static LOCKS: Mutex<BTreeMap<String, Arc<AsyncMutex<()>>>> = Mutex::new(BTreeMap::new());
+
+/// Wait for exclusive use of a draft within this process. Hold the guard from
+/// loading the draft until its last save so another task can't change it in between.
+pub async fn lock(id: &str) -> OwnedMutexGuard<()> {
+    let lock = {
+        let mut locks = LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+        // Drop locks nobody holds or waits on
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        Arc::clone(locks.entry(id.to_string()).or_default())
+    };
+    lock.lock_owned().await
+}
+
+/// Write `contents` to a hidden temp file next to `path`, then rename it over
+/// `path`, so readers (and other processes) see the old file or the new one,
+/// never half of each
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    write_atomic_as(path, contents, false)
+}
+
+/// `write_atomic` for secrets: the file is readable only by the current user
+/// from the moment it's created
+pub fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    write_atomic_as(path, contents, true)
+}
+
+fn write_atomic_as(path: &Path, contents: &[u8], private: bool) -> Result<()> {
+    let dir = path.parent().context("File has no parent directory")?;
+    let name = path
+        .file_name()
+        .context("File has no name")?
+        .to_string_lossy();
+    let temp = dir.join(format!(".{}.{}.tmp", name, Uuid::new_v4().simple()));
+
+    let written = (|| -> Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        if private {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options.open(&temp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        if !private {
+            if let Ok(existing) = fs::metadata(path) {
+                fs::set_permissions(&temp, existing.permissions())?;
+            }
+        }
+        fs::rename(&temp, path)?;
+        Ok(())
+    })();
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written.with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_lock_serializes_same_draft() {
+        let guard = lock("locked-draft").await;
+
+        // Another draft isn't blocked
+        let _other = lock("other-draft").await;
+
+        let waiting = tokio::spawn(async { lock("locked-draft").await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        drop(guard);
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("lock should be released")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file_without_leftovers() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("draft.md");
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        assert!(write_atomic(&dir.path().join("missing").join("draft.md"), b"x").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_private(&path, b"secret").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "secret");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,1107 @@
+// ABOUTME: Draft model for micropub: parsing, serialization, linting, and storage
+// ABOUTME: Drafts are Markdown files with YAML frontmatter in the data directory
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::blocking;
+use crate::config::{get_archive_dir, get_drafts_dir, Config};
+use crate::media::{find_media_references, resolve_path};
+use crate::server_config::SyndicationTarget;
+
+pub mod history;
+pub mod lock;
+pub mod paths;
+
+pub use lock::write_atomic;
+pub use paths::DraftId;
+
+lazy_static! {
+    static ref MD_IMG_NO_ALT_RE: Regex = Regex::new(r"!\[\s*\]\(([^)\s]*)").unwrap();
+    static ref HTML_IMG_RE: Regex = Regex::new(r"(?i)<img\b[^>]*>").unwrap();
+    static ref HTML_ALT_RE: Regex = Regex::new(r"(?i)\balt\s*=").unwrap();
+    static ref HTML_SRC_RE: Regex = Regex::new(r#"(?i)\bsrc\s*=\s*["']([^"']*)["']"#).unwrap();
+}
+
+/// Frontmatter fields that must hold RFC 3339 dates
+const DATE_FIELDS: [&str; 3] = ["published", "published-at", "scheduled-at"];
+
+/// Every frontmatter key `DraftMetadata` understands; anything else is silently dropped
+const FRONTMATTER_KEYS: [&str; 19] = [
+    "type",
+    "name",
+    "summary",
+    "in-reply-to",
+    "like-of",
+    "repost-of",
+    "bookmark-of",
+    "published",
+    "category",
+    "syndicate-to",
+    "profile",
+    "photo",
+    "license",
+    "slug",
+    "status",
+    "url",
+    "published-at",
+    "scheduled-at",
+    "pushed-hashes",
+];
+
+/// What a lint warning is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintKind {
+    MissingAlt,
+    BrokenPhoto,
+    InvalidDate,
+    InvalidUrl,
+    InvalidFrontmatter,
+    UnknownSyndication,
+    UnknownKey,
+    EmptyContent,
+    MissingMedia,
+    InvalidSlug,
+}
+
+impl LintKind {
+    /// Short label for badges in the TUI
+    pub fn badge(self) -> &'static str {
+        match self {
+            LintKind::MissingAlt => "alt",
+            LintKind::BrokenPhoto => "photo",
+            LintKind::InvalidDate => "date",
+            LintKind::InvalidUrl => "url",
+            LintKind::InvalidFrontmatter => "yaml",
+            LintKind::UnknownSyndication => "synd",
+            LintKind::UnknownKey => "key",
+            LintKind::EmptyContent => "empty",
+            LintKind::MissingMedia => "media",
+            LintKind::InvalidSlug => "slug",
+        }
+    }
+
+    /// Whether this problem would make publishing fail (rather than just being a warning)
+    pub fn is_error(self) -> bool {
+        !matches!(
+            self,
+            LintKind::MissingAlt | LintKind::UnknownSyndication | LintKind::UnknownKey
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintWarning {
+    pub kind: LintKind,
+    pub message: String,
+    /// 1-based line in the draft file, when the problem can be pinned to one
+    pub line: Option<usize>,
+    /// Text to look for when locating the problem in the source
+    needle: Option<String>,
+}
+
+impl LintWarning {
+    fn new(kind: LintKind, message: String) -> Self {
+        Self {
+            kind,
+            message,
+            line: None,
+            needle: None,
+        }
+    }
+
+    fn about(mut self, needle: impl Into<String>) -> Self {
+        self.needle = Some(needle.into());
+        self
+    }
+
+    fn at(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+}
+
+impl std::fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct DraftMetadata {
+    #[serde(rename = "type")]
+    pub post_type: String,
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_reply_to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub like_of: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repost_of: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookmark_of: Option<String>,
+    pub published: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub category: Vec<String>,
+    #[serde(default)]
+    pub syndicate_to: Vec<String>,
+    /// Profile(s) to publish with: a single name, or a list to publish to each site
+    #[serde(default, with = "profile_names", skip_serializing_if = "Vec::is_empty")]
+    pub profile: Vec<String>,
+    #[serde(default)]
+    pub photo: Vec<String>,
+    /// Content license (e.g. `CC-BY-4.0` or a license URL); the profile's `license` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// URL slug requested for a new post (sent as `mp-slug`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+    pub status: Option<String>,
+    pub url: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    /// When a queued draft should be published (see `schedule`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled_at: Option<DateTime<Utc>>,
+    /// Property hashes from the last `draft push`, so updates only send what changed
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub pushed_hashes: BTreeMap<String, String>,
+}
+
+impl Default for DraftMetadata {
+    fn default() -> Self {
+        Self {
+            post_type: "note".to_string(),
+            name: None,
+            summary: None,
+            in_reply_to: None,
+            like_of: None,
+            repost_of: None,
+            bookmark_of: None,
+            published: None,
+            category: Vec::new(),
+            syndicate_to: Vec::new(),
+            profile: Vec::new(),
+            photo: Vec::new(),
+            license: None,
+            slug: None,
+            status: None,
+            url: None,
+            published_at: None,
+            scheduled_at: None,
+            pushed_hashes: BTreeMap::new(),
+        }
+    }
+}
+
+/// `profile:` as written by hand: one name stays a string, several become a list
+mod profile_names {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    pub fn serialize<S: Serializer>(names: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+        match names {
+            [name] => serializer.serialize_str(name),
+            _ => names.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<String>, D::Error> {
+        Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+            None => Vec::new(),
+            Some(OneOrMany::One(name)) => vec![name],
+            Some(OneOrMany::Many(names)) => names,
+        })
+    }
+}
+
+/// Post types that respond to another URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseKind {
+    Reply,
+    Like,
+    Repost,
+    Bookmark,
+}
+
+impl ResponseKind {
+    pub const ALL: [ResponseKind; 4] = [
+        ResponseKind::Reply,
+        ResponseKind::Like,
+        ResponseKind::Repost,
+        ResponseKind::Bookmark,
+    ];
+
+    /// The micropub property holding the target URL
+    pub fn property(self) -> &'static str {
+        match self {
+            ResponseKind::Reply => "in-reply-to",
+            ResponseKind::Like => "like-of",
+            ResponseKind::Repost => "repost-of",
+            ResponseKind::Bookmark => "bookmark-of",
+        }
+    }
+
+    /// The draft `type:` used for this kind of post
+    pub fn post_type(self) -> &'static str {
+        match self {
+            ResponseKind::Reply => "reply",
+            ResponseKind::Like => "like",
+            ResponseKind::Repost => "repost",
+            ResponseKind::Bookmark => "bookmark",
+        }
+    }
+}
+
+impl DraftMetadata {
+    /// The draft's profile, or the first of them when it targets several sites
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.first().map(String::as_str)
+    }
+
+    /// The URL this draft responds to for the given kind, if set
+    pub fn response_target(&self, kind: ResponseKind) -> Option<&str> {
+        match kind {
+            ResponseKind::Reply => self.in_reply_to.as_deref(),
+            ResponseKind::Like => self.like_of.as_deref(),
+            ResponseKind::Repost => self.repost_of.as_deref(),
+            ResponseKind::Bookmark => self.bookmark_of.as_deref(),
+        }
+    }
+
+    /// Set the URL this draft responds to
+    pub fn set_response_target(&mut self, kind: ResponseKind, url: String) {
+        let field = match kind {
+            ResponseKind::Reply => &mut self.in_reply_to,
+            ResponseKind::Like => &mut self.like_of,
+            ResponseKind::Repost => &mut self.repost_of,
+            ResponseKind::Bookmark => &mut self.bookmark_of,
+        };
+        *field = Some(url);
+    }
+
+    /// Response properties (`in-reply-to`, `like-of`, ...) set on this draft
+    pub fn response_properties(&self) -> Vec<(&'static str, &str)> {
+        ResponseKind::ALL
+            .into_iter()
+            .filter_map(|kind| self.response_target(kind).map(|url| (kind.property(), url)))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Draft {
+    pub id: String,
+    pub metadata: DraftMetadata,
+    pub content: String,
+}
+
+impl Draft {
+    /// Create a new draft with default metadata
+    pub fn new(id: String) -> Self {
+        Self {
+            id,
+            metadata: DraftMetadata::default(),
+            content: String::new(),
+        }
+    }
+
+    /// Parse a draft from a string (YAML frontmatter + content)
+    pub fn from_string(id: String, source: String) -> Result<Self> {
+        // Split on --- delimiters
+        let parts: Vec<&str> = source.splitn(3, "---").collect();
+
+        if parts.len() < 3 {
+            anyhow::bail!("Invalid draft format: missing frontmatter delimiters");
+        }
+
+        let frontmatter = parts[1].trim();
+        let content = parts[2].trim().to_string();
+
+        let metadata: DraftMetadata =
+            serde_yaml::from_str(frontmatter).context("Failed to parse frontmatter")?;
+
+        Ok(Self {
+            id,
+            metadata,
+            content,
+        })
+    }
+
+    /// Load a draft from file
+    pub fn load(id: &str) -> Result<Self> {
+        let path = paths::draft_path(id)?;
+        let contents = fs::read_to_string(&path).context("Failed to read draft file")?;
+        Self::from_string(id.to_string(), contents)
+    }
+
+    /// `load` without blocking the async runtime
+    pub async fn load_async(id: &str) -> Result<Self> {
+        let id = id.to_string();
+        blocking::run(move || Self::load(&id)).await
+    }
+
+    /// Serialize draft to string (YAML frontmatter + content)
+    pub fn to_string(&self) -> Result<String> {
+        let frontmatter =
+            serde_yaml::to_string(&self.metadata).context("Failed to serialize frontmatter")?;
+
+        Ok(format!("---\n{}---\n\n{}", frontmatter, self.content))
+    }
+
+    /// Save draft to file, snapshotting the previous version if it changes.
+    /// With `normalize_whitespace` in config.toml, the file is normalized first.
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = paths::draft_path(&self.id)?;
+        let mut contents = self.to_string()?;
+        if Config::load().is_ok_and(|config| config.normalize_whitespace) {
+            contents = normalize_whitespace(&contents);
+        }
+        if fs::read_to_string(&path).is_ok_and(|existing| existing != contents) {
+            history::snapshot(&self.id, &path)?;
+        }
+        write_atomic(&path, contents.as_bytes()).context("Failed to write draft file")?;
+        Ok(path)
+    }
+
+    /// `save` without blocking the async runtime
+    pub async fn save_async(&self) -> Result<PathBuf> {
+        let draft = self.clone();
+        blocking::run(move || draft.save()).await
+    }
+
+    /// Archive this draft (move to archive directory)
+    pub fn archive(&self) -> Result<PathBuf> {
+        let id = DraftId::parse(&self.id)?;
+        let archive_path = id.archive_path()?;
+        let contents = self.to_string()?;
+        write_atomic(&archive_path, contents.as_bytes())
+            .context("Failed to write archived draft")?;
+
+        // Remove from drafts directory
+        let draft_path = id.draft_path()?;
+        if draft_path.exists() {
+            fs::remove_file(&draft_path)?;
+        }
+
+        Ok(archive_path)
+    }
+
+    /// Local `photo:` entries that don't point at an existing file
+    pub fn missing_photos(&self) -> Vec<String> {
+        self.metadata
+            .photo
+            .iter()
+            .filter(|p| !p.starts_with("http://") && !p.starts_with("https://"))
+            .filter(|p| resolve_path(p, None).is_err())
+            .cloned()
+            .collect()
+    }
+
+    /// Check the draft for problems before publishing
+    pub fn lint(&self) -> Vec<LintWarning> {
+        let mut warnings: Vec<LintWarning> = self
+            .missing_photos()
+            .into_iter()
+            .map(|p| {
+                LintWarning::new(
+                    LintKind::BrokenPhoto,
+                    format!("Photo file not found: {}", p),
+                )
+                .about(p)
+            })
+            .collect();
+
+        let responses = self.metadata.response_properties();
+        for (property, url) in &responses {
+            if !is_http_url(url) {
+                warnings.push(
+                    LintWarning::new(
+                        LintKind::InvalidUrl,
+                        format!("{} is not an http(s) URL: {}", property, url),
+                    )
+                    .about(*url),
+                );
+            }
+        }
+
+        if let Some(slug) = &self.metadata.slug {
+            if !is_valid_slug(slug) {
+                warnings.push(
+                    LintWarning::new(
+                        LintKind::InvalidSlug,
+                        format!(
+                            "slug must be lowercase letters, digits, and hyphens: {}",
+                            slug
+                        ),
+                    )
+                    .about("slug"),
+                );
+            }
+        }
+
+        // Likes, reposts, bookmarks, and photo posts can stand on their own
+        if self.content.trim().is_empty() && self.metadata.photo.is_empty() && responses.is_empty()
+        {
+            warnings.push(LintWarning::new(
+                LintKind::EmptyContent,
+                "Draft has no content".to_string(),
+            ));
+        }
+
+        for src in find_media_references(&self.content) {
+            if resolve_path(&src, None).is_err() {
+                warnings.push(
+                    LintWarning::new(
+                        LintKind::MissingMedia,
+                        format!("Image file not found: {}", src),
+                    )
+                    .about(src),
+                );
+            }
+        }
+
+        for src in images_missing_alt(&self.content) {
+            warnings.push(
+                LintWarning::new(
+                    LintKind::MissingAlt,
+                    format!("Image has no alt text: {}", src),
+                )
+                .about(src),
+            );
+        }
+
+        warnings
+    }
+
+    /// `syndicate-to` values that aren't among the profile's known targets
+    pub fn lint_syndication(&self, known: &[SyndicationTarget]) -> Vec<LintWarning> {
+        // Nothing cached yet means nothing to check against
+        if known.is_empty() {
+            return Vec::new();
+        }
+
+        self.metadata
+            .syndicate_to
+            .iter()
+            .filter(|uid| !known.iter().any(|t| &t.uid == *uid))
+            .map(|uid| {
+                LintWarning::new(
+                    LintKind::UnknownSyndication,
+                    format!("Unknown syndication target: {}", uid),
+                )
+                .about(uid.as_str())
+            })
+            .collect()
+    }
+
+    /// List all draft IDs
+    pub fn list_all() -> Result<Vec<String>> {
+        let drafts_dir = get_drafts_dir()?;
+        let mut draft_ids = Vec::new();
+
+        for entry in fs::read_dir(drafts_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+
+            if path.extension().and_then(|s| s.to_str()) == Some("md") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    draft_ids.push(stem.to_string());
+                }
+            }
+        }
+
+        Ok(draft_ids)
+    }
+
+    /// Load every draft that parses, without blocking the async runtime
+    pub async fn load_all_async() -> Result<Vec<Draft>> {
+        blocking::run(|| {
+            Ok(Self::list_all()?
+                .iter()
+                .filter_map(|id| Self::load(id).ok())
+                .collect())
+        })
+        .await
+    }
+}
+
+/// Sources of Markdown and HTML images in the content that have no alt text
+fn images_missing_alt(content: &str) -> Vec<String> {
+    let mut sources: Vec<String> = MD_IMG_NO_ALT_RE
+        .captures_iter(content)
+        .map(|caps| caps[1].to_string())
+        .collect();
+
+    for tag in HTML_IMG_RE.find_iter(content) {
+        if !HTML_ALT_RE.is_match(tag.as_str()) {
+            let src = HTML_SRC_RE
+                .captures(tag.as_str())
+                .map(|caps| caps[1].to_string())
+                .unwrap_or_else(|| tag.as_str().to_string());
+            sources.push(src);
+        }
+    }
+
+    sources
+}
+
+/// Line of the file where the (trimmed) frontmatter starts, as `Draft::from_string` sees it
+fn frontmatter_first_line(source: &str) -> usize {
+    let Some((before, rest)) = source.split_once("---") else {
+        return 1;
+    };
+    let leading = &rest[..rest.len() - rest.trim_start().len()];
+    1 + before.matches('\n').count() + leading.matches('\n').count()
+}
+
+/// First line of the file containing `needle`, preferring a frontmatter key line
+fn find_line(source: &str, needle: &str) -> Option<usize> {
+    let key = format!("{}:", needle);
+    let lines: Vec<&str> = source.lines().collect();
+    lines
+        .iter()
+        .position(|line| line.trim_start().starts_with(&key))
+        .or_else(|| lines.iter().position(|line| line.contains(needle)))
+        .map(|index| index + 1)
+}
+
+/// The known key `key` was probably meant to be: snake_case, or letters swapped
+fn suggest_key(key: &str) -> Option<&'static str> {
+    let sorted = |s: &str| {
+        let mut chars: Vec<char> = s.chars().collect();
+        chars.sort_unstable();
+        chars
+    };
+    let normalized = key.to_lowercase().replace('_', "-");
+    FRONTMATTER_KEYS
+        .into_iter()
+        .find(|known| *known == normalized || sorted(known) == sorted(&normalized))
+}
+
+/// Frontmatter keys that no field of `DraftMetadata` reads
+fn unknown_keys(source: &str) -> Vec<LintWarning> {
+    let frontmatter = source.split("---").nth(1).unwrap_or("");
+    let Ok(serde_yaml::Value::Mapping(map)) = serde_yaml::from_str(frontmatter) else {
+        return Vec::new();
+    };
+
+    map.keys()
+        .filter_map(|key| match key {
+            serde_yaml::Value::String(key) => Some(key.clone()),
+            _ => None,
+        })
+        .filter(|key| !FRONTMATTER_KEYS.contains(&key.as_str()))
+        .map(|key| {
+            let hint = suggest_key(&key)
+                .map(|known| format!(" (did you mean '{}'?)", known))
+                .unwrap_or_default();
+            LintWarning::new(
+                LintKind::UnknownKey,
+                format!("Unknown frontmatter key '{}' is ignored{}", key, hint),
+            )
+            .about(key)
+        })
+        .collect()
+}
+
+/// Explain why a draft's frontmatter failed to parse, pointing at bad dates when possible
+fn frontmatter_problems(source: &str, error: &anyhow::Error) -> Vec<LintWarning> {
+    let frontmatter = source.split("---").nth(1).unwrap_or("");
+    let mut warnings = Vec::new();
+
+    if let Ok(serde_yaml::Value::Mapping(map)) = serde_yaml::from_str(frontmatter) {
+        for field in DATE_FIELDS {
+            let valid = match map.get(field) {
+                None | Some(serde_yaml::Value::Null) => true,
+                Some(serde_yaml::Value::String(s)) => DateTime::parse_from_rfc3339(s).is_ok(),
+                Some(_) => false,
+            };
+            if !valid {
+                warnings.push(
+                    LintWarning::new(
+                        LintKind::InvalidDate,
+                        format!("{} is not a valid ISO 8601 date", field),
+                    )
+                    .about(field),
+                );
+            }
+        }
+    }
+
+    if warnings.is_empty() {
+        let mut warning = LintWarning::new(LintKind::InvalidFrontmatter, format!("{:#}", error));
+        if let Some(location) = error
+            .downcast_ref::<serde_yaml::Error>()
+            .and_then(|e| e.location())
+        {
+            warning = warning.at(frontmatter_first_line(source) + location.line() - 1);
+        }
+        warnings.push(warning);
+    }
+
+    warnings
+}
+
+/// Lint a draft's source text, pinning each problem to a line where possible
+pub fn lint_source(
+    draft_id: &str,
+    source: &str,
+    config: Option<&Config>,
+) -> (Option<Draft>, Vec<LintWarning>) {
+    let (draft, mut warnings) = match Draft::from_string(draft_id.to_string(), source.to_string()) {
+        Ok(draft) => {
+            let mut warnings = draft.lint();
+            if let Some(config) = config {
+                let profile_name = config.profile_for_draft(draft.metadata.profile());
+                if let Some(profile) = config.get_profile(&profile_name) {
+                    warnings.extend(draft.lint_syndication(&profile.syndicate_to));
+                }
+            }
+            (Some(draft), warnings)
+        }
+        Err(e) => (None, frontmatter_problems(source, &e)),
+    };
+    warnings.extend(unknown_keys(source));
+
+    for warning in &mut warnings {
+        if warning.line.is_none() {
+            warning.line = warning
+                .needle
+                .as_deref()
+                .and_then(|needle| find_line(source, needle));
+        }
+    }
+    (draft, warnings)
+}
+
+/// Load and lint a draft, reporting unparseable frontmatter as lint problems
+/// instead of failing. The draft is `None` when it couldn't be parsed.
+pub fn lint_file(draft_id: &str) -> Result<(Option<Draft>, Vec<LintWarning>)> {
+    lint_with_config(draft_id, Config::load().ok().as_ref())
+}
+
+/// `lint_file` with an already loaded config
+pub fn lint_with_config(
+    draft_id: &str,
+    config: Option<&Config>,
+) -> Result<(Option<Draft>, Vec<LintWarning>)> {
+    let path = paths::draft_path(draft_id)?;
+    let source = fs::read_to_string(&path).context("Failed to read draft file")?;
+    Ok(lint_source(draft_id, &source, config))
+}
+
+/// Lint every draft as `lint_file` does, loading the config once, without blocking the
+/// async runtime. Drafts that can't be read are skipped.
+pub async fn lint_all_async() -> Result<Vec<(String, Option<Draft>, Vec<LintWarning>)>> {
+    blocking::run(|| {
+        let config = Config::load().ok();
+        Ok(Draft::list_all()?
+            .into_iter()
+            .filter_map(|id| {
+                let (draft, warnings) = lint_with_config(&id, config.as_ref()).ok()?;
+                Some((id, draft, warnings))
+            })
+            .collect())
+    })
+    .await
+}
+
+/// Whether a slug is URL-safe: lowercase ASCII letters, digits, and inner hyphens
+pub fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Whether a string parses as an http(s) URL
+pub fn is_http_url(url: &str) -> bool {
+    url::Url::parse(url)
+        .map(|u| matches!(u.scheme(), "http" | "https"))
+        .unwrap_or(false)
+}
+
+/// Parse every draft file in a directory, skipping files that don't parse
+pub fn load_dir(dir: &Path) -> Result<Vec<(PathBuf, Draft)>> {
+    let mut drafts = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("md") {
+            continue;
+        }
+        let id = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+        if let Ok(draft) = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| Draft::from_string(id, contents))
+        {
+            drafts.push((path, draft));
+        }
+    }
+    Ok(drafts)
+}
+
+fn categories_in(dirs: &[PathBuf]) -> Result<Vec<String>> {
+    let mut categories = std::collections::BTreeSet::new();
+
+    for dir in dirs {
+        for (_, draft) in load_dir(dir)? {
+            categories.extend(draft.metadata.category);
+        }
+    }
+
+    Ok(categories.into_iter().collect())
+}
+
+/// Collect every category used across local drafts and archived posts
+pub fn known_categories() -> Result<Vec<String>> {
+    categories_in(&[get_drafts_dir()?, get_archive_dir()?])
+}
+
+/// Collect every category of archived (published) posts
+pub fn archived_categories() -> Result<Vec<String>> {
+    categories_in(&[get_archive_dir()?])
+}
+
+/// `categories` that neither `known` (such as the server's `q=category` list) nor
+/// any archived post has used, which are often typos. Empty when there's nothing
+/// to compare against.
+pub fn unfamiliar_categories(categories: &[String], known: Vec<String>) -> Vec<String> {
+    if categories.is_empty() {
+        return Vec::new();
+    }
+    let mut used: HashSet<String> = known.into_iter().collect();
+    used.extend(archived_categories().unwrap_or_default());
+    categories_not_in(categories, &used)
+}
+
+fn categories_not_in(categories: &[String], used: &HashSet<String>) -> Vec<String> {
+    if used.is_empty() {
+        return Vec::new();
+    }
+    categories
+        .iter()
+        .filter(|category| !used.contains(*category))
+        .cloned()
+        .collect()
+}
+
+/// `known_categories` without blocking the async runtime
+pub async fn known_categories_async() -> Result<Vec<String>> {
+    blocking::run(known_categories).await
+}
+
+/// CRLF and lone CR line endings become LF, trailing whitespace is stripped from
+/// each line, and the text ends with exactly one newline. Two or more trailing
+/// spaces before a non-blank line are a markdown hard break, so those lines keep two.
+pub fn normalize_whitespace(text: &str) -> String {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let lines: Vec<&str> = text.trim_end().split('\n').collect();
+    let mut normalized = String::with_capacity(text.len() + 1);
+    for (idx, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_end();
+        normalized.push_str(trimmed);
+        let hard_break = line.ends_with("  ")
+            && !trimmed.is_empty()
+            && lines
+                .get(idx + 1)
+                .is_some_and(|next| !next.trim().is_empty());
+        if hard_break {
+            normalized.push_str("  ");
+        }
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// Derive a plain-text summary from the first paragraph of markdown content,
+/// truncated at a word boundary to at most `max_chars` characters
+pub fn auto_summary(content: &str, max_chars: usize) -> String {
+    lazy_static::lazy_static! {
+        static ref IMAGE: regex::Regex = regex::Regex::new(r"!\[[^\]]*\]\([^)]*\)").unwrap();
+        static ref LINK: regex::Regex = regex::Regex::new(r"\[([^\]]+)\]\([^)]*\)").unwrap();
+        static ref HTML: regex::Regex = regex::Regex::new(r"<[^>]+>").unwrap();
+    }
+
+    let paragraph = content
+        .split("\n\n")
+        .map(|p| {
+            let p = IMAGE.replace_all(p, "");
+            let p = LINK.replace_all(&p, "$1");
+            let p = HTML.replace_all(&p, "");
+            p.lines()
+                .filter(|line| !line.trim_start().starts_with('#'))
+                .collect::<Vec<_>>()
+                .join(" ")
+                .replace(['*', '_', '`'], "")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .find(|p| !p.is_empty())
+        .unwrap_or_default();
+
+    if paragraph.chars().count() <= max_chars {
+        return paragraph;
+    }
+
+    let cut: String = paragraph
+        .chars()
+        .take(max_chars.saturating_sub(1))
+        .collect();
+    let trimmed = match cut.rfind(char::is_whitespace) {
+        Some(idx) if idx > 0 => &cut[..idx],
+        _ => cut.as_str(),
+    };
+    format!(
+        "{}…",
+        trimmed.trim_end_matches(|c: char| c.is_ascii_punctuation())
+    )
+}
+
+/// Generate a new draft ID
+pub fn generate_draft_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draft_roundtrip() {
+        let original = Draft {
+            id: "test".to_string(),
+            metadata: DraftMetadata {
+                post_type: "article".to_string(),
+                name: Some("Test Post".to_string()),
+                category: vec!["test".to_string()],
+                ..Default::default()
+            },
+            content: "Test content".to_string(),
+        };
+
+        let serialized = original.to_string().unwrap();
+        let parsed = Draft::from_string("test".to_string(), serialized).unwrap();
+
+        assert_eq!(parsed.metadata.name, original.metadata.name);
+        assert_eq!(parsed.content, original.content);
+    }
+
+    #[test]
+    fn test_response_properties_roundtrip() {
+        let mut metadata = DraftMetadata {
+            post_type: ResponseKind::Reply.post_type().to_string(),
+            ..Default::default()
+        };
+        metadata.set_response_target(ResponseKind::Reply, "https://example.com/a".to_string());
+        metadata.set_response_target(ResponseKind::Bookmark, "ftp://example.com/b".to_string());
+
+        let draft = Draft {
+            id: "test".to_string(),
+            metadata,
+            content: "Nice post".to_string(),
+        };
+        let serialized = draft.to_string().unwrap();
+        assert!(serialized.contains("in-reply-to: https://example.com/a"));
+        assert!(!serialized.contains("like-of"));
+
+        let parsed = Draft::from_string("test".to_string(), serialized).unwrap();
+        assert_eq!(
+            parsed.metadata.response_properties(),
+            vec![
+                ("in-reply-to", "https://example.com/a"),
+                ("bookmark-of", "ftp://example.com/b")
+            ]
+        );
+        let warnings = parsed.lint();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, LintKind::InvalidUrl);
+        assert_eq!(
+            warnings[0].to_string(),
+            "bookmark-of is not an http(s) URL: ftp://example.com/b"
+        );
+    }
+
+    #[test]
+    fn test_normalize_whitespace() {
+        assert_eq!(normalize_whitespace("a \t\r\nb\rc\n\n\n"), "a\nb\nc\n");
+        assert_eq!(normalize_whitespace("no newline"), "no newline\n");
+        // Markdown hard breaks survive; trailing spaces before a blank line don't
+        assert_eq!(
+            normalize_whitespace("line one   \nline two  \n\nend"),
+            "line one  \nline two\n\nend\n"
+        );
+    }
+
+    #[test]
+    fn test_auto_summary() {
+        let content = "# Heading\n\n![pic](a.jpg)\n\nThis is **the** [first](https://x.y) paragraph.\n\nSecond.";
+        assert_eq!(auto_summary(content, 100), "This is the first paragraph.");
+
+        let long = "one two three four five six";
+        let summary = auto_summary(long, 12);
+        assert_eq!(summary, "one two…");
+        assert!(summary.chars().count() <= 12);
+
+        assert_eq!(auto_summary("", 10), "");
+    }
+
+    #[test]
+    fn test_missing_photos_ignores_remote_urls() {
+        let mut draft = Draft::new("test".to_string());
+        draft.metadata.photo = vec![
+            "https://example.com/photo.jpg".to_string(),
+            "/nonexistent/micropub-test-photo.jpg".to_string(),
+        ];
+
+        assert_eq!(
+            draft.missing_photos(),
+            vec!["/nonexistent/micropub-test-photo.jpg".to_string()]
+        );
+        assert_eq!(draft.lint().len(), 1);
+    }
+
+    #[test]
+    fn test_lint_missing_alt_text() {
+        let mut draft = Draft::new("test".to_string());
+        draft.content =
+            "![](a.jpg)\n![A dog](b.jpg)\n<img src=\"c.png\">\n<img alt=\"\" src=\"d.png\">"
+                .to_string();
+
+        // The images don't exist either, which is reported separately
+        let warnings: Vec<_> = draft
+            .lint()
+            .into_iter()
+            .filter(|w| w.kind != LintKind::MissingMedia)
+            .collect();
+        assert!(warnings.iter().all(|w| w.kind == LintKind::MissingAlt));
+        assert_eq!(
+            warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+            vec![
+                "Image has no alt text: a.jpg",
+                "Image has no alt text: c.png"
+            ]
+        );
+        assert!(!LintKind::MissingAlt.is_error());
+    }
+
+    #[test]
+    fn test_lint_syndication() {
+        let mut draft = Draft::new("test".to_string());
+        draft.metadata.syndicate_to = vec![
+            "https://social.example/@me".to_string(),
+            "https://typo.example".to_string(),
+        ];
+        let known = vec![SyndicationTarget {
+            uid: "https://social.example/@me".to_string(),
+            name: None,
+        }];
+
+        let warnings = draft.lint_syndication(&known);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, LintKind::UnknownSyndication);
+        assert!(draft.lint_syndication(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_frontmatter_problems_flags_bad_dates() {
+        let source = "---\ntype: note\npublished: next tuesday\n---\n\nHi";
+        let err = Draft::from_string("test".to_string(), source.to_string()).unwrap_err();
+        let warnings = frontmatter_problems(source, &err);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].kind, LintKind::InvalidDate);
+        assert_eq!(warnings[0].kind.badge(), "date");
+
+        let source = "---\ntype: [unclosed\n---\n";
+        let err = Draft::from_string("test".to_string(), source.to_string()).unwrap_err();
+        assert_eq!(
+            frontmatter_problems(source, &err)[0].kind,
+            LintKind::InvalidFrontmatter
+        );
+    }
+
+    #[test]
+    fn test_lint_source_reports_lines() {
+        let source = "---\ntype: note\ncategroy: [rust]\nin_reply_to: https://example.com\n---\n\nSee ![a cat](/nonexistent/micropub-cat.jpg)\n";
+        let (draft, warnings) = lint_source("test", source, None);
+        assert!(draft.is_some());
+        assert_eq!(
+            warnings.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+            vec![
+                "line 7: Image file not found: /nonexistent/micropub-cat.jpg",
+                "line 3: Unknown frontmatter key 'categroy' is ignored (did you mean 'category'?)",
+                "line 4: Unknown frontmatter key 'in_reply_to' is ignored (did you mean 'in-reply-to'?)",
+            ]
+        );
+        assert!(warnings[0].kind.is_error());
+        assert!(!warnings[1].kind.is_error());
+
+        let source = "---\ntype: note\nname: [unclosed\n---\n";
+        let (draft, warnings) = lint_source("test", source, None);
+        assert!(draft.is_none());
+        assert_eq!(warnings[0].kind, LintKind::InvalidFrontmatter);
+        assert!(warnings[0].line.is_some());
+
+        let source = "---\ntype: note\npublished: soon\n---\nHi";
+        assert_eq!(lint_source("test", source, None).1[0].line, Some(3));
+    }
+
+    #[test]
+    fn test_lint_empty_content() {
+        let mut draft = Draft::new("test".to_string());
+        assert_eq!(draft.lint()[0].kind, LintKind::EmptyContent);
+
+        draft.metadata.like_of = Some("https://example.com/post".to_string());
+        assert!(draft.lint().is_empty());
+    }
+
+    #[test]
+    fn test_is_valid_slug() {
+        assert!(is_valid_slug("my-first-post-2"));
+        assert!(!is_valid_slug("My-Post"));
+        assert!(!is_valid_slug("a/b"));
+        assert!(!is_valid_slug("-leading"));
+        assert!(!is_valid_slug(""));
+
+        let mut draft = Draft::new("test".to_string());
+        draft.content = "Hi".to_string();
+        draft.metadata.slug = Some("Hello World".to_string());
+        assert_eq!(draft.lint()[0].kind, LintKind::InvalidSlug);
+    }
+
+    #[test]
+    fn test_categories_not_in() {
+        let categories = vec!["rust".to_string(), "rsut".to_string()];
+        let used: HashSet<String> = ["rust".to_string(), "cli".to_string()].into();
+        assert_eq!(categories_not_in(&categories, &used), ["rsut"]);
+        // Nothing to compare against
+        assert!(categories_not_in(&categories, &HashSet::new()).is_empty());
+    }
+}
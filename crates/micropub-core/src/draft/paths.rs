@@ -0,0 +1,130 @@
+// ABOUTME: Draft ID validation and on-disk path resolution
+// ABOUTME: Single place that maps draft IDs to draft and archive file paths
+
+use anyhow::{bail, Context, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::config::{get_archive_dir, get_drafts_dir, get_scheduled_dir};
+
+/// Validate draft ID to prevent path traversal attacks
+pub fn validate_draft_id(draft_id: &str) -> Result<()> {
+    // Check for empty string (vacuous truth issue)
+    if draft_id.is_empty() {
+        bail!("Draft ID cannot be empty");
+    }
+
+    // Check for null bytes
+    if draft_id.contains('\0') {
+        bail!("Draft ID contains null byte");
+    }
+
+    // Check for path traversal attempts
+    if draft_id.contains("..") || draft_id.contains('/') || draft_id.contains('\\') {
+        bail!("Draft ID contains invalid path characters");
+    }
+
+    // Ensure only alphanumeric, hyphens, and underscores
+    if !draft_id
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("Draft ID must contain only alphanumeric characters, hyphens, and underscores");
+    }
+
+    Ok(())
+}
+
+/// A validated draft ID, safe to turn into a file path
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DraftId(String);
+
+impl DraftId {
+    /// Validate a raw draft ID
+    pub fn parse(id: &str) -> Result<Self> {
+        validate_draft_id(id)?;
+        Ok(Self(id.to_string()))
+    }
+
+    /// Extract the draft ID from a draft path (or bare ID) by its file stem
+    pub fn from_path(path: &str) -> Result<Self> {
+        let stem = Path::new(path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .context("Invalid draft path")?;
+        Self::parse(stem)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn file_name(&self) -> String {
+        format!("{}.md", self.0)
+    }
+
+    /// Path of the draft in the drafts directory
+    pub fn draft_path(&self) -> Result<PathBuf> {
+        Ok(get_drafts_dir()?.join(self.file_name()))
+    }
+
+    /// Path the draft is moved to when archived
+    pub fn archive_path(&self) -> Result<PathBuf> {
+        Ok(get_archive_dir()?.join(self.file_name()))
+    }
+
+    /// Path of the draft while it waits in the schedule queue
+    pub fn scheduled_path(&self) -> Result<PathBuf> {
+        Ok(get_scheduled_dir()?.join(self.file_name()))
+    }
+
+    /// Path of an existing draft, or an error naming the missing ID
+    pub fn existing_draft_path(&self) -> Result<PathBuf> {
+        let path = self.draft_path()?;
+        if !path.exists() {
+            bail!("Draft not found: {}", self.0);
+        }
+        Ok(path)
+    }
+}
+
+impl fmt::Display for DraftId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for DraftId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Validate a draft ID and return its path in the drafts directory
+pub fn draft_path(id: &str) -> Result<PathBuf> {
+    DraftId::parse(id)?.draft_path()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rejects_traversal() {
+        assert!(DraftId::parse("../etc/passwd").is_err());
+        assert!(DraftId::parse("").is_err());
+        assert_eq!(DraftId::parse("abc-123").unwrap().as_str(), "abc-123");
+    }
+
+    #[test]
+    fn test_from_path_uses_file_stem() {
+        assert_eq!(
+            DraftId::from_path("/tmp/drafts/abc-123.md")
+                .unwrap()
+                .as_str(),
+            "abc-123"
+        );
+        assert_eq!(DraftId::from_path("abc-123").unwrap().as_str(), "abc-123");
+        assert!(DraftId::from_path("/tmp/drafts/bad id.md").is_err());
+    }
+}
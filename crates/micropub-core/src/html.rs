@@ -0,0 +1,430 @@
+// ABOUTME: Conversion between HTML post content and Markdown for editing
+// ABOUTME: Handles the common block and inline elements returned by q=source
+
+use regex::Regex;
+use scraper::{ElementRef, Html, Node};
+use serde_json::Value;
+
+lazy_static::lazy_static! {
+    static ref WHITESPACE: Regex = Regex::new(r"\s+").unwrap();
+    static ref EXTRA_NEWLINES: Regex = Regex::new(r"\n{3,}").unwrap();
+    static ref IMAGE: Regex = Regex::new(r"!\[([^\]]*)\]\(([^)\s]+)\)").unwrap();
+    static ref LINK: Regex = Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").unwrap();
+    static ref BOLD: Regex = Regex::new(r"\*\*([^*]+)\*\*").unwrap();
+    static ref ITALIC: Regex = Regex::new(r"\*([^*]+)\*").unwrap();
+    static ref CODE_SPAN: Regex = Regex::new(r"`([^`]+)`").unwrap();
+    static ref AMPERSAND: Regex = Regex::new(r"&(#?[a-zA-Z0-9]+;)?").unwrap();
+    static ref BARE_LT: Regex = Regex::new(r"<([^a-zA-Z/!]|$)").unwrap();
+    static ref ORDERED_ITEM: Regex = Regex::new(r"^\d+\. ").unwrap();
+}
+
+/// Read a micropub `content` value as Markdown.
+///
+/// Plain strings are returned as-is; `{"html": ...}` objects are converted and
+/// flagged so updates can send HTML back. Returns `None` for other shapes.
+pub fn content_as_markdown(value: &Value) -> Option<(String, bool)> {
+    match value {
+        Value::String(s) => Some((s.clone(), false)),
+        Value::Object(obj) => match obj.get("html").and_then(|v| v.as_str()) {
+            Some(html) => Some((html_to_markdown(html), true)),
+            None => obj
+                .get("value")
+                .and_then(|v| v.as_str())
+                .map(|s| (s.to_string(), false)),
+        },
+        _ => None,
+    }
+}
+
+/// Convert an HTML fragment to Markdown
+pub fn html_to_markdown(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let markdown = children_to_markdown(fragment.root_element());
+
+    let cleaned: Vec<&str> = markdown
+        .lines()
+        .map(|line| if line.trim().is_empty() { "" } else { line })
+        .collect();
+    EXTRA_NEWLINES
+        .replace_all(&cleaned.join("\n"), "\n\n")
+        .trim()
+        .to_string()
+}
+
+fn children_to_markdown(element: ElementRef) -> String {
+    element
+        .children()
+        .map(|child| match child.value() {
+            Node::Text(text) => WHITESPACE.replace_all(text, " ").into_owned(),
+            Node::Element(_) => ElementRef::wrap(child)
+                .map(element_to_markdown)
+                .unwrap_or_default(),
+            _ => String::new(),
+        })
+        .collect()
+}
+
+fn block(content: &str) -> String {
+    format!("\n\n{}\n\n", content.trim())
+}
+
+fn element_to_markdown(element: ElementRef) -> String {
+    let inner = || children_to_markdown(element);
+
+    match element.value().name() {
+        "p" | "div" | "section" | "article" | "figure" | "figcaption" => block(&inner()),
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let level = element.value().name()[1..].parse().unwrap_or(1);
+            block(&format!("{} {}", "#".repeat(level), inner().trim()))
+        }
+        "br" => "\\\n".to_string(),
+        "hr" => block("---"),
+        "strong" | "b" => format!("**{}**", inner()),
+        "em" | "i" => format!("*{}*", inner()),
+        "code" => format!("`{}`", element.text().collect::<String>()),
+        "pre" => format!(
+            "\n\n```\n{}\n```\n\n",
+            element.text().collect::<String>().trim_end()
+        ),
+        "a" => match element.value().attr("href") {
+            Some(href) => format!("[{}]({})", inner().trim(), href),
+            None => inner(),
+        },
+        "img" => format!(
+            "![{}]({})",
+            element.value().attr("alt").unwrap_or(""),
+            element.value().attr("src").unwrap_or("")
+        ),
+        "blockquote" => {
+            let quoted: Vec<String> = html_to_markdown_lines(&inner())
+                .iter()
+                .map(|line| {
+                    if line.is_empty() {
+                        ">".to_string()
+                    } else {
+                        format!("> {}", line)
+                    }
+                })
+                .collect();
+            block(&quoted.join("\n"))
+        }
+        "ul" | "ol" => {
+            let ordered = element.value().name() == "ol";
+            let items: Vec<String> = element
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|child| child.value().name() == "li")
+                .enumerate()
+                .map(|(idx, li)| {
+                    let marker = if ordered {
+                        format!("{}. ", idx + 1)
+                    } else {
+                        "- ".to_string()
+                    };
+                    let indent = " ".repeat(marker.len());
+                    let lines = html_to_markdown_lines(&children_to_markdown(li));
+                    lines
+                        .iter()
+                        .enumerate()
+                        .map(|(i, line)| match (i, line.is_empty()) {
+                            (0, _) => format!("{}{}", marker, line),
+                            (_, true) => String::new(),
+                            _ => format!("{}{}", indent, line),
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .collect();
+            block(&items.join("\n"))
+        }
+        "script" | "style" => String::new(),
+        _ => inner(),
+    }
+}
+
+/// Normalize converted Markdown into trimmed lines with single blank separators
+fn html_to_markdown_lines(markdown: &str) -> Vec<String> {
+    EXTRA_NEWLINES
+        .replace_all(markdown.trim(), "\n\n")
+        .lines()
+        .map(|line| line.trim_end().to_string())
+        .collect()
+}
+
+/// Render the Markdown produced by [`html_to_markdown`] (and hand-written
+/// Markdown of the same shape) back to HTML
+pub fn markdown_to_html(markdown: &str) -> String {
+    let lines: Vec<&str> = markdown.lines().collect();
+    render_blocks(&lines)
+}
+
+fn render_blocks(lines: &[&str]) -> String {
+    let mut html = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            i += 1;
+        } else if trimmed.starts_with("```") {
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() && !lines[i].trim().starts_with("```") {
+                code.push(escape_html(lines[i]));
+                i += 1;
+            }
+            i += 1;
+            html.push(format!("<pre><code>{}</code></pre>", code.join("\n")));
+        } else if let Some(level) = heading_level(trimmed) {
+            html.push(format!(
+                "<h{level}>{}</h{level}>",
+                render_inline(trimmed[level..].trim())
+            ));
+            i += 1;
+        } else if trimmed == "---" || trimmed == "***" {
+            html.push("<hr>".to_string());
+            i += 1;
+        } else if trimmed.starts_with('>') {
+            let mut quoted = Vec::new();
+            while i < lines.len() && lines[i].trim().starts_with('>') {
+                let l = lines[i].trim().trim_start_matches('>');
+                quoted.push(l.strip_prefix(' ').unwrap_or(l));
+                i += 1;
+            }
+            html.push(format!(
+                "<blockquote>{}</blockquote>",
+                render_blocks(&quoted)
+            ));
+        } else if is_list_item(trimmed) {
+            let ordered = ORDERED_ITEM.is_match(trimmed);
+            let mut items: Vec<Vec<&str>> = Vec::new();
+            while i < lines.len() {
+                let l = lines[i];
+                let item = l.trim();
+                if is_list_item(item) {
+                    let content = match ORDERED_ITEM.find(item) {
+                        Some(marker) => &item[marker.end()..],
+                        None => &item[2..],
+                    };
+                    items.push(vec![content]);
+                } else if l.starts_with("  ") && !items.is_empty() {
+                    items.last_mut().unwrap().push(l.trim_start());
+                } else if l.trim().is_empty()
+                    && i + 1 < lines.len()
+                    && lines[i + 1].starts_with("  ")
+                    && !items.is_empty()
+                {
+                    items.last_mut().unwrap().push("");
+                } else {
+                    break;
+                }
+                i += 1;
+            }
+            if items.is_empty() {
+                // Never stall on a line the loop above didn't take
+                html.push(format!("<p>{}</p>", render_inline(trimmed)));
+                i += 1;
+                continue;
+            }
+            let tag = if ordered { "ol" } else { "ul" };
+            let rendered: Vec<String> = items
+                .iter()
+                .map(|item| {
+                    let body = if item.iter().any(|l| l.is_empty()) {
+                        render_blocks(item)
+                    } else {
+                        render_inline(&item.join("\n"))
+                    };
+                    format!("<li>{}</li>", body)
+                })
+                .collect();
+            html.push(format!("<{tag}>{}</{tag}>", rendered.join("")));
+        } else if trimmed.starts_with('<') {
+            // Raw HTML blocks pass through untouched
+            let mut raw = Vec::new();
+            while i < lines.len() && !lines[i].trim().is_empty() {
+                raw.push(lines[i]);
+                i += 1;
+            }
+            html.push(raw.join("\n"));
+        } else {
+            let mut paragraph = Vec::new();
+            while i < lines.len() && !lines[i].trim().is_empty() && !starts_block(lines[i]) {
+                paragraph.push(lines[i].trim());
+                i += 1;
+            }
+            if paragraph.is_empty() {
+                paragraph.push(trimmed);
+                i += 1;
+            }
+            html.push(format!("<p>{}</p>", render_inline(&paragraph.join("\n"))));
+        }
+    }
+
+    html.join("\n")
+}
+
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    ((1..=6).contains(&level) && line[level..].starts_with(' ')).then_some(level)
+}
+
+fn is_list_item(line: &str) -> bool {
+    line.starts_with("- ") || line.starts_with("* ") || ORDERED_ITEM.is_match(line)
+}
+
+fn starts_block(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.starts_with("```")
+        || trimmed.starts_with('>')
+        || heading_level(trimmed).is_some()
+        || is_list_item(trimmed)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escape an attribute value whose ampersands are already escaped
+fn escape_attribute(text: &str) -> String {
+    text.replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn render_inline(text: &str) -> String {
+    // Pull code spans out first so their contents aren't formatted
+    let mut spans = Vec::new();
+    let text = CODE_SPAN.replace_all(text, |caps: &regex::Captures| {
+        spans.push(format!("<code>{}</code>", escape_html(&caps[1])));
+        format!("\u{0}{}\u{0}", spans.len() - 1)
+    });
+
+    // Escape bare ampersands but leave existing entities alone
+    let text = AMPERSAND.replace_all(&text, |caps: &regex::Captures| match caps.get(1) {
+        Some(entity) => format!("&{}", entity.as_str()),
+        None => "&amp;".to_string(),
+    });
+    let text = BARE_LT.replace_all(&text, "&lt;$1");
+    let text = IMAGE.replace_all(&text, |caps: &regex::Captures| {
+        format!(
+            r#"<img src="{}" alt="{}">"#,
+            escape_attribute(&caps[2]),
+            escape_attribute(&caps[1])
+        )
+    });
+    let text = LINK.replace_all(&text, |caps: &regex::Captures| {
+        format!(
+            r#"<a href="{}">{}</a>"#,
+            escape_attribute(&caps[2]),
+            &caps[1]
+        )
+    });
+    let text = BOLD.replace_all(&text, "<strong>$1</strong>");
+    let text = ITALIC.replace_all(&text, "<em>$1</em>");
+    let mut text = text.replace("\\\n", "<br>\n");
+
+    for (idx, span) in spans.iter().enumerate() {
+        text = text.replace(&format!("\u{0}{}\u{0}", idx), span);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_markdown_inline() {
+        let html = r#"<p>Hello <strong>bold</strong>, <em>it</em> and <a href="https://x.y">a link</a>.</p>
+            <p><img src="https://x.y/a.jpg" alt="pic"><br>next &amp; <code>a &lt; b</code></p>"#;
+        assert_eq!(
+            html_to_markdown(html),
+            "Hello **bold**, *it* and [a link](https://x.y).\n\n![pic](https://x.y/a.jpg)\\\nnext & `a < b`"
+        );
+    }
+
+    #[test]
+    fn test_html_to_markdown_blocks() {
+        let html = "<h2>Title</h2><ul><li>one</li><li>two</li></ul>\
+                    <ol><li>first</li></ol><blockquote><p>quoted</p></blockquote>\
+                    <pre><code>let x = 1;\n</code></pre><script>alert(1)</script>";
+        assert_eq!(
+            html_to_markdown(html),
+            "## Title\n\n- one\n- two\n\n1. first\n\n> quoted\n\n```\nlet x = 1;\n```"
+        );
+    }
+
+    #[test]
+    fn test_markdown_to_html() {
+        let md = "## Title\n\nHello **bold** and [link](https://x.y?a=1&b=2).\n\n- one\n- two\n\n> quoted\n\n```\na < b\n```";
+        assert_eq!(
+            markdown_to_html(md),
+            "<h2>Title</h2>\n\
+             <p>Hello <strong>bold</strong> and <a href=\"https://x.y?a=1&amp;b=2\">link</a>.</p>\n\
+             <ul><li>one</li><li>two</li></ul>\n\
+             <blockquote><p>quoted</p></blockquote>\n\
+             <pre><code>a &lt; b</code></pre>"
+        );
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_structure() {
+        let html = "<p>One <em>two</em></p>\n<ol><li>a</li><li>b</li></ol>\n<p><img src=\"x.jpg\" alt=\"\"></p>";
+        let markdown = html_to_markdown(html);
+        let back = markdown_to_html(&markdown);
+        assert_eq!(html_to_markdown(&back), markdown);
+        assert!(back.contains("<ol><li>a</li><li>b</li></ol>"));
+    }
+
+    #[test]
+    fn test_content_as_markdown() {
+        use serde_json::json;
+
+        assert_eq!(
+            content_as_markdown(&json!("plain")),
+            Some(("plain".to_string(), false))
+        );
+        assert_eq!(
+            content_as_markdown(&json!({"html": "<p><b>hi</b></p>", "value": "hi"})),
+            Some(("**hi**".to_string(), true))
+        );
+        assert_eq!(
+            content_as_markdown(&json!({"value": "hi"})),
+            Some(("hi".to_string(), false))
+        );
+        assert_eq!(content_as_markdown(&json!(1)), None);
+    }
+
+    #[test]
+    fn test_indented_list_items() {
+        assert_eq!(
+            markdown_to_html("Intro\n\n - one\n - two"),
+            "<p>Intro</p>\n<ul><li>one</li><li>two</li></ul>"
+        );
+        assert_eq!(
+            markdown_to_html("Intro\n\n 1. first\n 2. second\n\nAfter"),
+            "<p>Intro</p>\n<ol><li>first</li><li>second</li></ol>\n<p>After</p>"
+        );
+    }
+
+    #[test]
+    fn test_attributes_are_escaped() {
+        assert_eq!(
+            render_inline(r#"![a "b" <c>](x.jpg"onerror="y) [z](https://x.y/"><script>)"#),
+            r#"<img src="x.jpg&quot;onerror=&quot;y" alt="a &quot;b&quot; &lt;c&gt;"> <a href="https://x.y/&quot;&gt;&lt;script&gt;">z</a>"#
+        );
+    }
+
+    #[test]
+    fn test_inline_code_is_not_formatted() {
+        assert_eq!(
+            render_inline("use `*ptr` and *this*"),
+            "use <code>*ptr</code> and <em>this</em>"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+// ABOUTME: Conditional GET caching for micropub queries using ETag and Last-Modified
+// ABOUTME: Stores validators and response bodies on disk so unchanged listings return 304
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::get_cache_dir;
+use crate::retry::{send_with_retry, RetryPolicy};
+use crate::transport::{default_transport, HttpRequest, Transport};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: Value,
+}
+
+fn cache_path(url: &str) -> Result<PathBuf> {
+    let dir = get_cache_dir()?.join("http");
+    fs::create_dir_all(&dir)?;
+    let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+    Ok(dir.join(format!("{}.json", key)))
+}
+
+fn load(url: &str) -> Option<CachedResponse> {
+    let contents = fs::read_to_string(cache_path(url).ok()?).ok()?;
+    serde_json::from_str::<CachedResponse>(&contents)
+        .ok()
+        .filter(|cached| cached.url == url)
+}
+
+/// GET a JSON document, retrying transient failures and revalidating any cached
+/// copy with If-None-Match / If-Modified-Since. `action` names the operation in
+/// error messages.
+pub async fn get_json(url: &str, token: &str, action: &str) -> Result<Value> {
+    let transport = default_transport();
+    get_json_with(
+        transport.as_ref(),
+        url,
+        token,
+        action,
+        &RetryPolicy::configured(),
+    )
+    .await
+}
+
+async fn get_json_with(
+    transport: &dyn Transport,
+    url: &str,
+    token: &str,
+    action: &str,
+    policy: &RetryPolicy,
+) -> Result<Value> {
+    let cached = load(url);
+
+    let mut request = HttpRequest::get(url).header("Authorization", format!("Bearer {}", token));
+    if let Some(ref cached) = cached {
+        if let Some(ref etag) = cached.etag {
+            request = request.header("If-None-Match", etag);
+        }
+        if let Some(ref last_modified) = cached.last_modified {
+            request = request.header("If-Modified-Since", last_modified);
+        }
+    }
+
+    let response = send_with_retry(policy, true, "Request", url, || {
+        transport.send(request.clone())
+    })
+    .await
+    .with_context(|| format!("Failed to {}", action))?;
+
+    if response.status == 304 {
+        if let Some(cached) = cached {
+            return Ok(cached.body);
+        }
+    }
+
+    if !response.is_success() {
+        anyhow::bail!(
+            "Failed to {}: HTTP {}\n{}",
+            action,
+            response.status,
+            response.text()
+        );
+    }
+
+    let etag = response.header_value("ETag").map(String::from);
+    let last_modified = response.header_value("Last-Modified").map(String::from);
+
+    let body: Value = serde_json::from_slice(&response.body).context("Failed to parse response")?;
+
+    // Only responses with validators are worth caching
+    if etag.is_some() || last_modified.is_some() {
+        let entry = CachedResponse {
+            url: url.to_string(),
+            etag,
+            last_modified,
+            body: body.clone(),
+        };
+        // A failed cache write shouldn't fail the request
+        if let (Ok(path), Ok(contents)) = (cache_path(url), serde_json::to_string(&entry)) {
+            let _ = fs::write(path, contents);
+        }
+    }
+
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_path_is_stable_per_url() {
+        crate::config::use_temp_data_dir();
+        let a = cache_path("https://example.com/micropub?q=source&limit=10").unwrap();
+        let b = cache_path("https://example.com/micropub?q=source&limit=10").unwrap();
+        let c = cache_path("https://example.com/micropub?q=source&limit=20").unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(!a.to_string_lossy().contains("example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_get_json_retries_transient_failures() {
+        crate::config::use_temp_data_dir();
+        use crate::transport::{HttpResponse, TransportFuture};
+        use std::sync::Mutex;
+
+        /// Busy on the first request, then answers
+        struct Busy(Mutex<u32>);
+
+        impl Transport for Busy {
+            fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+                let mut sent = self.0.lock().unwrap();
+                *sent += 1;
+                let response = if *sent == 1 {
+                    HttpResponse::new(503).header("Retry-After", "0")
+                } else {
+                    HttpResponse::new(200).body(r#"{"properties": {"name": ["Hello"]}}"#)
+                };
+                Box::pin(async move { Ok(response) })
+            }
+        }
+
+        let transport = Busy(Mutex::new(0));
+        let body = get_json_with(
+            &transport,
+            "https://example.com/micropub?q=source",
+            "token",
+            "fetch post",
+            &RetryPolicy::default(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(body["properties"]["name"][0], "Hello");
+        assert_eq!(*transport.0.lock().unwrap(), 2);
+    }
+}
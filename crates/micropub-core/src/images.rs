@@ -0,0 +1,485 @@
+// ABOUTME: Optional image pre-processing applied to media before it's uploaded
+// ABOUTME: Scales large photos down, recompresses JPEGs, converts to WebP/AVIF, and drops EXIF such as GPS
+
+#[cfg(feature = "images")]
+use anyhow::{anyhow, Context};
+use anyhow::{bail, Result};
+#[cfg(feature = "avif")]
+use image::codecs::avif::AvifEncoder;
+#[cfg(feature = "images")]
+use image::{
+    codecs::jpeg::JpegEncoder, imageops::FilterType, metadata::Orientation, DynamicImage,
+    ImageDecoder, ImageFormat, ImageReader,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+#[cfg(feature = "images")]
+use std::fs::File;
+#[cfg(feature = "images")]
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::Config;
+
+/// `[images]` settings from config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct ImageSettings {
+    /// Process images before uploading them; off unless turned on
+    pub enabled: bool,
+    /// Larger images are scaled down to fit, keeping their aspect ratio
+    pub max_width: u32,
+    pub max_height: u32,
+    /// Quality (1-100) of JPEGs that are re-encoded
+    pub jpeg_quality: u8,
+    /// Re-encode images that carry EXIF metadata even when they fit, so it isn't uploaded
+    pub strip_exif: bool,
+    /// Convert images to this format before uploading; unset keeps their own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<OutputFormat>,
+    /// Quality (1-100) of WebP and AVIF output
+    pub quality: u8,
+}
+
+/// Format `[images] format` converts uploads to
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Lossy WebP at `quality`
+    Webp,
+    /// Lossy AVIF at `quality`; needs a build with the `avif` feature
+    Avif,
+}
+
+impl OutputFormat {
+    #[cfg(feature = "images")]
+    fn image_format(self) -> ImageFormat {
+        match self {
+            OutputFormat::Webp => ImageFormat::WebP,
+            OutputFormat::Avif => ImageFormat::Avif,
+        }
+    }
+
+    #[cfg(feature = "images")]
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Webp => "webp",
+            OutputFormat::Avif => "avif",
+        }
+    }
+
+    /// Whether this build can write the format
+    pub fn supported(self) -> bool {
+        match self {
+            OutputFormat::Webp => cfg!(feature = "images"),
+            OutputFormat::Avif => cfg!(feature = "avif"),
+        }
+    }
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_width: 2048,
+            max_height: 2048,
+            jpeg_quality: 85,
+            strip_exif: true,
+            format: None,
+            quality: 70,
+        }
+    }
+}
+
+static NO_PROCESS: AtomicBool = AtomicBool::new(false);
+
+/// Upload images as they are for the rest of the process (`--no-process`)
+pub fn set_no_process(no_process: bool) {
+    NO_PROCESS.store(no_process, Ordering::Relaxed);
+}
+
+impl ImageSettings {
+    /// The settings from config.toml, or the defaults; disabled by `--no-process`
+    pub fn configured() -> Self {
+        let settings = Config::load().map(|c| c.images).unwrap_or_default();
+        Self {
+            enabled: settings.enabled && !NO_PROCESS.load(Ordering::Relaxed),
+            ..settings
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    #[cfg(feature = "images")]
+    fn too_large(&self, (width, height): (u32, u32)) -> bool {
+        width > self.max_width || height > self.max_height
+    }
+}
+
+/// A processed copy of an image in its own temp directory, removed when dropped
+pub struct Processed {
+    dir: PathBuf,
+    path: PathBuf,
+    converted: bool,
+}
+
+impl Processed {
+    /// The copy to upload; it has the original's file name, with a `.webp` or
+    /// `.avif` extension when it was converted
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the copy is in the `[images] format` rather than the original's
+    pub fn converted(&self) -> bool {
+        self.converted
+    }
+}
+
+impl Drop for Processed {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// Encode `image` to `path`, as RGB or RGBA for the WebP and AVIF encoders.
+/// The `image` crate only writes lossless WebP, so libwebp encodes it instead.
+#[cfg(feature = "images")]
+fn write_image(
+    image: &DynamicImage,
+    path: &Path,
+    format: ImageFormat,
+    settings: &ImageSettings,
+) -> Result<()> {
+    let rgb = || {
+        if image.color().has_alpha() {
+            DynamicImage::ImageRgba8(image.to_rgba8())
+        } else {
+            DynamicImage::ImageRgb8(image.to_rgb8())
+        }
+    };
+
+    let mut out = BufWriter::new(File::create(path)?);
+    match format {
+        ImageFormat::Jpeg => image.write_with_encoder(JpegEncoder::new_with_quality(
+            &mut out,
+            settings.jpeg_quality.clamp(1, 100),
+        ))?,
+        #[cfg(feature = "avif")]
+        ImageFormat::Avif => rgb().write_with_encoder(AvifEncoder::new_with_speed_quality(
+            &mut out,
+            6,
+            settings.quality.clamp(1, 100),
+        ))?,
+        ImageFormat::WebP => {
+            let pixels = rgb();
+            let (width, height) = (pixels.width(), pixels.height());
+            let encoder = match &pixels {
+                DynamicImage::ImageRgba8(rgba) => webp::Encoder::from_rgba(rgba, width, height),
+                _ => webp::Encoder::from_rgb(pixels.as_bytes(), width, height),
+            };
+            let encoded = encoder
+                .encode_simple(false, settings.quality.clamp(1, 100) as f32)
+                .map_err(|e| anyhow!("Failed to encode WebP: {:?}", e))?;
+            out.write_all(&encoded)?;
+        }
+        _ => image.write_to(&mut out, format)?,
+    }
+    out.into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()
+        .context("Failed to write processed image")
+}
+
+/// Camera formats that carry EXIF metadata `process` can't rewrite
+fn may_carry_exif(path: &Path) -> bool {
+    const FORMATS: [&str; 6] = ["heic", "heif", "avif", "tif", "tiff", "dng"];
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| FORMATS.contains(&ext.to_lowercase().as_str()))
+}
+
+#[cfg(feature = "images")]
+fn file_size(path: &Path) -> Result<u64> {
+    Ok(fs::metadata(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?
+        .len())
+}
+
+/// Scale down, convert, and re-encode the JPEG, PNG, or WebP image at `path`
+/// as `settings` ask. `None` means it should be uploaded as it is: processing
+/// is off, it isn't one of those formats, or it already fits, is in the wanted
+/// format, and has no EXIF metadata to strip. HEIC, TIFF, and other camera
+/// formats it can't rewrite are an error while `strip_exif` is on. A
+/// conversion that comes out larger than the original is dropped. Re-encoded
+/// images never keep their metadata; the EXIF orientation is applied to the
+/// pixels first so photos stay upright.
+#[cfg(feature = "images")]
+pub fn process(path: &Path, settings: &ImageSettings) -> Result<Option<Processed>> {
+    if !settings.enabled {
+        return Ok(None);
+    }
+    let format = match ImageFormat::from_path(path) {
+        Ok(format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)) => format,
+        _ if settings.strip_exif && may_carry_exif(path) => bail!(
+            "Can't remove EXIF metadata from {}; convert it to JPEG first",
+            path.display()
+        ),
+        _ => return Ok(None),
+    };
+    let target = settings
+        .format
+        .filter(|target| {
+            if !target.supported() {
+                log::warn!(
+                    "This build can't write {} images (it needs the `{}` feature); uploading {} in its own format",
+                    target.extension(),
+                    target.extension(),
+                    path.display()
+                );
+            }
+            target.supported()
+        })
+        .filter(|target| target.image_format() != format);
+
+    let reader = ImageReader::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?
+        .with_guessed_format()?;
+    let mut decoder = reader
+        .into_decoder()
+        .with_context(|| format!("Failed to read image {}", path.display()))?;
+    let exif = decoder.exif_metadata()?;
+    let resize = settings.too_large(decoder.dimensions());
+    let strip = settings.strip_exif && exif.is_some();
+    if !resize && !strip && target.is_none() {
+        return Ok(None);
+    }
+
+    let mut image = DynamicImage::from_decoder(decoder)
+        .with_context(|| format!("Failed to decode image {}", path.display()))?;
+    if let Some(orientation) = exif.as_deref().and_then(Orientation::from_exif_chunk) {
+        image.apply_orientation(orientation);
+    }
+    if resize {
+        image = image.resize(
+            settings.max_width,
+            settings.max_height,
+            FilterType::Lanczos3,
+        );
+    }
+
+    let filename = path.file_name().context("Invalid filename")?;
+    let dir = std::env::temp_dir().join(format!("micropub-upload-{}", uuid::Uuid::new_v4()));
+    fs::create_dir(&dir).context("Failed to create temp directory")?;
+    let mut processed = Processed {
+        path: dir.join(filename),
+        dir,
+        converted: false,
+    };
+
+    if let Some(target) = target {
+        let converted = processed.path.with_extension(target.extension());
+        write_image(&image, &converted, target.image_format(), settings)?;
+        if file_size(&converted)? < file_size(path)? {
+            processed.path = converted;
+            processed.converted = true;
+            return Ok(Some(processed));
+        }
+        log::info!(
+            "{} isn't any smaller as {}; keeping its format",
+            path.display(),
+            target.extension()
+        );
+        fs::remove_file(&converted)?;
+        if !resize && !strip {
+            return Ok(None);
+        }
+    }
+
+    write_image(&image, &processed.path, format, settings)?;
+    Ok(Some(processed))
+}
+
+/// Without the `images` feature nothing can be processed, so processing an
+/// image while `[images]` is on is an error; other files go up as they are
+#[cfg(not(feature = "images"))]
+pub fn process(path: &Path, settings: &ImageSettings) -> Result<Option<Processed>> {
+    let is_image = mime_guess::from_path(path)
+        .first()
+        .is_some_and(|mime| mime.type_() == "image");
+    if !settings.enabled || !(is_image || may_carry_exif(path)) {
+        return Ok(None);
+    }
+    bail!(
+        "This build can't process {} (it needs the `images` feature)",
+        path.display()
+    )
+}
+
+#[cfg(all(test, feature = "images"))]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    fn enabled() -> ImageSettings {
+        ImageSettings {
+            enabled: true,
+            max_width: 100,
+            max_height: 50,
+            ..ImageSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_large_jpeg_is_scaled_to_fit() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("big.jpg");
+        RgbImage::from_pixel(400, 100, Rgb([200, 10, 10]))
+            .save(&path)
+            .unwrap();
+
+        let processed = process(&path, &enabled()).unwrap().unwrap();
+        assert_eq!(processed.path().file_name().unwrap(), "big.jpg");
+        assert_eq!(
+            image::image_dimensions(processed.path()).unwrap(),
+            (100, 25)
+        );
+
+        let temp = processed.path().parent().unwrap().to_path_buf();
+        drop(processed);
+        assert!(!temp.exists());
+    }
+
+    #[test]
+    fn test_exif_is_stripped_after_applying_orientation() {
+        use image::ImageEncoder;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("phone.jpg");
+        // Little-endian TIFF header and one IFD entry: Orientation = 6 (rotate 90°)
+        let exif = [
+            b"II*\0".as_slice(),
+            &8u32.to_le_bytes(),
+            &1u16.to_le_bytes(),
+            &[0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0],
+        ]
+        .concat();
+        let mut encoder = JpegEncoder::new(File::create(&path).unwrap());
+        encoder.set_exif_metadata(exif).unwrap();
+        DynamicImage::ImageRgb8(RgbImage::new(40, 20))
+            .write_with_encoder(encoder)
+            .unwrap();
+
+        let processed = process(&path, &enabled()).unwrap().unwrap();
+        let mut decoder = ImageReader::open(processed.path())
+            .unwrap()
+            .into_decoder()
+            .unwrap();
+        assert_eq!(decoder.dimensions(), (20, 40));
+        assert_eq!(decoder.exif_metadata().unwrap(), None);
+
+        let keep = ImageSettings {
+            strip_exif: false,
+            ..enabled()
+        };
+        assert!(process(&path, &keep).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_images_that_need_nothing_are_left_alone() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let small = dir.path().join("small.png");
+        RgbImage::new(10, 10).save(&small).unwrap();
+        let text = dir.path().join("notes.txt");
+        fs::write(&text, "not an image").unwrap();
+
+        assert!(process(&small, &enabled()).unwrap().is_none());
+        assert!(process(&text, &enabled()).unwrap().is_none());
+
+        let big = dir.path().join("big.png");
+        RgbImage::new(400, 400).save(&big).unwrap();
+        assert!(process(&big, &ImageSettings::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_conversion_kept_only_when_smaller() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let webp = ImageSettings {
+            format: Some(OutputFormat::Webp),
+            ..enabled()
+        };
+
+        // Flat colour is smaller as WebP than as PNG
+        let flat = dir.path().join("flat.png");
+        RgbImage::from_pixel(40, 20, Rgb([30, 60, 90]))
+            .save(&flat)
+            .unwrap();
+        let processed = process(&flat, &webp).unwrap().unwrap();
+        assert!(processed.converted());
+        assert_eq!(processed.path().file_name().unwrap(), "flat.webp");
+        assert_eq!(
+            ImageFormat::from_path(processed.path()).unwrap(),
+            ImageFormat::WebP
+        );
+        assert_eq!(image::image_dimensions(processed.path()).unwrap(), (40, 20));
+
+        // Noise saved as a rough JPEG is larger as a high-quality WebP
+        let noisy = dir.path().join("noisy.jpg");
+        let noise = noise(40, 20);
+        noise
+            .write_with_encoder(JpegEncoder::new_with_quality(
+                &mut File::create(&noisy).unwrap(),
+                10,
+            ))
+            .unwrap();
+        let best = ImageSettings {
+            quality: 100,
+            ..webp
+        };
+        assert!(process(&noisy, &best).unwrap().is_none());
+    }
+
+    fn noise(width: u32, height: u32) -> DynamicImage {
+        let mut seed = 7u32;
+        DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let [r, g, b, _] = seed.to_le_bytes();
+            Rgb([r, g, b])
+        }))
+    }
+
+    #[test]
+    fn test_webp_follows_quality() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let image = noise(64, 64);
+        let size = |quality| {
+            let path = dir.path().join(format!("q{}.webp", quality));
+            let settings = ImageSettings {
+                quality,
+                ..ImageSettings::default()
+            };
+            write_image(&image, &path, ImageFormat::WebP, &settings).unwrap();
+            assert_eq!(image::image_dimensions(&path).unwrap(), (64, 64));
+            file_size(&path).unwrap()
+        };
+        assert!(size(10) < size(90));
+    }
+
+    #[test]
+    fn test_image_settings_from_toml() {
+        let settings: ImageSettings = toml::from_str("enabled = true\nmax_width = 1600").unwrap();
+        assert!(settings.enabled && settings.strip_exif);
+        assert_eq!((settings.max_width, settings.max_height), (1600, 2048));
+        assert!(settings.too_large((1601, 10)));
+        assert!(!settings.too_large((1600, 2048)));
+        assert_eq!(settings.format, None);
+
+        let settings: ImageSettings =
+            toml::from_str("enabled = true\nformat = \"avif\"\nquality = 60").unwrap();
+        assert_eq!(settings.format, Some(OutputFormat::Avif));
+        assert_eq!(settings.quality, 60);
+    }
+}
@@ -0,0 +1,20 @@
+// ABOUTME: Micropub protocol client, configuration, media, and draft model
+// ABOUTME: Library core of the micropub CLI; it doesn't print or spawn processes
+
+pub mod blocking;
+pub mod client;
+pub mod config;
+pub mod draft;
+pub mod html;
+pub mod http_cache;
+pub mod images;
+pub mod media;
+pub mod media_sidecar;
+pub mod polite;
+pub mod post_notes;
+pub mod retry;
+pub mod server_config;
+pub mod transform;
+pub mod transport;
+
+pub use anyhow::{Error, Result};
@@ -0,0 +1,903 @@
+// ABOUTME: Media file handling and upload functionality
+// ABOUTME: Detects local file references, uploads to media endpoint, replaces URLs
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use futures_util::{stream, StreamExt};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::config::Config;
+use crate::images::{self, ImageSettings, Processed};
+use crate::media_sidecar::{self, MediaSidecar};
+use crate::retry::{send_with_retry, RetryPolicy};
+use crate::transport::{default_transport, BodyStream, HttpRequest, StreamBody, Transport};
+
+lazy_static! {
+    static ref MD_IMG_RE: Regex = Regex::new(r"!\[(.*?)\]\((.*?)\)").unwrap();
+    static ref HTML_IMG_RE: Regex = Regex::new(r#"<img[^>]+src=["']([^"']+)["']"#).unwrap();
+}
+
+/// Find all media file references in content
+pub fn find_media_references(content: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+
+    // Markdown images: ![alt](path)
+    for cap in MD_IMG_RE.captures_iter(content) {
+        if let Some(path) = cap.get(2) {
+            let path_str = path.as_str();
+            if is_local_path(path_str) {
+                refs.push(path_str.to_string());
+            }
+        }
+    }
+
+    // HTML img tags: <img src="path">
+    for cap in HTML_IMG_RE.captures_iter(content) {
+        if let Some(path) = cap.get(1) {
+            let path_str = path.as_str();
+            if is_local_path(path_str) {
+                refs.push(path_str.to_string());
+            }
+        }
+    }
+
+    refs
+}
+
+/// Alt text of the first Markdown image in `content` that points at `path`
+pub fn alt_text_for(content: &str, path: &str) -> Option<String> {
+    MD_IMG_RE
+        .captures_iter(content)
+        .find(|cap| &cap[2] == path)
+        .map(|cap| cap[1].trim().to_string())
+        .filter(|alt| !alt.is_empty())
+}
+
+/// Check if a path is local (not a URL)
+fn is_local_path(path: &str) -> bool {
+    !path.starts_with("http://") && !path.starts_with("https://")
+}
+
+/// Resolve a path (expand ~, handle relative paths)
+pub fn resolve_path(path: &str, base_dir: Option<&Path>) -> Result<PathBuf> {
+    // Validate input doesn't contain path traversal attempts
+    if path.contains("..") {
+        anyhow::bail!("Path traversal not allowed in file paths: {}", path);
+    }
+
+    let expanded = if let Some(stripped) = path.strip_prefix("~/") {
+        let home = dirs::home_dir().context("Could not determine home directory")?;
+        home.join(stripped)
+    } else if path.starts_with('/') {
+        PathBuf::from(path)
+    } else if let Some(base) = base_dir {
+        base.join(path)
+    } else {
+        PathBuf::from(path)
+    };
+
+    // Verify file exists and is accessible
+    if !expanded.exists() {
+        anyhow::bail!("File not found or inaccessible: {}", expanded.display());
+    }
+
+    Ok(expanded)
+}
+
+/// How far along a media upload is, for progress displays
+#[derive(Debug, Clone, PartialEq)]
+pub struct UploadProgress {
+    /// Name of the file being sent
+    pub file: String,
+    pub sent: u64,
+    pub total: u64,
+    /// Time since this attempt started sending
+    pub elapsed: Duration,
+}
+
+impl UploadProgress {
+    pub fn percent(&self) -> u64 {
+        (self.sent * 100).checked_div(self.total).unwrap_or(100)
+    }
+
+    /// Bytes per second so far
+    pub fn rate(&self) -> f64 {
+        self.sent as f64 / self.elapsed.as_secs_f64().max(0.001)
+    }
+
+    /// Time left at the current rate, once anything has been sent
+    pub fn eta(&self) -> Option<Duration> {
+        (self.sent > 0).then(|| {
+            Duration::from_secs_f64(self.total.saturating_sub(self.sent) as f64 / self.rate())
+        })
+    }
+}
+
+type UploadObserver = Box<dyn Fn(&UploadProgress) + Send + Sync>;
+
+static UPLOAD_OBSERVER: RwLock<Option<UploadObserver>> = RwLock::new(None);
+
+/// Call `observer` as each chunk of an upload is handed to the connection,
+/// replacing any earlier observer. The CLI draws a progress bar from it.
+pub fn set_upload_observer(observer: impl Fn(&UploadProgress) + Send + Sync + 'static) {
+    *UPLOAD_OBSERVER.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(observer));
+}
+
+fn report_upload(progress: &UploadProgress) {
+    if let Some(observer) = UPLOAD_OBSERVER
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+    {
+        observer(progress);
+    }
+}
+
+/// `[upload]` settings from config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct UploadSettings {
+    /// Files larger than this many MB are streamed from disk instead of read into memory
+    pub stream_threshold_mb: u64,
+    /// Retry a failed streamed upload from where the server says it stopped,
+    /// if it answers `HEAD` with an `Upload-Offset`
+    pub resume: bool,
+}
+
+impl Default for UploadSettings {
+    fn default() -> Self {
+        Self {
+            stream_threshold_mb: 16,
+            resume: true,
+        }
+    }
+}
+
+impl UploadSettings {
+    /// The settings from config.toml, or the defaults
+    pub fn configured() -> Self {
+        Config::load().map(|c| c.upload).unwrap_or_default()
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether a file of `size` bytes is streamed rather than read into memory
+    pub fn streams(&self, size: u64) -> bool {
+        size > self.stream_threshold_mb.saturating_mul(1_000_000)
+    }
+}
+
+/// Identifies a streamed upload across attempts, so the server can resume it
+const UPLOAD_ID_HEADER: &str = "Upload-Id";
+/// Bytes the server already has (on `HEAD`) or where this body starts (on `POST`)
+const UPLOAD_OFFSET_HEADER: &str = "Upload-Offset";
+
+/// Size of each piece of an upload body, and so of each progress step
+const UPLOAD_CHUNK: usize = 64 * 1024;
+
+/// Where an upload's bytes come from
+#[derive(Clone)]
+enum UploadSource {
+    Memory(Bytes),
+    /// Read a chunk at a time, for files too large to hold in memory
+    File(PathBuf),
+}
+
+/// A `multipart/form-data` body with the upload from byte `offset` on as its
+/// `file` field, fed to the connection in chunks that report progress as each
+/// one is taken. Returns the body and its content type.
+fn multipart_body(
+    file: &str,
+    mime_type: &str,
+    source: UploadSource,
+    offset: u64,
+    total: u64,
+) -> (StreamBody, String) {
+    let boundary = format!("micropub-{}", uuid::Uuid::new_v4().simple());
+    // Quotes and line breaks would end the header early, so they're percent-encoded as browsers do
+    let filename = file
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    let head = Bytes::from(format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+        boundary, filename, mime_type
+    ));
+    let tail = Bytes::from(format!("\r\n--{}--\r\n", boundary));
+    let length = head.len() as u64 + (total - offset) + tail.len() as u64;
+
+    let file = file.to_string();
+    let body = StreamBody::new(length, move || {
+        let (head, tail) = (head.clone(), tail.clone());
+        let parts: [BodyStream; 3] = [
+            Box::pin(stream::once(async move { Ok(head) })),
+            progress_stream(&file, source.clone(), offset, total),
+            Box::pin(stream::once(async move { Ok(tail) })),
+        ];
+        Box::pin(stream::iter(parts).flatten())
+    });
+    (body, format!("multipart/form-data; boundary={}", boundary))
+}
+
+/// The upload from byte `offset` on, in chunks, reporting progress as each one is taken
+fn progress_stream(file: &str, source: UploadSource, offset: u64, total: u64) -> BodyStream {
+    struct State {
+        file: String,
+        source: UploadSource,
+        reader: Option<tokio::fs::File>,
+        sent: u64,
+        start: Instant,
+    }
+
+    let state = State {
+        file: file.to_string(),
+        source,
+        reader: None,
+        sent: offset,
+        start: Instant::now(),
+    };
+    let chunks = stream::unfold(state, move |mut state| async move {
+        if state.sent >= total {
+            return None;
+        }
+        let chunk = match &state.source {
+            UploadSource::Memory(bytes) => {
+                let end = (state.sent as usize + UPLOAD_CHUNK).min(bytes.len());
+                Ok(bytes.slice(state.sent as usize..end))
+            }
+            UploadSource::File(path) => read_chunk(path, &mut state.reader, state.sent).await,
+        };
+        let chunk = match chunk {
+            Ok(chunk) if !chunk.is_empty() => chunk,
+            // Ending early makes the request fail rather than send a short file
+            Ok(_) => {
+                let short = std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!("{} got shorter while uploading", state.file),
+                );
+                state.sent = total;
+                return Some((Err(short), state));
+            }
+            Err(e) => {
+                state.sent = total;
+                return Some((Err(e), state));
+            }
+        };
+        state.sent += chunk.len() as u64;
+        report_upload(&UploadProgress {
+            file: state.file.clone(),
+            sent: state.sent,
+            total,
+            elapsed: state.start.elapsed(),
+        });
+        Some((Ok(chunk), state))
+    });
+    Box::pin(chunks)
+}
+
+/// The next chunk of a file, opening it at `offset` on the first call
+async fn read_chunk(
+    path: &Path,
+    reader: &mut Option<tokio::fs::File>,
+    offset: u64,
+) -> std::io::Result<Bytes> {
+    let reader = match reader {
+        Some(reader) => reader,
+        None => reader.insert(open_at(path, offset).await?),
+    };
+    let mut buf = vec![0; UPLOAD_CHUNK];
+    let n = reader.read(&mut buf).await?;
+    buf.truncate(n);
+    Ok(Bytes::from(buf))
+}
+
+async fn open_at(path: &Path, offset: u64) -> std::io::Result<tokio::fs::File> {
+    let mut file = tokio::fs::File::open(path).await?;
+    file.seek(std::io::SeekFrom::Start(offset)).await?;
+    Ok(file)
+}
+
+/// How many bytes of upload `id` the server already has; 0 when it doesn't
+/// support resuming or doesn't know the upload
+async fn resume_offset(
+    transport: &dyn Transport,
+    endpoint: &str,
+    token: &str,
+    id: &str,
+    total: u64,
+) -> u64 {
+    let request = HttpRequest::head(endpoint)
+        .header("Authorization", format!("Bearer {}", token))
+        .header(UPLOAD_ID_HEADER, id);
+    transport
+        .send(request)
+        .await
+        .ok()
+        .filter(|r| r.is_success())
+        .and_then(|r| r.header_value(UPLOAD_OFFSET_HEADER)?.trim().parse().ok())
+        .filter(|offset| *offset < total)
+        .unwrap_or(0)
+}
+
+/// A media endpoint's error response
+#[derive(Debug)]
+struct UploadRejected {
+    status: u16,
+    body: String,
+}
+
+impl std::fmt::Display for UploadRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Upload failed with status {}: {}",
+            self.status, self.body
+        )
+    }
+}
+
+impl std::error::Error for UploadRejected {}
+
+/// Whether the media endpoint turned a file down for what it is, as servers
+/// that don't accept WebP or AVIF do
+fn rejects_file_type(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<UploadRejected>()
+        .is_some_and(|r| matches!(r.status, 400 | 415 | 422))
+}
+
+/// Process an image for upload. When it can't be processed the original is
+/// uploaded instead, unless EXIF metadata is meant to be stripped: then the
+/// upload stops rather than publish GPS or camera details.
+async fn process_for_upload(
+    file_path: &Path,
+    settings: ImageSettings,
+) -> Result<Option<Processed>> {
+    let path = file_path.to_path_buf();
+    match tokio::task::spawn_blocking(move || images::process(&path, &settings)).await? {
+        Ok(processed) => Ok(processed),
+        Err(e) if settings.strip_exif => Err(e.context(format!(
+            "Not uploading {}: its EXIF metadata couldn't be removed (set strip_exif = false under [images] to upload it as it is)",
+            file_path.display()
+        ))),
+        Err(e) => {
+            log::warn!(
+                "Uploading {} without processing it: {:#}",
+                file_path.display(),
+                e
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Upload a file to media endpoint, processing images first if `[images]` is enabled.
+/// A converted image the server rejects is uploaded again in its original format.
+pub async fn upload_file(endpoint: &str, token: &str, file_path: &Path) -> Result<String> {
+    upload_processed(
+        endpoint,
+        token,
+        file_path,
+        ImageSettings::configured(),
+        &UploadSettings::configured(),
+        &RetryPolicy::configured(),
+    )
+    .await
+}
+
+async fn upload_processed(
+    endpoint: &str,
+    token: &str,
+    file_path: &Path,
+    settings: ImageSettings,
+    upload: &UploadSettings,
+    policy: &RetryPolicy,
+) -> Result<String> {
+    let processed = process_for_upload(file_path, settings).await?;
+
+    let transport = default_transport();
+    let result = upload_file_with(
+        transport.as_ref(),
+        endpoint,
+        token,
+        processed.as_ref().map_or(file_path, |p| p.path()),
+        upload,
+        policy,
+    )
+    .await;
+    match result {
+        Err(e) if processed.as_ref().is_some_and(Processed::converted) && rejects_file_type(&e) => {
+            log::warn!(
+                "{:#}; uploading {} in its original format",
+                e,
+                file_path.display()
+            );
+            let settings = ImageSettings {
+                format: None,
+                ..settings
+            };
+            let processed = process_for_upload(file_path, settings).await?;
+            upload_file_with(
+                transport.as_ref(),
+                endpoint,
+                token,
+                processed.as_ref().map_or(file_path, |p| p.path()),
+                upload,
+                policy,
+            )
+            .await
+        }
+        result => result,
+    }
+}
+
+async fn upload_file_with(
+    transport: &dyn Transport,
+    endpoint: &str,
+    token: &str,
+    file_path: &Path,
+    settings: &UploadSettings,
+    policy: &RetryPolicy,
+) -> Result<String> {
+    if !file_path.exists() {
+        anyhow::bail!("File not found: {}", file_path.display());
+    }
+
+    let filename = file_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .context("Invalid filename")?;
+
+    let mime_type = mime_guess::from_path(file_path).first_or_octet_stream();
+
+    let total = fs::metadata(file_path)
+        .context("Failed to read file")?
+        .len();
+    let source = if settings.streams(total) {
+        UploadSource::File(file_path.to_path_buf())
+    } else {
+        UploadSource::Memory(Bytes::from(
+            fs::read(file_path).context("Failed to read file")?,
+        ))
+    };
+    let resume_id = (settings.resume && matches!(source, UploadSource::File(_)))
+        .then(|| uuid::Uuid::new_v4().to_string());
+
+    // A repeated upload at worst leaves an unused copy, so it's always safe to retry
+    let mut attempt = 0;
+    let response = send_with_retry(policy, true, "Upload", endpoint, || {
+        attempt += 1;
+        let retrying = attempt > 1;
+        let (source, resume_id) = (source.clone(), resume_id.clone());
+        let mime_type = mime_type.clone();
+        async move {
+            let offset = match &resume_id {
+                Some(id) if retrying => resume_offset(transport, endpoint, token, id, total).await,
+                _ => 0,
+            };
+            if offset > 0 {
+                log::warn!(
+                    "Resuming upload of {} at {:.1} of {:.1} MB",
+                    filename,
+                    offset as f64 / 1_000_000.0,
+                    total as f64 / 1_000_000.0
+                );
+            }
+            let (body, content_type) =
+                multipart_body(filename, mime_type.as_ref(), source, offset, total);
+            let mut request = HttpRequest::post_stream(endpoint, body)
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", content_type);
+            if let Some(id) = &resume_id {
+                request = request.header(UPLOAD_ID_HEADER, id);
+                if offset > 0 {
+                    request = request.header(UPLOAD_OFFSET_HEADER, offset.to_string());
+                }
+            }
+            transport.send(request).await
+        }
+    })
+    .await
+    .context("Failed to upload file")?;
+
+    if !response.is_success() {
+        return Err(UploadRejected {
+            status: response.status,
+            body: response.text(),
+        }
+        .into());
+    }
+
+    // Get URL from Location header
+    let url = response
+        .header_value("location")
+        .context("No Location header in response")?
+        .to_string();
+
+    Ok(url)
+}
+
+/// A file on the media endpoint, from `upload_or_reuse`
+pub struct Upload {
+    pub url: String,
+    /// Whether an earlier upload of the same file was reused instead of sending it again
+    pub reused: bool,
+}
+
+/// Upload a file unless `profile` already has one with the same contents,
+/// recording a sidecar for new uploads
+pub async fn upload_or_reuse(
+    endpoint: &str,
+    token: &str,
+    file_path: &Path,
+    profile: &str,
+    alt: Option<&str>,
+) -> Result<Upload> {
+    let hash = media_sidecar::file_hash(file_path)?;
+    if let Some(mut existing) = media_sidecar::find(profile, &hash)? {
+        if existing.alt.is_none() && alt.is_some() {
+            existing.alt = alt.map(str::to_string);
+            media_sidecar::save(&existing)?;
+        }
+        return Ok(Upload {
+            url: existing.url,
+            reused: true,
+        });
+    }
+
+    let url = upload_file(endpoint, token, file_path).await?;
+    let sidecar = MediaSidecar {
+        url: url.clone(),
+        hash,
+        original_path: file_path.display().to_string(),
+        alt: alt.map(str::to_string),
+        uploaded_at: chrono::Utc::now(),
+        profile: profile.to_string(),
+    };
+    // The upload itself worked, so a failed record only costs dedup later
+    if let Err(e) = media_sidecar::save(&sidecar) {
+        log::warn!(
+            "Failed to record upload of {}: {:#}",
+            file_path.display(),
+            e
+        );
+    }
+    Ok(Upload { url, reused: false })
+}
+
+/// Replace local paths in content with URLs
+pub fn replace_paths(content: &str, replacements: &[(String, String)]) -> String {
+    let mut result = content.to_string();
+
+    for (local_path, url) in replacements {
+        result = result.replace(local_path, url);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_settings() {
+        let settings: UploadSettings = toml::from_str("stream_threshold_mb = 1").unwrap();
+        assert!(settings.resume);
+        assert!(!settings.streams(1_000_000));
+        assert!(settings.streams(1_000_001));
+    }
+
+    #[tokio::test]
+    async fn test_streamed_upload_resumes_from_server_offset() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("clip.mp4");
+        fs::write(&path, b"0123456789").unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let first = server
+            .mock("POST", "/media")
+            .match_header("upload-id", mockito::Matcher::Any)
+            .match_header("upload-offset", mockito::Matcher::Missing)
+            .with_status(503)
+            .create_async()
+            .await;
+        let head = server
+            .mock("HEAD", "/media")
+            .match_header("upload-id", mockito::Matcher::Any)
+            .with_header("Upload-Offset", "5")
+            .create_async()
+            .await;
+        let rest = server
+            .mock("POST", "/media")
+            .match_header("upload-offset", "5")
+            .match_body(mockito::Matcher::Regex("\r\n56789\r\n".to_string()))
+            .with_status(201)
+            .with_header("Location", "https://example.com/clip.mp4")
+            .create_async()
+            .await;
+
+        let settings = UploadSettings {
+            stream_threshold_mb: 0,
+            resume: true,
+        };
+        let policy = RetryPolicy {
+            base_delay_ms: 1,
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        let endpoint = format!("{}/media", server.url());
+        let transport = default_transport();
+        let url = upload_file_with(
+            transport.as_ref(),
+            &endpoint,
+            "token",
+            &path,
+            &settings,
+            &policy,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(url, "https://example.com/clip.mp4");
+        first.assert_async().await;
+        head.assert_async().await;
+        rest.assert_async().await;
+    }
+
+    #[cfg(feature = "images")]
+    #[tokio::test]
+    async fn test_rejected_conversion_is_uploaded_in_original_format() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let png = dir.path().join("flat.png");
+        image::RgbImage::from_pixel(40, 20, image::Rgb([30, 60, 90]))
+            .save(&png)
+            .unwrap();
+        let webp = ImageSettings {
+            enabled: true,
+            format: Some(images::OutputFormat::Webp),
+            ..ImageSettings::default()
+        };
+
+        for status in [400, 415, 422] {
+            let mut server = mockito::Server::new_async().await;
+            let converted = server
+                .mock("POST", "/media")
+                .match_body(mockito::Matcher::Regex(r#"filename="flat\.webp""#.into()))
+                .with_status(status)
+                .expect(1)
+                .create_async()
+                .await;
+            let original = server
+                .mock("POST", "/media")
+                .match_body(mockito::Matcher::Regex(r#"filename="flat\.png""#.into()))
+                .with_status(201)
+                .with_header("Location", "https://example.com/flat.png")
+                .expect(1)
+                .create_async()
+                .await;
+
+            let url = upload_processed(
+                &format!("{}/media", server.url()),
+                "token",
+                &png,
+                webp,
+                &UploadSettings::default(),
+                &RetryPolicy::default(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(url, "https://example.com/flat.png");
+            converted.assert_async().await;
+            original.assert_async().await;
+        }
+
+        // Other failures aren't about the format, so there's no second try
+        let mut server = mockito::Server::new_async().await;
+        let refused = server
+            .mock("POST", "/media")
+            .with_status(403)
+            .expect(1)
+            .create_async()
+            .await;
+        let result = upload_processed(
+            &format!("{}/media", server.url()),
+            "token",
+            &png,
+            webp,
+            &UploadSettings::default(),
+            &RetryPolicy::default(),
+        )
+        .await;
+        assert!(result.is_err());
+        refused.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unprocessable_image_is_not_uploaded_when_stripping_exif() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let heic = dir.path().join("photo.heic");
+        let broken = dir.path().join("broken.jpg");
+        fs::write(&heic, b"not decodable here").unwrap();
+        fs::write(&broken, b"not a jpeg").unwrap();
+
+        let mut server = mockito::Server::new_async().await;
+        let upload = server
+            .mock("POST", "/media")
+            .with_status(201)
+            .with_header("Location", "https://example.com/photo")
+            .expect(1)
+            .create_async()
+            .await;
+        let endpoint = format!("{}/media", server.url());
+        let strip = ImageSettings {
+            enabled: true,
+            ..ImageSettings::default()
+        };
+        let send = |path, settings| {
+            let endpoint = endpoint.clone();
+            async move {
+                upload_processed(
+                    &endpoint,
+                    "token",
+                    path,
+                    settings,
+                    &UploadSettings::default(),
+                    &RetryPolicy::default(),
+                )
+                .await
+            }
+        };
+
+        for path in [&heic, &broken] {
+            let err = send(path.as_path(), strip).await.unwrap_err();
+            assert!(format!("{:#}", err).contains("EXIF metadata couldn't be removed"));
+        }
+
+        // Without stripping, the original goes up as it is
+        let keep = ImageSettings {
+            strip_exif: false,
+            ..strip
+        };
+        assert_eq!(
+            send(broken.as_path(), keep).await.unwrap(),
+            "https://example.com/photo"
+        );
+        upload.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_upload_goes_through_transport() {
+        use crate::transport::{Body, HttpResponse, TransportFuture};
+        use std::sync::Mutex;
+
+        /// Reads each streamed body to the end, as a connection would
+        #[derive(Default)]
+        struct Recording {
+            sent: Mutex<Vec<(HttpRequest, Vec<u8>)>>,
+        }
+
+        impl Transport for Recording {
+            fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+                Box::pin(async move {
+                    let mut body = Vec::new();
+                    if let Body::Stream(stream) = &request.body {
+                        let mut chunks = stream.open();
+                        while let Some(chunk) = chunks.next().await {
+                            body.extend_from_slice(&chunk.unwrap());
+                        }
+                    }
+                    self.sent.lock().unwrap().push((request, body));
+                    Ok(HttpResponse::new(201).header("Location", "https://example.com/note.txt"))
+                })
+            }
+        }
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("note.txt");
+        fs::write(&path, b"hello").unwrap();
+
+        let transport = Recording::default();
+        for stream_threshold_mb in [16, 0] {
+            let settings = UploadSettings {
+                stream_threshold_mb,
+                resume: true,
+            };
+            let url = upload_file_with(
+                &transport,
+                "https://example.com/media",
+                "token",
+                &path,
+                &settings,
+                &RetryPolicy::default(),
+            )
+            .await
+            .unwrap();
+            assert_eq!(url, "https://example.com/note.txt");
+        }
+
+        let sent = transport.sent.lock().unwrap();
+        assert_eq!(sent.len(), 2);
+        for (request, body) in sent.iter() {
+            let Body::Stream(stream) = &request.body else {
+                panic!("uploads are streamed");
+            };
+            assert_eq!(stream.len(), body.len() as u64);
+            let content_type = request.header_value("content-type").unwrap();
+            let boundary = content_type
+                .strip_prefix("multipart/form-data; boundary=")
+                .unwrap();
+            let body = String::from_utf8_lossy(body);
+            assert!(body.starts_with(&format!("--{}\r\n", boundary)));
+            assert!(body.contains(r#"name="file"; filename="note.txt""#));
+            assert!(body.contains("Content-Type: text/plain\r\n\r\nhello\r\n"));
+            assert!(body.ends_with(&format!("\r\n--{}--\r\n", boundary)));
+        }
+        // Only a file large enough to stream from disk can be resumed
+        assert_eq!(sent[0].0.header_value(UPLOAD_ID_HEADER), None);
+        assert!(sent[1].0.header_value(UPLOAD_ID_HEADER).is_some());
+    }
+
+    #[test]
+    fn test_upload_progress_rate_and_eta() {
+        let progress = UploadProgress {
+            file: "video.mp4".to_string(),
+            sent: 2_000_000,
+            total: 10_000_000,
+            elapsed: Duration::from_secs(2),
+        };
+        assert_eq!(progress.percent(), 20);
+        assert_eq!(progress.rate(), 1_000_000.0);
+        assert_eq!(progress.eta(), Some(Duration::from_secs(8)));
+
+        let empty = UploadProgress {
+            sent: 0,
+            total: 0,
+            ..progress
+        };
+        assert_eq!(empty.percent(), 100);
+        assert_eq!(empty.eta(), None);
+    }
+
+    #[test]
+    fn test_is_local_path() {
+        assert!(is_local_path("~/photo.jpg"));
+        assert!(is_local_path("/abs/path.jpg"));
+        assert!(is_local_path("relative/path.jpg"));
+        assert!(!is_local_path("https://example.com/image.jpg"));
+    }
+
+    #[test]
+    fn test_alt_text_for() {
+        let content = "![A sunset](~/sunset.jpg)\n![](~/blank.jpg)\n![Dog](~/dog.jpg)";
+        assert_eq!(
+            alt_text_for(content, "~/sunset.jpg").as_deref(),
+            Some("A sunset")
+        );
+        assert_eq!(alt_text_for(content, "~/blank.jpg"), None);
+        assert_eq!(alt_text_for(content, "~/cat.jpg"), None);
+        assert_eq!(find_media_references(content).len(), 3);
+    }
+
+    #[test]
+    fn test_replace_paths() {
+        let content = "Image: ![](~/photo.jpg) here";
+        let replacements = vec![(
+            "~/photo.jpg".to_string(),
+            "https://cdn.com/abc.jpg".to_string(),
+        )];
+
+        let result = replace_paths(content, &replacements);
+        assert!(result.contains("https://cdn.com/abc.jpg"));
+        assert!(!result.contains("~/photo.jpg"));
+    }
+}
@@ -0,0 +1,154 @@
+// ABOUTME: Local sidecar records of uploaded media, one JSON file per file and profile
+// ABOUTME: Lets repeat uploads reuse a URL, lists uploads offline, and finds ones missing alt text
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::get_media_dir;
+
+/// What was uploaded, where it went, and how it was described
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaSidecar {
+    pub url: String,
+    /// SHA-256 of the file's contents
+    pub hash: String,
+    /// Path the file was uploaded from
+    pub original_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt: Option<String>,
+    pub uploaded_at: DateTime<Utc>,
+    pub profile: String,
+}
+
+/// SHA-256 of a file's contents, as hex
+pub fn file_hash(path: &Path) -> Result<String> {
+    // Streamed, so large videos aren't read into memory just to be hashed
+    let mut file =
+        fs::File::open(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Name of the directory holding a profile's sidecars
+pub fn profile_dir_name(profile: &str) -> String {
+    profile.replace(['/', '\\'], "_")
+}
+
+fn profile_dir(dir: &Path, profile: &str) -> PathBuf {
+    dir.join(profile_dir_name(profile))
+}
+
+fn find_in(dir: &Path, profile: &str, hash: &str) -> Result<Option<MediaSidecar>> {
+    let path = profile_dir(dir, profile).join(format!("{}.json", hash));
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let sidecar = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    Ok(Some(sidecar))
+}
+
+fn save_in(dir: &Path, sidecar: &MediaSidecar) -> Result<()> {
+    let profile_dir = profile_dir(dir, &sidecar.profile);
+    fs::create_dir_all(&profile_dir)?;
+    let path = profile_dir.join(format!("{}.json", sidecar.hash));
+    fs::write(&path, serde_json::to_string_pretty(sidecar)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn list_in(dir: &Path, profile: &str) -> Result<Vec<MediaSidecar>> {
+    let profile_dir = profile_dir(dir, profile);
+    if !profile_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sidecars = Vec::new();
+    for entry in fs::read_dir(&profile_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        // Skip unreadable records rather than hiding every other upload
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if let Ok(sidecar) = serde_json::from_str::<MediaSidecar>(&contents) {
+            sidecars.push(sidecar);
+        }
+    }
+    sidecars.sort_by_key(|sidecar| std::cmp::Reverse(sidecar.uploaded_at));
+    Ok(sidecars)
+}
+
+/// The earlier upload of a file with this hash to `profile`, if any
+pub fn find(profile: &str, hash: &str) -> Result<Option<MediaSidecar>> {
+    find_in(&get_media_dir()?, profile, hash)
+}
+
+/// Record an upload, replacing any earlier record of the same file
+pub fn save(sidecar: &MediaSidecar) -> Result<()> {
+    save_in(&get_media_dir()?, sidecar)
+}
+
+/// Every upload recorded for `profile`, newest first
+pub fn list(profile: &str) -> Result<Vec<MediaSidecar>> {
+    list_in(&get_media_dir()?, profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sidecar(profile: &str, hash: &str, minutes_ago: i64) -> MediaSidecar {
+        MediaSidecar {
+            url: format!("https://example.com/media/{}.jpg", hash),
+            hash: hash.to_string(),
+            original_path: "/photos/sunset.jpg".to_string(),
+            alt: None,
+            uploaded_at: Utc::now() - chrono::Duration::minutes(minutes_ago),
+            profile: profile.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sidecars_are_per_profile_and_newest_first() {
+        let dir = TempDir::new().unwrap();
+        save_in(dir.path(), &sidecar("blog", "aaa", 10)).unwrap();
+        save_in(dir.path(), &sidecar("blog", "bbb", 1)).unwrap();
+        save_in(dir.path(), &sidecar("other", "aaa", 5)).unwrap();
+
+        let found = find_in(dir.path(), "blog", "aaa").unwrap().unwrap();
+        assert_eq!(found.url, "https://example.com/media/aaa.jpg");
+        assert!(find_in(dir.path(), "blog", "ccc").unwrap().is_none());
+        assert!(find_in(dir.path(), "nobody", "aaa").unwrap().is_none());
+
+        let hashes: Vec<_> = list_in(dir.path(), "blog")
+            .unwrap()
+            .into_iter()
+            .map(|s| s.hash)
+            .collect();
+        assert_eq!(hashes, ["bbb", "aaa"]);
+    }
+
+    #[test]
+    fn test_file_hash_depends_on_contents() {
+        let dir = TempDir::new().unwrap();
+        let a = dir.path().join("a.jpg");
+        let b = dir.path().join("b.jpg");
+        fs::write(&a, b"same").unwrap();
+        fs::write(&b, b"same").unwrap();
+        assert_eq!(file_hash(&a).unwrap(), file_hash(&b).unwrap());
+
+        fs::write(&b, b"different").unwrap();
+        assert_ne!(file_hash(&a).unwrap(), file_hash(&b).unwrap());
+    }
+}
@@ -0,0 +1,119 @@
+// ABOUTME: Throttling for profiles with `polite = true` (small self-hosted servers)
+// ABOUTME: One request at a time to those hosts, spaced out, with smaller listing pages
+
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::{Mutex, MutexGuard};
+use tokio::time::Instant;
+use url::Url;
+
+use crate::config::Config;
+
+/// Minimum gap between one request finishing and the next starting
+pub const POLITE_DELAY: Duration = Duration::from_secs(1);
+
+/// Largest page requested from a polite server when paging through listings
+pub const POLITE_PAGE_SIZE: usize = 10;
+
+/// When the last polite request finished; holding the lock is holding the one slot
+static SLOT: Mutex<Option<Instant>> = Mutex::const_new(None);
+
+/// Hosts of every endpoint belonging to a polite profile, read once per process
+fn polite_hosts() -> &'static HashSet<String> {
+    static HOSTS: OnceLock<HashSet<String>> = OnceLock::new();
+    HOSTS.get_or_init(|| {
+        let Ok(config) = Config::load() else {
+            return HashSet::new();
+        };
+        config
+            .profiles
+            .values()
+            .filter(|profile| profile.polite)
+            .flat_map(|profile| {
+                [
+                    profile.micropub_endpoint.as_deref(),
+                    profile.media_endpoint.as_deref(),
+                    profile.token_endpoint.as_deref(),
+                    profile.authorization_endpoint.as_deref(),
+                ]
+            })
+            .flatten()
+            .filter_map(host_of)
+            .collect()
+    })
+}
+
+fn host_of(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    Some(format!(
+        "{}:{}",
+        url.host_str()?,
+        url.port_or_known_default()?
+    ))
+}
+
+/// Whether requests to this URL should be throttled
+pub fn for_url(url: &str) -> bool {
+    host_of(url).is_some_and(|host| polite_hosts().contains(&host))
+}
+
+/// Whether the active profile is polite
+pub fn active() -> bool {
+    Config::load()
+        .ok()
+        .and_then(|config| {
+            let (_, profile) = config.active_profile().ok()?;
+            Some(profile.polite)
+        })
+        .unwrap_or(false)
+}
+
+/// Page size to use for the active profile: `default`, or smaller when polite
+pub fn page_size(default: usize) -> usize {
+    if active() {
+        default.min(POLITE_PAGE_SIZE)
+    } else {
+        default
+    }
+}
+
+/// The single request slot for polite hosts; the request is spaced from the last one
+pub struct Turn(MutexGuard<'static, Option<Instant>>);
+
+impl Drop for Turn {
+    fn drop(&mut self) {
+        *self.0 = Some(Instant::now());
+    }
+}
+
+/// Wait for a turn to send a request to `url`; `None` (no waiting) unless it's polite
+pub async fn turn(url: &str) -> Option<Turn> {
+    if !for_url(url) {
+        return None;
+    }
+
+    let slot = SLOT.lock().await;
+    if let Some(last) = *slot {
+        tokio::time::sleep_until(last + POLITE_DELAY).await;
+    }
+    Some(Turn(slot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_of_includes_default_port() {
+        assert_eq!(
+            host_of("https://Example.com/micropub").as_deref(),
+            Some("example.com:443")
+        );
+        assert_eq!(
+            host_of("http://localhost:8080/mp").as_deref(),
+            Some("localhost:8080")
+        );
+        assert_eq!(host_of("not a url"), None);
+    }
+}
@@ -0,0 +1,127 @@
+// ABOUTME: Private notes attached to published posts, keyed by post URL
+// ABOUTME: Kept in notes.json in the data dir and never sent to the server
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::get_data_dir;
+use crate::draft::lock::write_atomic;
+
+/// A note about a post, e.g. "syndicated manually to list X"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostNote {
+    pub text: String,
+    pub added_at: DateTime<Utc>,
+}
+
+/// Every post's notes, oldest note first
+pub type PostNotes = BTreeMap<String, Vec<PostNote>>;
+
+fn notes_path() -> Result<PathBuf> {
+    Ok(get_data_dir()?.join("notes.json"))
+}
+
+fn load_from(path: &Path) -> Result<PostNotes> {
+    if !path.exists() {
+        return Ok(PostNotes::new());
+    }
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+fn save_to(path: &Path, notes: &PostNotes) -> Result<()> {
+    write_atomic(path, serde_json::to_string_pretty(notes)?.as_bytes())
+}
+
+fn add_in(path: &Path, url: &str, text: &str) -> Result<()> {
+    let mut notes = load_from(path)?;
+    notes.entry(url.to_string()).or_default().push(PostNote {
+        text: text.to_string(),
+        added_at: Utc::now(),
+    });
+    save_to(path, &notes)
+}
+
+/// Remove note `number` (1 = oldest) from a post, or all of its notes when
+/// `number` is `None`; returns how many were removed
+fn remove_in(path: &Path, url: &str, number: Option<usize>) -> Result<usize> {
+    let mut notes = load_from(path)?;
+    let Some(post_notes) = notes.get_mut(url) else {
+        return Ok(0);
+    };
+    let removed = match number {
+        None => post_notes.len(),
+        Some(n) if (1..=post_notes.len()).contains(&n) => {
+            post_notes.remove(n - 1);
+            1
+        }
+        Some(n) => anyhow::bail!(
+            "No note {} on {}; it has {} note(s)",
+            n,
+            url,
+            post_notes.len()
+        ),
+    };
+    if number.is_none() || post_notes.is_empty() {
+        notes.remove(url);
+    }
+    save_to(path, &notes)?;
+    Ok(removed)
+}
+
+/// Notes on every post that has any
+pub fn load() -> Result<PostNotes> {
+    load_from(&notes_path()?)
+}
+
+/// Notes on one post, oldest first
+pub fn for_post(url: &str) -> Result<Vec<PostNote>> {
+    Ok(load()?.remove(url).unwrap_or_default())
+}
+
+/// Attach a note to a post
+pub fn add(url: &str, text: &str) -> Result<()> {
+    add_in(&notes_path()?, url, text)
+}
+
+/// Remove one note from a post (1 = oldest), or all of them with `None`
+pub fn remove(url: &str, number: Option<usize>) -> Result<usize> {
+    remove_in(&notes_path()?, url, number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_notes_are_added_in_order_and_removed() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.json");
+        let url = "https://example.com/1";
+
+        assert!(load_from(&path).unwrap().is_empty());
+        add_in(&path, url, "syndicated manually").unwrap();
+        add_in(&path, url, "update pending").unwrap();
+        add_in(&path, "https://example.com/2", "other").unwrap();
+
+        let texts: Vec<_> = load_from(&path).unwrap()[url]
+            .iter()
+            .map(|note| note.text.clone())
+            .collect();
+        assert_eq!(texts, ["syndicated manually", "update pending"]);
+
+        assert!(remove_in(&path, url, Some(3)).is_err());
+        assert_eq!(remove_in(&path, url, Some(1)).unwrap(), 1);
+        assert_eq!(load_from(&path).unwrap()[url][0].text, "update pending");
+        assert_eq!(remove_in(&path, url, None).unwrap(), 1);
+        assert!(!load_from(&path).unwrap().contains_key(url));
+        assert_eq!(remove_in(&path, url, None).unwrap(), 0);
+        assert_eq!(load_from(&path).unwrap().len(), 1);
+    }
+}
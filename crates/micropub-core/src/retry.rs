@@ -0,0 +1,201 @@
+// ABOUTME: Retry with exponential backoff and jitter for micropub and media requests
+// ABOUTME: Only retries failures where resending can't duplicate a post
+
+use anyhow::{Context, Result};
+use http::StatusCode;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::transport::{HttpResponse, TransportError, TransportErrorKind, TransportResult};
+
+/// `[retry]` settings from config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total tries, including the first (1 disables retrying)
+    pub attempts: u32,
+    /// Delay before the first retry; doubles on each later one
+    pub base_delay_ms: u64,
+    /// Upper bound on any single delay, including a server's Retry-After
+    pub max_delay_ms: u64,
+    /// Randomize each delay between half and all of its value
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            base_delay_ms: 500,
+            max_delay_ms: 8000,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The policy from config.toml, or the default
+    pub fn configured() -> Self {
+        Config::load().map(|c| c.retry).unwrap_or_default()
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Delay before retry number `retry` (1-based), before jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u64.saturating_pow(retry.saturating_sub(1));
+        Duration::from_millis(
+            self.base_delay_ms
+                .saturating_mul(factor)
+                .min(self.max_delay_ms),
+        )
+    }
+
+    fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        let max = Duration::from_millis(self.max_delay_ms);
+        if let Some(retry_after) = retry_after {
+            return retry_after.min(max);
+        }
+
+        let backoff = self.backoff(retry);
+        if self.jitter {
+            backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Statuses that are safe to resend. Creates aren't idempotent, so they only retry
+/// when the server clearly didn't process the request; a gateway timeout might have.
+pub fn is_retryable_status(status: StatusCode, idempotent: bool) -> bool {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE => true,
+        StatusCode::GATEWAY_TIMEOUT => idempotent,
+        _ => false,
+    }
+}
+
+/// Network failures that are safe to resend: the request never reached the server,
+/// or (for idempotent requests) it timed out
+fn is_retryable_error(error: &TransportError, idempotent: bool) -> bool {
+    match error.kind {
+        TransportErrorKind::Connect => true,
+        TransportErrorKind::Timeout => idempotent,
+        TransportErrorKind::Other => false,
+    }
+}
+
+fn status_of(response: &HttpResponse) -> StatusCode {
+    StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+/// A `Retry-After` header given in seconds
+fn retry_after(response: &HttpResponse) -> Option<Duration> {
+    let seconds = response.header_value("retry-after")?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Send a request to `url` built by `send`, retrying transient failures according to
+/// `policy`. Non-retryable responses (including errors) are returned for the caller to handle.
+pub async fn send_with_retry<F, Fut>(
+    policy: &RetryPolicy,
+    idempotent: bool,
+    what: &str,
+    url: &str,
+    mut send: F,
+) -> Result<HttpResponse>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = TransportResult>,
+{
+    let attempts = policy.attempts.max(1);
+
+    for attempt in 1..=attempts {
+        let last = attempt == attempts;
+        let turn = crate::polite::turn(url).await;
+        let sent = send().await;
+        drop(turn);
+        let (problem, wait) = match sent {
+            Ok(response) if !is_retryable_status(status_of(&response), idempotent) => {
+                return Ok(response)
+            }
+            Ok(response) if last => {
+                anyhow::bail!(
+                    "{} failed after {} attempts: HTTP {}: {}",
+                    what,
+                    attempts,
+                    status_of(&response),
+                    response.text()
+                );
+            }
+            Ok(response) => (
+                format!("HTTP {}", status_of(&response)),
+                policy.delay(attempt, retry_after(&response)),
+            ),
+            Err(e) if !last && is_retryable_error(&e, idempotent) => {
+                (e.to_string(), policy.delay(attempt, None))
+            }
+            Err(e) if attempt > 1 => {
+                return Err(e)
+                    .with_context(|| format!("{} failed after {} attempts", what, attempt))
+            }
+            Err(e) => return Err(e).with_context(|| format!("{} failed", what)),
+        };
+
+        log::warn!(
+            "{} ({}); retrying in {:.1}s (attempt {}/{})",
+            what,
+            problem,
+            wait.as_secs_f64(),
+            attempt + 1,
+            attempts
+        );
+        tokio::time::sleep(wait).await;
+    }
+
+    unreachable!("the last attempt always returns")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = RetryPolicy {
+            attempts: 6,
+            base_delay_ms: 500,
+            max_delay_ms: 3000,
+            jitter: false,
+        };
+        let delays: Vec<u64> = (1..=4)
+            .map(|retry| policy.backoff(retry).as_millis() as u64)
+            .collect();
+        assert_eq!(delays, vec![500, 1000, 2000, 3000]);
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(60))),
+            Duration::from_secs(3)
+        );
+    }
+
+    #[test]
+    fn test_only_safe_statuses_retry() {
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE, false));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS, false));
+        assert!(!is_retryable_status(StatusCode::GATEWAY_TIMEOUT, false));
+        assert!(is_retryable_status(StatusCode::GATEWAY_TIMEOUT, true));
+        assert!(!is_retryable_status(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            true
+        ));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST, true));
+    }
+}
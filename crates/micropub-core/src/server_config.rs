@@ -0,0 +1,352 @@
+// ABOUTME: Micropub server configuration (q=config, q=category) queries and caching
+// ABOUTME: Caches per-profile config and category responses on disk with a TTL
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::get_cache_dir;
+use crate::transport::{default_transport, HttpRequest, HttpResponse, TransportError};
+
+/// How long a cached q=config response stays fresh
+pub const CONFIG_CACHE_TTL_SECS: i64 = 3600;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyndicationTarget {
+    pub uid: String,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PostType {
+    #[serde(rename = "type")]
+    pub post_type: String,
+    pub name: Option<String>,
+}
+
+/// Parsed response of a micropub `q=config` query
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ServerConfig {
+    pub media_endpoint: Option<String>,
+    #[serde(default)]
+    pub syndicate_to: Vec<SyndicationTarget>,
+    #[serde(default)]
+    pub q: Vec<String>,
+    #[serde(default)]
+    pub post_types: Vec<PostType>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedConfig {
+    fetched_at: DateTime<Utc>,
+    config: ServerConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedCategories {
+    fetched_at: DateTime<Utc>,
+    categories: Vec<String>,
+}
+
+fn cache_path(profile_name: &str) -> Result<PathBuf> {
+    Ok(get_cache_dir()?.join(format!("{}.config.json", profile_name)))
+}
+
+fn categories_cache_path(profile_name: &str) -> Result<PathBuf> {
+    Ok(get_cache_dir()?.join(format!("{}.categories.json", profile_name)))
+}
+
+/// GET `?q=<query>` from the micropub endpoint through the default transport
+async fn query(
+    micropub_endpoint: &str,
+    token: &str,
+    query: &str,
+) -> Result<HttpResponse, TransportError> {
+    let request = HttpRequest::get(format!("{}?q={}", micropub_endpoint, query))
+        .header("Authorization", format!("Bearer {}", token));
+    default_transport().send(request).await
+}
+
+/// Query the micropub endpoint for its configuration
+pub async fn query_config(micropub_endpoint: &str, token: &str) -> Result<ServerConfig> {
+    let _turn = crate::polite::turn(micropub_endpoint).await;
+    let response = query(micropub_endpoint, token, "config")
+        .await
+        .context("Failed to query micropub config endpoint")?;
+
+    if !response.is_success() {
+        anyhow::bail!("Failed to query server config: HTTP {}", response.status);
+    }
+
+    serde_json::from_slice(&response.body).context("Failed to parse micropub config response")
+}
+
+#[derive(Debug, Deserialize)]
+struct SyndicateToResponse {
+    #[serde(rename = "syndicate-to", default)]
+    syndicate_to: Vec<SyndicationTarget>,
+}
+
+/// Query the micropub endpoint's syndication targets (`q=syndicate-to`),
+/// falling back to the list in `q=config` for servers that only support that
+pub async fn query_syndicate_to(
+    micropub_endpoint: &str,
+    token: &str,
+) -> Result<Vec<SyndicationTarget>> {
+    let turn = crate::polite::turn(micropub_endpoint).await;
+    let response = query(micropub_endpoint, token, "syndicate-to")
+        .await
+        .context("Failed to query syndication targets")?;
+    drop(turn);
+
+    if response.is_success() {
+        if let Ok(parsed) = serde_json::from_slice::<SyndicateToResponse>(&response.body) {
+            return Ok(parsed.syndicate_to);
+        }
+    }
+
+    Ok(query_config(micropub_endpoint, token).await?.syndicate_to)
+}
+
+#[derive(Debug, Deserialize)]
+struct CategoryResponse {
+    #[serde(default, alias = "category")]
+    categories: Vec<serde_json::Value>,
+}
+
+/// Query the categories the server has seen (`q=category`). Servers that don't
+/// support the query answer with an error, which counts as no categories.
+pub async fn query_categories(micropub_endpoint: &str, token: &str) -> Result<Vec<String>> {
+    let _turn = crate::polite::turn(micropub_endpoint).await;
+    let response = query(micropub_endpoint, token, "category")
+        .await
+        .context("Failed to query categories")?;
+
+    if !response.is_success() {
+        return Ok(Vec::new());
+    }
+
+    let parsed: CategoryResponse =
+        serde_json::from_slice(&response.body).context("Failed to parse category response")?;
+    Ok(parsed
+        .categories
+        .iter()
+        .filter_map(|c| c.as_str())
+        .map(String::from)
+        .collect())
+}
+
+/// Match a `--syndicate` value against known targets by uid or (case-insensitive) name
+pub fn resolve_syndication_target(targets: &[SyndicationTarget], input: &str) -> Result<String> {
+    if targets.is_empty() {
+        anyhow::bail!("The server doesn't advertise any syndication targets");
+    }
+
+    targets
+        .iter()
+        .find(|t| t.uid == input)
+        .or_else(|| {
+            targets.iter().find(|t| {
+                t.name
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(input))
+            })
+        })
+        .map(|t| t.uid.clone())
+        .with_context(|| {
+            let valid: Vec<String> = targets
+                .iter()
+                .map(|t| match t.name {
+                    Some(ref name) => format!("{} ({})", t.uid, name),
+                    None => t.uid.clone(),
+                })
+                .collect();
+            format!(
+                "Unknown syndication target '{}'. Valid targets:\n  {}",
+                input,
+                valid.join("\n  ")
+            )
+        })
+}
+
+/// Get the server config for a profile, using the on-disk cache unless stale or `refresh` is set
+pub async fn get_server_config(
+    profile_name: &str,
+    micropub_endpoint: &str,
+    token: &str,
+    refresh: bool,
+) -> Result<ServerConfig> {
+    let path = cache_path(profile_name)?;
+
+    if !refresh {
+        if let Some(cached) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<CachedConfig>(&contents).ok())
+        {
+            if Utc::now() - cached.fetched_at < Duration::seconds(CONFIG_CACHE_TTL_SECS) {
+                return Ok(cached.config);
+            }
+        }
+    }
+
+    let config = query_config(micropub_endpoint, token).await?;
+
+    let cached = CachedConfig {
+        fetched_at: Utc::now(),
+        config: config.clone(),
+    };
+    // A failed cache write shouldn't fail the command
+    if let Ok(contents) = serde_json::to_string_pretty(&cached) {
+        let _ = fs::write(&path, contents);
+    }
+
+    Ok(config)
+}
+
+/// Categories for a profile, using the on-disk cache unless stale or `refresh` is set
+pub async fn get_categories(
+    profile_name: &str,
+    micropub_endpoint: &str,
+    token: &str,
+    refresh: bool,
+) -> Result<Vec<String>> {
+    let path = categories_cache_path(profile_name)?;
+
+    if !refresh {
+        if let Some(cached) = read_categories(&path) {
+            if Utc::now() - cached.fetched_at < Duration::seconds(CONFIG_CACHE_TTL_SECS) {
+                return Ok(cached.categories);
+            }
+        }
+    }
+
+    let categories = query_categories(micropub_endpoint, token).await?;
+
+    let cached = CachedCategories {
+        fetched_at: Utc::now(),
+        categories: categories.clone(),
+    };
+    // A failed cache write shouldn't fail the command
+    if let Ok(contents) = serde_json::to_string_pretty(&cached) {
+        let _ = fs::write(&path, contents);
+    }
+
+    Ok(categories)
+}
+
+fn read_categories(path: &std::path::Path) -> Option<CachedCategories> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+}
+
+/// Categories from the last `q=category` query for a profile, however old, without a request
+pub fn cached_categories(profile_name: &str) -> Vec<String> {
+    categories_cache_path(profile_name)
+        .ok()
+        .and_then(|path| read_categories(&path))
+        .map(|cached| cached.categories)
+        .unwrap_or_default()
+}
+
+/// Drop the cached server config and categories for a profile
+pub fn invalidate(profile_name: &str) -> Result<()> {
+    for path in [
+        cache_path(profile_name)?,
+        categories_cache_path(profile_name)?,
+    ] {
+        if path.exists() {
+            fs::remove_file(&path).context("Failed to remove cached server config")?;
+        }
+    }
+    Ok(())
+}
+
+/// Drop cached server configs for every profile
+pub fn invalidate_all() -> Result<()> {
+    for entry in fs::read_dir(get_cache_dir()?)? {
+        let path = entry?.path();
+        if path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.ends_with(".config.json") || n.ends_with(".categories.json"))
+        {
+            fs::remove_file(&path).context("Failed to remove cached server config")?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_server_config() {
+        let json = r#"{
+            "media-endpoint": "https://example.com/media",
+            "syndicate-to": [{"uid": "https://social.example/@me", "name": "Mastodon"}],
+            "q": ["config", "source", "syndicate-to"],
+            "post-types": [{"type": "note", "name": "Note"}]
+        }"#;
+
+        let config: ServerConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            config.media_endpoint.as_deref(),
+            Some("https://example.com/media")
+        );
+        assert_eq!(config.syndicate_to.len(), 1);
+        assert_eq!(config.syndicate_to[0].uid, "https://social.example/@me");
+        assert!(config.q.contains(&"source".to_string()));
+        assert_eq!(config.post_types[0].post_type, "note");
+    }
+
+    #[test]
+    fn test_resolve_syndication_target() {
+        let targets = vec![
+            SyndicationTarget {
+                uid: "https://social.example/@me".to_string(),
+                name: Some("Mastodon".to_string()),
+            },
+            SyndicationTarget {
+                uid: "https://bsky.app/profile/me".to_string(),
+                name: None,
+            },
+        ];
+
+        assert_eq!(
+            resolve_syndication_target(&targets, "https://bsky.app/profile/me").unwrap(),
+            "https://bsky.app/profile/me"
+        );
+        assert_eq!(
+            resolve_syndication_target(&targets, "mastodon").unwrap(),
+            "https://social.example/@me"
+        );
+        let err = resolve_syndication_target(&targets, "twitter").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("https://social.example/@me (Mastodon)"));
+        assert!(resolve_syndication_target(&[], "x").is_err());
+    }
+
+    #[test]
+    fn test_parse_minimal_server_config() {
+        let config: ServerConfig = serde_json::from_str("{}").unwrap();
+        assert!(config.media_endpoint.is_none());
+        assert!(config.syndicate_to.is_empty());
+    }
+
+    #[test]
+    fn test_parse_category_response() {
+        let parsed: CategoryResponse =
+            serde_json::from_str(r#"{"categories": ["rust", "indieweb", 3]}"#).unwrap();
+        assert_eq!(parsed.categories.len(), 3);
+
+        // Some servers name the list after the query
+        let parsed: CategoryResponse = serde_json::from_str(r#"{"category": ["rust"]}"#).unwrap();
+        assert_eq!(parsed.categories, vec![serde_json::json!("rust")]);
+    }
+}
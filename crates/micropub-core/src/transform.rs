@@ -0,0 +1,205 @@
+// ABOUTME: Per-profile content transformation rules applied at publish time
+// ABOUTME: Supports comment stripping, wiki-links, gallery shortcodes, and regex rewrites
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// A single content rewrite rule, configured per profile in config.toml
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Transform {
+    /// Remove `<!-- ... -->` comments
+    StripComments,
+    /// Rewrite `[[Page]]` and `[[Page|label]]` into links under `base_url`
+    WikiLinks { base_url: String },
+    /// Wrap `::gallery` ... `::` blocks in a gallery container
+    Gallery {
+        #[serde(default = "default_gallery_class")]
+        class: String,
+    },
+    /// Arbitrary regex replacement (supports `$1`-style captures)
+    Replace {
+        pattern: String,
+        replacement: String,
+    },
+}
+
+fn default_gallery_class() -> String {
+    "gallery".to_string()
+}
+
+impl Transform {
+    /// Apply this rule to a piece of content
+    pub fn apply(&self, content: &str) -> Result<String> {
+        match self {
+            Transform::StripComments => {
+                let re = Regex::new(r"(?s)<!--.*?-->\n?").unwrap();
+                Ok(re.replace_all(content, "").into_owned())
+            }
+            Transform::WikiLinks { base_url } => {
+                let re = Regex::new(r"\[\[([^\]|]+)(?:\|([^\]]+))?\]\]").unwrap();
+                let base = base_url.trim_end_matches('/');
+                Ok(re
+                    .replace_all(content, |caps: &regex::Captures| {
+                        let page = caps[1].trim();
+                        let label = caps.get(2).map(|m| m.as_str().trim()).unwrap_or(page);
+                        format!("[{}]({}/{}/)", label, base, slugify(page))
+                    })
+                    .into_owned())
+            }
+            Transform::Gallery { class } => Ok(expand_galleries(content, class)),
+            Transform::Replace {
+                pattern,
+                replacement,
+            } => {
+                let re = Regex::new(pattern)
+                    .with_context(|| format!("Invalid transform pattern: {}", pattern))?;
+                Ok(re.replace_all(content, replacement.as_str()).into_owned())
+            }
+        }
+    }
+}
+
+/// Run every transform in order over the content
+pub fn apply_transforms(content: &str, transforms: &[Transform]) -> Result<String> {
+    transforms
+        .iter()
+        .try_fold(content.to_string(), |acc, transform| transform.apply(&acc))
+}
+
+fn slugify(page: &str) -> String {
+    page.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn expand_galleries(content: &str, class: &str) -> String {
+    let mut out = Vec::new();
+    let mut in_gallery = false;
+
+    for line in content.lines() {
+        match line.trim() {
+            "::gallery" if !in_gallery => {
+                in_gallery = true;
+                out.push(format!("<div class=\"{}\">", class));
+                out.push(String::new());
+            }
+            "::" if in_gallery => {
+                in_gallery = false;
+                out.push(String::new());
+                out.push("</div>".to_string());
+            }
+            _ => out.push(line.to_string()),
+        }
+    }
+
+    // Close an unterminated block rather than emitting broken HTML
+    if in_gallery {
+        out.push(String::new());
+        out.push("</div>".to_string());
+    }
+
+    let mut result = out.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_comments() {
+        let out = Transform::StripComments
+            .apply("Hello <!-- hidden -->world\n<!-- a\nb -->\nend")
+            .unwrap();
+        assert_eq!(out, "Hello world\nend");
+    }
+
+    #[test]
+    fn test_wiki_links() {
+        let t = Transform::WikiLinks {
+            base_url: "https://example.com/wiki/".to_string(),
+        };
+        let out = t
+            .apply("See [[Rust Notes]] and [[Home|the start]].")
+            .unwrap();
+        assert_eq!(
+            out,
+            "See [Rust Notes](https://example.com/wiki/rust-notes/) and [the start](https://example.com/wiki/home/)."
+        );
+    }
+
+    #[test]
+    fn test_gallery() {
+        let t = Transform::Gallery {
+            class: default_gallery_class(),
+        };
+        let out = t
+            .apply("Intro\n::gallery\n![a](a.jpg)\n::\nDone\n")
+            .unwrap();
+        assert_eq!(
+            out,
+            "Intro\n<div class=\"gallery\">\n\n![a](a.jpg)\n\n</div>\nDone\n"
+        );
+    }
+
+    #[test]
+    fn test_replace_and_ordering() {
+        let transforms = vec![
+            Transform::StripComments,
+            Transform::Replace {
+                pattern: r"TODO\((\w+)\)".to_string(),
+                replacement: "@$1".to_string(),
+            },
+        ];
+        let out = apply_transforms("<!-- x -->TODO(harper)", &transforms).unwrap();
+        assert_eq!(out, "@harper");
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let t = Transform::Replace {
+            pattern: "(".to_string(),
+            replacement: String::new(),
+        };
+        assert!(t.apply("text").is_err());
+    }
+
+    #[test]
+    fn test_parse_from_toml() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            transforms: Vec<Transform>,
+        }
+
+        let parsed: Wrapper = toml::from_str(
+            r#"
+            [[transforms]]
+            type = "strip-comments"
+
+            [[transforms]]
+            type = "wiki-links"
+            base_url = "https://example.com"
+
+            [[transforms]]
+            type = "gallery"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(parsed.transforms.len(), 3);
+        assert_eq!(parsed.transforms[0], Transform::StripComments);
+        assert_eq!(
+            parsed.transforms[2],
+            Transform::Gallery {
+                class: "gallery".to_string()
+            }
+        );
+    }
+}
@@ -0,0 +1,345 @@
+// ABOUTME: Pluggable HTTP layer for micropub requests
+// ABOUTME: Embedders can supply their own client or a mock; reqwest is the default
+
+use bytes::Bytes;
+use futures_util::Stream;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Head,
+    Post,
+}
+
+pub type BodyStream = Pin<Box<dyn Stream<Item = std::io::Result<Bytes>> + Send>>;
+
+/// A body read a chunk at a time as it's sent, for uploads too large to hold
+/// in memory. `open` starts it from the beginning, so a request carrying one
+/// can be cloned and sent again.
+#[derive(Clone)]
+pub struct StreamBody {
+    length: u64,
+    open: Arc<dyn Fn() -> BodyStream + Send + Sync>,
+}
+
+impl StreamBody {
+    /// A body of exactly `length` bytes, produced by each call to `open`
+    pub fn new(length: u64, open: impl Fn() -> BodyStream + Send + Sync + 'static) -> Self {
+        Self {
+            length,
+            open: Arc::new(open),
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn open(&self) -> BodyStream {
+        (self.open)()
+    }
+}
+
+impl fmt::Debug for StreamBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamBody")
+            .field("length", &self.length)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for StreamBody {
+    fn eq(&self, other: &Self) -> bool {
+        self.length == other.length && Arc::ptr_eq(&self.open, &other.open)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Body {
+    Bytes(Vec<u8>),
+    Stream(StreamBody),
+}
+
+impl Body {
+    /// The bytes of a body held in memory; `None` for a stream
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Body::Bytes(bytes) => Some(bytes),
+            Body::Stream(_) => None,
+        }
+    }
+}
+
+impl<T: Into<Vec<u8>>> From<T> for Body {
+    fn from(bytes: T) -> Self {
+        Body::Bytes(bytes.into())
+    }
+}
+
+/// A request as handed to a `Transport`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Body,
+}
+
+impl HttpRequest {
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new(Method::Get, url, Vec::new())
+    }
+
+    pub fn head(url: impl Into<String>) -> Self {
+        Self::new(Method::Head, url, Vec::new())
+    }
+
+    pub fn post(url: impl Into<String>, body: impl Into<Vec<u8>>) -> Self {
+        Self::new(Method::Post, url, body.into())
+    }
+
+    /// A POST whose body is streamed rather than held in memory
+    pub fn post_stream(url: impl Into<String>, body: StreamBody) -> Self {
+        Self::new(Method::Post, url, Body::Stream(body))
+    }
+
+    fn new(method: Method, url: impl Into<String>, body: impl Into<Body>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    /// First value of a header, matched case-insensitively
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// A response as returned by a `Transport`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// First value of a header, matched case-insensitively
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// What went wrong when a request got no response; retries depend on the kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportErrorKind {
+    /// The request never reached the server
+    Connect,
+    Timeout,
+    Other,
+}
+
+#[derive(Debug)]
+pub struct TransportError {
+    pub kind: TransportErrorKind,
+    pub message: String,
+}
+
+impl TransportError {
+    pub fn new(kind: TransportErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+/// Whether an error chain ends in a failure to reach the server at all
+pub fn is_connection_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<TransportError>()
+            .is_some_and(|e| e.kind == TransportErrorKind::Connect)
+    })
+}
+
+pub type TransportResult = Result<HttpResponse, TransportError>;
+
+pub type TransportFuture<'a> = Pin<Box<dyn Future<Output = TransportResult> + Send + 'a>>;
+
+/// Sends HTTP requests for `MicropubClient`
+pub trait Transport: Send + Sync {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_>;
+}
+
+static DEFAULT_TRANSPORT: RwLock<Option<Arc<dyn Transport>>> = RwLock::new(None);
+
+/// Send requests through `transport` wherever one isn't passed explicitly:
+/// `MicropubClient::new`, server queries (`q=config`, `q=syndicate-to`,
+/// `q=category`), cached listings, and media uploads.
+pub fn set_default_transport(transport: Arc<dyn Transport>) {
+    *DEFAULT_TRANSPORT.write().unwrap_or_else(|e| e.into_inner()) = Some(transport);
+}
+
+/// The transport from `set_default_transport`, or reqwest
+#[cfg(feature = "reqwest")]
+pub fn default_transport() -> Arc<dyn Transport> {
+    DEFAULT_TRANSPORT
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| Arc::new(ReqwestTransport::default()))
+}
+
+/// The transport from `set_default_transport`; without the `reqwest` feature
+/// there's nothing else to send requests with
+#[cfg(not(feature = "reqwest"))]
+pub fn default_transport() -> Arc<dyn Transport> {
+    DEFAULT_TRANSPORT
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_else(|| Arc::new(NoTransport))
+}
+
+/// Fails every request, until `set_default_transport` is called
+#[cfg(not(feature = "reqwest"))]
+struct NoTransport;
+
+#[cfg(not(feature = "reqwest"))]
+impl Transport for NoTransport {
+    fn send(&self, _request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async {
+            Err(TransportError::new(
+                TransportErrorKind::Other,
+                "No HTTP transport: call transport::set_default_transport, or build micropub-core with the reqwest feature",
+            ))
+        })
+    }
+}
+
+/// The default transport, backed by reqwest
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Transport for ReqwestTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let mut builder = match request.method {
+                Method::Get => self.client.get(&request.url),
+                Method::Head => self.client.head(&request.url),
+                Method::Post => self.client.post(&request.url),
+            };
+            for (name, value) in &request.headers {
+                builder = builder.header(name, value);
+            }
+            builder = match request.body {
+                Body::Bytes(bytes) if request.method == Method::Post => builder.body(bytes),
+                Body::Bytes(_) => builder,
+                // A length lets the server refuse an oversized upload up front
+                Body::Stream(body) => builder
+                    .header(reqwest::header::CONTENT_LENGTH, body.len())
+                    .body(reqwest::Body::wrap_stream(body.open())),
+            };
+            from_reqwest(builder.send().await).await
+        })
+    }
+}
+
+/// Convert a reqwest result, reading the whole body
+#[cfg(feature = "reqwest")]
+pub async fn from_reqwest(result: reqwest::Result<reqwest::Response>) -> TransportResult {
+    let response = result.map_err(transport_error)?;
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect();
+    let body = response.bytes().await.map_err(transport_error)?.to_vec();
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+#[cfg(feature = "reqwest")]
+fn transport_error(error: reqwest::Error) -> TransportError {
+    let kind = if error.is_connect() {
+        TransportErrorKind::Connect
+    } else if error.is_timeout() {
+        TransportErrorKind::Timeout
+    } else {
+        TransportErrorKind::Other
+    };
+    TransportError::new(kind, error.to_string())
+}
@@ -0,0 +1,81 @@
+use micropub_core::client::{MicropubAction, MicropubRequest};
+use serde_json::json;
+
+#[test]
+fn test_create_request_json() {
+    let mut props = serde_json::Map::new();
+    props.insert("content".to_string(), json!(["Hello world"]));
+
+    let req = MicropubRequest {
+        action: MicropubAction::Create,
+        properties: props,
+        url: None,
+    };
+
+    let json = req.to_json().expect("Should serialize");
+    assert!(json.contains("content"));
+    assert!(json.contains("Hello world"));
+}
+
+#[tokio::test]
+async fn test_client_uses_custom_transport() {
+    use micropub_core::client::MicropubClient;
+    use micropub_core::retry::RetryPolicy;
+    use micropub_core::transport::{
+        HttpRequest, HttpResponse, Transport, TransportError, TransportErrorKind, TransportFuture,
+    };
+    use std::sync::{Arc, Mutex};
+
+    /// Refuses the first connection, then accepts the create
+    struct MockTransport {
+        sent: Mutex<Vec<HttpRequest>>,
+    }
+
+    impl Transport for MockTransport {
+        fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+            Box::pin(async move {
+                let mut sent = self.sent.lock().unwrap();
+                sent.push(request);
+                if sent.len() == 1 {
+                    return Err(TransportError::new(
+                        TransportErrorKind::Connect,
+                        "connection refused",
+                    ));
+                }
+                Ok(HttpResponse::new(201).header("Location", "https://example.com/posts/1"))
+            })
+        }
+    }
+
+    let transport = Arc::new(MockTransport {
+        sent: Mutex::new(Vec::new()),
+    });
+    let client = MicropubClient::new(
+        "https://example.com/micropub".to_string(),
+        "token".to_string(),
+    )
+    .with_transport(transport.clone())
+    .with_retry(RetryPolicy {
+        attempts: 2,
+        base_delay_ms: 0,
+        max_delay_ms: 0,
+        jitter: false,
+    });
+
+    let mut props = serde_json::Map::new();
+    props.insert("content".to_string(), json!(["Hello"]));
+    let response = client
+        .send(&MicropubRequest {
+            action: MicropubAction::Create,
+            properties: props,
+            url: None,
+        })
+        .await
+        .expect("Should succeed on retry");
+
+    assert_eq!(response.url.as_deref(), Some("https://example.com/posts/1"));
+    let sent = transport.sent.lock().unwrap();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[1].header_value("authorization"), Some("Bearer token"));
+    assert!(String::from_utf8_lossy(sent[1].body.as_bytes().unwrap()).contains("Hello"));
+}
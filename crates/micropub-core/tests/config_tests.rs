@@ -1,4 +1,4 @@
-use micropub::config::{get_config_dir, get_data_dir};
+use micropub_core::config::{get_config_dir, get_data_dir};
 
 #[test]
 fn test_config_dir_exists() {
@@ -1,4 +1,4 @@
-use micropub::media::find_media_references;
+use micropub_core::media::find_media_references;
 
 #[test]
 fn test_find_markdown_images() {
@@ -0,0 +1,53 @@
+use micropub_core::server_config::{query_categories, query_config};
+use micropub_core::transport::{
+    set_default_transport, HttpRequest, HttpResponse, Transport, TransportFuture,
+};
+use std::sync::{Arc, Mutex};
+
+/// Answers server queries from canned bodies and records what was asked
+struct MockTransport {
+    sent: Mutex<Vec<HttpRequest>>,
+}
+
+impl Transport for MockTransport {
+    fn send(&self, request: HttpRequest) -> TransportFuture<'_> {
+        Box::pin(async move {
+            let response = if request.url.ends_with("?q=config") {
+                HttpResponse::new(200)
+                    .body(r#"{"media-endpoint": "https://example.com/media", "q": ["config"]}"#)
+            } else {
+                HttpResponse::new(400).body(r#"{"error": "invalid_request"}"#)
+            };
+            self.sent.lock().unwrap().push(request);
+            Ok(response)
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_server_queries_use_default_transport() {
+    let transport = Arc::new(MockTransport {
+        sent: Mutex::new(Vec::new()),
+    });
+    set_default_transport(transport.clone());
+
+    let config = query_config("https://example.com/micropub", "token")
+        .await
+        .expect("config query should go through the mock");
+    assert_eq!(
+        config.media_endpoint.as_deref(),
+        Some("https://example.com/media")
+    );
+
+    // A server without q=category has none
+    let categories = query_categories("https://example.com/micropub", "token")
+        .await
+        .unwrap();
+    assert!(categories.is_empty());
+
+    let sent = transport.sent.lock().unwrap();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[0].url, "https://example.com/micropub?q=config");
+    assert_eq!(sent[0].header_value("authorization"), Some("Bearer token"));
+    assert_eq!(sent[1].url, "https://example.com/micropub?q=category");
+}
@@ -1,15 +0,0 @@
-// ABOUTME: Main library file for micropub CLI
-// ABOUTME: Exports all public modules and types
-
-pub mod auth;
-pub mod client;
-pub mod config;
-pub mod draft;
-pub mod draft_push;
-pub mod mcp;
-pub mod media;
-pub mod operations;
-pub mod publish;
-pub mod tui;
-
-pub use anyhow::{Error, Result};